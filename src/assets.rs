@@ -0,0 +1,179 @@
+// src/assets.rs
+
+use crate::universal_rule::UniversalRule;
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// The directory of the rules directory holding the images and other files rules link
+/// to. It is not searched for rules.
+pub const ASSETS_DIR: &str = "assets";
+
+/// Resolves the `.` and `..` components of `path` without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Replaces the targets of the Markdown links and images of `line` (`[text](target)`)
+/// with the value `rewrite` returns for them, keeping those it returns `None` for.
+pub fn rewrite_link_targets(
+    line: &str,
+    mut rewrite: impl FnMut(&str) -> Result<Option<String>>,
+) -> Result<String> {
+    let mut result = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find("](") {
+        let (before, after) = rest.split_at(start + 2);
+        result.push_str(before);
+        let end = after
+            .find(|c: char| c == ')' || c.is_whitespace())
+            .unwrap_or(after.len());
+        let target = &after[..end];
+        match rewrite(target)? {
+            Some(new_target) => result.push_str(&new_target),
+            None => result.push_str(target),
+        }
+        rest = &after[end..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Points the links of the rules to files under the `assets` directory of `rules_dir` at
+/// copies in `assets_dir` (relative to `output_dir`), keeping their path below `assets`.
+/// Returns the rules with those links, relative to `output_dir`, and the copies to make,
+/// as the path of each copy paired with its source. Nothing is copied.
+///
+/// Links are resolved relative to the rule file; URLs, anchors, absolute paths, links to
+/// other files and links inside fenced code blocks are kept as they are. Fails if a
+/// linked asset does not exist.
+pub fn link_assets(
+    rules: Vec<UniversalRule>,
+    rules_dir: &Path,
+    output_dir: &Path,
+    assets_dir: &Path,
+) -> Result<(Vec<UniversalRule>, BTreeMap<PathBuf, PathBuf>)> {
+    let assets_root = normalize(&rules_dir.join(ASSETS_DIR));
+    let mut copies = BTreeMap::new();
+    let rules = rules
+        .into_iter()
+        .map(|mut rule| {
+            let rule_dir = rules_dir.join(&rule.relative_dir);
+            let mut rewrite = |target: &str| -> Result<Option<String>> {
+                if target.is_empty() || target.starts_with(['#', '/']) || target.contains(':') {
+                    return Ok(None);
+                }
+                let (path, fragment) = match target.find(['#', '?']) {
+                    Some(index) => target.split_at(index),
+                    None => (target, ""),
+                };
+                let source = normalize(&rule_dir.join(path));
+                let Ok(asset) = source.strip_prefix(&assets_root) else {
+                    return Ok(None);
+                };
+                if !source.is_file() {
+                    bail!(
+                        "Rule {:?} links to the missing asset {:?}",
+                        rule.relative_path(),
+                        source
+                    );
+                }
+                copies.insert(output_dir.join(assets_dir).join(asset), source.clone());
+                let new_path = assets_dir.join(asset).to_string_lossy().replace('\\', "/");
+                Ok(Some(format!("{}{}", new_path, fragment)))
+            };
+
+            let mut content = String::with_capacity(rule.content.len());
+            let mut in_fence = false;
+            for line in rule.content.split_inclusive('\n') {
+                let trimmed = line.trim_start();
+                if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                    in_fence = !in_fence;
+                }
+                if in_fence {
+                    content.push_str(line);
+                } else {
+                    content.push_str(&rewrite_link_targets(line, &mut rewrite)?);
+                }
+            }
+            rule.content = content;
+            Ok(rule)
+        })
+        .collect::<Result<_>>()?;
+    Ok((rules, copies))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Test that the links to assets are rewritten to their copies, leaving other links.
+    #[test]
+    fn test_link_assets() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let rules_dir = dir.path().join(".rules");
+        fs::create_dir_all(rules_dir.join("assets/diagrams"))?;
+        fs::write(rules_dir.join("assets/diagrams/arch.png"), [0x89, b'P'])?;
+        fs::write(rules_dir.join("assets/setup.md"), "Setup.\n")?;
+        let output_dir = dir.path().join("out");
+        let rule = UniversalRule {
+            name: "api".to_string(),
+            relative_dir: PathBuf::from("backend"),
+            content: "![Architecture](../assets/diagrams/arch.png \"Overview\")\n\
+                      See [setup](../assets/setup.md#steps), [docs](../docs.md) and [site](https://example.com).\n\
+                      ```\n[code](../assets/setup.md)\n```\n"
+                .to_string(),
+            ..Default::default()
+        };
+
+        let (rules, copies) = link_assets(
+            vec![rule],
+            &rules_dir,
+            &output_dir,
+            Path::new(".claude/assets"),
+        )?;
+        assert_eq!(
+            rules[0].content,
+            "![Architecture](.claude/assets/diagrams/arch.png \"Overview\")\n\
+             See [setup](.claude/assets/setup.md#steps), [docs](../docs.md) and [site](https://example.com).\n\
+             ```\n[code](../assets/setup.md)\n```\n"
+        );
+        assert_eq!(
+            copies.into_iter().collect::<Vec<_>>(),
+            [
+                (
+                    output_dir.join(".claude/assets/diagrams/arch.png"),
+                    rules_dir.join("assets/diagrams/arch.png")
+                ),
+                (
+                    output_dir.join(".claude/assets/setup.md"),
+                    rules_dir.join("assets/setup.md")
+                ),
+            ]
+        );
+        assert!(!output_dir.exists());
+
+        let missing = UniversalRule {
+            name: "style".to_string(),
+            content: "![Logo](assets/logo.png)\n".to_string(),
+            ..Default::default()
+        };
+        let error =
+            link_assets(vec![missing], &rules_dir, &output_dir, Path::new("a")).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Rule \"style.md\" links to the missing asset"));
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+// src/backup.rs
+
+use crate::dry_run::{ChangeKind, PlannedChange};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How `--backup` names the copies of replaced output files.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum BackupMode {
+    /// `<file>.bak`, replacing the backup of an earlier run.
+    Simple,
+    /// `<file>.<unix timestamp>.bak`, keeping the backups of earlier runs.
+    Timestamped,
+}
+
+/// Returns the path of the backup of `path`, using `timestamp` for timestamped backups.
+fn backup_path(path: &Path, mode: BackupMode, timestamp: u64) -> PathBuf {
+    let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+    if mode == BackupMode::Timestamped {
+        file_name.push(format!(".{}", timestamp));
+    }
+    file_name.push(".bak");
+    path.with_file_name(file_name)
+}
+
+/// Copies every file that the planned `changes` would replace or remove to its backup
+/// path, and returns the `(file, backup)` pairs. Files that would be created have nothing
+/// to back up.
+pub fn back_up_files(
+    changes: &[PlannedChange],
+    mode: BackupMode,
+) -> Result<Vec<(PathBuf, PathBuf)>> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let mut backups = Vec::new();
+    for change in changes
+        .iter()
+        .filter(|c| matches!(c.kind, ChangeKind::Update | ChangeKind::Delete))
+    {
+        let backup = backup_path(&change.path, mode, timestamp);
+        fs::copy(&change.path, &backup)
+            .with_context(|| format!("Failed to back up {:?} to {:?}", change.path, backup))?;
+        backups.push((change.path.clone(), backup));
+    }
+    Ok(backups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Test the names of simple and timestamped backups.
+    #[test]
+    fn test_backup_path() {
+        let path = Path::new("out/CLAUDE.md");
+        assert_eq!(
+            backup_path(path, BackupMode::Simple, 1700000000),
+            PathBuf::from("out/CLAUDE.md.bak")
+        );
+        assert_eq!(
+            backup_path(path, BackupMode::Timestamped, 1700000000),
+            PathBuf::from("out/CLAUDE.md.1700000000.bak")
+        );
+    }
+
+    /// Test that only replaced files are backed up, with their current content.
+    #[test]
+    fn test_back_up_files() -> Result<()> {
+        let dir = tempdir()?;
+        let claude_path = dir.path().join("CLAUDE.md");
+        fs::write(&claude_path, "Old content.\n")?;
+        let changes = vec![
+            PlannedChange {
+                path: claude_path.clone(),
+                kind: ChangeKind::Update,
+                diff: None,
+            },
+            PlannedChange {
+                path: dir.path().join("WARP.md"),
+                kind: ChangeKind::Create,
+                diff: None,
+            },
+        ];
+
+        let backups = back_up_files(&changes, BackupMode::Simple)?;
+
+        let backup = dir.path().join("CLAUDE.md.bak");
+        assert_eq!(backups, vec![(claude_path, backup.clone())]);
+        assert_eq!(fs::read_to_string(backup)?, "Old content.\n");
+        assert!(!dir.path().join("WARP.md.bak").exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+// src/banner.rs
+
+use crate::universal_rule::UniversalRule;
+use std::path::Path;
+
+/// The default text of the banner prepended to generated files, where `{source}` stands
+/// for the rule file (or the rules directory) the file was generated from.
+pub const DEFAULT_BANNER: &str = "Generated by urules from {source} — do not edit";
+
+/// Returns the opening and closing delimiters of a comment line in the file at `path`,
+/// or `None` if its format has no comments (e.g. JSON) or is not known.
+fn comment_syntax(path: &Path) -> Option<(&'static str, &'static str)> {
+    if crate::minify::is_markdown_file(path) {
+        return Some(("<!-- ", " -->"));
+    }
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("html" | "htm" | "xml") => Some(("<!-- ", " -->")),
+        Some("yaml" | "yml" | "toml") => Some(("# ", "")),
+        _ => None,
+    }
+}
+
+/// Returns the name of the file at `path` up to its first `.`, without the `-1`, `-2`,
+/// ... suffix of the parts of a split output (e.g. `api` for `api-2.instructions.md`).
+fn file_stems(path: &Path) -> Vec<&str> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let stem = name.split('.').next().unwrap_or_default();
+    let mut stems = vec![stem];
+    if let Some((base, part)) = stem.rsplit_once('-') {
+        if !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()) {
+            stems.push(base);
+        }
+    }
+    stems
+}
+
+/// Returns what the file at `path` was generated from, for the banner's `{source}`: the
+/// path of the only rule in `rules` whose output name (or alias) names the file, or else
+/// `rules_dir`, for files combining several rules.
+pub fn banner_source(path: &Path, rules: &[&UniversalRule], rules_dir: &Path) -> String {
+    let stems = file_stems(path);
+    let mut sources = rules
+        .iter()
+        .filter(|rule| rule.output_stems().iter().any(|stem| stems.contains(stem)));
+    let source = match (sources.next(), sources.next()) {
+        (Some(rule), None) => rules_dir.join(rule.relative_path()),
+        _ => rules_dir.to_path_buf(),
+    };
+    source.to_string_lossy().replace('\\', "/")
+}
+
+/// Returns the most characters `add_banner` adds to a Markdown file generated from
+/// `rules`, whatever the file's `{source}`, so that converters can keep files with the
+/// banner within the character limits of their agent.
+pub fn banner_chars(banner: &str, rules: &[&UniversalRule], rules_dir: &Path) -> usize {
+    let longest_source = rules
+        .iter()
+        .map(|rule| rules_dir.join(rule.relative_path()))
+        .chain(std::iter::once(rules_dir.to_path_buf()))
+        .map(|source| source.to_string_lossy().replace('\\', "/"))
+        .max_by_key(|source| source.chars().count())
+        .unwrap_or_default();
+    // The comment line, then the blank line separating it from content without frontmatter
+    let line = format!(
+        "<!-- {} -->\n\n",
+        banner.replace("{source}", &longest_source)
+    );
+    line.chars().count()
+}
+
+/// Returns `content`, the content of the generated file at `path`, with `banner` as a
+/// comment line at its top, below the frontmatter of Markdown files so that agents still
+/// find it. Files whose format has no comments are returned unchanged.
+pub fn add_banner(path: &Path, content: &str, banner: &str) -> String {
+    let Some((open, close)) = comment_syntax(path) else {
+        return content.to_string();
+    };
+    let line = format!("{}{}{}\n", open, banner, close);
+    let frontmatter_end = content
+        .strip_prefix("---\n")
+        .filter(|_| open == "<!-- ")
+        .and_then(|rest| rest.find("\n---\n").map(|end| 4 + end + 5));
+    match frontmatter_end {
+        Some(end) => format!("{}{}{}", &content[..end], line, &content[end..]),
+        None if content.is_empty() => line,
+        None => format!("{}\n{}", line, content),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the banner goes below the frontmatter, in the comment syntax of the file.
+    #[test]
+    fn test_add_banner() {
+        let banner = "Generated by urules from .rules/api.md — do not edit";
+        assert_eq!(
+            add_banner(
+                Path::new(".cursor/rules/api.mdc"),
+                "---\nalwaysApply: true\n---\nUse REST.\n",
+                banner
+            ),
+            "---\nalwaysApply: true\n---\n<!-- Generated by urules from .rules/api.md — do not edit -->\nUse REST.\n"
+        );
+        assert_eq!(
+            add_banner(Path::new("CLAUDE.md"), "## Rule: Api\n", "Generated"),
+            "<!-- Generated -->\n\n## Rule: Api\n"
+        );
+        assert_eq!(
+            add_banner(Path::new("rules.yaml"), "a: 1\n", "Generated"),
+            "# Generated\n\na: 1\n"
+        );
+        assert_eq!(
+            add_banner(Path::new(".vscode/settings.json"), "{}\n", "Generated"),
+            "{}\n"
+        );
+    }
+
+    /// Test that files are attributed to the rule named like them, or to the rules
+    /// directory.
+    #[test]
+    fn test_banner_source() {
+        let api = UniversalRule {
+            name: "api".to_string(),
+            relative_dir: "backend".into(),
+            ..Default::default()
+        };
+        let style = UniversalRule {
+            name: "style".to_string(),
+            ..Default::default()
+        };
+        let rules = [&api, &style];
+        let rules_dir = Path::new(".rules");
+        assert_eq!(
+            banner_source(
+                Path::new(".github/instructions/api.instructions.md"),
+                &rules,
+                rules_dir
+            ),
+            ".rules/backend/api.md"
+        );
+        assert_eq!(
+            banner_source(Path::new(".windsurf/rules/style-2.md"), &rules, rules_dir),
+            ".rules/style.md"
+        );
+        assert_eq!(
+            banner_source(Path::new("CLAUDE.md"), &rules, rules_dir),
+            ".rules"
+        );
+    }
+
+    /// Test that the banner length is counted with the longest source it can name.
+    #[test]
+    fn test_banner_chars() {
+        let api = UniversalRule {
+            name: "api".to_string(),
+            relative_dir: "backend".into(),
+            ..Default::default()
+        };
+        let rules_dir = Path::new(".rules");
+        let banner = "From {source}";
+        let line = add_banner(
+            Path::new("api.md"),
+            "Use REST.",
+            &banner.replace("{source}", ".rules/backend/api.md"),
+        )
+        .replace("Use REST.", "");
+        assert_eq!(
+            banner_chars(banner, &[&api], rules_dir),
+            line.chars().count()
+        );
+        assert_eq!(
+            banner_chars(banner, &[], rules_dir),
+            "<!-- From .rules -->\n\n".len()
+        );
+    }
+}
@@ -0,0 +1,2358 @@
+// src/cli.rs
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use glob::Pattern;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use crate::assets::link_assets;
+use crate::backup::{back_up_files, BackupMode};
+use crate::color::ColorChoice;
+use crate::commands::bundle::{self, PackArgs, UnpackArgs};
+use crate::commands::convert::{self, ConvertArgs};
+use crate::commands::coverage::{self, CoverageArgs};
+use crate::commands::doctor;
+use crate::commands::explain::{self, ExplainArgs};
+use crate::commands::fmt::{self, FmtArgs};
+use crate::commands::graph::{self, GraphArgs};
+use crate::commands::hook::{self, HookArgs};
+use crate::commands::import::{self, ImportArgs};
+use crate::commands::init::{self, InitArgs};
+use crate::commands::lint::{self, LintArgs};
+use crate::commands::list::{self, ListArgs};
+use crate::commands::rename::{self, RenameArgs};
+use crate::commands::rm::{self, RmArgs};
+use crate::commands::show::{self, ShowArgs};
+use crate::report::{write_report, AgentReport, ReportFormat, RunReport, SkippedRule};
+use crate::rule_parser::{
+    discover_rules, filter_env_gated, filter_expired, filter_rules, print_rule_warnings,
+    DiscoveryOptions,
+};
+use crate::template::{builtin_vars, ProjectContext};
+use crate::tokens::budget_problems;
+use crate::universal_rule::{AutoDescription, UniversalRule};
+use crate::{color, config, exit_code, interactive, template};
+use crate::{skipped_rule_reason, AgentName};
+// Import the trait and specific converter structs
+use crate::banner::{add_banner, banner_chars, banner_source, DEFAULT_BANNER};
+use crate::config::{find_project_dir, probe_rules_dir, ProjectConfig};
+use crate::converters::claude::ClaudeFormat;
+use crate::converters::copilot::CopilotFormat;
+use crate::converters::cursor::CursorFormat;
+use crate::converters::custom::CustomConverterDefinition;
+use crate::converters::registry::{self, agent_help, all_agents, ConverterOptions};
+use crate::converters::windsurf::{self, WindsurfFormat};
+use crate::converters::{GeneratedFile, OversizeMode, RuleConverter};
+use crate::dry_run::{print_planned_changes, ChangeKind, OutputPlan, PlannedChange};
+use crate::exit_code::{RuleParseError, UsageError};
+use crate::gitignore_manager::with_managed_section;
+use crate::ignore_files::plan_ignore_files;
+use crate::line_endings::LineEndings;
+use crate::manifest::{Manifest, MANIFEST_FILE};
+use crate::minify::{is_markdown_file, minify_markdown};
+use crate::secrets::{secret_problems, SecretsMode};
+
+/// Command-line interface for the Universal Rule Unifier.
+/// This tool processes universal rule files (Markdown with optional YAML frontmatter)
+/// and converts them into formats specific to different AI coding agents.
+#[derive(Parser, Debug)]
+#[clap(name = "urules", version = "0.1.0", about = "Unifies coding agent rules from a universal format.", long_about = None)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub(crate) struct Cli {
+    /// An optional subcommand; without one, rules are generated for `--agent`.
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    /// Options for the default rule generation.
+    #[clap(flatten)]
+    pub(crate) generate: GenerateArgs,
+
+    /// When to color the output. `auto` colors terminal output unless `NO_COLOR` is set.
+    #[clap(
+        long,
+        value_enum,
+        global = true,
+        default_value_t = ColorChoice::Auto,
+        help = "When to color the output (auto, always or never)."
+    )]
+    color: ColorChoice,
+}
+
+/// Options controlling rule generation, shared by the default command, `check`, `doctor`,
+/// `rename`, `rm`, `show`, `convert`, `explain` and `coverage`.
+#[derive(Args, Debug, Clone)]
+pub(crate) struct GenerateArgs {
+    /// Directory containing the universal rule files (Markdown `.md` files).
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = ".rules",
+        help = "Directory containing universal rule files (.md)."
+    )]
+    pub(crate) rules_dir: PathBuf,
+
+    /// The depth and patterns limiting which rule files are discovered.
+    #[clap(flatten)]
+    pub(crate) discovery: DiscoveryOptions,
+
+    /// Target AI agents for which to generate rules. Repeat the flag (or separate values
+    /// with commas) to generate several agents in one run, or use `all`.
+    /// Defaults to the `agents` of the project configuration.
+    #[clap(
+        short,
+        long,
+        value_enum,
+        value_delimiter = ',',
+        help = "Target agent(s) for rule generation (repeatable, or `all`).",
+        long_help = agent_help()
+    )]
+    pub(crate) agent: Vec<AgentName>,
+
+    /// Directory where the agent-specific rules will be generated.
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = ".",
+        help = "Directory to output generated agent-specific rules."
+    )]
+    pub(crate) output_dir: PathBuf,
+
+    /// Output directories of single agents, from `output_dirs` in the project
+    /// configuration. The other agents use `output_dir`.
+    #[clap(skip)]
+    pub(crate) agent_output_dirs: BTreeMap<AgentName, PathBuf>,
+
+    /// The most tokens the rules emitted for an agent may take together, from
+    /// `token_budgets` in the project configuration.
+    #[clap(skip)]
+    pub(crate) token_budgets: BTreeMap<AgentName, usize>,
+
+    /// Disable automatic update of .gitignore in the output directory.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Disable automatic update of .gitignore."
+    )]
+    pub(crate) no_gitignore: bool,
+
+    /// Print the files that would be created or updated, with diffs, without writing anything.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Show the files that would change (with diffs) without writing them."
+    )]
+    pub(crate) dry_run: bool,
+
+    /// Print the generated file to standard output instead of writing it, for
+    /// single-file targets such as `CLAUDE.md`.
+    #[clap(
+        long,
+        default_value_t = false,
+        conflicts_with = "dry_run",
+        help = "Print the generated file of a single-file agent to stdout instead of writing it."
+    )]
+    pub(crate) stdout: bool,
+
+    /// Emit a machine-readable summary of the run in the given format.
+    #[clap(
+        long,
+        value_enum,
+        conflicts_with = "stdout",
+        help = "Emit a summary of the run (rules, skipped rules, written files) in this format."
+    )]
+    pub(crate) report: Option<ReportFormat>,
+
+    /// Write the `--report` summary to this file instead of standard output.
+    #[clap(
+        long,
+        value_parser,
+        requires = "report",
+        help = "Write the --report summary to this file instead of stdout."
+    )]
+    pub(crate) report_file: Option<PathBuf>,
+
+    /// Pick the agents and rules to generate from checkbox lists before generating.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Pick the agents and rules to generate from checkbox lists in the terminal."
+    )]
+    pub(crate) interactive: bool,
+
+    /// Fail instead of skipping rule files that cannot be parsed.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Fail (exit code 3) if any rule file cannot be parsed, instead of skipping it."
+    )]
+    pub(crate) strict: bool,
+
+    /// Overwrite generated files even if they were edited by hand since the last run.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Overwrite generated files even if they were modified since the last generation."
+    )]
+    pub(crate) force: bool,
+
+    /// Save a copy of every existing output file before it is replaced.
+    #[clap(
+        long,
+        value_enum,
+        num_args = 0..=1,
+        default_missing_value = "simple",
+        conflicts_with_all = ["dry_run", "stdout"],
+        help = "Save a copy of each replaced output file: <file>.bak (simple, the default) or <file>.<timestamp>.bak (timestamped)."
+    )]
+    pub(crate) backup: Option<BackupMode>,
+
+    /// Only regenerate the outputs of the rules whose content or frontmatter changed
+    /// since the last run, as recorded in the generation manifest.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Only regenerate the outputs of rules changed since the last run."
+    )]
+    pub(crate) changed_only: bool,
+
+    /// Only generate the rules whose name or path (relative to the rules directory)
+    /// matches one of these globs.
+    #[clap(
+        long,
+        value_parser,
+        help = "Only generate rules whose name or relative path matches this glob (repeatable)."
+    )]
+    pub(crate) only: Vec<Pattern>,
+
+    /// Leave out the rules whose name or path (relative to the rules directory) matches
+    /// one of these globs.
+    #[clap(
+        long,
+        value_parser,
+        help = "Leave out rules whose name or relative path matches this glob (repeatable)."
+    )]
+    pub(crate) exclude: Vec<Pattern>,
+
+    /// Values for the `{{name}}` references of rule contents, overriding the rules' `vars`
+    /// and the `vars` of the project configuration.
+    #[clap(
+        long = "set",
+        value_name = "KEY=VALUE",
+        value_parser = template::parse_var_assignment,
+        help = "Set the value of a {{KEY}} variable used in rule contents (repeatable)."
+    )]
+    pub(crate) vars: Vec<(String, String)>,
+
+    /// Use the first sentence or paragraph of a rule's content as its `description`
+    /// when it has none.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = AutoDescription::Off,
+        help = "Derive missing descriptions from the first sentence or paragraph of rules (off, sentence or paragraph)."
+    )]
+    pub(crate) auto_description: AutoDescription,
+
+    /// Keep the HTML comments of rule contents in the outputs instead of stripping them.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Keep <!-- ... --> comments of rule contents in the generated files."
+    )]
+    pub(crate) keep_comments: bool,
+
+    /// Prepend an `<!-- Owner: ... -->` comment line to the content of rules with an `owner`.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Render each rule's owner as a comment line in the generated files."
+    )]
+    pub(crate) owner_comments: bool,
+
+    /// Leave out the time-dependent built-in variables, such as `generation_date`.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Leave out time-dependent built-in variables ({{generation_date}}) so that outputs are reproducible."
+    )]
+    pub(crate) reproducible: bool,
+
+    /// What to do with outputs over the size an agent reads of them.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = OversizeMode::Split,
+        help = "Split, trim or keep outputs over an agent's size limit (e.g. Windsurf's 6000 characters per file)."
+    )]
+    pub(crate) oversized: OversizeMode,
+
+    /// Minify the generated Markdown to reduce the context it takes.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Strip comments, separators and extra blank lines from the generated Markdown to save context."
+    )]
+    pub(crate) minify: bool,
+
+    /// Re-render rule contents with consistent Markdown syntax.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Re-render rule contents with consistent list markers, headings, code fences and spacing."
+    )]
+    pub(crate) normalize_markdown: bool,
+
+    /// The comment prepended to generated files, where `{source}` is replaced with the
+    /// rule file or rules directory they were generated from.
+    #[clap(
+        long,
+        default_value = DEFAULT_BANNER,
+        help = "Text of the comment prepended to generated files ({source} is the rule file or rules directory they come from)."
+    )]
+    pub(crate) banner: String,
+
+    /// Leave the banner comment out of the generated files.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Do not prepend the \"Generated by urules\" banner comment to generated files."
+    )]
+    pub(crate) no_banner: bool,
+
+    /// The line endings of the generated files.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = LineEndings::Lf,
+        help = "Line endings of the generated files: lf, crlf, or native (crlf on Windows)."
+    )]
+    pub(crate) line_endings: LineEndings,
+
+    /// What to do when the rules seem to contain secrets.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = SecretsMode::Fail,
+        help = "Fail, warn or do nothing when rules seem to contain secrets such as API keys, tokens or private keys."
+    )]
+    pub(crate) secrets: SecretsMode,
+
+    /// Output format for Cursor rules: `.mdc` files or a single legacy `.cursorrules` file.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = CursorFormat::Mdc,
+        help = "Output format for Cursor rules (mdc or legacy .cursorrules)."
+    )]
+    pub(crate) cursor_format: CursorFormat,
+
+    /// Mirror the rules directory's subdirectories under `.cursor/rules/`.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Mirror rule subdirectories under .cursor/rules/ instead of flattening them."
+    )]
+    pub(crate) cursor_nested: bool,
+
+    /// Output format for Claude rules: one concatenated `CLAUDE.md` or `@` imports.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = ClaudeFormat::Concat,
+        help = "Output format for Claude rules (concat or imports from .claude/rules/)."
+    )]
+    pub(crate) claude_format: ClaudeFormat,
+
+    /// Where Copilot's repository-wide and review instructions are written.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = CopilotFormat::Files,
+        help = "Output format for Copilot instructions (files or .vscode/settings.json)."
+    )]
+    pub(crate) copilot_format: CopilotFormat,
+
+    /// Output format for Windsurf workspace rules: `.windsurf/rules/` or `.windsurfrules`.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = WindsurfFormat::Rules,
+        help = "Output format for Windsurf workspace rules (rules or legacy .windsurfrules)."
+    )]
+    pub(crate) windsurf_format: WindsurfFormat,
+
+    /// Mirror the rules directory's subdirectories under `.windsurf/rules/`.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Mirror rule subdirectories under .windsurf/rules/ instead of flattening them."
+    )]
+    pub(crate) windsurf_nested: bool,
+
+    /// Write Windsurf global rules to this directory instead of the output directory.
+    /// Without a value, Windsurf's global location (`~/.codeium/windsurf/memories/`) is used.
+    #[clap(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        help = "Write Windsurf global rules here (default: ~/.codeium/windsurf/memories/)."
+    )]
+    pub(crate) windsurf_global_dir: Option<Option<PathBuf>>,
+
+    /// YAML file defining the output layout and templates for `--agent custom`.
+    #[clap(
+        long,
+        value_parser,
+        value_name = "FILE",
+        help = "Converter definition (YAML with minijinja templates) for --agent custom."
+    )]
+    pub(crate) custom_converter: Option<PathBuf>,
+}
+
+/// Replaces the `{{name}}` references in the contents of `rules` with the values of the
+/// built-in variables, their `vars`, the configuration's `vars` and `--set` (rendering
+/// the `template: true` rules with them), strips their HTML comments unless
+/// `--keep-comments` is set, derives missing descriptions with `--auto-description`,
+/// appends their `references` and adds their owner comments with `--owner-comments`.
+pub(crate) fn with_vars(
+    args: &GenerateArgs,
+    rules: Vec<UniversalRule>,
+) -> Result<Vec<UniversalRule>> {
+    let today = (!args.reproducible).then(|| Utc::now().date_naive());
+    let builtins = builtin_vars(&args.output_dir, today);
+    let project = ProjectContext {
+        rules_dir: args.rules_dir.display().to_string(),
+        output_dir: args.output_dir.display().to_string(),
+        agents: resolve_agents(args),
+    };
+    rules
+        .into_iter()
+        .map(|rule| {
+            let rule = rule.with_template(&builtins, &args.vars, &project)?;
+            let rule = if args.keep_comments {
+                rule
+            } else {
+                rule.without_comments()
+            };
+            let rule = rule
+                .with_derived_description(args.auto_description)
+                .with_references();
+            Ok(if args.owner_comments {
+                rule.with_owner_comment()
+            } else {
+                rule
+            })
+        })
+        .collect()
+}
+
+/// Expands the `--agent` values into the list of agents to generate rules for.
+///
+/// `all` stands for every built-in coding agent, plus `custom` when a custom converter
+/// definition was given. Duplicates are dropped, keeping the first occurrence.
+pub(crate) fn resolve_agents(args: &GenerateArgs) -> Vec<AgentName> {
+    let mut agents: Vec<AgentName> = Vec::new();
+    for agent in &args.agent {
+        let expanded = match agent {
+            AgentName::All => all_agents(args.custom_converter.is_some()),
+            _ => vec![agent.clone()],
+        };
+        for agent in expanded {
+            if !agents.contains(&agent) {
+                agents.push(agent);
+            }
+        }
+    }
+    agents
+}
+
+/// Returns the agents among `agents` that only `--agent all` selects and whose single
+/// output file is taken by a directory, with the path of that directory, e.g. Zed when
+/// the rules directory is `.rules/`. Those are skipped, while agents named explicitly
+/// fail to generate instead.
+pub(crate) fn agents_blocked_by_directories(
+    args: &GenerateArgs,
+    agents: &[AgentName],
+) -> Vec<(AgentName, PathBuf)> {
+    agents
+        .iter()
+        .filter(|agent| !args.agent.contains(agent))
+        .filter_map(|agent| {
+            let output_dir = args
+                .agent_output_dirs
+                .get(agent)
+                .unwrap_or(&args.output_dir);
+            let path = output_dir.join(single_output_file(args, agent)?);
+            path.is_dir().then(|| (agent.clone(), path))
+        })
+        .collect()
+}
+
+/// Subcommands besides the default rule generation.
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Create a rules directory with a documented example rule to get started.
+    Init(InitArgs),
+    /// Exit with an error if any generated output is missing or out of date (for CI).
+    Check(GenerateArgs),
+    /// Check the project for common misconfigurations, such as conflicting rule names
+    /// or generated files that are not ignored.
+    Doctor(GenerateArgs),
+    /// Convert an agent's existing rule files into universal rule files.
+    Import(ImportArgs),
+    /// List the discovered rules with their targeting metadata.
+    List(ListArgs),
+    /// Rename a rule, update the links to it and regenerate the outputs.
+    Rename(RenameArgs),
+    /// Delete a rule, remove the outputs only it produced and regenerate the others.
+    Rm(RmArgs),
+    /// Print the files one rule produces for each agent, to debug its frontmatter.
+    Show(ShowArgs),
+    /// Convert one universal rule, from a file or stdin, and print the result to stdout.
+    Convert(ConvertArgs),
+    /// Describe how each agent activates a rule: always, for matching files, on request,
+    /// globally or per workspace.
+    Explain(ExplainArgs),
+    /// Report which rules are left out for which agents, and why.
+    Coverage(CoverageArgs),
+    /// Normalize the formatting of the rule files: frontmatter key order, glob quoting
+    /// and whitespace.
+    Fmt(FmtArgs),
+    /// Check the rule bodies for dead relative links, `TODO`/`FIXME` markers, empty
+    /// headings and sections, unclosed code fences and untagged code blocks.
+    Lint(LintArgs),
+    /// Print which files each rule includes, as a tree or a Graphviz digraph, flagging
+    /// include cycles and partials that no rule includes.
+    Graph(GraphArgs),
+    /// Manage the git hook that keeps stale generated files from being committed.
+    Hook(HookArgs),
+    /// Archive the rules directory and the project configuration into a shareable
+    /// `.urules.tar.gz` bundle.
+    Pack(PackArgs),
+    /// Restore a bundle written by `urules pack`.
+    Unpack(UnpackArgs),
+}
+
+/// Builds the converter for `agent` from the converter registry, configured from the
+/// CLI options.
+///
+/// `windsurf_global_dir` is the resolved `--windsurf-global-dir`.
+pub(crate) fn build_converter(
+    args: &GenerateArgs,
+    agent: &AgentName,
+    custom_definition: Option<&CustomConverterDefinition>,
+    windsurf_global_dir: Option<&Path>,
+) -> Result<Box<dyn RuleConverter>> {
+    let options = converter_options(args, custom_definition, windsurf_global_dir);
+    registry::build_converter(agent, &options)
+}
+
+/// Returns the options converters are built with from the CLI options, without a banner.
+fn converter_options<'a>(
+    args: &GenerateArgs,
+    custom_definition: Option<&'a CustomConverterDefinition>,
+    windsurf_global_dir: Option<&'a Path>,
+) -> ConverterOptions<'a> {
+    ConverterOptions {
+        cursor_format: args.cursor_format.clone(),
+        cursor_nested: args.cursor_nested,
+        windsurf_format: args.windsurf_format.clone(),
+        windsurf_nested: args.windsurf_nested,
+        windsurf_global_dir,
+        claude_format: args.claude_format.clone(),
+        copilot_format: args.copilot_format.clone(),
+        oversize: args.oversized,
+        banner_chars: 0,
+        custom_definition,
+    }
+}
+
+/// Replaces `field` with the configured value, unless the option was given on the command line.
+fn apply_config_value<T: Clone>(field: &mut T, config_value: Option<&T>, given_on_cli: bool) {
+    if let (false, Some(value)) = (given_on_cli, config_value) {
+        *field = value.clone();
+    }
+}
+
+/// Returns the rules directory used when `--rules-dir` is not given: the configured one,
+/// or else the first existing directory of `config::RULES_DIR_FALLBACKS`, relative to
+/// `project_dir`.
+///
+/// Picking a directory other than the default `.rules/` is reported on stderr.
+fn default_rules_dir(config: &ProjectConfig, project_dir: &Path) -> PathBuf {
+    if let Some(rules_dir) = &config.rules_dir {
+        return project_dir.join(rules_dir);
+    }
+    match probe_rules_dir(project_dir) {
+        Some(rules_dir) => {
+            if rules_dir != Path::new(config::DEFAULT_RULES_DIR) {
+                eprintln!("Using rules directory {:?}", project_dir.join(&rules_dir));
+            }
+            project_dir.join(rules_dir)
+        }
+        None => project_dir.join(config::DEFAULT_RULES_DIR),
+    }
+}
+
+/// Fills the rule discovery options that were not given on the command line from the
+/// project configuration, for every command that discovers rules.
+fn apply_discovery_config(
+    discovery: &mut DiscoveryOptions,
+    config: &ProjectConfig,
+    matches: &ArgMatches,
+) {
+    let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    apply_config_value(
+        &mut discovery.deny_unknown_keys,
+        config.deny_unknown_keys.as_ref(),
+        given("deny_unknown_keys"),
+    );
+    apply_config_value(
+        &mut discovery.env_interpolation,
+        config.env_interpolation.as_ref(),
+        given("env_interpolation"),
+    );
+    if let Some(languages) = &config.languages {
+        discovery.languages = languages.clone();
+    }
+}
+
+/// Fills the generation options that were not given on the command line from the
+/// project configuration.
+///
+/// Paths from the configuration and the default paths are relative to `project_dir`,
+/// while paths given on the command line stay relative to the current directory.
+fn apply_config(
+    args: &mut GenerateArgs,
+    config: &ProjectConfig,
+    matches: &ArgMatches,
+    project_dir: &Path,
+) {
+    let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    if !given("rules_dir") {
+        args.rules_dir = default_rules_dir(config, project_dir);
+    }
+    apply_config_value(
+        &mut args.output_dir,
+        config.output_dir.as_ref(),
+        given("output_dir"),
+    );
+    apply_config_value(&mut args.agent, config.agents.as_ref(), given("agent"));
+    apply_config_value(
+        &mut args.no_gitignore,
+        config.no_gitignore.as_ref(),
+        given("no_gitignore"),
+    );
+    apply_discovery_config(&mut args.discovery, config, matches);
+    apply_config_value(
+        &mut args.auto_description,
+        config.auto_description.as_ref(),
+        given("auto_description"),
+    );
+    apply_config_value(
+        &mut args.keep_comments,
+        config.keep_comments.as_ref(),
+        given("keep_comments"),
+    );
+    apply_config_value(
+        &mut args.owner_comments,
+        config.owner_comments.as_ref(),
+        given("owner_comments"),
+    );
+    apply_config_value(
+        &mut args.reproducible,
+        config.reproducible.as_ref(),
+        given("reproducible"),
+    );
+    apply_config_value(
+        &mut args.oversized,
+        config.oversized.as_ref(),
+        given("oversized"),
+    );
+    apply_config_value(&mut args.minify, config.minify.as_ref(), given("minify"));
+    apply_config_value(
+        &mut args.normalize_markdown,
+        config.normalize_markdown.as_ref(),
+        given("normalize_markdown"),
+    );
+    apply_config_value(&mut args.banner, config.banner.as_ref(), given("banner"));
+    apply_config_value(
+        &mut args.no_banner,
+        config.no_banner.as_ref(),
+        given("no_banner"),
+    );
+    apply_config_value(
+        &mut args.line_endings,
+        config.line_endings.as_ref(),
+        given("line_endings"),
+    );
+    apply_config_value(&mut args.secrets, config.secrets.as_ref(), given("secrets"));
+    apply_config_value(
+        &mut args.custom_converter,
+        config.custom_converter.clone().map(Some).as_ref(),
+        given("custom_converter"),
+    );
+    apply_config_value(
+        &mut args.cursor_format,
+        config.cursor.format.as_ref(),
+        given("cursor_format"),
+    );
+    apply_config_value(
+        &mut args.cursor_nested,
+        config.cursor.nested.as_ref(),
+        given("cursor_nested"),
+    );
+    apply_config_value(
+        &mut args.claude_format,
+        config.claude.format.as_ref(),
+        given("claude_format"),
+    );
+    apply_config_value(
+        &mut args.copilot_format,
+        config.copilot.format.as_ref(),
+        given("copilot_format"),
+    );
+    apply_config_value(
+        &mut args.windsurf_format,
+        config.windsurf.format.as_ref(),
+        given("windsurf_format"),
+    );
+    apply_config_value(
+        &mut args.windsurf_nested,
+        config.windsurf.nested.as_ref(),
+        given("windsurf_nested"),
+    );
+    apply_config_value(
+        &mut args.windsurf_global_dir,
+        config
+            .windsurf
+            .global_dir
+            .clone()
+            .map(Some)
+            .map(Some)
+            .as_ref(),
+        given("windsurf_global_dir"),
+    );
+
+    if let Some(token_budgets) = &config.token_budgets {
+        args.token_budgets = token_budgets.clone();
+    }
+
+    if let Some(vars) = &config.vars {
+        // `--set` values come last so that they win
+        let cli_vars = std::mem::take(&mut args.vars);
+        args.vars = vars.clone().into_iter().chain(cli_vars).collect();
+    }
+
+    if !given("output_dir") {
+        args.output_dir = project_dir.join(&args.output_dir);
+        // An `--output-dir` given on the command line applies to every agent
+        if let Some(output_dirs) = &config.output_dirs {
+            args.agent_output_dirs = output_dirs
+                .iter()
+                .map(|(agent, dir)| (agent.clone(), project_dir.join(dir)))
+                .collect();
+        }
+    }
+    if !given("custom_converter") {
+        args.custom_converter = args.custom_converter.as_ref().map(|p| project_dir.join(p));
+    }
+    if let (false, Some(Some(global_dir))) =
+        (given("windsurf_global_dir"), &mut args.windsurf_global_dir)
+    {
+        *global_dir = project_dir.join(&global_dir);
+    }
+}
+
+/// Main entry point for the CLI application.
+///
+/// Parses the command-line arguments and runs the selected subcommand, or the default
+/// rule generation (see `run_generation`), returning the exit code of the process.
+pub fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::from(exit_code::SUCCESS),
+        Err(error) => {
+            // Printed like an error returned from `main`, but with a specific exit code
+            eprintln!("{} {:?}", color::error_label(), error);
+            ExitCode::from(exit_code::exit_code(&error))
+        }
+    }
+}
+
+/// Runs the command given on the command line.
+fn run() -> Result<()> {
+    // Parse command-line arguments, then fill in defaults from the project configuration
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    color::init(cli.color);
+    // Like git, find the project from anywhere inside it
+    let current_dir = std::env::current_dir().context("Failed to read the current directory")?;
+    let project_dir = match find_project_dir(&current_dir) {
+        Some(dir) if dir != current_dir => dir,
+        // Keep paths relative when running from the project directory itself
+        _ => PathBuf::new(),
+    };
+    let config = ProjectConfig::load(&project_dir)?.unwrap_or_default();
+    match (&mut cli.command, matches.subcommand()) {
+        (Some(Command::Check(args) | Command::Doctor(args)), Some((_, sub_matches))) => {
+            apply_config(args, &config, sub_matches, &project_dir)
+        }
+        (
+            Some(
+                Command::List(ListArgs {
+                    rules_dir,
+                    discovery,
+                    ..
+                })
+                | Command::Fmt(FmtArgs {
+                    rules_dir,
+                    discovery,
+                    ..
+                })
+                | Command::Lint(LintArgs {
+                    rules_dir,
+                    discovery,
+                    ..
+                })
+                | Command::Graph(GraphArgs {
+                    rules_dir,
+                    discovery,
+                    ..
+                }),
+            ),
+            Some((_, sub_matches)),
+        ) => {
+            if sub_matches.value_source("rules_dir") != Some(ValueSource::CommandLine) {
+                *rules_dir = default_rules_dir(&config, project_dir.as_path());
+            }
+            apply_discovery_config(discovery, &config, sub_matches);
+        }
+        (Some(Command::Pack(PackArgs { rules_dir, .. })), Some((_, sub_matches)))
+            if sub_matches.value_source("rules_dir") != Some(ValueSource::CommandLine) =>
+        {
+            *rules_dir = default_rules_dir(&config, project_dir.as_path());
+        }
+        (Some(Command::Rename(RenameArgs { generate, .. })), Some((_, sub_matches)))
+        | (Some(Command::Rm(RmArgs { generate, .. })), Some((_, sub_matches)))
+        | (Some(Command::Show(ShowArgs { generate, .. })), Some((_, sub_matches)))
+        | (Some(Command::Convert(ConvertArgs { generate, .. })), Some((_, sub_matches)))
+        | (Some(Command::Explain(ExplainArgs { generate, .. })), Some((_, sub_matches)))
+        | (Some(Command::Coverage(CoverageArgs { generate, .. })), Some((_, sub_matches))) => {
+            apply_config(generate, &config, sub_matches, &project_dir)
+        }
+        (None, _) => apply_config(&mut cli.generate, &config, &matches, &project_dir),
+        _ => {}
+    }
+
+    match &cli.command {
+        Some(Command::Init(args)) => init::init_project(args, Path::new(".")),
+        Some(Command::Import(args)) => import::import_rules(args),
+        Some(Command::List(args)) => list::list_rules(args),
+        Some(Command::Rename(args)) => rename::rename_rule(args),
+        Some(Command::Rm(args)) => rm::remove_rule(args),
+        Some(Command::Show(args)) => show::show_rule(args),
+        Some(Command::Convert(args)) => convert::convert_rule(args),
+        Some(Command::Explain(args)) => explain::explain_rule(args),
+        Some(Command::Coverage(args)) => coverage::report_coverage(args),
+        Some(Command::Fmt(args)) => fmt::format_rules(args),
+        Some(Command::Lint(args)) => lint::lint_rules(args),
+        Some(Command::Graph(args)) => graph::graph_rules(args),
+        Some(Command::Hook(args)) => hook::run_hook(args, &current_dir),
+        Some(Command::Pack(args)) => bundle::pack_rules(args, &project_dir),
+        Some(Command::Unpack(args)) => bundle::unpack_bundle(args),
+        Some(Command::Check(args)) => run_generation(args, RunMode::Check),
+        Some(Command::Doctor(args)) => doctor::run_doctor(args),
+        None if cli.generate.dry_run => run_generation(&cli.generate, RunMode::DryRun),
+        None if cli.generate.stdout => run_generation(&cli.generate, RunMode::Stdout),
+        None => run_generation(&cli.generate, RunMode::Write),
+    }
+}
+
+/// How `run_generation` applies the generated outputs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RunMode {
+    /// Write the outputs to the output directory.
+    Write,
+    /// Print the changes a real run would make (`--dry-run`).
+    DryRun,
+    /// Fail if a real run would change anything (`urules check`).
+    Check,
+    /// Print the single generated file to standard output (`--stdout`).
+    Stdout,
+}
+
+impl RunMode {
+    /// The name of the mode in run reports.
+    fn label(self) -> &'static str {
+        match self {
+            RunMode::Write => "write",
+            RunMode::DryRun => "dry-run",
+            RunMode::Check => "check",
+            RunMode::Stdout => "stdout",
+        }
+    }
+}
+
+/// Generates rules for the agents selected in `args`.
+///
+/// Orchestrates the process of:
+/// 1. Expanding `--agent all` into the selected agents.
+/// 2. Validating the existence of the rules directory.
+/// 3. Discovering and parsing universal rules from the specified directory.
+/// 4. Validating the existence of the output directory, creating it if necessary.
+/// 5. For each selected agent: filtering out rules whose `agents` list excludes it,
+///    then generating its rules with the matching converter and updating its AI
+///    ignore files from the rules' `ignore_patterns`.
+/// 6. Optionally updating the .gitignore file in the output directory with the
+///    patterns of every agent that was generated.
+/// 7. Printing a summary with the output location of each agent.
+///
+/// Steps 5 and 6 are planned in memory first (see `plan_outputs`), and the plan is
+/// only written once no hand-edited file would be overwritten. In `RunMode::DryRun` and
+/// `RunMode::Check`, the differences between the plan and the output directory are
+/// printed instead; `check` fails if there are any. In `RunMode::Stdout`, the single
+/// agent's output file is printed. With `--report`, a summary of the run is written at
+/// the end, after the outputs.
+///
+/// Agents with their own directory in `output_dirs` of the project configuration go
+/// through steps 4 to 6 in that directory (see `output_groups`).
+pub(crate) fn run_generation(args: &GenerateArgs, mode: RunMode) -> Result<()> {
+    let mut agents = resolve_agents(args);
+    for (agent, path) in agents_blocked_by_directories(args, &agents) {
+        eprintln!(
+            "{} Skipping {}: its output {:?} is a directory; use --output-dir to generate it elsewhere",
+            color::warning_label(),
+            agent,
+            path
+        );
+        agents.retain(|selected| *selected != agent);
+    }
+    if args.interactive {
+        interactive::ensure_terminal()?;
+        agents = interactive::select_agents(&agents, args.custom_converter.is_some())?;
+    }
+    if agents.is_empty() {
+        bail!(UsageError(format!(
+            "No agent selected; pass --agent or set `agents` in {}",
+            config::CONFIG_FILE
+        )));
+    }
+    if args.changed_only && mode != RunMode::Write {
+        bail!(UsageError(
+            "--changed-only only applies when writing the outputs".to_string()
+        ));
+    }
+
+    // Ensure the specified rules directory exists
+    if !args.rules_dir.exists() {
+        bail!(UsageError(format!(
+            "Rules directory {:?} does not exist. Please create it or specify a valid directory with --rules-dir.",
+            args.rules_dir
+        )));
+    }
+
+    // Load the converter definition up front so a bad definition fails before any work
+    let custom_definition = load_custom_definition(args, &agents)?;
+
+    // Discover and parse all universal rules from the rules directory
+    let (mut rules, parse_failures) =
+        discover_rules(&args.rules_dir, &args.discovery).map_err(|e| {
+            // Provide context for errors during rule discovery and parsing
+            eprintln!(
+                "Error discovering or parsing rules from {:?}: {}",
+                args.rules_dir, e
+            );
+            e
+        })?;
+    for failure in &parse_failures {
+        // Report files that failed to parse but continue with the others
+        eprintln!(
+            "Failed to parse rule file {:?}: {}",
+            failure.path, failure.error
+        );
+    }
+    print_rule_warnings(&rules);
+    let required_rules: Vec<String> = rules
+        .iter()
+        .filter(|rule| rule.frontmatter.required)
+        .map(|rule| rule.name.clone())
+        .collect();
+    if args.strict && !parse_failures.is_empty() {
+        bail!(RuleParseError {
+            failures: parse_failures.len()
+        });
+    }
+
+    // With the report on standard output, other messages are left out to keep it parseable
+    let verbose = args.report.is_none() || args.report_file.is_some();
+    let mut report = RunReport::new(mode.label(), &args.rules_dir, &args.output_dir);
+    report.rules_parsed = rules.iter().map(|rule| rule.name.clone()).collect();
+    report.rules_skipped = parse_failures
+        .iter()
+        .map(|failure| SkippedRule {
+            rule: failure.path.display().to_string(),
+            agent: None,
+            reason: failure.error.clone(),
+        })
+        .collect();
+
+    let (current_rules, expired_rules) = filter_expired(rules, Utc::now().date_naive());
+    for rule in &expired_rules {
+        eprintln!(
+            "{} Rule {:?} {}; skipping it",
+            color::warning_label(),
+            rule.relative_path(),
+            expiry_reason(rule)
+        );
+    }
+    if args.strict && !expired_rules.is_empty() {
+        bail!(
+            "{} rule(s) have expired (--strict); remove them or move their `expires` date",
+            expired_rules.len()
+        );
+    }
+    report
+        .rules_skipped
+        .extend(expired_rules.iter().map(|rule| SkippedRule {
+            rule: rule.name.clone(),
+            agent: None,
+            reason: expiry_reason(rule),
+        }));
+
+    // Rules gated on an unset environment variable are left out on purpose, without a warning
+    let (current_rules, env_gated_rules) = filter_env_gated(current_rules, env_var_is_set);
+    report
+        .rules_skipped
+        .extend(env_gated_rules.iter().map(|rule| SkippedRule {
+            rule: rule.name.clone(),
+            agent: None,
+            reason: env_gate_reason(rule),
+        }));
+
+    let (selected_rules, filtered_out) = filter_rules(current_rules, &args.only, &args.exclude);
+    // Outputs are only removed with their deleted rules when none of the rules that still
+    // exist is left out, by a parse failure or by the selection
+    let mut remove_outputs = parse_failures.is_empty() && filtered_out.is_empty();
+
+    // Before templating, while the comments marking false positives are still there
+    if args.secrets != SecretsMode::Off {
+        let secret_problems = secret_problems(&selected_rules);
+        let fail = args.secrets == SecretsMode::Fail;
+        for problem in &secret_problems {
+            match fail {
+                true => eprintln!("{} {}", color::error_label(), problem),
+                false => eprintln!("{} {}", color::warning_label(), problem),
+            }
+        }
+        if fail && !secret_problems.is_empty() {
+            bail!(
+                "{} possible secret(s) found in the rules; nothing was generated (use --secrets warn to generate anyway)",
+                secret_problems.len()
+            );
+        }
+    }
+
+    rules = with_vars(args, selected_rules)?;
+    report
+        .rules_skipped
+        .extend(filtered_out.into_iter().map(|rule| SkippedRule {
+            rule: rule.name,
+            agent: None,
+            reason: "filtered out by --only/--exclude".to_string(),
+        }));
+
+    if args.interactive && !rules.is_empty() {
+        let (picked, deselected) = interactive::select_rules(rules)?;
+        rules = picked;
+        remove_outputs &= deselected.is_empty();
+        report
+            .rules_skipped
+            .extend(deselected.into_iter().map(|rule| SkippedRule {
+                rule: rule.name,
+                agent: None,
+                reason: "deselected in the interactive picker".to_string(),
+            }));
+    }
+
+    let missing_required =
+        required_rule_problems(&required_rules, &report.rules_skipped, &rules, &agents);
+    if !missing_required.is_empty() {
+        for problem in &missing_required {
+            eprintln!("{} {}", color::error_label(), problem);
+        }
+        bail!(
+            "{} required rule(s) are left out of the outputs",
+            missing_required.len()
+        );
+    }
+
+    // If no rules are found, inform the user and exit gracefully
+    if rules.is_empty() {
+        if verbose {
+            println!("No rules found in {:?}.", args.rules_dir);
+        }
+        return write_requested_report(args, &report);
+    }
+
+    let budget_problems = budget_problems(&rules, &agents, &args.token_budgets);
+    for problem in &budget_problems {
+        eprintln!("{} {}", color::warning_label(), problem);
+    }
+    if args.strict && !budget_problems.is_empty() {
+        bail!(
+            "{} token budget(s) are exceeded (--strict); shorten the rules or raise the budgets",
+            budget_problems.len()
+        );
+    }
+
+    let windsurf_global_dir = resolve_windsurf_global_dir(args)?;
+
+    if mode == RunMode::Stdout {
+        return print_to_stdout(args, &agents, &rules, custom_definition.as_ref());
+    }
+
+    // In a dry run or check, plan the outputs and report the differences instead
+    if mode != RunMode::Write {
+        let (changes, outcome) = plan_all_changes(
+            args,
+            &agents,
+            &rules,
+            custom_definition.as_ref(),
+            windsurf_global_dir.as_deref(),
+            remove_outputs,
+        )?;
+        outcome.print_warnings();
+        record_generation(&mut report, args, &agents, &rules, &outcome, &changes);
+        write_requested_report(args, &report)?;
+        report_failed_agents(&outcome.failed_agents)?;
+        if changes.is_empty() {
+            if verbose {
+                println!(
+                    "{}",
+                    color::success("All generated outputs are up to date.")
+                );
+            }
+            return Ok(());
+        }
+        if mode == RunMode::Check {
+            if verbose {
+                let summary = format!(
+                    "{} generated file(s) are missing or out of date:",
+                    changes.len()
+                );
+                println!("{}", color::problem(summary));
+                print_planned_changes(&changes);
+            }
+            bail!(
+                "Generated outputs are out of date; run urules without `check` to regenerate them"
+            );
+        }
+        if verbose {
+            let summary = format!("Dry run: {} file(s) would change:", changes.len());
+            println!("{}", color::problem(summary));
+            print_planned_changes(&changes);
+        }
+        return Ok(());
+    }
+
+    // Plan the outputs once, to know which files change before writing them, so that
+    // hand edits are not overwritten and the manifest and report list the written files
+    let mut groups = Vec::new();
+    let mut modified_files = Vec::new();
+    let mut outcome = GenerationOutcome::default();
+    for (group_args, group_agents) in output_groups(args, &agents) {
+        let manifest = Manifest::load(&group_args.output_dir)?;
+        let planned = if args.changed_only {
+            plan_changed_outputs(
+                &group_args,
+                &group_agents,
+                &rules,
+                custom_definition.as_ref(),
+                windsurf_global_dir.as_deref(),
+                &manifest,
+            )
+            .map(|changed| (changed.plan, changed.outcome, Some(changed.agents)))
+        } else {
+            plan_outputs(
+                &group_args,
+                &group_agents,
+                &rules,
+                custom_definition.as_ref(),
+                windsurf_global_dir.as_deref(),
+            )
+            .map(|(plan, group_outcome)| (plan, group_outcome, None))
+        };
+        let (mut plan, group_outcome, changed_agents) = match planned {
+            Ok(planned) => planned,
+            // Like `plan_outputs`, only a single agent's error stops the run
+            Err(e) if agents.len() > 1 => {
+                outcome.fail(&group_agents, &e);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if remove_outputs {
+            plan_removed_rule_outputs(&mut plan, &group_args, &group_outcome)?;
+        }
+        group_outcome.print_warnings();
+        let mut planned_changes = plan.planned_changes()?;
+        for path in manifest.modified_files(&group_args.output_dir, &planned_changes)? {
+            let deleted = planned_changes
+                .iter()
+                .any(|change| change.path == path && change.kind == ChangeKind::Delete);
+            if !deleted || args.force {
+                modified_files.push(path);
+                continue;
+            }
+            // Hand-edited outputs that are no longer generated are left to the user
+            eprintln!(
+                "{} Keeping stale output {:?}, which was edited since it was generated; pass --force to remove it",
+                color::warning_label(),
+                path
+            );
+            plan.deletions.remove(&path);
+            planned_changes.retain(|change| change.path != path);
+        }
+        groups.push(PlannedGroup {
+            args: group_args,
+            agents: group_agents,
+            plan,
+            outcome: group_outcome,
+            changed_agents,
+            planned_changes,
+            manifest,
+        });
+    }
+    if !modified_files.is_empty() && !args.force {
+        for path in &modified_files {
+            eprintln!(
+                "{} {:?} was modified since it was last generated; not overwriting it",
+                color::warning_label(),
+                path
+            );
+        }
+        bail!(
+            "Refusing to overwrite {} hand-edited file(s); pass --force to overwrite them",
+            modified_files.len()
+        );
+    }
+
+    let mut all_changes = Vec::new();
+    let mut changed_agent_count = 0;
+    for PlannedGroup {
+        args: group_args,
+        agents: group_agents,
+        plan,
+        outcome: group_outcome,
+        changed_agents,
+        planned_changes,
+        mut manifest,
+    } in groups
+    {
+        let output_dir = &group_args.output_dir;
+        // Ensure the output directory exists, create it if it doesn't
+        if !output_dir.exists() {
+            std::fs::create_dir_all(output_dir).map_err(|e| {
+                // Provide context for errors during output directory creation
+                eprintln!("Error creating output directory {:?}: {}", output_dir, e);
+                e
+            })?;
+        }
+
+        if let Some(mode) = args.backup {
+            for (path, backup) in back_up_files(&planned_changes, mode)? {
+                if verbose {
+                    println!("Backed up {:?} to {:?}", path, backup);
+                }
+            }
+        }
+
+        // The planned files are exactly what converting the rules writes
+        match plan.write() {
+            Ok(()) => {}
+            Err(e) if agents.len() > 1 => {
+                outcome.fail(&group_agents, &e);
+                continue;
+            }
+            Err(e) => return Err(e),
+        }
+        if verbose {
+            for change in planned_changes
+                .iter()
+                .filter(|change| change.kind == ChangeKind::Delete)
+            {
+                println!("Removed stale output {:?}", change.path);
+            }
+        }
+        match changed_agents {
+            Some(changed_agents) => {
+                for (agent, changed_rules) in &changed_agents {
+                    if verbose {
+                        println!(
+                            "Regenerated {} changed rule(s) for {} in {:?}",
+                            changed_rules, agent, output_dir
+                        );
+                    }
+                    manifest.record_rules(agent, &rules);
+                }
+                changed_agent_count += changed_agents.len();
+            }
+            None => {
+                if verbose {
+                    group_outcome.print_generated_agents();
+                }
+                for agent in group_agents
+                    .iter()
+                    .filter(|agent| !group_outcome.failed_agents.contains(agent))
+                {
+                    manifest.record_rules(agent, &rules);
+                }
+            }
+        }
+        for (agent, files) in &group_outcome.agent_files {
+            let outputs = output_rules(
+                &group_args,
+                agent,
+                files,
+                &rules,
+                custom_definition.as_ref(),
+                windsurf_global_dir.as_deref(),
+            );
+            manifest.record_outputs(output_dir, agent, &outputs);
+        }
+        outcome.merge(group_outcome);
+        manifest.record(output_dir, &planned_changes)?;
+        manifest.save(output_dir)?;
+        all_changes.extend(planned_changes);
+    }
+    if verbose && args.changed_only && changed_agent_count == 0 {
+        println!("{}", color::success("No rules changed since the last run."));
+    } else if verbose && agents.len() > 1 {
+        println!(
+            "Generated rules for {} of {} agents.",
+            agents.len() - outcome.failed_agents.len(),
+            agents.len()
+        );
+    }
+    record_generation(&mut report, args, &agents, &rules, &outcome, &all_changes);
+    write_requested_report(args, &report)?;
+    report_failed_agents(&outcome.failed_agents)
+}
+
+/// Loads the `--custom-converter` definition if the `custom` agent is selected.
+pub(crate) fn load_custom_definition(
+    args: &GenerateArgs,
+    agents: &[AgentName],
+) -> Result<Option<CustomConverterDefinition>> {
+    match (agents.contains(&AgentName::Custom), &args.custom_converter) {
+        (true, Some(path)) => Ok(Some(CustomConverterDefinition::from_file(path)?)),
+        (true, None) => bail!(UsageError(
+            "--agent custom requires --custom-converter <FILE>".to_string()
+        )),
+        (false, _) => Ok(None),
+    }
+}
+
+/// Returns the directory Windsurf global rules are written to for `--windsurf-global-dir`,
+/// or `None` if they stay in the output directory.
+pub(crate) fn resolve_windsurf_global_dir(args: &GenerateArgs) -> Result<Option<PathBuf>> {
+    Ok(match &args.windsurf_global_dir {
+        Some(Some(dir)) => Some(dir.clone()),
+        Some(None) => Some(windsurf::default_global_dir()?),
+        None => None,
+    })
+}
+
+/// Splits `agents` by output directory: `output_dir`, or the agent's own directory from
+/// `output_dirs` in the project configuration. Returns the options for each directory
+/// (`args` with its `output_dir`) with its agents, in the order of `agents`.
+pub(crate) fn output_groups(
+    args: &GenerateArgs,
+    agents: &[AgentName],
+) -> Vec<(GenerateArgs, Vec<AgentName>)> {
+    let mut groups: Vec<(GenerateArgs, Vec<AgentName>)> = Vec::new();
+    for agent in agents {
+        let output_dir = args
+            .agent_output_dirs
+            .get(agent)
+            .unwrap_or(&args.output_dir);
+        match groups
+            .iter_mut()
+            .find(|(group_args, _)| group_args.output_dir == *output_dir)
+        {
+            Some((_, group_agents)) => group_agents.push(agent.clone()),
+            None => {
+                let mut group_args = args.clone();
+                group_args.output_dir = output_dir.clone();
+                groups.push((group_args, vec![agent.clone()]));
+            }
+        }
+    }
+    groups
+}
+
+/// Like `plan_outputs`, for agents that may write to different output directories:
+/// plans each directory of `output_groups` and returns the changes a real run would make
+/// to all of them. With `remove_outputs`, the outputs of deleted rules are planned for
+/// removal (see `plan_removed_rule_outputs`).
+fn plan_all_changes(
+    args: &GenerateArgs,
+    agents: &[AgentName],
+    rules: &[UniversalRule],
+    custom_definition: Option<&CustomConverterDefinition>,
+    windsurf_global_dir: Option<&Path>,
+    remove_outputs: bool,
+) -> Result<(Vec<PlannedChange>, GenerationOutcome)> {
+    let mut changes = Vec::new();
+    let mut outcome = GenerationOutcome::default();
+    for (group_args, group_agents) in output_groups(args, agents) {
+        match plan_outputs(
+            &group_args,
+            &group_agents,
+            rules,
+            custom_definition,
+            windsurf_global_dir,
+        )
+        .and_then(|(mut plan, group_outcome)| {
+            if remove_outputs {
+                plan_removed_rule_outputs(&mut plan, &group_args, &group_outcome)?;
+            }
+            Ok((plan.planned_changes()?, group_outcome))
+        }) {
+            Ok((group_changes, group_outcome)) => {
+                changes.extend(group_changes);
+                outcome.merge(group_outcome);
+            }
+            // Like `plan_outputs`, only a single agent's error stops the run
+            Err(e) if agents.len() > 1 => outcome.fail(&group_agents, &e),
+            Err(e) => return Err(e),
+        }
+    }
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok((changes, outcome))
+}
+
+/// The outputs planned for one output directory of `output_groups`, before writing them.
+struct PlannedGroup {
+    args: GenerateArgs,
+    agents: Vec<AgentName>,
+    plan: OutputPlan,
+    outcome: GenerationOutcome,
+
+    /// With `--changed-only`, the agents with changed rules, with the number of changed
+    /// rules.
+    changed_agents: Option<Vec<(AgentName, usize)>>,
+
+    /// The changes writing `plan` makes.
+    planned_changes: Vec<PlannedChange>,
+
+    /// The manifest of the output directory, as it was before the run.
+    manifest: Manifest,
+}
+
+/// The outputs of the rules changed since the last run (`--changed-only`), planned for
+/// one output directory.
+struct ChangedOutputs {
+    /// The agents with changed rules, with the number of changed rules.
+    agents: Vec<(AgentName, usize)>,
+
+    /// The planned files that the changed rules produce.
+    plan: OutputPlan,
+
+    /// The result of generating the agents with changed rules.
+    outcome: GenerationOutcome,
+}
+
+/// Plans the outputs of the rules that changed since the last run recorded in
+/// `manifest`, for `--changed-only`.
+///
+/// Only the files the changed rules produce are kept. Those are regenerated from every
+/// rule, so files shared by several rules (e.g. `CLAUDE.md`) stay complete, while the
+/// outputs of unchanged rules (e.g. their `.cursor/rules/*.mdc`) are left untouched.
+fn plan_changed_outputs(
+    args: &GenerateArgs,
+    agents: &[AgentName],
+    rules: &[UniversalRule],
+    custom_definition: Option<&CustomConverterDefinition>,
+    windsurf_global_dir: Option<&Path>,
+    manifest: &Manifest,
+) -> Result<ChangedOutputs> {
+    // Converting only the changed rules tells which files they produce
+    let mut produced_files = BTreeSet::new();
+    let mut changed_agents = Vec::new();
+    for agent in agents {
+        let changed_rules = manifest.changed_rules(agent, rules);
+        if changed_rules.is_empty() {
+            continue;
+        }
+        let (changed_plan, _) = plan_outputs(
+            args,
+            std::slice::from_ref(agent),
+            &changed_rules,
+            custom_definition,
+            windsurf_global_dir,
+        )?;
+        produced_files.extend(changed_plan.files.into_keys());
+        changed_agents.push((agent.clone(), changed_rules.len()));
+    }
+
+    let agents_to_generate: Vec<AgentName> = changed_agents
+        .iter()
+        .map(|(agent, _)| agent.clone())
+        .collect();
+    let (mut plan, outcome) = if agents_to_generate.is_empty() {
+        (OutputPlan::default(), GenerationOutcome::default())
+    } else {
+        plan_outputs(
+            args,
+            &agents_to_generate,
+            rules,
+            custom_definition,
+            windsurf_global_dir,
+        )?
+    };
+    plan.files.retain(|path, _| produced_files.contains(path));
+    Ok(ChangedOutputs {
+        agents: changed_agents,
+        plan,
+        outcome,
+    })
+}
+
+/// Describes why an expired rule is skipped, e.g. `expired on 2025-12-31`.
+pub(crate) fn expiry_reason(rule: &UniversalRule) -> String {
+    match rule.frontmatter.expires {
+        Some(expires) => format!("expired on {}", expires),
+        None => "expired".to_string(),
+    }
+}
+
+/// Returns `true` if the environment variable `name` is set, to any value.
+pub(crate) fn env_var_is_set(name: &str) -> bool {
+    std::env::var_os(name).is_some()
+}
+
+/// Describes why a rule gated on an environment variable is skipped, e.g. `requires the
+/// environment variable ACME_INTERNAL to be set`.
+pub(crate) fn env_gate_reason(rule: &UniversalRule) -> String {
+    format!(
+        "requires the environment variable {} to be set",
+        rule.frontmatter.requires_env.as_deref().unwrap_or_default()
+    )
+}
+
+/// Returns a message for each `required: true` rule (named in `required`) that is left out:
+/// listed in `skipped`, or among `rules` but not emitted for one of `agents`.
+pub(crate) fn required_rule_problems(
+    required: &[String],
+    skipped: &[SkippedRule],
+    rules: &[UniversalRule],
+    agents: &[AgentName],
+) -> Vec<String> {
+    let mut problems: Vec<String> = skipped
+        .iter()
+        .filter(|skipped| required.contains(&skipped.rule))
+        .map(|skipped| {
+            format!(
+                "Required rule {:?} is left out: {}",
+                skipped.rule, skipped.reason
+            )
+        })
+        .collect();
+    for rule in rules.iter().filter(|rule| rule.frontmatter.required) {
+        for agent in agents {
+            // Ignore-only rules still reach the agent through its ignore file
+            if let Some(reason) =
+                skipped_rule_reason(agent, rule).filter(|_| !rule.is_ignore_only())
+            {
+                problems.push(format!(
+                    "Required rule {:?} is left out for {}: {}",
+                    rule.name, agent, reason
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// Adds the per-agent results, skipped rules, written files and `.gitignore` changes of a
+/// run to `report`.
+fn record_generation(
+    report: &mut RunReport,
+    args: &GenerateArgs,
+    agents: &[AgentName],
+    rules: &[UniversalRule],
+    outcome: &GenerationOutcome,
+    changes: &[PlannedChange],
+) {
+    for agent in agents {
+        let mut emitted_rules = 0;
+        for rule in rules {
+            match skipped_rule_reason(agent, rule) {
+                Some(reason) => report.rules_skipped.push(SkippedRule {
+                    rule: rule.name.clone(),
+                    agent: Some(agent.clone()),
+                    reason: reason.to_string(),
+                }),
+                None => emitted_rules += 1,
+            }
+        }
+        report.agents.push(AgentReport {
+            agent: agent.clone(),
+            success: !outcome.failed_agents.contains(agent),
+            rules: emitted_rules,
+        });
+    }
+    let gitignore_patterns = if args.no_gitignore {
+        Vec::new()
+    } else {
+        outcome.gitignore_patterns.clone()
+    };
+    report.record_changes(changes, gitignore_patterns);
+}
+
+/// Writes the run report if `--report` was given.
+fn write_requested_report(args: &GenerateArgs, report: &RunReport) -> Result<()> {
+    match &args.report {
+        Some(format) => write_report(report, format, args.report_file.as_deref()),
+        None => Ok(()),
+    }
+}
+
+/// Returns the file generated for `agent` if it writes its rules to a single file in
+/// the output layout of `args`, or `None` if its output spans several files.
+fn single_output_file(args: &GenerateArgs, agent: &AgentName) -> Option<&'static str> {
+    registry::single_output_file(agent, &converter_options(args, None, None))
+}
+
+/// Generates the output of a single-file agent in memory and prints it to standard
+/// output, leaving the output directory untouched.
+///
+/// Files the agent generates besides its main file (e.g. Claude slash commands) are
+/// discarded with a warning on standard error.
+fn print_to_stdout(
+    args: &GenerateArgs,
+    agents: &[AgentName],
+    rules: &[UniversalRule],
+    custom_definition: Option<&CustomConverterDefinition>,
+) -> Result<()> {
+    let [agent] = agents else {
+        bail!(UsageError(format!(
+            "--stdout supports a single agent, but {} were selected",
+            agents.len()
+        )));
+    };
+    let Some(file_name) = single_output_file(args, agent) else {
+        bail!(UsageError(format!(
+            "--stdout is only supported for agents that generate a single file; {} generates several",
+            agent
+        )));
+    };
+
+    let outputs = generate_agent(
+        args,
+        agent,
+        rules,
+        custom_definition,
+        &args.output_dir,
+        None,
+        &OutputPlan::default(),
+    )?;
+    for warning in &outputs.warnings {
+        eprintln!("{} {}", color::warning_label(), warning);
+    }
+
+    let output_path = args.output_dir.join(file_name);
+    match outputs.files.iter().find(|(path, _)| *path == output_path) {
+        Some((_, content)) => {
+            let content = String::from_utf8_lossy(content);
+            print!("{}", content);
+            if !content.ends_with('\n') {
+                println!();
+            }
+        }
+        None => eprintln!("No {} was generated for {}.", file_name, agent),
+    }
+
+    let discarded = outputs
+        .files
+        .iter()
+        .filter(|(path, _)| *path != output_path)
+        .count();
+    if discarded > 0 {
+        eprintln!(
+            "{} --stdout only prints {}; {} other generated file(s) were discarded.",
+            color::warning_label(),
+            file_name,
+            discarded
+        );
+    }
+    Ok(())
+}
+
+/// The result of `plan_outputs`.
+#[derive(Default)]
+pub(crate) struct GenerationOutcome {
+    /// The agents whose generation failed.
+    pub(crate) failed_agents: Vec<AgentName>,
+
+    /// The agents that were generated, with a description of where their rules go.
+    pub(crate) generated_agents: Vec<(AgentName, String)>,
+
+    /// The `.gitignore` patterns of the agents that were generated.
+    pub(crate) gitignore_patterns: Vec<String>,
+
+    /// The warnings about the generated outputs, printed once planning is done.
+    pub(crate) warnings: Vec<String>,
+
+    /// The files planned for each agent that was generated, recorded in the manifest.
+    pub(crate) agent_files: Vec<(AgentName, Vec<PathBuf>)>,
+}
+
+impl GenerationOutcome {
+    /// Records that generating `agents` failed with `error`.
+    fn fail(&mut self, agents: &[AgentName], error: &anyhow::Error) {
+        for agent in agents {
+            eprintln!("Error generating rules for {}: {:#}", agent, error);
+            self.failed_agents.push(agent.clone());
+        }
+    }
+
+    /// Adds the results of generating other agents.
+    fn merge(&mut self, other: GenerationOutcome) {
+        self.failed_agents.extend(other.failed_agents);
+        self.generated_agents.extend(other.generated_agents);
+        self.warnings.extend(other.warnings);
+        self.agent_files.extend(other.agent_files);
+        for pattern in other.gitignore_patterns {
+            if !self.gitignore_patterns.contains(&pattern) {
+                self.gitignore_patterns.push(pattern);
+            }
+        }
+    }
+
+    /// Prints the warnings about the generated outputs on standard error.
+    fn print_warnings(&self) {
+        for warning in &self.warnings {
+            eprintln!("{} {}", color::warning_label(), warning);
+        }
+    }
+
+    /// Prints where the rules of each generated agent went.
+    fn print_generated_agents(&self) {
+        for (agent, output_description) in &self.generated_agents {
+            println!(
+                "Rules generated successfully for {} in {}",
+                agent, // Uses the Display impl of AgentName
+                output_description
+            );
+        }
+    }
+}
+
+/// Returns an error naming the agents whose generation failed, if any.
+fn report_failed_agents(failed_agents: &[AgentName]) -> Result<()> {
+    if !failed_agents.is_empty() {
+        let failed_names: Vec<String> = failed_agents.iter().map(|a| a.to_string()).collect();
+        bail!("Rule generation failed for: {}", failed_names.join(", "));
+    }
+    Ok(())
+}
+
+/// The files of one agent, generated in memory by `generate_agent`.
+pub(crate) struct AgentOutputs {
+    /// Every file of the agent with the content it would be written with, copied
+    /// assets included.
+    pub(crate) files: Vec<(PathBuf, Vec<u8>)>,
+
+    /// The `.gitignore` pattern of the directory assets are copied to, if any are.
+    pub(crate) assets_pattern: Option<String>,
+
+    /// A description of where the agent's rules go, for the success message.
+    pub(crate) description: String,
+
+    /// Warnings about the outputs, e.g. rule settings the agent can't express.
+    pub(crate) warnings: Vec<String>,
+}
+
+/// Generates the files of `agent` from the `rules` targeting it, in memory.
+///
+/// The files get the content they would be written with: merged with their content so
+/// far in `plan` or on disk (see `RuleConverter::merge_with_existing`), then minified,
+/// given a banner and converted to the requested line endings. Linked assets are read
+/// from the rules directory.
+pub(crate) fn generate_agent(
+    args: &GenerateArgs,
+    agent: &AgentName,
+    rules: &[UniversalRule],
+    custom_definition: Option<&CustomConverterDefinition>,
+    output_dir: &Path,
+    windsurf_global_dir: Option<&Path>,
+    plan: &OutputPlan,
+) -> Result<AgentOutputs> {
+    // Keep only the rules that target this agent (exports keep every rule)
+    let agent_rules: Vec<&UniversalRule> = rules
+        .iter()
+        .filter(|rule| agent.is_export() || rule.applies_to(agent))
+        .collect();
+    let banner = !args.no_banner && !agent.is_export();
+    let options = ConverterOptions {
+        // Files are kept within the agents' limits with the banner added afterwards
+        banner_chars: match banner {
+            true => banner_chars(&args.banner, &agent_rules, &args.rules_dir),
+            false => 0,
+        },
+        ..converter_options(args, custom_definition, windsurf_global_dir)
+    };
+    let converter = registry::build_converter(agent, &options)?;
+    let mut warnings = Vec::new();
+
+    // Exports show the rules as written, agent blocks included
+    let converted_rules: Vec<UniversalRule> = if agent.is_export() {
+        agent_rules.iter().map(|rule| (*rule).clone()).collect()
+    } else {
+        agent_rules
+            .iter()
+            .map(|rule| (*rule).clone().for_agent(agent))
+            .map(|rule| match args.normalize_markdown {
+                true => rule.map(UniversalRule::with_normalized_markdown),
+                false => rule,
+            })
+            .collect::<Result<_>>()?
+    };
+    let assets_dir = converter.assets_dir();
+    let (converted_rules, assets) = match &assets_dir {
+        Some(assets_dir) => link_assets(converted_rules, &args.rules_dir, output_dir, assets_dir)?,
+        None => (converted_rules, BTreeMap::new()),
+    };
+    let generated = if agent.is_export() {
+        converter.generate_rules(&converted_rules, output_dir)?
+    } else {
+        // Ignore-only rules feed the ignore files and produce no rule output
+        let emitted_rules: Vec<UniversalRule> = converted_rules
+            .into_iter()
+            .filter(|rule| !rule.is_ignore_only())
+            .collect();
+        for rule in &emitted_rules {
+            let exclude_globs = rule.frontmatter.exclude_globs();
+            if !exclude_globs.is_empty() && !converter.supports_negated_globs(rule) {
+                warnings.push(format!(
+                    "Rule {:?} has negated globs (!{}), which {} cannot express; they are left out",
+                    rule.relative_path(),
+                    exclude_globs.join(", !"),
+                    agent
+                ));
+            }
+        }
+        converter.generate_rules(&emitted_rules, output_dir)?
+    };
+    warnings.extend(generated.warnings.iter().cloned());
+    check_output_collisions(agent, &generated.files, &agent_rules)?;
+
+    let mut files = Vec::new();
+    for file in &generated.files {
+        let existing = plan.current_text(&file.path)?;
+        let content = converter.merge_with_existing(file, existing.as_deref())?;
+        let content = match args.minify && is_markdown_file(&file.path) {
+            true => minify_markdown(&content),
+            false => content,
+        };
+        let content = match banner {
+            true => {
+                let source = banner_source(&file.path, &agent_rules, &args.rules_dir);
+                add_banner(
+                    &file.path,
+                    &content,
+                    &args.banner.replace("{source}", &source),
+                )
+            }
+            false => content,
+        };
+        let content = match args.line_endings.is_crlf() {
+            true => args.line_endings.apply(&content),
+            false => content,
+        };
+        files.push((file.path.clone(), content.into_bytes()));
+    }
+
+    // Copied assets are generated too, and ignored with the other outputs
+    let assets_pattern = assets_dir
+        .filter(|_| !assets.is_empty())
+        .map(|assets_dir| format!("{}/", assets_dir.to_string_lossy().replace('\\', "/")));
+    for (copy, source) in assets {
+        let content =
+            std::fs::read(&source).with_context(|| format!("Failed to read asset {:?}", source))?;
+        files.push((copy, content));
+    }
+    Ok(AgentOutputs {
+        files,
+        assets_pattern,
+        description: converter.get_output_description(output_dir),
+        warnings,
+    })
+}
+
+/// Fails if two of the `files` generated for `agent` have the same path but different
+/// content, since one would silently overwrite the other. This happens when rules with
+/// the same name in different directories, `output_name`s or `aliases` map to the same
+/// file, e.g. in Cursor's flat layout. The error names the `rules` whose file names match.
+fn check_output_collisions(
+    agent: &AgentName,
+    files: &[GeneratedFile],
+    rules: &[&UniversalRule],
+) -> Result<()> {
+    let mut contents: BTreeMap<&Path, &str> = BTreeMap::new();
+    for file in files {
+        match contents.insert(&file.path, &file.contents) {
+            Some(previous) if previous != file.contents => {}
+            _ => continue,
+        }
+        let stem = file.path.file_stem().unwrap_or_default().to_string_lossy();
+        let paths: Vec<PathBuf> = rules
+            .iter()
+            .filter(|rule| rule.output_stems().contains(&stem.as_ref()))
+            .map(|rule| rule.relative_path())
+            .collect();
+        bail!(
+            "Rules {:?} generate the same {} file {:?}; give them distinct `output_name`s and `aliases`",
+            paths,
+            agent,
+            file.path
+        );
+    }
+    Ok(())
+}
+
+/// Plans the outputs of `agents` in the output directory of `args`, without touching the
+/// project: the files of every selected converter run against the same parsed rules
+/// (see `generate_agent`), the agents' AI ignore files updated from the rules'
+/// `ignore_patterns`, and the `.gitignore` updated with the patterns of every agent that
+/// succeeded.
+///
+/// A failing agent is reported without stopping the others, so one bad output doesn't
+/// block the rest; the failed agents are part of the returned outcome. With a single
+/// agent, its error is returned directly.
+pub(crate) fn plan_outputs(
+    args: &GenerateArgs,
+    agents: &[AgentName],
+    rules: &[UniversalRule],
+    custom_definition: Option<&CustomConverterDefinition>,
+    windsurf_global_dir: Option<&Path>,
+) -> Result<(OutputPlan, GenerationOutcome)> {
+    let output_dir = &args.output_dir;
+    let mut plan = OutputPlan::default();
+    let mut outcome = GenerationOutcome::default();
+    for agent in agents {
+        let result = generate_agent(
+            args,
+            agent,
+            rules,
+            custom_definition,
+            output_dir,
+            windsurf_global_dir,
+            &plan,
+        )
+        .and_then(|outputs| {
+            if let Some((path, _)) = outputs.files.iter().find(|(path, _)| path.is_dir()) {
+                bail!(
+                    "Cannot write {} rules to {:?}: a directory with that name already exists. \
+                     Use --output-dir to generate them elsewhere.",
+                    agent,
+                    path
+                );
+            }
+            let agent_rules: Vec<UniversalRule> = rules
+                .iter()
+                .filter(|rule| rule.applies_to(agent))
+                .cloned()
+                .collect();
+            plan_ignore_files(&mut plan, output_dir, agent, &agent_rules)?;
+            Ok(outputs)
+        });
+
+        match result {
+            Ok(outputs) => {
+                let agent_files = outputs.files.iter().map(|(path, _)| path.clone()).collect();
+                outcome.agent_files.push((agent.clone(), agent_files));
+                plan.files.extend(outputs.files);
+                let options = converter_options(args, custom_definition, windsurf_global_dir);
+                let mut patterns = registry::gitignore_patterns(agent, &options);
+                if let Some(assets_pattern) = outputs.assets_pattern {
+                    if !patterns
+                        .iter()
+                        .any(|p| p.ends_with('/') && assets_pattern.starts_with(p.as_str()))
+                    {
+                        patterns.push(assets_pattern);
+                    }
+                }
+                for pattern in patterns {
+                    if !outcome.gitignore_patterns.contains(&pattern) {
+                        outcome.gitignore_patterns.push(pattern);
+                    }
+                }
+                outcome
+                    .generated_agents
+                    .push((agent.clone(), outputs.description));
+                outcome.warnings.extend(outputs.warnings);
+            }
+            Err(e) if agents.len() == 1 => return Err(e),
+            Err(e) => outcome.fail(std::slice::from_ref(agent), &e),
+        }
+    }
+
+    // The manifest of a real run sits next to the generated files
+    if !outcome.gitignore_patterns.is_empty() {
+        outcome.gitignore_patterns.push(MANIFEST_FILE.to_string());
+    }
+
+    // Update .gitignore if not disabled by the user
+    if !args.no_gitignore && !outcome.gitignore_patterns.is_empty() {
+        let gitignore_path = output_dir.join(".gitignore");
+        match plan.current_text(&gitignore_path) {
+            Ok(content) => {
+                if let Some(new_content) =
+                    with_managed_section(content.as_deref(), &outcome.gitignore_patterns)
+                {
+                    plan.insert(gitignore_path, new_content);
+                }
+            }
+            // Log the error but don't cause the program to fail, as .gitignore update is auxiliary
+            Err(e) => eprintln!(
+                "{} Failed to update .gitignore in {:?}: {}",
+                color::warning_label(),
+                output_dir,
+                e
+            ),
+        }
+    }
+
+    Ok((plan, outcome))
+}
+
+/// Plans the removal of the outputs an earlier run generated in the output directory of
+/// `args` for the agents of `outcome`, from rules that no longer exist in the rules
+/// directory (see `Manifest::removed_rule_outputs`).
+///
+/// Only call this when every rule file was parsed and no rule was left out by
+/// `--only`, `--exclude` or the interactive picker, since the outputs of rules that are
+/// merely left out must stay.
+pub(crate) fn plan_removed_rule_outputs(
+    plan: &mut OutputPlan,
+    args: &GenerateArgs,
+    outcome: &GenerationOutcome,
+) -> Result<()> {
+    let manifest = Manifest::load(&args.output_dir)?;
+    for (agent, _) in &outcome.agent_files {
+        for path in manifest.removed_rule_outputs(&args.output_dir, &args.rules_dir, agent) {
+            if !plan.files.contains_key(&path) {
+                plan.delete(path);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns each of the `files` generated for `agent` with the `rules` producing it,
+/// found by generating each rule on its own. Files that no single rule produces have no
+/// rules.
+fn output_rules<'a>(
+    args: &GenerateArgs,
+    agent: &AgentName,
+    files: &[PathBuf],
+    rules: &'a [UniversalRule],
+    custom_definition: Option<&CustomConverterDefinition>,
+    windsurf_global_dir: Option<&Path>,
+) -> Vec<(PathBuf, Vec<&'a UniversalRule>)> {
+    let mut output_rules: BTreeMap<&PathBuf, Vec<&UniversalRule>> =
+        files.iter().map(|path| (path, Vec::new())).collect();
+    for rule in rules.iter().filter(|rule| rule.applies_to(agent)) {
+        // A rule that fails on its own produces no file that could be removed with it
+        let Ok(outputs) = generate_agent(
+            args,
+            agent,
+            std::slice::from_ref(rule),
+            custom_definition,
+            &args.output_dir,
+            windsurf_global_dir,
+            &OutputPlan::default(),
+        ) else {
+            continue;
+        };
+        for (path, _) in outputs.files {
+            if let Some(producing_rules) = output_rules.get_mut(&path) {
+                producing_rules.push(rule);
+            }
+        }
+    }
+    output_rules
+        .into_iter()
+        .map(|(path, producing_rules)| (path.clone(), producing_rules))
+        .collect()
+}
+
+// Optional: Add some basic integration tests for the CLI itself
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converters::registry::coding_agents;
+    use crate::rule_parser::discover_and_parse_rules;
+    use crate::universal_rule::UniversalRuleFrontmatter;
+    use std::fs;
+    use tempfile::tempdir; // For creating test rule files
+
+    /// Helper function to set up a temporary rules directory with specified rule files for testing.
+    ///
+    /// # Arguments
+    /// * `rules_dir` - The `PathBuf` for the directory where rule files will be created.
+    /// * `rules_data` - A slice of tuples, where each tuple contains:
+    ///     - `name`: The base name of the rule file (without `.md`).
+    ///     - `content`: The Markdown content of the rule.
+    ///     - `globs_opt`: An optional vector of glob patterns for the frontmatter.
+    ///       Frontmatter is added if this is `Some` or if the name contains "cursor_always".
+    fn setup_rules_dir(
+        rules_dir: &PathBuf,
+        rules_data: &[(&str, &str, Option<Vec<&str>>)],
+    ) -> Result<()> {
+        fs::create_dir_all(rules_dir)?;
+        for (name, content, globs_opt) in rules_data {
+            let mut file_content = String::new();
+            // Add YAML frontmatter if globs are specified or for specific cursor rule types
+            if globs_opt.is_some() || name.contains("cursor_always") {
+                // Add frontmatter for specific cases
+                file_content.push_str("---\n");
+                if let Some(globs) = globs_opt {
+                    file_content.push_str("globs: [");
+                    file_content.push_str(
+                        &globs
+                            .iter()
+                            .map(|g| format!("\"{}\"", g))
+                            .collect::<Vec<String>>()
+                            .join(", "),
+                    );
+                    file_content.push_str("]\n");
+                }
+                if name.contains("cursor_always") {
+                    file_content.push_str("cursorRuleType: Always\n");
+                }
+                file_content.push_str("---\n");
+            }
+            file_content.push_str(content);
+            fs::write(rules_dir.join(format!("{}.md", name)), file_content)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the options of a default run generating `agent` rules from `rules_dir`
+    /// into `output_dir`.
+    fn generate_args(agent: &str, rules_dir: PathBuf, output_dir: PathBuf) -> GenerateArgs {
+        GenerateArgs {
+            rules_dir,
+            output_dir,
+            ..Cli::parse_from(["urules", "--agent", agent]).generate
+        }
+    }
+
+    #[test]
+    fn test_cli_cursor_output() -> Result<()> {
+        let dir = tempdir()?;
+        let rules_path = dir.path().join("test_rules");
+        let output_path = dir.path().join("test_output");
+        fs::create_dir_all(&output_path)?;
+
+        setup_rules_dir(
+            &rules_path,
+            &[
+                ("cursor_rule1", "Cursor content 1", Some(vec!["*.rs"])),
+                ("cursor_always", "Always content", None),
+            ],
+        )?;
+
+        let cli = generate_args("cursor", rules_path, output_path.clone());
+
+        // Simulate running main's logic for Cursor
+        let rules = discover_and_parse_rules(&cli.rules_dir, &cli.discovery)?;
+        plan_outputs(&cli, &[AgentName::Cursor], &rules, None, None)?
+            .0
+            .write()?;
+
+        let cursor_output_dir = output_path.join(".cursor").join("rules");
+        assert!(cursor_output_dir.join("cursor_rule1.mdc").exists());
+        let content = fs::read_to_string(cursor_output_dir.join("cursor_rule1.mdc"))?;
+        assert!(content.contains("globs:"));
+        assert!(content.contains("- '*.rs'"));
+        assert!(content.contains("Cursor content 1"));
+
+        assert!(cursor_output_dir.join("cursor_always.mdc").exists());
+        let always_content = fs::read_to_string(cursor_output_dir.join("cursor_always.mdc"))?;
+        assert!(always_content.contains("alwaysApply: true"));
+        assert!(always_content.contains("Always content"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cli_windsurf_output() -> Result<()> {
+        let dir = tempdir()?;
+        let rules_path = dir.path().join("test_rules_ws");
+        let output_path = dir.path().join("test_output_ws");
+        fs::create_dir_all(&output_path)?;
+
+        // Global rule needs 'apply_globally: true' in its frontmatter to be treated as global
+        // For simplicity, discover_and_parse_rules will need to handle this.
+        // The test setup_rules_dir needs to be smarter or we simplify here.
+        // Let's assume all rules are workspace for this CLI test to avoid complex setup.
+        let _rule_fm_global = UniversalRuleFrontmatter {
+            apply_globally: true,
+            description: Some("Global rule".to_string()),
+            ..Default::default()
+        };
+
+        let global_rule_file_content =
+            "---\ndescription: Global rule\napplyGlobally: true\n---\nGlobal content";
+        fs::create_dir_all(&rules_path)?;
+        fs::write(rules_path.join("global_rule.md"), global_rule_file_content)?;
+        setup_rules_dir(
+            &rules_path,
+            &[("ws_rule1", "WS content 1", Some(vec!["*.txt"]))],
+        )?;
+
+        let cli = generate_args("windsurf", rules_path, output_path.clone());
+
+        let rules = discover_and_parse_rules(&cli.rules_dir, &cli.discovery)?;
+        plan_outputs(&cli, &[AgentName::Windsurf], &rules, None, None)?
+            .0
+            .write()?;
+
+        assert!(output_path.join("global_rules.md").exists());
+        let global_content = fs::read_to_string(output_path.join("global_rules.md"))?;
+        assert!(global_content.contains("# Description: Global rule"));
+        assert!(global_content.contains("Global content"));
+
+        assert!(output_path
+            .join(".windsurf")
+            .join("rules")
+            .join("ws_rule1.md")
+            .exists());
+        let ws_content = fs::read_to_string(
+            output_path
+                .join(".windsurf")
+                .join("rules")
+                .join("ws_rule1.md"),
+        )?;
+        assert!(ws_content.contains("# Globs: [\"*.txt\"]"));
+        assert!(ws_content.contains("WS content 1"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cli_claude_output() -> Result<()> {
+        let dir = tempdir()?;
+        let rules_path = dir.path().join("test_rules_claude");
+        let output_path = dir.path().join("test_output_claude");
+        fs::create_dir_all(&output_path)?;
+
+        setup_rules_dir(
+            &rules_path,
+            &[
+                ("claude_rule1", "Claude content 1", None),
+                ("claude_rule2", "Claude content 2", None),
+            ],
+        )?;
+
+        let cli = generate_args("claude", rules_path, output_path.clone());
+
+        let rules = discover_and_parse_rules(&cli.rules_dir, &cli.discovery)?;
+        plan_outputs(&cli, &[AgentName::Claude], &rules, None, None)?
+            .0
+            .write()?;
+
+        let claude_file = output_path.join("CLAUDE.md");
+        assert!(claude_file.exists());
+        let content = fs::read_to_string(claude_file)?;
+        assert!(content.contains("## Rule: Claude rule1"));
+        assert!(content.contains("Claude content 1"));
+        assert!(content.contains("## Rule: Claude rule2"));
+        assert!(content.contains("Claude content 2"));
+        assert!(content.contains("\n\n---\n\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rules_dir_not_exists() {
+        let dir = tempdir().unwrap(); // Create a temp dir that exists
+        let non_existent_rules_path = dir.path().join("non_existent_rules");
+        // Do not create non_existent_rules_path
+
+        // We need to run the binary or simulate its main execution flow
+        // For now, let's just check the condition as it is in main()
+        // A more robust test would use assert_cmd or similar.
+        assert!(!non_existent_rules_path.exists());
+        // run_generation would fail with a usage error (exit code 2);
+        // the integration tests check that through the binary.
+    }
+
+    /// Test that `all` expands to every built-in agent, without duplicates.
+    #[test]
+    fn test_resolve_agents_expands_all() {
+        let cli = Cli::parse_from(["urules", "--agent", "claude", "--agent", "all"]);
+        let agents = resolve_agents(&cli.generate);
+        assert_eq!(agents.len(), coding_agents().len());
+        assert_eq!(agents[0], AgentName::Claude);
+        assert!(!agents.contains(&AgentName::Custom));
+
+        let cli = Cli::parse_from([
+            "urules",
+            "--agent",
+            "all",
+            "--custom-converter",
+            "agent.yaml",
+        ]);
+        assert_eq!(
+            resolve_agents(&cli.generate).last(),
+            Some(&AgentName::Custom)
+        );
+    }
+
+    /// Test that required rules are reported when skipped for all agents or for one.
+    #[test]
+    fn test_required_rule_problems() {
+        let required_rule = |name: &str, agents: Option<Vec<AgentName>>| UniversalRule {
+            name: name.to_string(),
+            frontmatter: UniversalRuleFrontmatter {
+                required: true,
+                agents,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let rules = vec![
+            required_rule("security", None),
+            required_rule("licensing", Some(vec![AgentName::Cursor])),
+        ];
+        let skipped = [SkippedRule {
+            rule: "compliance".to_string(),
+            agent: None,
+            reason: "filtered out by --only/--exclude".to_string(),
+        }];
+        let required = ["compliance".to_string(), "security".to_string()];
+
+        assert_eq!(
+            required_rule_problems(
+                &required,
+                &skipped,
+                &rules,
+                &[AgentName::Cursor, AgentName::Claude]
+            ),
+            [
+                "Required rule \"compliance\" is left out: filtered out by --only/--exclude",
+                "Required rule \"licensing\" is left out for Claude: not targeted at this agent by its `agents` list",
+            ]
+        );
+    }
+}
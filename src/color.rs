@@ -0,0 +1,98 @@
+// src/color.rs
+
+use clap::ValueEnum;
+use console::{style, Style, StyledObject, Term};
+
+/// When urules colors its output (`--color`).
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Default)]
+pub enum ColorChoice {
+    /// Color the output written to a terminal, unless `NO_COLOR` is set.
+    #[default]
+    Auto,
+    /// Always color the output, even when it is piped.
+    Always,
+    /// Never color the output.
+    Never,
+}
+
+/// Enables or disables colors on standard output and standard error for `choice`.
+///
+/// With `auto`, each stream is colored if it is a terminal and the `NO_COLOR`
+/// environment variable is unset or empty (see <https://no-color.org>).
+pub fn init(choice: ColorChoice) {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    let (stdout, stderr) = match choice {
+        ColorChoice::Always => (true, true),
+        ColorChoice::Never => (false, false),
+        ColorChoice::Auto => (
+            !no_color && Term::stdout().is_term(),
+            !no_color && Term::stderr().is_term(),
+        ),
+    };
+    console::set_colors_enabled(stdout);
+    console::set_colors_enabled_stderr(stderr);
+}
+
+/// The `Error:` prefix of error messages on standard error.
+pub fn error_label() -> StyledObject<&'static str> {
+    style("Error:").for_stderr().red().bold()
+}
+
+/// The `Warning:` prefix of warnings on standard error.
+pub fn warning_label() -> StyledObject<&'static str> {
+    style("Warning:").for_stderr().yellow().bold()
+}
+
+/// Styles a summary message on standard output that reports success.
+pub fn success<D>(message: D) -> StyledObject<D> {
+    style(message).green()
+}
+
+/// Styles a summary message on standard output that reports a problem.
+pub fn problem<D>(message: D) -> StyledObject<D> {
+    style(message).yellow()
+}
+
+/// Returns the style of a line of a unified diff: added lines are green, removed lines
+/// red and hunk headers cyan.
+fn diff_line_style(line: &str) -> Style {
+    if line.starts_with("+++") || line.starts_with("---") {
+        Style::new().bold()
+    } else if line.starts_with('+') {
+        Style::new().green()
+    } else if line.starts_with('-') {
+        Style::new().red()
+    } else if line.starts_with("@@") {
+        Style::new().cyan()
+    } else {
+        Style::new()
+    }
+}
+
+/// Colors a unified diff for standard output.
+pub fn diff(text: &str) -> String {
+    text.split_inclusive('\n')
+        .map(|line| {
+            let (content, newline) = match line.strip_suffix('\n') {
+                Some(content) => (content, "\n"),
+                None => (line, ""),
+            };
+            format!("{}{}", diff_line_style(content).apply_to(content), newline)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that diff lines are styled by their prefix.
+    #[test]
+    fn test_diff_line_style() {
+        assert_eq!(diff_line_style("--- a/CLAUDE.md"), Style::new().bold());
+        assert_eq!(diff_line_style("+Added line"), Style::new().green());
+        assert_eq!(diff_line_style("-Removed line"), Style::new().red());
+        assert_eq!(diff_line_style("@@ -1,2 +1,2 @@"), Style::new().cyan());
+        assert_eq!(diff_line_style(" Context line"), Style::new());
+    }
+}
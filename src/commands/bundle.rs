@@ -0,0 +1,280 @@
+// src/commands/bundle.rs
+
+use crate::config::{self, CONFIG_FILE, YAML_CONFIG_FILE};
+use crate::exit_code::UsageError;
+use crate::rule_parser::EXCLUDED_DIRS;
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::{Component, Path, PathBuf};
+use tar::{Archive, Builder};
+use walkdir::WalkDir;
+
+/// The file name suffix of rule bundles.
+const BUNDLE_EXTENSION: &str = ".urules.tar.gz";
+
+/// Arguments for `urules pack`.
+#[derive(Args, Debug)]
+pub struct PackArgs {
+    /// Directory containing the universal rule files.
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = ".rules",
+        help = "Directory containing universal rule files (.md)."
+    )]
+    pub rules_dir: PathBuf,
+
+    /// The bundle to write.
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = "rules.urules.tar.gz",
+        help = "The bundle file to write (.urules.tar.gz)."
+    )]
+    pub output: PathBuf,
+}
+
+/// Arguments for `urules unpack`.
+#[derive(Args, Debug)]
+pub struct UnpackArgs {
+    /// The bundle to restore.
+    #[clap(value_parser, help = "The bundle file to restore (.urules.tar.gz).")]
+    pub bundle: PathBuf,
+
+    /// The project directory to restore the bundle into.
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = ".",
+        help = "Directory to restore the rules directory and configuration into."
+    )]
+    pub output_dir: PathBuf,
+
+    /// Overwrite the files that already exist.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Overwrite existing files with the ones from the bundle."
+    )]
+    pub force: bool,
+}
+
+/// Returns the path of the rules directory inside a bundle: its path relative to the
+/// project directory, or `.rules` if it lies outside of the project.
+fn bundled_rules_dir(rules_dir: &Path, project_dir: &Path) -> PathBuf {
+    // Compare absolute paths, since either may be relative to the current directory
+    let absolute = |path: &Path| {
+        std::path::absolute(Path::new(".").join(path)).unwrap_or_else(|_| path.to_path_buf())
+    };
+    let (rules_dir, project_dir) = (absolute(rules_dir), absolute(project_dir));
+    let relative_path = rules_dir.strip_prefix(&project_dir).unwrap_or(&rules_dir);
+    if relative_path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        relative_path
+            .components()
+            .filter(|c| matches!(c, Component::Normal(_)))
+            .collect()
+    } else {
+        PathBuf::from(config::DEFAULT_RULES_DIR)
+    }
+}
+
+/// Returns the files to bundle, keyed by their path in the bundle: every file of the
+/// rules directory (besides `EXCLUDED_DIRS` and other bundles), and the project
+/// configuration files.
+fn bundle_files(rules_dir: &Path, project_dir: &Path) -> BTreeMap<PathBuf, PathBuf> {
+    let bundled_dir = bundled_rules_dir(rules_dir, project_dir);
+    let mut files = BTreeMap::new();
+    for entry in WalkDir::new(rules_dir)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || !EXCLUDED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref())
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| !e.file_name().to_string_lossy().ends_with(BUNDLE_EXTENSION))
+    {
+        if let Ok(relative_path) = entry.path().strip_prefix(rules_dir) {
+            files.insert(bundled_dir.join(relative_path), entry.into_path());
+        }
+    }
+    for config_file in [CONFIG_FILE, YAML_CONFIG_FILE] {
+        let path = project_dir.join(config_file);
+        if path.is_file() {
+            files.insert(PathBuf::from(config_file), path);
+        }
+    }
+    files
+}
+
+/// Writes `files` (bundle paths to source files) into a gzipped tarball at `bundle`,
+/// keeping their permissions and modification times.
+fn write_bundle(files: &BTreeMap<PathBuf, PathBuf>, bundle: &Path) -> Result<()> {
+    let file = File::create(bundle).with_context(|| format!("Failed to create {:?}", bundle))?;
+    let mut builder = Builder::new(GzEncoder::new(file, Compression::default()));
+    for (name, path) in files {
+        builder
+            .append_path_with_name(path, name)
+            .with_context(|| format!("Failed to add {:?} to the bundle", path))?;
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .with_context(|| format!("Failed to write {:?}", bundle))?;
+    Ok(())
+}
+
+/// Opens the gzipped tarball at `bundle`.
+fn open_bundle(bundle: &Path) -> Result<Archive<GzDecoder<File>>> {
+    let file = File::open(bundle).with_context(|| format!("Failed to open {:?}", bundle))?;
+    Ok(Archive::new(GzDecoder::new(file)))
+}
+
+/// Restores the files of `bundle` into `target_dir`, keeping their permissions and
+/// modification times, and returns their paths in the bundle.
+///
+/// Fails before writing anything if a file already exists, unless `force` is set.
+fn unpack_into(bundle: &Path, target_dir: &Path, force: bool) -> Result<Vec<PathBuf>> {
+    let read_error = || format!("Failed to read bundle {:?}", bundle);
+    let mut existing = Vec::new();
+    for entry in open_bundle(bundle)?.entries().with_context(read_error)? {
+        let path = entry.with_context(read_error)?.path()?.into_owned();
+        if target_dir.join(&path).exists() {
+            existing.push(path);
+        }
+    }
+    if !existing.is_empty() && !force {
+        bail!(UsageError(format!(
+            "{} file(s) of the bundle already exist in {:?} (e.g. {:?}); pass --force to overwrite them",
+            existing.len(),
+            target_dir,
+            existing[0]
+        )));
+    }
+
+    fs::create_dir_all(target_dir)
+        .with_context(|| format!("Failed to create directory {:?}", target_dir))?;
+    let mut archive = open_bundle(bundle)?;
+    archive.set_preserve_mtime(true);
+    let mut unpacked = Vec::new();
+    for entry in archive.entries().with_context(read_error)? {
+        let mut entry = entry.with_context(read_error)?;
+        let path = entry.path()?.into_owned();
+        if !entry
+            .unpack_in(target_dir)
+            .with_context(|| format!("Failed to unpack {:?}", path))?
+        {
+            bail!("Refusing to unpack {:?} outside of {:?}", path, target_dir);
+        }
+        unpacked.push(path);
+    }
+    Ok(unpacked)
+}
+
+/// Executes `urules pack`: archives the rules directory and the project configuration
+/// of `project_dir` into a single bundle.
+pub fn pack_rules(args: &PackArgs, project_dir: &Path) -> Result<()> {
+    if !args.rules_dir.is_dir() {
+        bail!(UsageError(format!(
+            "Rules directory {:?} does not exist",
+            args.rules_dir
+        )));
+    }
+    let files = bundle_files(&args.rules_dir, project_dir);
+    write_bundle(&files, &args.output)?;
+    println!("Packed {} file(s) into {:?}", files.len(), args.output);
+    Ok(())
+}
+
+/// Executes `urules unpack`: restores a bundle written by `urules pack`.
+pub fn unpack_bundle(args: &UnpackArgs) -> Result<()> {
+    let unpacked = unpack_into(&args.bundle, &args.output_dir, args.force)?;
+    println!(
+        "Unpacked {} file(s) into {:?}; run `urules` to generate the agent files.",
+        unpacked.len(),
+        args.output_dir
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+    use tempfile::tempdir;
+
+    /// Test that a packed project is restored with its configuration and file metadata.
+    #[test]
+    fn test_pack_and_unpack() -> Result<()> {
+        let project = tempdir()?;
+        let rules_dir = project.path().join(".rules");
+        fs::create_dir_all(rules_dir.join("frontend"))?;
+        fs::create_dir_all(rules_dir.join("node_modules"))?;
+        fs::write(rules_dir.join("style.md"), "Style.")?;
+        fs::write(rules_dir.join("frontend").join("react.md"), "React.")?;
+        fs::write(rules_dir.join("node_modules").join("dep.md"), "Dependency.")?;
+        fs::write(project.path().join(CONFIG_FILE), "agents = [\"claude\"]\n")?;
+        let mtime = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        File::options()
+            .write(true)
+            .open(rules_dir.join("style.md"))?
+            .set_modified(mtime)?;
+
+        let files = bundle_files(&rules_dir, project.path());
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            [
+                Path::new(".rules/frontend/react.md"),
+                Path::new(".rules/style.md"),
+                Path::new(CONFIG_FILE),
+            ]
+        );
+        let bundle = project.path().join("rules.urules.tar.gz");
+        write_bundle(&files, &bundle)?;
+
+        let target = tempdir()?;
+        assert_eq!(unpack_into(&bundle, target.path(), false)?.len(), 3);
+        let restored = target.path().join(".rules").join("style.md");
+        assert_eq!(fs::read_to_string(&restored)?, "Style.");
+        assert_eq!(fs::metadata(&restored)?.modified()?, mtime);
+        assert!(target.path().join(CONFIG_FILE).is_file());
+
+        // Existing files are only overwritten with `force`
+        fs::write(&restored, "Changed.")?;
+        assert!(unpack_into(&bundle, target.path(), false).is_err());
+        assert_eq!(fs::read_to_string(&restored)?, "Changed.");
+        unpack_into(&bundle, target.path(), true)?;
+        assert_eq!(fs::read_to_string(&restored)?, "Style.");
+        Ok(())
+    }
+
+    /// Test that rules directories outside of the project are bundled as `.rules`.
+    #[test]
+    fn test_bundled_rules_dir() {
+        let project = Path::new("/work/app");
+        assert_eq!(
+            bundled_rules_dir(Path::new("/work/app/docs/rules"), project),
+            Path::new("docs/rules")
+        );
+        assert_eq!(
+            bundled_rules_dir(Path::new("./.rules"), Path::new("")),
+            Path::new(".rules")
+        );
+        assert_eq!(
+            bundled_rules_dir(Path::new("../shared/rules"), Path::new("")),
+            Path::new(".rules")
+        );
+    }
+}
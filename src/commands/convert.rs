@@ -0,0 +1,111 @@
+// src/commands/convert.rs
+
+use super::show::{file_section, render_rule};
+use crate::cli::{resolve_agents, GenerateArgs};
+use crate::color::warning_label;
+use crate::config;
+use crate::exit_code::UsageError;
+use crate::languages::expand_languages;
+use crate::rule_parser::{interpolate_rule_env, parse_rule_content, print_rule_warnings};
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// The name of a rule read from stdin without `--name`.
+const DEFAULT_RULE_NAME: &str = "rule";
+
+/// Arguments for `urules convert`.
+#[derive(Args, Debug)]
+pub(crate) struct ConvertArgs {
+    /// The universal rule file to convert, or `-` to read it from stdin.
+    #[clap(
+        value_parser,
+        help = "The universal rule file to convert, or - to read it from stdin."
+    )]
+    pub input: PathBuf,
+
+    /// The name of the rule, which names its outputs such as `.cursor/rules/<name>.mdc`.
+    /// Defaults to the file name, or to `rule` on stdin.
+    #[clap(
+        long,
+        value_name = "NAME",
+        help = "Name of the rule, used for outputs such as .cursor/rules/<NAME>.mdc (default: the file name, or `rule` on stdin)."
+    )]
+    pub name: Option<String>,
+
+    /// Options controlling how the rule is converted.
+    #[clap(flatten)]
+    pub generate: GenerateArgs,
+}
+
+/// Executes `urules convert`: converts one universal rule, read from a file or stdin,
+/// for the selected agents and prints the result to stdout.
+///
+/// A single output file is printed as it is, so editor plugins can use it directly;
+/// several files are printed as sections like `urules show` prints them.
+pub(crate) fn convert_rule(args: &ConvertArgs) -> Result<()> {
+    let generate = &args.generate;
+    let agents = resolve_agents(generate);
+    if agents.is_empty() {
+        bail!(UsageError(format!(
+            "No agent selected to convert for; pass --agent or set `agents` in {}",
+            config::CONFIG_FILE
+        )));
+    }
+
+    let (file_path, content) = if args.input == Path::new("-") {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .context("Failed to read the rule from stdin")?;
+        let name = args.name.as_deref().unwrap_or(DEFAULT_RULE_NAME);
+        (PathBuf::from(format!("{}.md", name)), content)
+    } else {
+        let content = fs::read_to_string(&args.input)
+            .with_context(|| format!("Failed to read rule file: {:?}", args.input))?;
+        let file_path = match &args.name {
+            Some(name) => args.input.with_file_name(format!("{}.md", name)),
+            None => args.input.clone(),
+        };
+        (file_path, content)
+    };
+    let content = interpolate_rule_env(&file_path, content, &generate.discovery)?;
+    let mut rule = parse_rule_content(&file_path, &content, generate.discovery.deny_unknown_keys)?;
+    expand_languages(&mut rule, &generate.discovery.languages)?;
+    print_rule_warnings(std::slice::from_ref(&rule));
+
+    let mut outputs = Vec::new();
+    for agent in &agents {
+        if !agent.is_export() && !rule.applies_to(agent) {
+            eprintln!(
+                "{} The rule is not converted for {}, which its `agents` list does not target.",
+                warning_label(),
+                agent
+            );
+            continue;
+        }
+        for (path, content) in render_rule(generate, agent, &rule)? {
+            outputs.push((agent, path, content));
+        }
+    }
+
+    match outputs.as_slice() {
+        [] => bail!("The rule produces no output for the selected agent(s)"),
+        [(_, _, content)] => {
+            print!("{}", content);
+            if !content.ends_with('\n') {
+                println!();
+            }
+        }
+        _ => {
+            let sections: Vec<String> = outputs
+                .iter()
+                .map(|(agent, path, content)| file_section(agent, path, content))
+                .collect();
+            print!("{}", sections.join("\n"));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,346 @@
+// src/commands/coverage.rs
+
+use super::list::ListFormat;
+use crate::cli::{env_gate_reason, env_var_is_set, expiry_reason, resolve_agents, GenerateArgs};
+use crate::converters::cursor::CursorFormat;
+use crate::converters::registry::coding_agents;
+use crate::converters::windsurf::WindsurfFormat;
+use crate::exit_code::UsageError;
+use crate::report::SkippedRule;
+use crate::rule_parser::{
+    discover_rules, filter_env_gated, filter_expired, filter_rules, RuleParseFailure,
+};
+use crate::universal_rule::{Activation, UniversalRule};
+use crate::{skipped_rule_reason, AgentName};
+use anyhow::{bail, Context, Result};
+use chrono::{NaiveDate, Utc};
+use clap::Args;
+use serde::Serialize;
+
+/// Arguments for `urules coverage`.
+#[derive(Args, Debug)]
+pub(crate) struct CoverageArgs {
+    /// How the report is printed.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = ListFormat::Table,
+        help = "Output format (table or json)."
+    )]
+    pub format: ListFormat,
+
+    /// Options selecting the agents and rules, as for generation.
+    #[clap(flatten)]
+    pub generate: GenerateArgs,
+}
+
+/// Which rules reach which agents.
+#[derive(Serialize, Debug)]
+struct CoverageReport {
+    /// The agents the rules were checked against.
+    agents: Vec<AgentName>,
+
+    /// The number of rules discovered, including those that failed to parse.
+    rules: usize,
+
+    /// The rules left out of the outputs, for every agent or for one.
+    excluded: Vec<SkippedRule>,
+
+    /// The rules that are emitted but whose frontmatter keeps the agent from ever
+    /// activating them on its own.
+    inactive: Vec<SkippedRule>,
+}
+
+/// Returns why the agent never activates `rule` on its own even though it is emitted,
+/// or `None` if its activation is well defined.
+fn inactive_rule_reason(
+    args: &GenerateArgs,
+    agent: &AgentName,
+    rule: &UniversalRule,
+) -> Option<&'static str> {
+    let frontmatter = &rule.frontmatter;
+    let has_globs = !frontmatter.include_globs().is_empty();
+    let activation = match agent {
+        AgentName::Cursor if args.cursor_format == CursorFormat::Legacy => None,
+        AgentName::Cursor => frontmatter.cursor_activation(),
+        AgentName::Windsurf if args.windsurf_format == WindsurfFormat::Legacy => None,
+        AgentName::Windsurf => frontmatter.effective_activation(),
+        _ => None,
+    };
+    let explicit = frontmatter.activation.is_some();
+    let from_cursor_rule_type = !explicit && frontmatter.cursor_rule_type.is_some();
+    match activation {
+        Some(Activation::Glob) if !has_globs => Some(if explicit {
+            "`activation: glob` without `globs`, so it is never attached"
+        } else {
+            "`cursor_rule_type: AutoAttached` without `globs`, so it is never attached"
+        }),
+        Some(Activation::AgentDecision) if frontmatter.trigger_description().is_none() => {
+            Some(if explicit {
+                "`activation: agent_decision` without a `description` to decide by"
+            } else if from_cursor_rule_type && *agent == AgentName::Cursor {
+                "`cursor_rule_type: AgentRequested` without a `description` to request it by"
+            } else {
+                "`content_type: knowledge` without a `description` to decide by"
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Checks `rules` against `agents`: records the parse failures, the rules expired by
+/// `today`, gated on an unset environment variable or filtered out by `--only`/`--exclude`
+/// and, per agent, the rules skipped like
+/// during generation.
+fn coverage_report(
+    args: &GenerateArgs,
+    agents: Vec<AgentName>,
+    rules: Vec<UniversalRule>,
+    parse_failures: &[RuleParseFailure],
+    today: NaiveDate,
+) -> CoverageReport {
+    let mut report = CoverageReport {
+        agents,
+        rules: rules.len() + parse_failures.len(),
+        excluded: parse_failures
+            .iter()
+            .map(|failure| SkippedRule {
+                rule: failure.path.display().to_string(),
+                agent: None,
+                reason: failure.error.clone(),
+            })
+            .collect(),
+        inactive: Vec::new(),
+    };
+    let (rules, expired_rules) = filter_expired(rules, today);
+    report
+        .excluded
+        .extend(expired_rules.iter().map(|rule| SkippedRule {
+            rule: rule.name.clone(),
+            agent: None,
+            reason: expiry_reason(rule),
+        }));
+    let (rules, env_gated_rules) = filter_env_gated(rules, env_var_is_set);
+    report
+        .excluded
+        .extend(env_gated_rules.iter().map(|rule| SkippedRule {
+            rule: rule.name.clone(),
+            agent: None,
+            reason: env_gate_reason(rule),
+        }));
+    let (rules, filtered_out) = filter_rules(rules, &args.only, &args.exclude);
+    report
+        .excluded
+        .extend(filtered_out.into_iter().map(|rule| SkippedRule {
+            rule: rule.name,
+            agent: None,
+            reason: "filtered out by --only/--exclude".to_string(),
+        }));
+    for rule in rules {
+        let rule = &rule.with_derived_description(args.auto_description);
+        for agent in &report.agents {
+            let (list, reason) = match skipped_rule_reason(agent, rule) {
+                Some(reason) => (&mut report.excluded, reason),
+                None => match inactive_rule_reason(args, agent, rule) {
+                    Some(reason) => (&mut report.inactive, reason),
+                    None => continue,
+                },
+            };
+            list.push(SkippedRule {
+                rule: rule.name.clone(),
+                agent: Some(agent.clone()),
+                reason: reason.to_string(),
+            });
+        }
+    }
+    report
+}
+
+/// Renders a section of the text report, with one line per rule and reason listing the
+/// affected agents.
+fn render_section(title: &str, entries: &[SkippedRule]) -> Option<String> {
+    if entries.is_empty() {
+        return None;
+    }
+    let mut groups: Vec<(&str, &str, Vec<String>)> = Vec::new();
+    for entry in entries {
+        let agent = match &entry.agent {
+            Some(agent) => agent.to_string(),
+            None => "all agents".to_string(),
+        };
+        match groups
+            .iter_mut()
+            .find(|(rule, reason, _)| *rule == entry.rule && *reason == entry.reason)
+        {
+            Some((_, _, agents)) => agents.push(agent),
+            None => groups.push((&entry.rule, &entry.reason, vec![agent])),
+        }
+    }
+    let lines: Vec<String> = groups
+        .iter()
+        .map(|(rule, reason, agents)| format!("  {} ({}): {}", rule, agents.join(", "), reason))
+        .collect();
+    Some(format!("{}\n{}", title, lines.join("\n")))
+}
+
+/// Renders the report as text.
+fn render_text(report: &CoverageReport) -> String {
+    let agents: Vec<String> = report.agents.iter().map(ToString::to_string).collect();
+    let mut sections = vec![format!(
+        "Coverage of {} rule(s) for {}:",
+        report.rules,
+        agents.join(", ")
+    )];
+    sections.extend(render_section("Excluded:", &report.excluded));
+    sections.extend(render_section("Never activated:", &report.inactive));
+    if report.excluded.is_empty() && report.inactive.is_empty() {
+        sections.push("Every rule is emitted for every agent.".to_string());
+    }
+    sections.join("\n")
+}
+
+/// Executes `urules coverage`: reports which rules are left out for which agents and
+/// why, so that silently dropped rules get noticed.
+///
+/// Without `--agent`, the rules are checked against every built-in agent.
+pub(crate) fn report_coverage(args: &CoverageArgs) -> Result<()> {
+    let generate = &args.generate;
+    if !generate.rules_dir.is_dir() {
+        bail!(UsageError(format!(
+            "Rules directory {:?} does not exist",
+            generate.rules_dir
+        )));
+    }
+    let (rules, parse_failures) = discover_rules(&generate.rules_dir, &generate.discovery)?;
+    let mut agents = resolve_agents(generate);
+    if agents.is_empty() {
+        agents = coding_agents();
+    }
+
+    let report = coverage_report(
+        generate,
+        agents,
+        rules,
+        &parse_failures,
+        Utc::now().date_naive(),
+    );
+    match args.format {
+        ListFormat::Table => println!("{}", render_text(&report)),
+        ListFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).context("Failed to serialize the report")?
+        ),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use crate::universal_rule::{CursorRuleType, UniversalRuleFrontmatter};
+    use clap::Parser;
+
+    /// Test that exclusions and inactive rules are reported per agent with their reason.
+    #[test]
+    fn test_coverage_report() {
+        let args = Cli::parse_from(["urules", "--exclude", "draft"]).generate;
+        let rule = |name: &str, frontmatter: UniversalRuleFrontmatter| UniversalRule {
+            name: name.to_string(),
+            frontmatter,
+            content: "Content.".to_string(),
+            ..Default::default()
+        };
+        let rules = vec![
+            rule("style", UniversalRuleFrontmatter::default()),
+            rule(
+                "rust",
+                UniversalRuleFrontmatter {
+                    agents: Some(vec![AgentName::Cursor]),
+                    cursor_rule_type: Some(CursorRuleType::AutoAttached),
+                    ..Default::default()
+                },
+            ),
+            rule("draft", UniversalRuleFrontmatter::default()),
+            rule(
+                "internal",
+                UniversalRuleFrontmatter {
+                    requires_env: Some("URULES_TEST_UNSET_VARIABLE".to_string()),
+                    ..Default::default()
+                },
+            ),
+            rule(
+                "migration",
+                UniversalRuleFrontmatter {
+                    expires: NaiveDate::from_ymd_opt(2025, 6, 30),
+                    ..Default::default()
+                },
+            ),
+        ];
+        let report = coverage_report(
+            &args,
+            vec![AgentName::Cursor, AgentName::Claude],
+            rules,
+            &[],
+            NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(),
+        );
+
+        assert_eq!(report.rules, 5);
+        assert_eq!(
+            report.excluded,
+            [
+                SkippedRule {
+                    rule: "migration".to_string(),
+                    agent: None,
+                    reason: "expired on 2025-06-30".to_string(),
+                },
+                SkippedRule {
+                    rule: "internal".to_string(),
+                    agent: None,
+                    reason:
+                        "requires the environment variable URULES_TEST_UNSET_VARIABLE to be set"
+                            .to_string(),
+                },
+                SkippedRule {
+                    rule: "draft".to_string(),
+                    agent: None,
+                    reason: "filtered out by --only/--exclude".to_string(),
+                },
+                SkippedRule {
+                    rule: "rust".to_string(),
+                    agent: Some(AgentName::Claude),
+                    reason: "not targeted at this agent by its `agents` list".to_string(),
+                },
+            ]
+        );
+        assert_eq!(report.inactive.len(), 1);
+        assert_eq!(report.inactive[0].agent, Some(AgentName::Cursor));
+
+        let text = render_text(&report);
+        assert!(text.contains("  draft (all agents): filtered out by --only/--exclude"));
+        assert!(text.contains("Never activated:\n  rust (Cursor): "));
+    }
+
+    /// Test that `activation` is checked for the agents mapping it and wins over the
+    /// deprecated `cursor_rule_type`.
+    #[test]
+    fn test_inactive_rule_reason_activation() {
+        let args = Cli::parse_from(["urules"]).generate;
+        let rule = UniversalRule {
+            name: "api".to_string(),
+            frontmatter: UniversalRuleFrontmatter {
+                activation: Some(Activation::AgentDecision),
+                cursor_rule_type: Some(CursorRuleType::Always),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        for agent in [AgentName::Cursor, AgentName::Windsurf] {
+            assert_eq!(
+                inactive_rule_reason(&args, &agent, &rule),
+                Some("`activation: agent_decision` without a `description` to decide by")
+            );
+        }
+        assert_eq!(inactive_rule_reason(&args, &AgentName::Claude, &rule), None);
+    }
+}
@@ -0,0 +1,455 @@
+// src/commands/doctor.rs
+
+use crate::cli::GenerateArgs;
+use crate::cli::{
+    agents_blocked_by_directories, env_gate_reason, env_var_is_set, expiry_reason,
+    load_custom_definition, output_groups, plan_outputs, plan_removed_rule_outputs,
+    required_rule_problems, resolve_agents, resolve_windsurf_global_dir, with_vars,
+};
+use crate::color;
+use crate::commands::import::ImportSource;
+use crate::commands::lint::lint_rule;
+use crate::config;
+use crate::converters::registry::coding_agents;
+use crate::dry_run::ChangeKind;
+use crate::gitignore_manager::{gitignore_patterns, missing_patterns};
+use crate::report::SkippedRule;
+use crate::rule_parser::{
+    discover_rules, filter_env_gated, filter_expired, filter_rules, rule_warnings,
+    unreferenced_partials,
+};
+use crate::tokens::budget_problems;
+use crate::universal_rule::UniversalRule;
+use crate::AgentName;
+use anyhow::{bail, Result};
+use chrono::Utc;
+use console::style;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// How serious a problem found by `urules doctor` is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Severity {
+    /// Generation fails or produces broken outputs.
+    Error,
+    /// Generation works, but probably not as intended.
+    Warning,
+}
+
+/// A problem found by `urules doctor`.
+#[derive(Debug, Clone, PartialEq)]
+struct Finding {
+    severity: Severity,
+    message: String,
+}
+
+impl Finding {
+    fn error(message: String) -> Self {
+        Finding {
+            severity: Severity::Error,
+            message,
+        }
+    }
+
+    fn warning(message: String) -> Self {
+        Finding {
+            severity: Severity::Warning,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Error => write!(f, "{} {}", style("error:").red().bold(), self.message),
+            Severity::Warning => {
+                write!(f, "{} {}", style("warning:").yellow().bold(), self.message)
+            }
+        }
+    }
+}
+
+/// Checks the rules for `agents: []` lists, rules no selected agent receives, unknown
+/// frontmatter keys, outdated `schema_version`s, names used by several rule files, and
+/// colliding `output_name`s and `aliases`.
+fn check_rules(rules: &[UniversalRule], agents: &[AgentName]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for rule in rules {
+        match rule.frontmatter.agents.as_deref() {
+            Some([]) => findings.push(Finding::error(format!(
+                "Rule {:?} targets no agent (its `agents` list is empty)",
+                rule.relative_path()
+            ))),
+            Some(_) if !agents.is_empty() && !agents.iter().any(|a| rule.applies_to(a)) => findings
+                .push(Finding::warning(format!(
+                    "Rule {:?} targets none of the selected agents",
+                    rule.relative_path()
+                ))),
+            _ => {}
+        }
+        findings.extend(rule_warnings(rule).into_iter().map(Finding::warning));
+    }
+
+    let mut reported_names: Vec<&str> = Vec::new();
+    for rule in rules {
+        if reported_names.contains(&rule.name.as_str()) {
+            continue;
+        }
+        let paths: Vec<PathBuf> = rules
+            .iter()
+            .filter(|other| other.name == rule.name)
+            .map(UniversalRule::relative_path)
+            .collect();
+        if paths.len() > 1 {
+            findings.push(Finding::error(format!(
+                "Rule name {:?} is used by {} files ({:?}); their outputs overwrite each other",
+                rule.name,
+                paths.len(),
+                paths
+            )));
+            reported_names.push(&rule.name);
+        }
+    }
+    findings
+}
+
+/// Lints the bodies of the rule files in `rules_dir` (see `lint_rule`), reporting each
+/// issue as a warning.
+fn check_rule_contents(rules_dir: &Path, rules: &[UniversalRule]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for rule in rules {
+        let path = rules_dir.join(rule.relative_path());
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let rule_dir = path.parent().unwrap_or(rules_dir);
+        findings.extend(lint_rule(&content, rule_dir).into_iter().map(|issue| {
+            Finding::warning(format!(
+                "Rule {:?}, line {}: {}",
+                rule.relative_path(),
+                issue.line,
+                issue.message
+            ))
+        }));
+    }
+    findings
+}
+
+/// Lists the expired, environment-gated and filtered out rules with the reason they are
+/// left out, as generation reports them.
+fn skipped_rules(
+    expired_rules: &[UniversalRule],
+    env_gated_rules: &[UniversalRule],
+    filtered_out: &[UniversalRule],
+) -> Vec<SkippedRule> {
+    expired_rules
+        .iter()
+        .map(|rule| (rule, expiry_reason(rule)))
+        .chain(
+            env_gated_rules
+                .iter()
+                .map(|rule| (rule, env_gate_reason(rule))),
+        )
+        .chain(
+            filtered_out
+                .iter()
+                .map(|rule| (rule, "filtered out by --only/--exclude".to_string())),
+        )
+        .map(|(rule, reason)| SkippedRule {
+            rule: rule.name.clone(),
+            agent: None,
+            reason,
+        })
+        .collect()
+}
+
+/// The `urules import` source reading an agent's files, if there is one.
+fn import_source(agent: &AgentName) -> Option<ImportSource> {
+    match agent {
+        AgentName::Cursor => Some(ImportSource::Cursor),
+        AgentName::Windsurf => Some(ImportSource::Windsurf),
+        AgentName::Claude => Some(ImportSource::Claude),
+        AgentName::Copilot => Some(ImportSource::Copilot),
+        _ => None,
+    }
+}
+
+/// Checks `output_dir` for the files of agents that are not selected, which urules
+/// neither generates nor keeps up to date.
+fn check_unmanaged_agent_files(output_dir: &Path, agents: &[AgentName]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for agent in coding_agents().iter().filter(|a| !agents.contains(a)) {
+        for pattern in gitignore_patterns(agent) {
+            let path = output_dir.join(pattern.trim_matches('/'));
+            let exists = if pattern.ends_with('/') {
+                path.is_dir()
+            } else {
+                path.is_file()
+            };
+            if !exists {
+                continue;
+            }
+            let hint = match import_source(agent) {
+                Some(source) => format!(
+                    "import it with `urules import --from {}` or add the agent",
+                    source.to_string().to_lowercase()
+                ),
+                None => "add the agent to generate it from the rules".to_string(),
+            };
+            findings.push(Finding::warning(format!(
+                "{:?} exists but {} is not generated by urules; {}",
+                path, agent, hint
+            )));
+        }
+    }
+    findings
+}
+
+/// Plans the outputs of the selected agents, then checks that the existing
+/// outputs match and that the generated files are ignored by git. With
+/// `remove_outputs`, the outputs of deleted rules are reported too.
+fn check_generated_outputs(
+    args: &GenerateArgs,
+    agents: &[AgentName],
+    rules: &[UniversalRule],
+    remove_outputs: bool,
+) -> Result<Vec<Finding>> {
+    let custom_definition = load_custom_definition(args, agents)?;
+    let windsurf_global_dir = resolve_windsurf_global_dir(args)?;
+    let (mut plan, outcome) = plan_outputs(
+        args,
+        agents,
+        rules,
+        custom_definition.as_ref(),
+        windsurf_global_dir.as_deref(),
+    )?;
+    if remove_outputs {
+        plan_removed_rule_outputs(&mut plan, args, &outcome)?;
+    }
+    let changes = plan.planned_changes()?;
+
+    let mut findings = Vec::new();
+    let gitignore_path = args.output_dir.join(".gitignore");
+    for agent in &outcome.failed_agents {
+        findings.push(Finding::error(format!("Generating {} rules failed", agent)));
+    }
+    for warning in &outcome.warnings {
+        findings.push(Finding::warning(warning.clone()));
+    }
+    for change in changes.iter().filter(|c| c.path != gitignore_path) {
+        findings.push(Finding::warning(match change.kind {
+            ChangeKind::Create => format!("{:?} has not been generated yet", change.path),
+            ChangeKind::Update => format!(
+                "{:?} differs from the generated output (edited by hand or out of date)",
+                change.path
+            ),
+            ChangeKind::Delete => format!(
+                "{:?} is no longer generated; regenerate the outputs to remove it",
+                change.path
+            ),
+        }));
+    }
+
+    if !args.no_gitignore {
+        for pattern in missing_patterns(&gitignore_path, &outcome.gitignore_patterns)? {
+            if args.output_dir.join(pattern.trim_matches('/')).exists() {
+                findings.push(Finding::warning(format!(
+                    "Generated {:?} is not ignored in {:?}",
+                    pattern, gitignore_path
+                )));
+            }
+        }
+    }
+    Ok(findings)
+}
+
+/// Executes `urules doctor`: checks the project for common misconfigurations and prints
+/// one line per problem, failing if any of them is an error.
+pub(crate) fn run_doctor(args: &GenerateArgs) -> Result<()> {
+    let mut agents = resolve_agents(args);
+    let mut findings = Vec::new();
+    for (agent, path) in agents_blocked_by_directories(args, &agents) {
+        findings.push(Finding::warning(format!(
+            "{} is skipped by `all`, since its output {:?} is a directory; use --output-dir to generate it",
+            agent, path
+        )));
+        agents.retain(|selected| *selected != agent);
+    }
+    if agents.is_empty() {
+        findings.push(Finding::warning(format!(
+            "No agent selected; pass --agent or set `agents` in {}",
+            config::CONFIG_FILE
+        )));
+    }
+
+    if args.rules_dir.is_dir() {
+        let (rules, parse_failures) = discover_rules(&args.rules_dir, &args.discovery)?;
+        for failure in &parse_failures {
+            findings.push(Finding::error(format!(
+                "Failed to parse rule file {:?}: {}",
+                failure.path, failure.error
+            )));
+        }
+        findings.extend(check_rule_contents(&args.rules_dir, &rules));
+        for partial in unreferenced_partials(&args.rules_dir, &args.discovery) {
+            findings.push(Finding::warning(format!(
+                "Partial {:?} is not included by any rule; include it or remove it",
+                partial
+            )));
+        }
+        let required_rules: Vec<String> = rules
+            .iter()
+            .filter(|rule| rule.frontmatter.required)
+            .map(|rule| rule.name.clone())
+            .collect();
+        let (rules, expired_rules) = filter_expired(rules, Utc::now().date_naive());
+        for rule in &expired_rules {
+            findings.push(Finding::warning(format!(
+                "Rule {:?} {} and is skipped; remove it or move its `expires` date",
+                rule.relative_path(),
+                expiry_reason(rule)
+            )));
+        }
+        let (rules, env_gated_rules) = filter_env_gated(rules, env_var_is_set);
+        let (rules, filtered_out) = filter_rules(rules, &args.only, &args.exclude);
+        // Like a real run, which only removes outputs when no rule is left out
+        let remove_outputs = parse_failures.is_empty() && filtered_out.is_empty();
+        let skipped = skipped_rules(&expired_rules, &env_gated_rules, &filtered_out);
+        findings.extend(
+            required_rule_problems(&required_rules, &skipped, &rules, &agents)
+                .into_iter()
+                .map(Finding::error),
+        );
+        let rules = with_vars(args, rules)?;
+        if rules.is_empty() {
+            findings.push(Finding::warning(format!(
+                "No rules found in {:?}",
+                args.rules_dir
+            )));
+        }
+        findings.extend(check_rules(&rules, &agents));
+        findings.extend(
+            budget_problems(&rules, &agents, &args.token_budgets)
+                .into_iter()
+                .map(Finding::warning),
+        );
+        if !agents.is_empty() && !rules.is_empty() {
+            for (group_args, group_agents) in output_groups(args, &agents) {
+                match check_generated_outputs(&group_args, &group_agents, &rules, remove_outputs) {
+                    Ok(output_findings) => findings.extend(output_findings),
+                    Err(e) => findings.push(Finding::error(format!("{:#}", e))),
+                }
+            }
+        }
+    } else {
+        findings.push(Finding::error(format!(
+            "Rules directory {:?} does not exist; create it with `urules init` or pass --rules-dir",
+            args.rules_dir
+        )));
+    }
+
+    findings.extend(check_unmanaged_agent_files(&args.output_dir, &agents));
+
+    for finding in &findings {
+        println!("{}", finding);
+    }
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+    if findings.is_empty() {
+        println!("{}", color::success("No problems found."));
+    } else {
+        let summary = format!("Found {} error(s) and {} warning(s).", errors, warnings);
+        println!("{}", color::problem(summary));
+    }
+    if errors > 0 {
+        bail!("urules doctor found {} error(s)", errors);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universal_rule::UniversalRuleFrontmatter;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn rule(name: &str, relative_dir: &str, agents: Option<Vec<AgentName>>) -> UniversalRule {
+        UniversalRule {
+            name: name.to_string(),
+            content: "Content.".to_string(),
+            frontmatter: UniversalRuleFrontmatter {
+                agents,
+                ..Default::default()
+            },
+            relative_dir: PathBuf::from(relative_dir),
+        }
+    }
+
+    /// Test that empty `agents` lists, untargeted rules and duplicate names are found.
+    #[test]
+    fn test_check_rules() {
+        let rules = vec![
+            rule("style", "", None),
+            rule("style", "frontend", None),
+            rule("nobody", "", Some(Vec::new())),
+            rule("windsurf_only", "", Some(vec![AgentName::Windsurf])),
+        ];
+
+        let findings = check_rules(&rules, &[AgentName::Claude]);
+
+        assert_eq!(findings.len(), 3);
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert!(findings[0].message.contains("targets no agent"));
+        assert_eq!(findings[1].severity, Severity::Warning);
+        assert!(findings[1].message.contains("windsurf_only"));
+        assert_eq!(findings[2].severity, Severity::Error);
+        assert!(findings[2].message.contains("used by 2 files"));
+
+        // Without selected agents, no rule is reported as untargeted
+        assert_eq!(check_rules(&rules[3..], &[]), Vec::new());
+    }
+
+    /// Test that the files of unselected agents are reported with an import hint.
+    #[test]
+    fn test_check_unmanaged_agent_files() -> Result<()> {
+        let dir = tempdir()?;
+        fs::write(dir.path().join("CLAUDE.md"), "# Notes\n")?;
+        fs::create_dir_all(dir.path().join(".cursor"))?;
+        // Zed's `.rules` is a file, so a rules directory of that name is not reported
+        fs::create_dir_all(dir.path().join(".rules"))?;
+
+        let findings = check_unmanaged_agent_files(dir.path(), &[AgentName::Cursor]);
+
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("CLAUDE.md"));
+        assert!(findings[0].message.contains("urules import --from claude"));
+        Ok(())
+    }
+
+    /// Test that lint issues of the rule bodies are reported as warnings with their line.
+    #[test]
+    fn test_check_rule_contents() -> Result<()> {
+        let dir = tempdir()?;
+        fs::create_dir_all(dir.path().join("backend"))?;
+        fs::write(
+            dir.path().join("backend/api.md"),
+            "---\ndescription: API\n---\nSee [the guide](guide.md).\n",
+        )?;
+
+        let findings = check_rule_contents(dir.path(), &[rule("api", "backend", None)]);
+
+        assert_eq!(
+            findings,
+            [Finding::warning(
+                "Rule \"backend/api.md\", line 4: link to \"guide.md\", which does not exist"
+                    .to_string()
+            )]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+// src/commands/explain.rs
+
+use super::rename::find_rule;
+use crate::cli::{
+    build_converter, load_custom_definition, resolve_agents, resolve_windsurf_global_dir,
+    GenerateArgs,
+};
+use crate::converters::registry::coding_agents;
+use crate::exit_code::UsageError;
+use crate::rule_parser::discover_rules;
+use anyhow::{bail, Result};
+use clap::Args;
+
+/// Arguments for `urules explain`.
+#[derive(Args, Debug)]
+pub(crate) struct ExplainArgs {
+    /// The rule to explain.
+    #[clap(
+        value_parser,
+        help = "The rule to explain: its name, or its path in the rules directory without .md."
+    )]
+    pub name: String,
+
+    /// Options controlling how the rule would be generated.
+    #[clap(flatten)]
+    pub generate: GenerateArgs,
+}
+
+/// Executes `urules explain`: prints, for each selected agent, how the agent activates
+/// the rule once generated (always, for matching files, on request, globally, ...), as
+/// the agent's converter maps the rule's frontmatter.
+///
+/// Without `--agent`, the rule is explained for every built-in agent it targets.
+pub(crate) fn explain_rule(args: &ExplainArgs) -> Result<()> {
+    let generate = &args.generate;
+    if !generate.rules_dir.is_dir() {
+        bail!(UsageError(format!(
+            "Rules directory {:?} does not exist",
+            generate.rules_dir
+        )));
+    }
+    let (rules, _) = discover_rules(&generate.rules_dir, &generate.discovery)?;
+    let rule = find_rule(&rules, &args.name)?;
+
+    let mut agents = resolve_agents(generate);
+    if agents.is_empty() {
+        agents = coding_agents()
+            .into_iter()
+            .filter(|agent| rule.applies_to(agent))
+            .collect();
+    }
+
+    let custom_definition = load_custom_definition(generate, &agents)?;
+    let global_dir = resolve_windsurf_global_dir(generate)?;
+    for agent in &agents {
+        if !agent.is_export() && !rule.applies_to(agent) {
+            println!(
+                "{}: not generated, since the rule's `agents` list does not target it.",
+                agent
+            );
+            continue;
+        }
+        let converter = build_converter(
+            generate,
+            agent,
+            custom_definition.as_ref(),
+            global_dir.as_deref(),
+        )?;
+        let output_dir = generate
+            .agent_output_dirs
+            .get(agent)
+            .unwrap_or(&generate.output_dir);
+        println!(
+            "{}: {}",
+            agent,
+            converter.explain_activation(rule, output_dir)
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,293 @@
+// src/commands/fmt.rs
+
+use crate::rule_parser::{rule_files, DiscoveryOptions};
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use std::fs;
+use std::path::PathBuf;
+
+/// The canonical order of the frontmatter keys. Unknown keys are kept after these, in
+/// their original order.
+const KEY_ORDER: &[&str] = &[
+    "schema_version",
+    "version",
+    "expires",
+    "requires_env",
+    "required",
+    "priority",
+    "owner",
+    "author",
+    "title",
+    "output_name",
+    "aliases",
+    "description",
+    "trigger_description",
+    "globs",
+    "languages",
+    "scope",
+    "activation",
+    "apply_globally",
+    "cursor_rule_type",
+    "agents",
+    "prompt",
+    "content_type",
+    "model",
+    "references",
+    "max_tokens",
+    "tags",
+    "ignore_patterns",
+    "vars",
+    "template",
+    "subagent",
+    "claude",
+    "extra",
+];
+
+/// Arguments for `urules fmt`.
+#[derive(Args, Debug)]
+pub struct FmtArgs {
+    /// Directory containing the universal rule files.
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = ".rules",
+        help = "Directory containing universal rule files (.md)."
+    )]
+    pub rules_dir: PathBuf,
+
+    /// The depth and patterns limiting which rule files are discovered.
+    #[clap(flatten)]
+    pub discovery: DiscoveryOptions,
+
+    /// Only report the files that are not formatted, without changing them.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Exit with an error if any rule file is not formatted, without changing it (for CI)."
+    )]
+    pub check: bool,
+}
+
+/// A top-level frontmatter entry: a key with its value lines and the comments and blank
+/// lines preceding it.
+struct Entry {
+    key: String,
+    lines: Vec<String>,
+}
+
+/// Returns the key of a top-level `key: value` line, or `None` for comments, blank lines,
+/// indented lines and sequence items.
+fn top_level_key(line: &str) -> Option<String> {
+    if line.is_empty() || line.starts_with([' ', '\t', '#', '-']) {
+        return None;
+    }
+    let (key, _) = line.split_once(':')?;
+    Some(key.trim().trim_matches(['"', '\'']).to_string())
+}
+
+/// Splits the frontmatter into its top-level entries and its trailing comments, or
+/// returns `None` if it does not start with a key.
+fn split_entries(frontmatter: &str) -> Option<(Vec<Entry>, Vec<String>)> {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut pending = Vec::new();
+    for line in frontmatter.trim().lines().map(str::trim_end) {
+        if let Some(key) = top_level_key(line) {
+            pending.push(line.to_string());
+            entries.push(Entry {
+                key,
+                lines: std::mem::take(&mut pending),
+            });
+        } else if line.is_empty() || line.starts_with('#') {
+            pending.push(line.to_string());
+        } else {
+            // A continuation of the previous value, including its inner blank lines
+            let entry = entries.last_mut()?;
+            entry.lines.append(&mut pending);
+            entry.lines.push(line.to_string());
+        }
+    }
+    Some((entries, pending))
+}
+
+/// Renders a `globs` entry as a flow sequence of double-quoted patterns, keeping its
+/// comments, or returns `None` if its value is not a list of strings.
+fn format_globs(entry: &Entry) -> Option<Vec<String>> {
+    let value: serde_yaml::Mapping = serde_yaml::from_str(&entry.lines.join("\n")).ok()?;
+    let globs: Vec<String> = serde_yaml::from_value(value.get("globs")?.clone()).ok()?;
+    let quoted: Vec<String> = globs
+        .iter()
+        .map(|glob| serde_json::to_string(glob).ok())
+        .collect::<Option<_>>()?;
+    let mut lines: Vec<String> = entry
+        .lines
+        .iter()
+        .take_while(|line| top_level_key(line).is_none())
+        .cloned()
+        .collect();
+    lines.push(format!("globs: [{}]", quoted.join(", ")));
+    Some(lines)
+}
+
+/// Formats a frontmatter block: sorts its keys into the canonical order, quotes its globs
+/// and trims trailing whitespace.
+fn format_frontmatter(frontmatter: &str) -> String {
+    let Some((mut entries, trailing)) = split_entries(frontmatter) else {
+        return frontmatter
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n");
+    };
+    // A stable sort keeps unknown keys in their original order
+    entries.sort_by_key(|entry| {
+        KEY_ORDER
+            .iter()
+            .position(|key| *key == entry.key)
+            .unwrap_or(KEY_ORDER.len())
+    });
+    let mut lines = Vec::new();
+    for entry in &entries {
+        match format_globs(entry).filter(|_| entry.key == "globs") {
+            Some(globs) => lines.extend(globs),
+            None => lines.extend(entry.lines.iter().cloned()),
+        }
+    }
+    lines.extend(trailing);
+    lines.join("\n").trim().to_string()
+}
+
+/// Formats the content of a rule file: normalizes its frontmatter, trims trailing
+/// whitespace from every line and ends it with a single newline.
+///
+/// Fails if the frontmatter is not valid YAML, or if formatting would change its value.
+pub fn format_rule(file_content: &str) -> Result<String> {
+    let body = |text: &str| {
+        text.lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .trim_end()
+            .to_string()
+    };
+    // Split like `split_frontmatter`, but keep the blank lines leading the body
+    let Some(rest) = file_content.strip_prefix("---") else {
+        return Ok(format!("{}\n", body(file_content)));
+    };
+    let (frontmatter, content) = rest.split_once("---").unwrap_or((rest, ""));
+    let content = content.split_once('\n').map_or("", |(_, content)| content);
+
+    let formatted = format_frontmatter(frontmatter);
+    let value = |yaml: &str| -> Result<serde_yaml::Value> {
+        serde_yaml::from_str(yaml).context("Failed to parse YAML frontmatter")
+    };
+    if value(frontmatter)? != value(&formatted)? {
+        bail!("Formatting would change the meaning of the frontmatter");
+    }
+    let separator = if formatted.is_empty() { "" } else { "\n" };
+    Ok(format!(
+        "---\n{}{}---\n{}\n",
+        formatted,
+        separator,
+        body(content)
+    ))
+}
+
+/// Executes `urules fmt`: formats every rule file in the rules directory, or with
+/// `--check` fails if any of them is not formatted.
+pub fn format_rules(args: &FmtArgs) -> Result<()> {
+    if !args.rules_dir.is_dir() {
+        bail!(crate::exit_code::UsageError(format!(
+            "Rules directory {:?} does not exist",
+            args.rules_dir
+        )));
+    }
+    let mut unformatted = 0;
+    let mut failures = 0;
+    for path in rule_files(&args.rules_dir, &args.discovery) {
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        let formatted = match format_rule(&content) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("Failed to format rule file {:?}: {:#}", path, e);
+                failures += 1;
+                continue;
+            }
+        };
+        if formatted == content {
+            continue;
+        }
+        unformatted += 1;
+        if args.check {
+            println!("Would reformat {:?}", path);
+        } else {
+            fs::write(&path, formatted).with_context(|| format!("Failed to write {:?}", path))?;
+            println!("Formatted {:?}", path);
+        }
+    }
+
+    if failures > 0 {
+        bail!("{} rule file(s) could not be formatted", failures);
+    }
+    if args.check && unformatted > 0 {
+        bail!(
+            "{} rule file(s) are not formatted; run `urules fmt` to format them",
+            unformatted
+        );
+    }
+    if unformatted == 0 {
+        println!("All rule files are formatted.");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that keys are sorted, globs quoted and whitespace trimmed.
+    #[test]
+    fn test_format_rule() {
+        let content = "---\n\
+            agents: [cursor]\n\
+            # Where the rule applies\n\
+            globs:\n\
+            - '*.rs'\n\
+            - src/**/*.ts\n\
+            team: platform   \n\
+            description: |\n  First line.\n\n  Second line.\n\
+            ---\n\
+            \n\
+            ## Heading   \n\
+            \n\
+            Body.\n\n\n";
+
+        assert_eq!(
+            format_rule(content).unwrap(),
+            "---\n\
+             description: |\n  First line.\n\n  Second line.\n\
+             # Where the rule applies\n\
+             globs: [\"*.rs\", \"src/**/*.ts\"]\n\
+             agents: [cursor]\n\
+             team: platform\n\
+             ---\n\
+             \n\
+             ## Heading\n\
+             \n\
+             Body.\n"
+        );
+    }
+
+    /// Test that formatted files, and files without frontmatter, are left as they are.
+    #[test]
+    fn test_format_rule_is_idempotent() {
+        let formatted = "---\ndescription: Rust.\nglobs: [\"*.rs\"]\n---\nBody.\n";
+        assert_eq!(format_rule(formatted).unwrap(), formatted);
+        assert_eq!(
+            format_rule("Just content.  \n\n").unwrap(),
+            "Just content.\n"
+        );
+        assert!(format_rule("---\nglobs: [\n---\nBody.\n").is_err());
+    }
+}
@@ -0,0 +1,398 @@
+// src/commands/graph.rs
+
+use crate::exit_code::UsageError;
+use crate::rule_parser::{included_files, partial_files, rule_files, DiscoveryOptions};
+use anyhow::{bail, Result};
+use clap::{Args, ValueEnum};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// The output format of `urules graph`.
+#[derive(ValueEnum, Clone, Debug, PartialEq, Default)]
+pub enum GraphFormat {
+    /// An indented tree of the files each rule includes.
+    #[default]
+    Tree,
+    /// A Graphviz digraph, e.g. for `dot -Tsvg`.
+    Dot,
+}
+
+/// Arguments for `urules graph`.
+#[derive(Args, Debug)]
+pub struct GraphArgs {
+    /// Directory containing the universal rule files.
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = ".rules",
+        help = "Directory containing universal rule files (.md)."
+    )]
+    pub rules_dir: PathBuf,
+
+    /// The depth and patterns limiting which rule files are discovered.
+    #[clap(flatten)]
+    pub discovery: DiscoveryOptions,
+
+    /// How the graph is printed.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = GraphFormat::Tree,
+        help = "Output format (tree or dot)."
+    )]
+    pub format: GraphFormat,
+}
+
+/// The files of a rules directory and the files each of them includes, by name: the path
+/// relative to the rules directory.
+#[derive(Debug, Default)]
+struct IncludeGraph {
+    /// The files each rule file and partial includes, in the order of their directives.
+    includes: BTreeMap<String, Vec<String>>,
+
+    /// The partials, which are only generated through the rules including them.
+    partials: BTreeSet<String>,
+
+    /// The included files that do not exist.
+    missing: BTreeSet<String>,
+}
+
+/// Returns the name of `path` in the graph: its path relative to `rules_dir` with `/`
+/// separators and `..` resolved, or the whole path if it is outside `rules_dir`.
+fn node_name(rules_dir: &Path, path: &Path) -> String {
+    let Ok(relative_path) = path.strip_prefix(rules_dir) else {
+        return path.display().to_string();
+    };
+    let mut components: Vec<Cow<str>> = Vec::new();
+    for component in relative_path.components() {
+        match component {
+            Component::ParentDir if components.last().is_some_and(|c| c != "..") => {
+                components.pop();
+            }
+            Component::CurDir => {}
+            _ => components.push(component.as_os_str().to_string_lossy()),
+        }
+    }
+    components.join("/")
+}
+
+impl IncludeGraph {
+    /// Reads the includes of the rule files and partials of `rules_dir`.
+    fn read(rules_dir: &Path, options: &DiscoveryOptions) -> Self {
+        let rule_files = rule_files(rules_dir, options);
+        let partials = partial_files(rules_dir);
+        // Included files are matched by their canonical path, so that `../style.md` from
+        // a subdirectory names the same file as `style.md`
+        let mut names: BTreeMap<PathBuf, String> = rule_files
+            .iter()
+            .chain(&partials)
+            .filter_map(|path| Some((fs::canonicalize(path).ok()?, node_name(rules_dir, path))))
+            .collect();
+
+        let mut graph = IncludeGraph {
+            partials: partials
+                .iter()
+                .map(|path| node_name(rules_dir, path))
+                .collect(),
+            ..Default::default()
+        };
+        for source in rule_files.iter().chain(&partials) {
+            let mut includes = Vec::new();
+            for path in included_files(source, rules_dir) {
+                let name = match fs::canonicalize(&path) {
+                    Ok(canonical_path) => names
+                        .entry(canonical_path)
+                        .or_insert_with(|| node_name(rules_dir, &path))
+                        .clone(),
+                    Err(_) => {
+                        let name = node_name(rules_dir, &path);
+                        graph.missing.insert(name.clone());
+                        name
+                    }
+                };
+                includes.push(name);
+            }
+            graph
+                .includes
+                .insert(node_name(rules_dir, source), includes);
+        }
+        graph
+    }
+
+    /// Returns the files `name` includes.
+    fn children(&self, name: &str) -> &[String] {
+        self.includes
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the partials that no rule file or other partial includes.
+    fn orphaned_partials(&self) -> Vec<&String> {
+        self.partials
+            .iter()
+            .filter(|partial| {
+                !self
+                    .includes
+                    .iter()
+                    .any(|(source, includes)| source != *partial && includes.contains(partial))
+            })
+            .collect()
+    }
+
+    /// Returns `true` if `to` is `from` or is included by it, directly or not.
+    fn reaches(&self, from: &str, to: &str) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut stack = vec![from];
+        while let Some(name) = stack.pop() {
+            if name == to {
+                return true;
+            }
+            if visited.insert(name) {
+                stack.extend(self.children(name).iter().map(String::as_str));
+            }
+        }
+        false
+    }
+
+    /// Returns the marker printed after the included file `name` below `ancestors`, if
+    /// any. Files whose includes were already printed in `expanded` are not printed
+    /// again, so that shared includes don't repeat whole subtrees.
+    fn marker(&self, name: &str, ancestors: &[&str], expanded: &BTreeSet<&str>) -> &'static str {
+        if ancestors.contains(&name) {
+            " (cycle)"
+        } else if self.missing.contains(name) {
+            " (missing)"
+        } else if expanded.contains(name) {
+            " (see above)"
+        } else {
+            ""
+        }
+    }
+
+    /// Appends the files `name` includes to `out`, one per line below `prefix`, stopping
+    /// at the file that closes a cycle and at files whose includes are already printed.
+    fn write_children<'a>(
+        &'a self,
+        name: &'a str,
+        prefix: &str,
+        ancestors: &mut Vec<&'a str>,
+        expanded: &mut BTreeSet<&'a str>,
+        out: &mut String,
+    ) {
+        let children = self.children(name);
+        if !children.is_empty() {
+            expanded.insert(name);
+        }
+        ancestors.push(name);
+        for (index, child) in children.iter().enumerate() {
+            let last = index + 1 == children.len();
+            let marker = self.marker(child, ancestors, expanded);
+            out.push_str(&format!(
+                "{}{}{}{}\n",
+                prefix,
+                if last { "└── " } else { "├── " },
+                child,
+                marker
+            ));
+            if marker.is_empty() {
+                let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+                self.write_children(child, &prefix, ancestors, expanded, out);
+            }
+        }
+        ancestors.pop();
+    }
+
+    /// Renders the graph as a tree for each rule file, followed by the orphaned partials.
+    fn to_tree(&self) -> String {
+        let orphaned_partials = self.orphaned_partials();
+        let roots = self
+            .includes
+            .keys()
+            .filter(|name| !self.partials.contains(*name))
+            .map(|name| (name, ""))
+            .chain(
+                orphaned_partials
+                    .into_iter()
+                    .map(|name| (name, " (orphaned partial)")),
+            );
+        let mut out = String::new();
+        let mut expanded = BTreeSet::new();
+        for (name, marker) in roots {
+            if expanded.contains(name.as_str()) {
+                out.push_str(&format!("{}{} (see above)\n", name, marker));
+                continue;
+            }
+            out.push_str(&format!("{}{}\n", name, marker));
+            self.write_children(name, "", &mut Vec::new(), &mut expanded, &mut out);
+        }
+        out
+    }
+
+    /// Renders the graph as a Graphviz digraph. Includes that are part of a cycle are
+    /// red, and orphaned partials and missing files are dashed.
+    fn to_dot(&self) -> String {
+        let quote = |name: &str| format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""));
+        let orphaned_partials = self.orphaned_partials();
+        let mut out = String::from("digraph rules {\n");
+        for name in self.includes.keys() {
+            if orphaned_partials.contains(&name) {
+                out.push_str(&format!(
+                    "    {} [style=dashed, color=orange, xlabel=\"orphaned partial\"];\n",
+                    quote(name)
+                ));
+            } else {
+                out.push_str(&format!("    {};\n", quote(name)));
+            }
+        }
+        for name in &self.missing {
+            out.push_str(&format!(
+                "    {} [style=dashed, color=red, xlabel=\"missing\"];\n",
+                quote(name)
+            ));
+        }
+        for (source, includes) in &self.includes {
+            for included in includes {
+                let attributes = if self.reaches(included, source) {
+                    " [color=red, label=\"cycle\"]"
+                } else {
+                    ""
+                };
+                out.push_str(&format!(
+                    "    {} -> {}{};\n",
+                    quote(source),
+                    quote(included),
+                    attributes
+                ));
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Executes `urules graph`: prints which files each rule includes, flagging include
+/// cycles, missing files and partials that nothing includes.
+pub fn graph_rules(args: &GraphArgs) -> Result<()> {
+    if !args.rules_dir.is_dir() {
+        bail!(UsageError(format!(
+            "Rules directory {:?} does not exist",
+            args.rules_dir
+        )));
+    }
+    let graph = IncludeGraph::read(&args.rules_dir, &args.discovery);
+    print!(
+        "{}",
+        match args.format {
+            GraphFormat::Tree => graph.to_tree(),
+            GraphFormat::Dot => graph.to_dot(),
+        }
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Writes the rules directory of the tests: two rules including a partial that
+    /// includes another, an include cycle between two partials, a missing include and an orphaned
+    /// partial.
+    fn write_rules(rules_dir: &Path) {
+        fs::create_dir_all(rules_dir.join("_partials/shared")).unwrap();
+        fs::create_dir_all(rules_dir.join("backend")).unwrap();
+        for (file, content) in [
+            ("style.md", "Style.\n<!-- include: _partials/tone.md -->\n"),
+            (
+                "backend/api.md",
+                "{{include \"../_partials/loop.md\"}}\n{{include \"../_partials/gone.md\"}}\n",
+            ),
+            ("testing.md", "{{include \"_partials/tone.md\"}}\n"),
+            ("_partials/tone.md", "{{include \"shared/voice.md\"}}\n"),
+            ("_partials/shared/voice.md", "Be brief.\n"),
+            ("_partials/loop.md", "{{include \"again.md\"}}\n"),
+            ("_partials/again.md", "{{include \"loop.md\"}}\n"),
+            ("_partials/old.md", "Unused.\n"),
+        ] {
+            fs::write(rules_dir.join(file), content).unwrap();
+        }
+    }
+
+    /// Test the tree of includes, with its cycle, missing file and orphaned partial.
+    #[test]
+    fn test_tree() {
+        let dir = tempdir().unwrap();
+        write_rules(dir.path());
+        let graph = IncludeGraph::read(dir.path(), &DiscoveryOptions::default());
+
+        assert_eq!(
+            graph.to_tree(),
+            [
+                "backend/api.md",
+                "├── _partials/loop.md",
+                "│   └── _partials/again.md",
+                "│       └── _partials/loop.md (cycle)",
+                "└── _partials/gone.md (missing)",
+                "style.md",
+                "└── _partials/tone.md",
+                "    └── _partials/shared/voice.md",
+                "testing.md",
+                "└── _partials/tone.md (see above)",
+                "_partials/old.md (orphaned partial)",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    /// Test that a chain of diamond includes prints each shared partial's includes once,
+    /// instead of a tree that doubles with each level.
+    #[test]
+    fn test_tree_prints_shared_includes_once() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("_partials")).unwrap();
+        fs::write(
+            dir.path().join("style.md"),
+            "{{include \"_partials/0.md\"}}\n",
+        )
+        .unwrap();
+        for level in 0..20 {
+            fs::write(
+                dir.path().join(format!("_partials/{}.md", level)),
+                format!(
+                    "{{{{include \"{0}.md\"}}}}\n{{{{include \"{0}.md\"}}}}\n",
+                    level + 1
+                ),
+            )
+            .unwrap();
+        }
+        fs::write(dir.path().join("_partials/20.md"), "Done.\n").unwrap();
+        let graph = IncludeGraph::read(dir.path(), &DiscoveryOptions::default());
+
+        let tree = graph.to_tree();
+        assert_eq!(tree.lines().count(), 42);
+        assert_eq!(tree.matches("(see above)").count(), 19);
+    }
+
+    /// Test that the digraph marks the includes of a cycle and the orphaned partials.
+    #[test]
+    fn test_dot() {
+        let dir = tempdir().unwrap();
+        write_rules(dir.path());
+        let graph = IncludeGraph::read(dir.path(), &DiscoveryOptions::default());
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph rules {\n"));
+        assert!(dot.contains("    \"style.md\" -> \"_partials/tone.md\";\n"));
+        assert!(dot.contains(
+            "    \"_partials/loop.md\" -> \"_partials/again.md\" [color=red, label=\"cycle\"];\n"
+        ));
+        assert!(dot.contains("    \"backend/api.md\" -> \"_partials/loop.md\";\n"));
+        assert!(dot.contains("    \"_partials/old.md\" [style=dashed, color=orange"));
+        assert!(dot.contains("    \"_partials/gone.md\" [style=dashed, color=red"));
+    }
+}
@@ -0,0 +1,218 @@
+// src/commands/hook.rs
+
+use crate::exit_code::UsageError;
+use crate::git::{find_git_dir, hooks_dir};
+use anyhow::{bail, Context, Result};
+use clap::{Args, Subcommand};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The comment marking hooks and pre-commit entries written by urules.
+const HOOK_MARKER: &str = "Installed by `urules hook install`";
+
+/// The git pre-commit hook running `urules check`.
+const PRE_COMMIT_HOOK: &str = "#!/bin/sh
+# Installed by `urules hook install`: fails the commit if the generated agent files
+# are out of date. Run `urules` to regenerate them, or bypass with `git commit --no-verify`.
+exec urules check
+";
+
+/// The pre-commit framework configuration file.
+const PRE_COMMIT_CONFIG_FILE: &str = ".pre-commit-config.yaml";
+
+/// The `repos` entry of the pre-commit framework running `urules check`, indented by
+/// `{indent}`.
+const PRE_COMMIT_REPO: &str = "{indent}# Installed by `urules hook install`
+{indent}- repo: local
+{indent}  hooks:
+{indent}    - id: urules-check
+{indent}      name: urules check
+{indent}      entry: urules check
+{indent}      language: system
+{indent}      pass_filenames: false
+";
+
+/// Arguments for `urules hook`.
+#[derive(Args, Debug)]
+pub struct HookArgs {
+    #[clap(subcommand)]
+    pub command: HookCommand,
+}
+
+/// Subcommands of `urules hook`.
+#[derive(Subcommand, Debug)]
+pub enum HookCommand {
+    /// Install a pre-commit hook running `urules check`.
+    Install(HookInstallArgs),
+}
+
+/// Arguments for `urules hook install`.
+#[derive(Args, Debug)]
+pub struct HookInstallArgs {
+    /// Add a hook to `.pre-commit-config.yaml` instead of writing a git hook.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Add the hook to .pre-commit-config.yaml (pre-commit framework) instead of .git/hooks."
+    )]
+    pub pre_commit_config: bool,
+
+    /// Replace an existing pre-commit hook that was not installed by urules.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Replace an existing git pre-commit hook."
+    )]
+    pub force: bool,
+}
+
+/// Writes the git pre-commit hook to `hooks_dir`, refusing to replace a foreign hook
+/// without `force`.
+fn install_git_hook(hooks_dir: &Path, force: bool) -> Result<PathBuf> {
+    let hook_path = hooks_dir.join("pre-commit");
+    if hook_path.is_file() {
+        let existing = fs::read_to_string(&hook_path)
+            .with_context(|| format!("Failed to read {:?}", hook_path))?;
+        if !existing.contains(HOOK_MARKER) && !force {
+            bail!(UsageError(format!(
+                "{:?} already exists; pass --force to replace it, or add `urules check` to it",
+                hook_path
+            )));
+        }
+    }
+    fs::create_dir_all(hooks_dir)
+        .with_context(|| format!("Failed to create directory {:?}", hooks_dir))?;
+    fs::write(&hook_path, PRE_COMMIT_HOOK)
+        .with_context(|| format!("Failed to write {:?}", hook_path))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("Failed to make {:?} executable", hook_path))?;
+    }
+    Ok(hook_path)
+}
+
+/// Adds the `urules check` hook to the pre-commit framework configuration `config`,
+/// as the first entry of its `repos` list. Returns `None` if it is already there.
+fn add_pre_commit_repo(config: &str) -> Result<Option<String>> {
+    if config.contains("id: urules-check") {
+        return Ok(None);
+    }
+    let lines: Vec<&str> = config.lines().collect();
+    let Some(repos_line) = lines.iter().position(|line| line.trim_end() == "repos:") else {
+        if config.lines().any(|line| line.starts_with("repos:")) {
+            bail!(
+                "Cannot add the hook to the `repos` of {}; add a `repo: local` hook running `urules check` by hand",
+                PRE_COMMIT_CONFIG_FILE
+            );
+        }
+        let separator = if config.is_empty() || config.ends_with('\n') {
+            ""
+        } else {
+            "\n"
+        };
+        return Ok(Some(format!(
+            "{}{}repos:\n{}",
+            config,
+            separator,
+            PRE_COMMIT_REPO.replace("{indent}", "  ")
+        )));
+    };
+    // Indent the entry like the existing ones
+    let indent: String = lines[repos_line + 1..]
+        .iter()
+        .find(|line| line.trim_start().starts_with('-'))
+        .map(|line| line.chars().take_while(|c| *c == ' ').collect())
+        .unwrap_or_else(|| "  ".to_string());
+    let mut updated: Vec<String> = lines[..=repos_line].iter().map(|l| l.to_string()).collect();
+    updated.push(
+        PRE_COMMIT_REPO
+            .replace("{indent}", &indent)
+            .trim_end()
+            .to_string(),
+    );
+    updated.extend(lines[repos_line + 1..].iter().map(|l| l.to_string()));
+    Ok(Some(updated.join("\n") + "\n"))
+}
+
+/// Executes `urules hook install` for the git repository containing `start_dir`.
+fn install_hook(args: &HookInstallArgs, start_dir: &Path) -> Result<()> {
+    let (repo_root, git_dir) = find_git_dir(start_dir)?;
+    if args.pre_commit_config {
+        let config_path = repo_root.join(PRE_COMMIT_CONFIG_FILE);
+        let config = if config_path.is_file() {
+            fs::read_to_string(&config_path)
+                .with_context(|| format!("Failed to read {:?}", config_path))?
+        } else {
+            String::new()
+        };
+        match add_pre_commit_repo(&config)? {
+            Some(updated) => {
+                fs::write(&config_path, updated)
+                    .with_context(|| format!("Failed to write {:?}", config_path))?;
+                println!(
+                    "Added the urules-check hook to {:?}; run `pre-commit install` to enable it.",
+                    config_path
+                );
+            }
+            None => println!("{:?} already runs urules-check.", config_path),
+        }
+    } else {
+        let hook_path = install_git_hook(&hooks_dir(&repo_root, &git_dir)?, args.force)?;
+        println!(
+            "Installed {:?}; commits now fail while generated files are out of date.",
+            hook_path
+        );
+    }
+    Ok(())
+}
+
+/// Executes `urules hook` from `start_dir`.
+pub fn run_hook(args: &HookArgs, start_dir: &Path) -> Result<()> {
+    match &args.command {
+        HookCommand::Install(install_args) => install_hook(install_args, start_dir),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Test that the hook is added to new and existing pre-commit configurations once.
+    #[test]
+    fn test_add_pre_commit_repo() -> Result<()> {
+        let created = add_pre_commit_repo("")?.unwrap();
+        assert!(created
+            .starts_with("repos:\n  # Installed by `urules hook install`\n  - repo: local\n"));
+        let config: serde_yaml::Value = serde_yaml::from_str(&created)?;
+        assert_eq!(config["repos"][0]["hooks"][0]["entry"], "urules check");
+        assert_eq!(add_pre_commit_repo(&created)?, None);
+
+        let existing = "repos:\n- repo: https://github.com/pre-commit/pre-commit-hooks\n  rev: v4.6.0\n  hooks:\n  - id: trailing-whitespace\n";
+        let updated = add_pre_commit_repo(existing)?.unwrap();
+        let config: serde_yaml::Value = serde_yaml::from_str(&updated)?;
+        assert_eq!(config["repos"][0]["repo"], "local");
+        assert_eq!(config["repos"][1]["hooks"][0]["id"], "trailing-whitespace");
+
+        assert!(add_pre_commit_repo("repos: []\n").is_err());
+        Ok(())
+    }
+
+    /// Test that foreign git hooks are only replaced with `force`.
+    #[test]
+    fn test_install_git_hook() -> Result<()> {
+        let dir = tempdir()?;
+        let hook_path = install_git_hook(dir.path(), false)?;
+        assert_eq!(fs::read_to_string(&hook_path)?, PRE_COMMIT_HOOK);
+        // Reinstalling over our own hook is fine
+        install_git_hook(dir.path(), false)?;
+
+        fs::write(&hook_path, "#!/bin/sh\nnpm test\n")?;
+        assert!(install_git_hook(dir.path(), false).is_err());
+        install_git_hook(dir.path(), true)?;
+        assert_eq!(fs::read_to_string(&hook_path)?, PRE_COMMIT_HOOK);
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+// src/commands/import.rs
+
+use crate::importers::claude::ClaudeImporter;
+use crate::importers::copilot::CopilotImporter;
+use crate::importers::cursor::CursorImporter;
+use crate::importers::windsurf::WindsurfImporter;
+use crate::importers::{render_rule_file, RuleImporter};
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+/// The agents whose existing rule files `urules import` can read.
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum ImportSource {
+    /// `.cursor/rules/*.mdc` and a legacy `.cursorrules` file.
+    Cursor,
+    /// `global_rules.md`, `.windsurf/rules/*.md` and a legacy `.windsurfrules` file.
+    Windsurf,
+    /// `CLAUDE.md`, split into one rule per section.
+    Claude,
+    /// `.github/copilot-instructions.md` and `.github/instructions/*.instructions.md`.
+    Copilot,
+}
+
+impl fmt::Display for ImportSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportSource::Cursor => write!(f, "Cursor"),
+            ImportSource::Windsurf => write!(f, "Windsurf"),
+            ImportSource::Claude => write!(f, "Claude"),
+            ImportSource::Copilot => write!(f, "Copilot"),
+        }
+    }
+}
+
+impl ImportSource {
+    /// Returns the importer reading this agent's rule files.
+    fn importer(&self) -> Box<dyn RuleImporter> {
+        match self {
+            ImportSource::Cursor => Box::new(CursorImporter),
+            ImportSource::Windsurf => Box::new(WindsurfImporter),
+            ImportSource::Claude => Box::new(ClaudeImporter),
+            ImportSource::Copilot => Box::new(CopilotImporter),
+        }
+    }
+}
+
+/// Arguments for `urules import`.
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// The agent whose rule files are imported.
+    #[clap(long, value_enum, help = "The agent to import rules from.")]
+    pub from: ImportSource,
+
+    /// Directory containing the agent's existing rule files.
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = ".",
+        help = "Project directory containing the agent's rule files."
+    )]
+    pub input_dir: PathBuf,
+
+    /// Directory where the universal rule files are written.
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = ".rules",
+        help = "Directory to write universal rule files (.md) to."
+    )]
+    pub rules_dir: PathBuf,
+}
+
+/// Imports an agent's existing rule files from `args.input_dir` and writes them as
+/// universal rule files into `args.rules_dir`.
+///
+/// Existing rule files are left untouched, so importing again never overwrites rules
+/// that were edited after a previous import.
+pub fn import_rules(args: &ImportArgs) -> Result<()> {
+    let rules = args.from.importer().import_rules(&args.input_dir)?;
+    if rules.is_empty() {
+        println!("No {} rules found in {:?}.", args.from, args.input_dir);
+        return Ok(());
+    }
+
+    let mut imported = 0;
+    for rule in &rules {
+        let rule_dir = args.rules_dir.join(&rule.relative_dir);
+        let rule_path = rule_dir.join(format!("{}.md", rule.name));
+        if rule_path.exists() {
+            println!("Skipped {:?} (already exists)", rule_path);
+            continue;
+        }
+        fs::create_dir_all(&rule_dir)
+            .with_context(|| format!("Failed to create rules directory {:?}", rule_dir))?;
+        fs::write(&rule_path, render_rule_file(rule)?)
+            .with_context(|| format!("Failed to write rule file {:?}", rule_path))?;
+        println!("Created {:?}", rule_path);
+        imported += 1;
+    }
+
+    println!(
+        "Imported {} of {} {} rule(s) into {:?}.",
+        imported,
+        rules.len(),
+        args.from,
+        args.rules_dir
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rule_parser::{discover_and_parse_rules, DiscoveryOptions};
+    use tempfile::tempdir;
+
+    /// Test that imported rules round-trip through the rule parser and that existing
+    /// rule files are kept.
+    #[test]
+    fn test_import_rules_from_cursor() -> Result<()> {
+        let dir = tempdir()?;
+        let cursor_rules_dir = dir.path().join(".cursor").join("rules");
+        fs::create_dir_all(&cursor_rules_dir)?;
+        fs::write(
+            cursor_rules_dir.join("rust.mdc"),
+            "---\ndescription: Rust rules\nglobs: \"*.rs\"\n---\nUse clippy.\n",
+        )?;
+        fs::write(cursor_rules_dir.join("style.mdc"), "Use tabs.")?;
+        let rules_dir = dir.path().join(".rules");
+        fs::create_dir_all(&rules_dir)?;
+        fs::write(rules_dir.join("style.md"), "My own style.")?;
+
+        let args = ImportArgs {
+            from: ImportSource::Cursor,
+            input_dir: dir.path().to_path_buf(),
+            rules_dir: rules_dir.clone(),
+        };
+        import_rules(&args)?;
+
+        let rules = discover_and_parse_rules(&rules_dir, &DiscoveryOptions::default())?;
+        let rust = rules.iter().find(|r| r.name == "rust").unwrap();
+        assert_eq!(rust.frontmatter.description.as_deref(), Some("Rust rules"));
+        assert_eq!(rust.frontmatter.globs, Some(vec!["*.rs".to_string()]));
+        assert_eq!(rust.content, "Use clippy.");
+        assert_eq!(
+            fs::read_to_string(rules_dir.join("style.md"))?,
+            "My own style."
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+// src/commands/init.rs
+
+use crate::config::CONFIG_FILE;
+use anyhow::{Context, Result};
+use clap::Args;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the example rule created by `urules init`.
+pub const EXAMPLE_RULE_FILE: &str = "example.md";
+
+/// An example rule documenting every commonly used frontmatter field.
+const EXAMPLE_RULE: &str = r#"---
+# The heading of the rule in concatenated outputs such as CLAUDE.md. Defaults to the
+# file name ("Example" for example.md); output file names always follow the file name.
+# title: Example rule
+
+# A short summary of the rule, used as a comment or trigger description by some agents.
+description: Example rule created by `urules init`. Edit or replace it.
+
+# Glob patterns of the files this rule applies to (e.g. Cursor auto-attach, Copilot applyTo).
+# Leave unset for rules that apply to the whole project.
+# globs: ["**/*.rs"]
+
+# How agents activate the rule: `always` (e.g. Windsurf global_rules.md, Cursor alwaysApply),
+# `glob` (files matching `globs`), `agent_decision` (when the description is relevant) or
+# `manual`. Leave unset for each agent's default.
+# activation: always
+
+# Limit the rule to some agents; unset means every agent.
+# agents: [cursor, claude]
+
+# The kind of artifact: `instructions` (default), `prompt` or `command` (a Copilot prompt
+# file or Claude slash command), or `knowledge` (pulled in when relevant).
+# content_type: instructions
+
+# Free-form tags; `review` routes the rule to Copilot code review instructions.
+# tags: [review]
+---
+# Example rule
+
+Everything after the frontmatter is the rule content, written in Markdown.
+
+- Describe conventions the coding agent should follow in this project.
+- Keep each rule focused on one topic; add more `.md` files for other topics.
+"#;
+
+/// An example configuration with the default values commented out.
+///
+/// Every key is documented in `crate::config::ProjectConfig`.
+const EXAMPLE_CONFIG: &str = r#"# urules project configuration.
+# Values set here are used as defaults for command-line options.
+
+# Directory containing the universal rule files. When unset, the first existing
+# directory of .rules/, .urules/, rules/ and docs/ai-rules/ is used.
+# rules_dir = ".rules"
+
+# Directory where agent-specific rules are generated.
+# output_dir = "."
+
+# Agents generated when `--agent` is not given.
+# agents = ["cursor", "claude"]
+
+# Set to true to leave .gitignore untouched.
+# no_gitignore = false
+
+# Set to true to fail on unknown frontmatter keys, such as typos.
+# deny_unknown_keys = false
+
+# Set to true to replace ${NAME} references in rule files with environment variables.
+# env_interpolation = false
+
+# Set to "sentence" or "paragraph" to derive missing descriptions from rule contents.
+# auto_description = "off"
+
+# Set to true to keep the <!-- ... --> comments of rules in the generated files.
+# keep_comments = false
+
+# Set to true to render each rule's owner as a comment in the generated files.
+# owner_comments = false
+
+# Set to true to leave out {{generation_date}}, so that outputs do not change every day.
+# reproducible = false
+
+# Set to "trim" or "keep" to not split outputs over an agent's size limit.
+# oversized = "split"
+
+# Set to true to strip comments, separators and extra blank lines from generated Markdown.
+# minify = false
+
+# Set to true to re-render rule contents with consistent Markdown syntax.
+# normalize_markdown = false
+
+# The comment prepended to generated files ({source} is the rule file they come from).
+# banner = "Generated by urules from {source} — do not edit"
+# no_banner = false
+
+# Set to "crlf" or "native" (crlf on Windows) for other line endings in generated files.
+# line_endings = "lf"
+
+# Set to "warn" or "off" to generate even when rules seem to contain secrets.
+# secrets = "fail"
+
+# Agents generated somewhere else than output_dir.
+# [output_dirs]
+# copilot = "."
+# cursor = "packages/web"
+
+# Per-agent options, matching the --<agent>-format style flags.
+# [cursor]
+# format = "mdc"      # or "legacy"
+# nested = false
+#
+# [claude]
+# format = "concat"   # or "imports"
+#
+# [copilot]
+# format = "files"    # or "settings"
+#
+# [windsurf]
+# format = "rules"    # or "legacy"
+# nested = false
+# global_dir = "/path/to/global/rules"
+#
+# Globs of the languages listed in `languages`, replacing the built-in ones
+# [languages]
+# typescript = ["web/**/*.ts", "web/**/*.tsx"]
+#
+# The most tokens the rules generated for an agent may take together
+# [token_budgets]
+# claude = 8000
+#
+# Values for {{name}} references in rule contents (overridden by --set name=value)
+# [vars]
+# project = "Acme"
+"#;
+
+/// Arguments for `urules init`.
+#[derive(Args, Debug)]
+pub struct InitArgs {
+    /// Directory to create for the universal rule files.
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = ".rules",
+        help = "Directory to create for universal rule files."
+    )]
+    pub rules_dir: PathBuf,
+
+    /// Also create a `urules.toml` config file in the current directory.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Also create a urules.toml config file."
+    )]
+    pub config: bool,
+}
+
+/// Writes `content` to `path` unless the file already exists, so `init` never
+/// overwrites user files. Returns `true` if the file was created.
+fn write_if_missing(path: &Path, content: &str) -> Result<bool> {
+    if path.exists() {
+        return Ok(false);
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(true)
+}
+
+/// Scaffolds a new rules setup: the rules directory with an example rule and,
+/// optionally, a `urules.toml` config file in `project_dir`.
+///
+/// Existing files are left untouched, so running `init` again is safe.
+pub fn init_project(args: &InitArgs, project_dir: &Path) -> Result<()> {
+    let rules_dir = project_dir.join(&args.rules_dir);
+    fs::create_dir_all(&rules_dir)
+        .with_context(|| format!("Failed to create rules directory {:?}", rules_dir))?;
+
+    let mut files = vec![(rules_dir.join(EXAMPLE_RULE_FILE), EXAMPLE_RULE)];
+    if args.config {
+        files.push((project_dir.join(CONFIG_FILE), EXAMPLE_CONFIG));
+    }
+    for (path, content) in files {
+        if write_if_missing(&path, content)? {
+            println!("Created {:?}", path);
+        } else {
+            println!("Skipped {:?} (already exists)", path);
+        }
+    }
+
+    println!(
+        "Rules directory ready. Next, run e.g. `urules --agent cursor --rules-dir {}`.",
+        args.rules_dir.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ProjectConfig;
+    use crate::rule_parser::parse_rule_file;
+    use tempfile::tempdir;
+
+    /// Test that `init` creates a parseable example rule and the optional config.
+    #[test]
+    fn test_init_project_creates_files() -> Result<()> {
+        let dir = tempdir()?;
+        let args = InitArgs {
+            rules_dir: PathBuf::from(".rules"),
+            config: true,
+        };
+
+        init_project(&args, dir.path())?;
+
+        let example_path = dir.path().join(".rules").join(EXAMPLE_RULE_FILE);
+        let rule = parse_rule_file(&example_path, true)?;
+        assert_eq!(rule.name, "example");
+        assert!(rule.frontmatter.description.is_some());
+        assert!(rule.content.starts_with("# Example rule"));
+        // The example config only has comments, so it parses as an empty config
+        let config = ProjectConfig::from_file(&dir.path().join(CONFIG_FILE))?;
+        assert_eq!(config, ProjectConfig::default());
+        Ok(())
+    }
+
+    /// Test that `init` leaves existing files untouched.
+    #[test]
+    fn test_init_project_keeps_existing_files() -> Result<()> {
+        let dir = tempdir()?;
+        let rules_dir = dir.path().join(".rules");
+        fs::create_dir_all(&rules_dir)?;
+        fs::write(rules_dir.join(EXAMPLE_RULE_FILE), "My own rule")?;
+        let args = InitArgs {
+            rules_dir: PathBuf::from(".rules"),
+            config: false,
+        };
+
+        init_project(&args, dir.path())?;
+
+        assert_eq!(
+            fs::read_to_string(rules_dir.join(EXAMPLE_RULE_FILE))?,
+            "My own rule"
+        );
+        assert!(!dir.path().join(CONFIG_FILE).exists());
+        Ok(())
+    }
+}
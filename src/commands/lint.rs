@@ -0,0 +1,561 @@
+// src/commands/lint.rs
+
+use crate::assets::rewrite_link_targets;
+use crate::exit_code::UsageError;
+use crate::rule_parser::{rule_files, DiscoveryOptions};
+use crate::secrets::find_secrets;
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Arguments for `urules lint`.
+#[derive(Args, Debug)]
+pub struct LintArgs {
+    /// Directory containing the universal rule files.
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = ".rules",
+        help = "Directory containing universal rule files (.md)."
+    )]
+    pub rules_dir: PathBuf,
+
+    /// The depth and patterns limiting which rule files are discovered.
+    #[clap(flatten)]
+    pub discovery: DiscoveryOptions,
+
+    /// Fix the mechanical issues in place before reporting the remaining ones.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Remove empty headings, close unclosed code fences and tag obvious code blocks in place."
+    )]
+    pub fix: bool,
+}
+
+/// The language identifiers a code fence may be tagged with (compared case-insensitively),
+/// as recognized by the syntax highlighters agents and Markdown renderers use.
+const KNOWN_LANGUAGES: &[&str] = &[
+    "bash",
+    "c",
+    "c++",
+    "clojure",
+    "cmake",
+    "console",
+    "cpp",
+    "cs",
+    "csharp",
+    "css",
+    "csv",
+    "dart",
+    "diff",
+    "dockerfile",
+    "elixir",
+    "elm",
+    "env",
+    "erlang",
+    "fish",
+    "fsharp",
+    "gitignore",
+    "go",
+    "gradle",
+    "graphql",
+    "groovy",
+    "haskell",
+    "hcl",
+    "html",
+    "http",
+    "ini",
+    "java",
+    "javascript",
+    "jinja",
+    "js",
+    "json",
+    "json5",
+    "jsonc",
+    "jsx",
+    "kotlin",
+    "kt",
+    "latex",
+    "less",
+    "lua",
+    "make",
+    "makefile",
+    "markdown",
+    "md",
+    "mermaid",
+    "nginx",
+    "nix",
+    "objc",
+    "objective-c",
+    "ocaml",
+    "perl",
+    "php",
+    "plaintext",
+    "powershell",
+    "prisma",
+    "proto",
+    "protobuf",
+    "ps1",
+    "py",
+    "python",
+    "r",
+    "rb",
+    "regex",
+    "rs",
+    "ruby",
+    "rust",
+    "sass",
+    "scala",
+    "scss",
+    "sh",
+    "shell",
+    "sql",
+    "svelte",
+    "swift",
+    "terraform",
+    "text",
+    "tf",
+    "toml",
+    "ts",
+    "tsx",
+    "txt",
+    "typescript",
+    "vue",
+    "xml",
+    "yaml",
+    "yml",
+    "zig",
+    "zsh",
+];
+
+/// A problem found in the body of a rule file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintIssue {
+    /// The 1-based line of the rule file the issue is on.
+    pub line: usize,
+    pub message: String,
+}
+
+/// An ATX heading (`## Title`) of a rule body.
+struct Heading {
+    /// The 0-based index of its line.
+    index: usize,
+    level: usize,
+    text: String,
+}
+
+/// Returns the 0-based index of the first body line of a rule file: the line after its
+/// frontmatter block, or `0` if it has none.
+fn body_start(lines: &[&str]) -> usize {
+    if lines.first().map(|line| line.trim_end()) != Some("---") {
+        return 0;
+    }
+    lines
+        .iter()
+        .skip(1)
+        .position(|line| line.trim_end() == "---")
+        .map_or(0, |end| end + 2)
+}
+
+/// Returns the marker (e.g. ```` ``` ```` or `~~~~`) of a code fence line, or `None` if
+/// the line does not start or end a fenced code block.
+fn fence_marker(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start();
+    let fence_char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let length = trimmed.len() - trimmed.trim_start_matches(fence_char).len();
+    (length >= 3).then(|| &trimmed[..length])
+}
+
+/// Returns the language identifier of a code fence opening line (`rust` for
+/// ```` ```rust,ignore ````), or an empty string if it has none.
+fn fence_language<'a>(line: &'a str, marker: &str) -> &'a str {
+    let info = line.trim_start()[marker.len()..].trim();
+    let language = info.split_whitespace().next().unwrap_or_default();
+    language.split(',').next().unwrap_or_default()
+}
+
+/// Guesses the language of an untagged code block from its lines, in the obvious cases
+/// only: a shebang, a JSON document, XML or HTML markup, a PHP script, a diff, shell
+/// prompts or Rust items.
+fn guess_language(lines: &[&str]) -> Option<&'static str> {
+    let first = lines
+        .iter()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty())?;
+    if let Some(shebang) = first.strip_prefix("#!") {
+        let interpreter = shebang.split_whitespace().last()?.rsplit('/').next()?;
+        return match interpreter {
+            "bash" | "sh" => Some("bash"),
+            "zsh" => Some("zsh"),
+            "fish" => Some("fish"),
+            "python" | "python3" => Some("python"),
+            "node" => Some("javascript"),
+            "ruby" => Some("ruby"),
+            _ => None,
+        };
+    }
+    let text = lines.join("\n");
+    if first.starts_with(['{', '[']) && serde_json::from_str::<serde_json::Value>(&text).is_ok() {
+        return Some("json");
+    }
+    if first.starts_with("<?xml") {
+        return Some("xml");
+    }
+    if first.starts_with("<?php") {
+        return Some("php");
+    }
+    let lowercase = first.to_lowercase();
+    if lowercase.starts_with("<!doctype html") || lowercase.starts_with("<html") {
+        return Some("html");
+    }
+    if first.starts_with("diff --git ") || first.starts_with("--- a/") {
+        return Some("diff");
+    }
+    let non_blank = || lines.iter().filter(|line| !line.trim().is_empty());
+    if non_blank().all(|line| line.trim_start().starts_with("$ ")) {
+        return Some("console");
+    }
+    let rust_items = [
+        "fn ",
+        "pub fn ",
+        "impl ",
+        "use crate::",
+        "use std::",
+        "#[derive(",
+    ];
+    if non_blank().any(|line| rust_items.iter().any(|item| line.starts_with(item))) {
+        return Some("rust");
+    }
+    None
+}
+
+/// Returns `true` if `line` closes the fenced code block opened with `marker`.
+fn closes_fence(line: &str, marker: &str) -> bool {
+    fence_marker(line).is_some_and(|closing| {
+        closing.starts_with(&marker[..1]) && closing.len() >= marker.len() && line.trim() == closing
+    })
+}
+
+/// Returns the level and text of an ATX heading line, or `None` if the line is none.
+fn parse_heading(line: &str) -> Option<(usize, String)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let trimmed = &line[indent..];
+    let level = trimmed.len() - trimmed.trim_start_matches('#').len();
+    let rest = &trimmed[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    let text = rest.trim().trim_end_matches('#').trim_end();
+    Some((level, text.to_string()))
+}
+
+/// Returns `true` if `line` contains `word` as a whole word.
+fn contains_word(line: &str, word: &str) -> bool {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    line.match_indices(word).any(|(start, _)| {
+        !line[..start].chars().next_back().is_some_and(is_word_char)
+            && !line[start + word.len()..]
+                .chars()
+                .next()
+                .is_some_and(is_word_char)
+    })
+}
+
+/// Returns the relative link targets of `line` (`[text](target)`) that point at no
+/// existing file, resolved relative to `rule_dir`. URLs, anchors, absolute paths and
+/// templated targets are not checked.
+fn dead_links(line: &str, rule_dir: &Path) -> Vec<String> {
+    let mut dead = Vec::new();
+    let _ = rewrite_link_targets(line, |target| {
+        let path = target.split(['#', '?']).next().unwrap_or_default();
+        let checked = !path.is_empty()
+            && !target.starts_with(['#', '/', '<'])
+            && !target.contains(':')
+            && !target.contains("{{");
+        if checked && !rule_dir.join(path).exists() {
+            dead.push(target.to_string());
+        }
+        Ok(None)
+    });
+    dead
+}
+
+/// Lints the body of a rule file, whose relative links are resolved against `rule_dir`:
+/// reports dead relative links, `TODO` and `FIXME` markers, headings without text,
+/// sections with no content before the next heading of the same or a higher level,
+/// unclosed code fences, and code blocks without a language tag or tagged with one not
+/// in `KNOWN_LANGUAGES`. The frontmatter and fenced code blocks are not linted.
+pub fn lint_rule(file_content: &str, rule_dir: &Path) -> Vec<LintIssue> {
+    let lines: Vec<&str> = file_content.lines().collect();
+    let start = body_start(&lines);
+    let mut issues = Vec::new();
+    let mut headings = Vec::new();
+    let mut fence: Option<(usize, &str)> = None;
+    for (index, line) in lines.iter().enumerate().skip(start) {
+        let issue = |message: String| LintIssue {
+            line: index + 1,
+            message,
+        };
+        if let Some((_, marker)) = fence {
+            if closes_fence(line, marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if let Some(marker) = fence_marker(line) {
+            fence = Some((index, marker));
+            let language = fence_language(line, marker);
+            if language.is_empty() {
+                issues.push(issue("code block without a language tag".to_string()));
+            } else if !KNOWN_LANGUAGES.contains(&language.to_lowercase().as_str()) {
+                issues.push(issue(format!(
+                    "code block tagged with the unknown language {:?}",
+                    language
+                )));
+            }
+            continue;
+        }
+        for target in dead_links(line, rule_dir) {
+            issues.push(issue(format!("link to {:?}, which does not exist", target)));
+        }
+        for marker in ["TODO", "FIXME"] {
+            if contains_word(line, marker) {
+                issues.push(issue(format!("`{}` marker", marker)));
+            }
+        }
+        if let Some((level, text)) = parse_heading(line) {
+            if text.is_empty() {
+                issues.push(issue("heading without text".to_string()));
+            }
+            headings.push(Heading { index, level, text });
+        }
+    }
+    if let Some((index, marker)) = fence {
+        issues.push(LintIssue {
+            line: index + 1,
+            message: format!("code fence {:?} is never closed", marker),
+        });
+    }
+    // Secrets are looked for in code blocks too
+    for finding in find_secrets(&lines[start..].join("\n")) {
+        issues.push(LintIssue {
+            line: start + finding.line,
+            message: format!("what looks like a {} ({:?})", finding.kind, finding.excerpt),
+        });
+    }
+
+    for (position, heading) in headings.iter().enumerate() {
+        let next = headings.get(position + 1);
+        if heading.text.is_empty() || next.is_some_and(|next| next.level > heading.level) {
+            continue;
+        }
+        let end = next.map_or(lines.len(), |next| next.index);
+        if lines[heading.index + 1..end]
+            .iter()
+            .all(|line| line.trim().is_empty())
+        {
+            issues.push(LintIssue {
+                line: heading.index + 1,
+                message: format!("section {:?} is empty", heading.text),
+            });
+        }
+    }
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+/// Fixes the mechanical issues `lint_rule` reports: removes the headings without text,
+/// tags the untagged code blocks whose language is obvious (see `guess_language`) and
+/// closes an unclosed code fence at the end of the file. Other lines are kept as they
+/// are.
+pub fn fix_rule(file_content: &str) -> String {
+    let lines: Vec<&str> = file_content.lines().collect();
+    let start = body_start(&lines);
+    let mut fixed: Vec<String> = Vec::with_capacity(lines.len());
+    let mut fence: Option<&str> = None;
+    for (index, line) in lines.iter().enumerate() {
+        if index < start {
+            fixed.push(line.to_string());
+            continue;
+        }
+        match (fence, fence_marker(line)) {
+            (Some(marker), _) if closes_fence(line, marker) => fence = None,
+            (None, Some(marker)) => {
+                fence = Some(marker);
+                if fence_language(line, marker).is_empty() {
+                    let block: Vec<&str> = lines[index + 1..]
+                        .iter()
+                        .take_while(|line| !closes_fence(line, marker))
+                        .copied()
+                        .collect();
+                    if let Some(language) = guess_language(&block) {
+                        fixed.push(format!("{}{}", line.trim_end(), language));
+                        continue;
+                    }
+                }
+            }
+            (None, None) if parse_heading(line).is_some_and(|(_, text)| text.is_empty()) => {
+                continue;
+            }
+            _ => {}
+        }
+        fixed.push(line.to_string());
+    }
+    if let Some(marker) = fence {
+        fixed.push(marker.to_string());
+    }
+    let mut result = fixed.join("\n");
+    if file_content.ends_with('\n') || fence.is_some() {
+        result.push('\n');
+    }
+    result
+}
+
+/// Executes `urules lint`: prints the issues found in the bodies of the rule files, one
+/// `path:line: message` line each, after fixing the mechanical ones with `--fix`. Fails
+/// if any issue remains.
+pub fn lint_rules(args: &LintArgs) -> Result<()> {
+    if !args.rules_dir.is_dir() {
+        bail!(UsageError(format!(
+            "Rules directory {:?} does not exist",
+            args.rules_dir
+        )));
+    }
+    let mut issue_count = 0;
+    let mut file_count = 0;
+    for path in rule_files(&args.rules_dir, &args.discovery) {
+        let mut content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        if args.fix {
+            let fixed = fix_rule(&content);
+            if fixed != content {
+                fs::write(&path, &fixed).with_context(|| format!("Failed to write {:?}", path))?;
+                println!("Fixed {:?}", path);
+                content = fixed;
+            }
+        }
+        let issues = lint_rule(&content, path.parent().unwrap_or(&args.rules_dir));
+        for issue in &issues {
+            println!("{}:{}: {}", path.display(), issue.line, issue.message);
+        }
+        if !issues.is_empty() {
+            issue_count += issues.len();
+            file_count += 1;
+        }
+    }
+
+    if issue_count > 0 {
+        bail!(
+            "Found {} issue(s) in {} rule file(s)",
+            issue_count,
+            file_count
+        );
+    }
+    println!("No issues found.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that every kind of issue is reported on its line, outside code blocks.
+    #[test]
+    fn test_lint_rule() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("setup.md"), "Setup.").unwrap();
+        let content = "---\ndescription: TODO\n---\n# Style\n\n## Naming\n\nSee [setup](setup.md#steps) and [old](old.md).\nTODO: explain. TODOS and FIXME_LATER are words.\n\n##\n## Empty\n\n## Code\n\n```rust\n// TODO in code\n# not a heading\n```\n\n## Last\n\n````\nunclosed\n```\n";
+
+        let issues = lint_rule(content, dir.path());
+        let found: Vec<(usize, &str)> = issues
+            .iter()
+            .map(|issue| (issue.line, issue.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (8, "link to \"old.md\", which does not exist"),
+                (9, "`TODO` marker"),
+                (11, "heading without text"),
+                (12, "section \"Empty\" is empty"),
+                (23, "code block without a language tag"),
+                (23, "code fence \"````\" is never closed"),
+            ]
+        );
+        assert!(lint_rule(
+            "# Title\n\nContent with [a site](https://example.com).\n",
+            dir.path()
+        )
+        .is_empty());
+        let tagged = lint_rule("```Rust,ignore\n```\n~~~rsut\n~~~\n", dir.path());
+        assert_eq!(
+            tagged,
+            [LintIssue {
+                line: 3,
+                message: "code block tagged with the unknown language \"rsut\"".to_string(),
+            }]
+        );
+    }
+
+    /// Test that `fix_rule` removes empty headings, tags obvious code blocks and closes
+    /// the open fence, and that the fixed rule only has the issues left that need a person.
+    #[test]
+    fn test_fix_rule() {
+        let content =
+            "---\ndescription: Style\n---\n#\nUse tabs.\n```\n{\"a\": 1}\n```\n\n```\nfn main() {}\n";
+        let fixed = fix_rule(content);
+        assert_eq!(
+            fixed,
+            "---\ndescription: Style\n---\nUse tabs.\n```json\n{\"a\": 1}\n```\n\n```rust\nfn main() {}\n```\n"
+        );
+        assert!(lint_rule(&fixed, Path::new(".")).is_empty());
+        assert_eq!(fix_rule(&fixed), fixed);
+        assert_eq!(fix_rule("```\nsome text\n```\n"), "```\nsome text\n```\n");
+    }
+
+    /// Test that only obvious code blocks get a language.
+    #[test]
+    fn test_guess_language() {
+        assert_eq!(
+            guess_language(&["#!/usr/bin/env python3", "print(1)"]),
+            Some("python")
+        );
+        assert_eq!(guess_language(&["#!/bin/sh"]), Some("bash"));
+        assert_eq!(guess_language(&["[1, 2]"]), Some("json"));
+        assert_eq!(guess_language(&["<!DOCTYPE html>"]), Some("html"));
+        assert_eq!(
+            guess_language(&["$ cargo test", "", "$ cargo fmt"]),
+            Some("console")
+        );
+        assert_eq!(
+            guess_language(&["#[derive(Debug)]", "struct A;"]),
+            Some("rust")
+        );
+        assert_eq!(guess_language(&["[section]", "key = 1"]), None);
+        assert_eq!(guess_language(&["cargo test"]), None);
+        assert_eq!(guess_language(&[]), None);
+    }
+
+    /// Test that secrets are reported on their line of the file, in code blocks too.
+    #[test]
+    fn test_lint_rule_finds_secrets() {
+        let content = "---\ndescription: Deploy\n---\n# Deploy\n\n```sh\nexport API_KEY=4f9c2e81b7d34a6e9f01\n```\n";
+        let messages: Vec<(usize, String)> = lint_rule(content, Path::new("."))
+            .into_iter()
+            .map(|issue| (issue.line, issue.message))
+            .collect();
+        assert_eq!(
+            messages,
+            [(
+                7,
+                "what looks like a hard-coded credential (\"4f9c2e81…\")".to_string()
+            )]
+        );
+    }
+}
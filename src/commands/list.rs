@@ -0,0 +1,232 @@
+// src/commands/list.rs
+
+use crate::rule_parser::{discover_and_parse_rules, DiscoveryOptions};
+use crate::universal_rule::UniversalRule;
+use crate::AgentName;
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Descriptions longer than this are shortened in the table output.
+const MAX_DESCRIPTION_WIDTH: usize = 48;
+
+/// The output format of `urules list`.
+#[derive(ValueEnum, Clone, Debug, PartialEq, Default)]
+pub enum ListFormat {
+    /// An aligned, human-readable table.
+    #[default]
+    Table,
+    /// A JSON array, for scripting.
+    Json,
+}
+
+/// Arguments for `urules list`.
+#[derive(Args, Debug)]
+pub struct ListArgs {
+    /// Directory containing the universal rule files.
+    #[clap(
+        short,
+        long,
+        value_parser,
+        default_value = ".rules",
+        help = "Directory containing universal rule files (.md)."
+    )]
+    pub rules_dir: PathBuf,
+
+    /// The depth and patterns limiting which rule files are discovered.
+    #[clap(flatten)]
+    pub discovery: DiscoveryOptions,
+
+    /// How the rules are printed.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = ListFormat::Table,
+        help = "Output format (table or json)."
+    )]
+    pub format: ListFormat,
+}
+
+/// The summary of a rule printed by `urules list`.
+#[derive(Serialize, Debug)]
+struct RuleSummary<'a> {
+    name: &'a str,
+    title: String,
+    description: Option<&'a str>,
+    globs: &'a [String],
+    agents: Option<&'a [AgentName]>,
+    apply_globally: bool,
+    owner: Option<&'a str>,
+}
+
+impl<'a> From<&'a UniversalRule> for RuleSummary<'a> {
+    fn from(rule: &'a UniversalRule) -> Self {
+        RuleSummary {
+            name: &rule.name,
+            title: rule.title(),
+            description: rule.frontmatter.description.as_deref(),
+            globs: rule.frontmatter.globs.as_deref().unwrap_or_default(),
+            agents: rule.frontmatter.agents.as_deref(),
+            apply_globally: rule.frontmatter.is_always_applied(),
+            owner: rule.frontmatter.owner.as_deref(),
+        }
+    }
+}
+
+/// Shortens `text` to `max_width` characters, marking the cut with `...`.
+fn truncate(text: &str, max_width: usize) -> String {
+    if text.chars().count() <= max_width {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_width.saturating_sub(3)).collect();
+    format!("{}...", kept)
+}
+
+/// Renders the rules as a table with one row per rule and aligned columns.
+fn render_table(rules: &[UniversalRule]) -> String {
+    let mut rows = vec![[
+        "NAME".to_string(),
+        "TITLE".to_string(),
+        "DESCRIPTION".to_string(),
+        "GLOBS".to_string(),
+        "AGENTS".to_string(),
+        "GLOBAL".to_string(),
+        "OWNER".to_string(),
+    ]];
+    for rule in rules {
+        let agents = match &rule.frontmatter.agents {
+            Some(agents) => agents
+                .iter()
+                .map(|a| a.to_string().to_lowercase())
+                .collect::<Vec<String>>()
+                .join(","),
+            None => "all".to_string(),
+        };
+        rows.push([
+            rule.name.clone(),
+            rule.title(),
+            truncate(
+                rule.frontmatter.description.as_deref().unwrap_or("-"),
+                MAX_DESCRIPTION_WIDTH,
+            ),
+            rule.frontmatter
+                .globs
+                .as_ref()
+                .filter(|g| !g.is_empty())
+                .map_or("-".to_string(), |g| g.join(",")),
+            agents,
+            if rule.frontmatter.is_always_applied() {
+                "yes"
+            } else {
+                "no"
+            }
+            .to_string(),
+            rule.frontmatter.owner.clone().unwrap_or("-".to_string()),
+        ]);
+    }
+
+    let mut widths = [0usize; 7];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<String>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Renders the rules as a pretty-printed JSON array of rule summaries.
+fn render_json(rules: &[UniversalRule]) -> Result<String> {
+    let summaries: Vec<RuleSummary> = rules.iter().map(RuleSummary::from).collect();
+    serde_json::to_string_pretty(&summaries).context("Failed to serialize rule list to JSON")
+}
+
+/// Prints the rules discovered in `args.rules_dir` in the requested format.
+pub fn list_rules(args: &ListArgs) -> Result<()> {
+    let rules = discover_and_parse_rules(&args.rules_dir, &args.discovery)?;
+    match args.format {
+        ListFormat::Table if rules.is_empty() => {
+            println!("No rules found in {:?}.", args.rules_dir)
+        }
+        ListFormat::Table => println!("{}", render_table(&rules)),
+        ListFormat::Json => println!("{}", render_json(&rules)?),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universal_rule::UniversalRuleFrontmatter;
+
+    /// Helper function to create the rules listed in the tests.
+    fn create_test_rules() -> Vec<UniversalRule> {
+        vec![
+            UniversalRule {
+                name: "rust".to_string(),
+                frontmatter: UniversalRuleFrontmatter {
+                    title: Some("Rust style".to_string()),
+                    description: Some("Rust conventions".to_string()),
+                    globs: Some(vec!["*.rs".to_string(), "build.rs".to_string()]),
+                    agents: Some(vec![AgentName::Cursor, AgentName::Claude]),
+                    owner: Some("@rust-team".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            UniversalRule {
+                name: "global".to_string(),
+                frontmatter: UniversalRuleFrontmatter {
+                    apply_globally: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ]
+    }
+
+    /// Test that the table has aligned columns and placeholders for unset fields.
+    #[test]
+    fn test_render_table() {
+        let table = render_table(&create_test_rules());
+        assert_eq!(
+            table,
+            "NAME    TITLE       DESCRIPTION       GLOBS          AGENTS         GLOBAL  OWNER\n\
+             rust    Rust style  Rust conventions  *.rs,build.rs  cursor,claude  no      @rust-team\n\
+             global  Global      -                 -              all            yes     -"
+        );
+    }
+
+    /// Test that the JSON output exposes every listed field.
+    #[test]
+    fn test_render_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(&render_json(&create_test_rules()).unwrap()).unwrap();
+        assert_eq!(json[0]["name"], "rust");
+        assert_eq!(json[0]["title"], "Rust style");
+        // Without a `title`, the title is derived from the name
+        assert_eq!(json[1]["title"], "Global");
+        assert_eq!(json[0]["agents"], serde_json::json!(["cursor", "claude"]));
+        assert_eq!(json[1]["description"], serde_json::Value::Null);
+        assert_eq!(json[1]["apply_globally"], true);
+        assert_eq!(json[0]["owner"], "@rust-team");
+    }
+
+    /// Test that long descriptions are shortened for the table.
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a much longer text", 10), "a much ...");
+    }
+}
@@ -0,0 +1,19 @@
+// src/commands/mod.rs
+
+//! Subcommands of the `urules` CLI, besides the default rule generation.
+
+pub mod bundle;
+pub mod convert;
+pub mod coverage;
+pub mod doctor;
+pub mod explain;
+pub mod fmt;
+pub mod graph;
+pub mod hook;
+pub mod import;
+pub mod init;
+pub mod lint;
+pub mod list;
+pub mod rename;
+pub mod rm;
+pub mod show;
@@ -0,0 +1,355 @@
+// src/commands/rename.rs
+
+use crate::cli::{
+    load_custom_definition, output_groups, plan_outputs, resolve_agents,
+    resolve_windsurf_global_dir, run_generation, with_vars, GenerateArgs, RunMode,
+};
+use crate::color;
+use crate::exit_code::UsageError;
+use crate::manifest::{is_merged_path, Manifest};
+use crate::rule_parser::{discover_rules, filter_rules};
+use crate::universal_rule::UniversalRule;
+use crate::AgentName;
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Arguments for `urules rename`.
+#[derive(Args, Debug)]
+pub(crate) struct RenameArgs {
+    /// The rule to rename.
+    #[clap(
+        value_parser,
+        help = "The rule to rename: its name, or its path in the rules directory without .md."
+    )]
+    pub old: String,
+
+    /// The new name of the rule.
+    #[clap(
+        value_parser,
+        help = "The new name, or a new path in the rules directory without .md."
+    )]
+    pub new: String,
+
+    /// Options for regenerating the outputs after the rename.
+    #[clap(flatten)]
+    pub generate: GenerateArgs,
+}
+
+/// Returns `path` with `.` components removed and `..` components applied.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Returns the relative path leading from the directory `from_dir` to `to`, both being
+/// relative to the same base directory.
+fn relative_path(from_dir: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from_dir.components().collect();
+    let to_components: Vec<Component> = to.components().collect();
+    let common = from
+        .iter()
+        .zip(&to_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut path = PathBuf::new();
+    for _ in common..from.len() {
+        path.push("..");
+    }
+    for component in &to_components[common..] {
+        path.push(component);
+    }
+    path
+}
+
+/// Rewrites the Markdown links of `text`, the content of the rule file at `file`, that
+/// point to the rule file `old_path` so that they point to `new_path` instead. Paths are
+/// relative to the rules directory. Returns `None` if no link was changed.
+fn rewrite_links(text: &str, file: &Path, old_path: &Path, new_path: &Path) -> Option<String> {
+    let file_dir = file.parent().unwrap_or(Path::new(""));
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut changed = false;
+    while let Some(start) = rest.find("](") {
+        let target_start = start + 2;
+        result.push_str(&rest[..target_start]);
+        rest = &rest[target_start..];
+        let Some(end) = rest.find(')') else {
+            break;
+        };
+        let target = &rest[..end];
+        let (target_path, fragment) = match target.find('#') {
+            Some(i) => target.split_at(i),
+            None => (target, ""),
+        };
+        let is_local = !target_path.is_empty()
+            && !target_path.contains(char::is_whitespace)
+            && !target_path.contains("://");
+        if is_local && normalize(&file_dir.join(target_path)) == old_path {
+            let new_target = relative_path(file_dir, new_path);
+            result.push_str(&new_target.to_string_lossy().replace('\\', "/"));
+            result.push_str(fragment);
+            changed = true;
+        } else {
+            result.push_str(target);
+        }
+        rest = &rest[end..];
+    }
+    result.push_str(rest);
+    changed.then_some(result)
+}
+
+/// Finds the rule named `name`, or whose path relative to the rules directory (without
+/// `.md`) is `name`.
+pub(super) fn find_rule<'a>(rules: &'a [UniversalRule], name: &str) -> Result<&'a UniversalRule> {
+    let matches: Vec<&UniversalRule> = rules
+        .iter()
+        .filter(|rule| {
+            rule.name == name || rule.relative_path().with_extension("") == Path::new(name)
+        })
+        .collect();
+    match matches.as_slice() {
+        [rule] => Ok(rule),
+        [] => bail!(UsageError(format!("No rule named {:?}", name))),
+        _ => bail!(UsageError(format!(
+            "Several rules are named {:?} ({:?}); pass the rule's path instead",
+            name,
+            matches
+                .iter()
+                .map(|rule| rule.relative_path())
+                .collect::<Vec<_>>()
+        ))),
+    }
+}
+
+/// Plans the outputs of the current rules and returns their contents, keyed by the path
+/// of each output. Outputs merged with their existing content (e.g. `.gitignore`) are
+/// left out, since they are never stale.
+pub(super) fn plan_generated_files(
+    args: &GenerateArgs,
+    agents: &[AgentName],
+) -> Result<BTreeMap<PathBuf, Vec<u8>>> {
+    let (rules, _) = discover_rules(&args.rules_dir, &args.discovery)?;
+    let (rules, _) = filter_rules(rules, &args.only, &args.exclude);
+    let rules = with_vars(args, rules)?;
+    let custom_definition = load_custom_definition(args, agents)?;
+    let windsurf_global_dir = resolve_windsurf_global_dir(args)?;
+    let mut files = BTreeMap::new();
+    for (group_args, group_agents) in output_groups(args, agents) {
+        let (plan, _) = plan_outputs(
+            &group_args,
+            &group_agents,
+            &rules,
+            custom_definition.as_ref(),
+            windsurf_global_dir.as_deref(),
+        )?;
+        files.extend(
+            plan.files
+                .into_iter()
+                .filter(|(path, _)| !is_merged_path(&group_args.output_dir, path)),
+        );
+    }
+    Ok(files)
+}
+
+/// Removes the outputs in `outputs_before` that are no longer generated, i.e. missing from
+/// `outputs_after`.
+///
+/// An output is only removed if it was not edited since it was generated: its content
+/// matches either its generated content or its hash in the generation manifest. Edited
+/// outputs are kept with a warning, unless `--force` was given.
+///
+/// Outputs the manifest records for an agent are left to the regeneration that follows,
+/// which removes them the same way.
+pub(super) fn remove_stale_outputs(
+    args: &GenerateArgs,
+    agents: &[AgentName],
+    outputs_before: &BTreeMap<PathBuf, Vec<u8>>,
+    outputs_after: &BTreeMap<PathBuf, Vec<u8>>,
+) -> Result<()> {
+    let manifests = output_groups(args, agents)
+        .into_iter()
+        .map(|(group_args, _)| {
+            let manifest = Manifest::load(&group_args.output_dir)?;
+            Ok((group_args.output_dir, manifest))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    for (path, generated_content) in outputs_before {
+        if outputs_after.contains_key(path)
+            || !path.is_file()
+            || manifests
+                .iter()
+                .any(|(output_dir, manifest)| manifest.records_output(output_dir, path))
+        {
+            continue;
+        }
+        let content = fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let unmodified = content == *generated_content
+            || manifests
+                .iter()
+                .any(|(output_dir, manifest)| manifest.is_recorded(output_dir, path, &content));
+        if unmodified || args.force {
+            fs::remove_file(path).with_context(|| format!("Failed to remove {:?}", path))?;
+            println!("Removed stale output {:?}", path);
+        } else {
+            eprintln!(
+                "{} Keeping stale output {:?}, which was edited since it was generated; pass --force to remove it",
+                color::warning_label(),
+                path
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Executes `urules rename`: renames a rule file, updates the links to it in other rules,
+/// removes the outputs only the old name produced and regenerates the outputs.
+///
+/// Stale outputs that were edited since they were generated are kept, with a warning.
+pub(crate) fn rename_rule(args: &RenameArgs) -> Result<()> {
+    let generate = &args.generate;
+    let agents = resolve_agents(generate);
+    if agents.is_empty() {
+        bail!(UsageError(format!(
+            "No agent selected to regenerate; pass --agent or set `agents` in {}",
+            crate::config::CONFIG_FILE
+        )));
+    }
+    if !generate.rules_dir.is_dir() {
+        bail!(UsageError(format!(
+            "Rules directory {:?} does not exist",
+            generate.rules_dir
+        )));
+    }
+
+    let (rules, _) = discover_rules(&generate.rules_dir, &generate.discovery)?;
+    let rule = find_rule(&rules, &args.old)?;
+    let old_path = rule.relative_path();
+    let new_name = args.new.strip_suffix(".md").unwrap_or(&args.new);
+    let new_path = if new_name.contains('/') {
+        normalize(Path::new(&format!("{}.md", new_name)))
+    } else {
+        rule.relative_dir.join(format!("{}.md", new_name))
+    };
+    if new_name.is_empty() || new_path.file_stem().is_none() {
+        bail!(UsageError(format!("Invalid rule name {:?}", args.new)));
+    }
+    if generate.rules_dir.join(&new_path).exists() {
+        bail!(UsageError(format!(
+            "Cannot rename {:?}: {:?} already exists",
+            old_path,
+            generate.rules_dir.join(&new_path)
+        )));
+    }
+
+    let outputs_before = plan_generated_files(generate, &agents)?;
+
+    let old_file = generate.rules_dir.join(&old_path);
+    let new_file = generate.rules_dir.join(&new_path);
+    if let Some(parent) = new_file.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {:?}", parent))?;
+    }
+    fs::rename(&old_file, &new_file)
+        .with_context(|| format!("Failed to rename {:?} to {:?}", old_file, new_file))?;
+    println!("Renamed {:?} to {:?}", old_file, new_file);
+
+    for other in rules.iter().filter(|r| r.relative_path() != old_path) {
+        let other_path = other.relative_path();
+        let file = generate.rules_dir.join(&other_path);
+        let content =
+            fs::read_to_string(&file).with_context(|| format!("Failed to read {:?}", file))?;
+        if let Some(updated) = rewrite_links(&content, &other_path, &old_path, &new_path) {
+            fs::write(&file, updated).with_context(|| format!("Failed to write {:?}", file))?;
+            println!("Updated links in {:?}", file);
+        }
+    }
+
+    let outputs_after = plan_generated_files(generate, &agents)?;
+    remove_stale_outputs(generate, &agents, &outputs_before, &outputs_after)?;
+
+    run_generation(generate, RunMode::Write)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that links to the renamed rule are rewritten relative to the linking rule.
+    #[test]
+    fn test_rewrite_links() {
+        let old_path = Path::new("style.md");
+        let new_path = Path::new("frontend/conventions.md");
+
+        let updated = rewrite_links(
+            "See [style](style.md#naming) and [other](other.md), or <https://x.org/style.md>.",
+            Path::new("rust.md"),
+            old_path,
+            new_path,
+        );
+        assert_eq!(
+            updated.as_deref(),
+            Some("See [style](frontend/conventions.md#naming) and [other](other.md), or <https://x.org/style.md>.")
+        );
+
+        let updated = rewrite_links(
+            "[style](../style.md)",
+            Path::new("frontend/react.md"),
+            old_path,
+            new_path,
+        );
+        assert_eq!(updated.as_deref(), Some("[style](conventions.md)"));
+
+        assert_eq!(
+            rewrite_links("No links.", Path::new("rust.md"), old_path, new_path),
+            None
+        );
+    }
+
+    /// Test relative paths between directories of the rules directory.
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path(Path::new("a/b"), Path::new("a/c/d.md")),
+            PathBuf::from("../c/d.md")
+        );
+        assert_eq!(
+            relative_path(Path::new(""), Path::new("d.md")),
+            PathBuf::from("d.md")
+        );
+    }
+
+    /// Test that rules are found by name or by path, and that ambiguous names fail.
+    #[test]
+    fn test_find_rule() {
+        let rule = |name: &str, relative_dir: &str| UniversalRule {
+            name: name.to_string(),
+            relative_dir: PathBuf::from(relative_dir),
+            ..Default::default()
+        };
+        let rules = vec![
+            rule("style", ""),
+            rule("style", "frontend"),
+            rule("rust", ""),
+        ];
+
+        assert_eq!(find_rule(&rules, "rust").unwrap().name, "rust");
+        assert_eq!(
+            find_rule(&rules, "frontend/style").unwrap().relative_dir,
+            PathBuf::from("frontend")
+        );
+        assert!(find_rule(&rules, "style").is_err());
+        assert!(find_rule(&rules, "missing").is_err());
+    }
+}
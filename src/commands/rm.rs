@@ -0,0 +1,59 @@
+// src/commands/rm.rs
+
+use super::rename::{find_rule, plan_generated_files, remove_stale_outputs};
+use crate::cli::{resolve_agents, run_generation, GenerateArgs, RunMode};
+use crate::config;
+use crate::exit_code::UsageError;
+use crate::rule_parser::discover_rules;
+use anyhow::{bail, Context, Result};
+use clap::Args;
+use std::fs;
+
+/// Arguments for `urules rm`.
+#[derive(Args, Debug)]
+pub(crate) struct RmArgs {
+    /// The rule to delete.
+    #[clap(
+        value_parser,
+        help = "The rule to delete: its name, or its path in the rules directory without .md."
+    )]
+    pub name: String,
+
+    /// Options for regenerating the outputs after the deletion.
+    #[clap(flatten)]
+    pub generate: GenerateArgs,
+}
+
+/// Executes `urules rm`: deletes a rule file, removes the outputs only that rule produced
+/// (e.g. its `.cursor/rules/<name>.mdc`) and regenerates the remaining outputs.
+///
+/// Outputs that were edited since they were generated are kept, with a warning.
+pub(crate) fn remove_rule(args: &RmArgs) -> Result<()> {
+    let generate = &args.generate;
+    let agents = resolve_agents(generate);
+    if agents.is_empty() {
+        bail!(UsageError(format!(
+            "No agent selected to regenerate; pass --agent or set `agents` in {}",
+            config::CONFIG_FILE
+        )));
+    }
+    if !generate.rules_dir.is_dir() {
+        bail!(UsageError(format!(
+            "Rules directory {:?} does not exist",
+            generate.rules_dir
+        )));
+    }
+
+    let (rules, _) = discover_rules(&generate.rules_dir, &generate.discovery)?;
+    let rule_file = generate
+        .rules_dir
+        .join(find_rule(&rules, &args.name)?.relative_path());
+
+    let outputs_before = plan_generated_files(generate, &agents)?;
+    fs::remove_file(&rule_file).with_context(|| format!("Failed to remove {:?}", rule_file))?;
+    println!("Removed {:?}", rule_file);
+    let outputs_after = plan_generated_files(generate, &agents)?;
+    remove_stale_outputs(generate, &agents, &outputs_before, &outputs_after)?;
+
+    run_generation(generate, RunMode::Write)
+}
@@ -0,0 +1,139 @@
+// src/commands/show.rs
+
+use super::rename::find_rule;
+use crate::cli::{
+    generate_agent, load_custom_definition, resolve_agents, resolve_windsurf_global_dir, with_vars,
+    GenerateArgs,
+};
+use crate::color;
+use crate::converters::registry::coding_agents;
+use crate::dry_run::OutputPlan;
+use crate::exit_code::UsageError;
+use crate::ignore_files::plan_ignore_files;
+use crate::rule_parser::discover_rules;
+use crate::universal_rule::UniversalRule;
+use crate::AgentName;
+use anyhow::{bail, Result};
+use clap::Args;
+use std::path::{Path, PathBuf};
+
+/// Arguments for `urules show`.
+#[derive(Args, Debug)]
+pub(crate) struct ShowArgs {
+    /// The rule to render.
+    #[clap(
+        value_parser,
+        help = "The rule to render: its name, or its path in the rules directory without .md."
+    )]
+    pub name: String,
+
+    /// Options controlling how the rule is rendered.
+    #[clap(flatten)]
+    pub generate: GenerateArgs,
+}
+
+/// Generates `rule` alone for `agent` in memory, and returns the files it produces, as
+/// paths relative to the output directory (or to the Windsurf global rules directory)
+/// paired with their content. The `.gitignore` is left out.
+pub(crate) fn render_rule(
+    args: &GenerateArgs,
+    agent: &AgentName,
+    rule: &UniversalRule,
+) -> Result<Vec<(PathBuf, String)>> {
+    let agents = [agent.clone()];
+    let custom_definition = load_custom_definition(args, &agents)?;
+    let global_dir = resolve_windsurf_global_dir(args)?;
+    let rules = with_vars(args, vec![rule.clone()])?;
+    let mut plan = OutputPlan::default();
+    let outputs = generate_agent(
+        args,
+        agent,
+        &rules,
+        custom_definition.as_ref(),
+        &args.output_dir,
+        global_dir.as_deref(),
+        &plan,
+    )?;
+    for warning in &outputs.warnings {
+        eprintln!("{} {}", color::warning_label(), warning);
+    }
+    plan_ignore_files(&mut plan, &args.output_dir, agent, &rules)?;
+    plan.files.extend(outputs.files);
+
+    let mut files = Vec::new();
+    for root in std::iter::once(&args.output_dir).chain(&global_dir) {
+        for (path, content) in &plan.files {
+            let Ok(relative_path) = path.strip_prefix(root) else {
+                continue;
+            };
+            if files
+                .iter()
+                .any(|(file, _): &(PathBuf, String)| file == relative_path)
+            {
+                continue;
+            }
+            files.push((
+                relative_path.to_path_buf(),
+                String::from_utf8_lossy(content).into_owned(),
+            ));
+        }
+    }
+    Ok(files)
+}
+
+/// Formats one generated file as a section headed like `head` heads several files.
+pub(crate) fn file_section(agent: &AgentName, path: &Path, content: &str) -> String {
+    let newline = if content.ends_with('\n') { "" } else { "\n" };
+    format!(
+        "==> {}: {} <==\n{}{}",
+        agent,
+        path.display(),
+        content,
+        newline
+    )
+}
+
+/// Executes `urules show`: prints the files a rule produces for each selected agent, as
+/// generated from that rule alone.
+///
+/// Without `--agent`, the rule is shown for every built-in agent it targets.
+pub(crate) fn show_rule(args: &ShowArgs) -> Result<()> {
+    let generate = &args.generate;
+    if !generate.rules_dir.is_dir() {
+        bail!(UsageError(format!(
+            "Rules directory {:?} does not exist",
+            generate.rules_dir
+        )));
+    }
+    let (rules, _) = discover_rules(&generate.rules_dir, &generate.discovery)?;
+    let rule = find_rule(&rules, &args.name)?;
+
+    let mut agents = resolve_agents(generate);
+    if agents.is_empty() {
+        agents = coding_agents()
+            .into_iter()
+            .filter(|agent| rule.applies_to(agent))
+            .collect();
+    }
+
+    // Sections are printed like `head` prints several files
+    let mut sections = Vec::new();
+    for agent in &agents {
+        if !agent.is_export() && !rule.applies_to(agent) {
+            sections.push(format!(
+                "==> {}: not targeted by the rule's `agents` list <==\n",
+                agent
+            ));
+            continue;
+        }
+        let files = render_rule(generate, agent, rule)?;
+        if files.is_empty() {
+            sections.push(format!("==> {}: no output <==\n", agent));
+        }
+        for (path, content) in files {
+            sections.push(file_section(agent, &path, &content));
+        }
+    }
+    print!("{}", sections.join("\n"));
+    Ok(())
+}
@@ -0,0 +1,420 @@
+// src/config.rs
+
+use crate::converters::claude::ClaudeFormat;
+use crate::converters::copilot::CopilotFormat;
+use crate::converters::cursor::CursorFormat;
+use crate::converters::windsurf::WindsurfFormat;
+use crate::converters::OversizeMode;
+use crate::exit_code::UsageError;
+use crate::line_endings::LineEndings;
+use crate::secrets::SecretsMode;
+use crate::universal_rule::AutoDescription;
+use crate::AgentName;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the TOML project configuration file.
+pub const CONFIG_FILE: &str = "urules.toml";
+
+/// The name of the YAML project configuration file, an alternative to `urules.toml`.
+pub const YAML_CONFIG_FILE: &str = ".urules.yaml";
+
+/// Cursor options of the project configuration (`[cursor]`).
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CursorConfig {
+    /// Default for `--cursor-format`.
+    pub format: Option<CursorFormat>,
+
+    /// Default for `--cursor-nested`.
+    pub nested: Option<bool>,
+}
+
+/// Claude options of the project configuration (`[claude]`).
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ClaudeConfig {
+    /// Default for `--claude-format`.
+    pub format: Option<ClaudeFormat>,
+}
+
+/// Copilot options of the project configuration (`[copilot]`).
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CopilotConfig {
+    /// Default for `--copilot-format`.
+    pub format: Option<CopilotFormat>,
+}
+
+/// Windsurf options of the project configuration (`[windsurf]`).
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct WindsurfConfig {
+    /// Default for `--windsurf-format`.
+    pub format: Option<WindsurfFormat>,
+
+    /// Default for `--windsurf-nested`.
+    pub nested: Option<bool>,
+
+    /// Default for `--windsurf-global-dir <DIR>`.
+    pub global_dir: Option<PathBuf>,
+}
+
+/// The project configuration read from `urules.toml` or `.urules.yaml`.
+///
+/// Every value is optional and only replaces the built-in default of the matching
+/// command-line option; options given on the command line always win.
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// Default for `--rules-dir`.
+    pub rules_dir: Option<PathBuf>,
+
+    /// Default for `--output-dir`.
+    pub output_dir: Option<PathBuf>,
+
+    /// Output directories of single agents (e.g. `cursor = "packages/web"`), used instead
+    /// of `output_dir` unless `--output-dir` is given.
+    pub output_dirs: Option<BTreeMap<AgentName, PathBuf>>,
+
+    /// Agents generated when `--agent` is not given.
+    pub agents: Option<Vec<AgentName>>,
+
+    /// Default for `--no-gitignore`.
+    pub no_gitignore: Option<bool>,
+
+    /// Default for `--deny-unknown-keys`.
+    pub deny_unknown_keys: Option<bool>,
+
+    /// Default for `--env-interpolation`.
+    pub env_interpolation: Option<bool>,
+
+    /// Default for `--auto-description`.
+    pub auto_description: Option<AutoDescription>,
+
+    /// Default for `--keep-comments`.
+    pub keep_comments: Option<bool>,
+
+    /// Default for `--owner-comments`.
+    pub owner_comments: Option<bool>,
+
+    /// Default for `--reproducible`.
+    pub reproducible: Option<bool>,
+
+    /// Default for `--oversized`.
+    pub oversized: Option<OversizeMode>,
+
+    /// Default for `--minify`.
+    pub minify: Option<bool>,
+
+    /// Default for `--normalize-markdown`.
+    pub normalize_markdown: Option<bool>,
+
+    /// Default for `--banner`.
+    pub banner: Option<String>,
+
+    /// Default for `--no-banner`.
+    pub no_banner: Option<bool>,
+
+    /// Default for `--line-endings`.
+    pub line_endings: Option<LineEndings>,
+
+    /// Default for `--secrets`.
+    pub secrets: Option<SecretsMode>,
+
+    /// Default for `--custom-converter`.
+    pub custom_converter: Option<PathBuf>,
+
+    /// Globs of the languages rules list in `languages` (e.g. `rust = ["**/*.rs"]`),
+    /// replacing the built-in globs of the same language.
+    pub languages: Option<BTreeMap<String, Vec<String>>>,
+
+    /// The most tokens the rules emitted for an agent may take together (e.g.
+    /// `claude = 8000`); generation warns about agents over budget, or fails with `--strict`.
+    pub token_budgets: Option<BTreeMap<AgentName, usize>>,
+
+    /// Values for the `{{name}}` references of rule contents, overriding the rules' own
+    /// `vars` and overridden by `--set`.
+    pub vars: Option<BTreeMap<String, String>>,
+
+    /// Cursor options.
+    #[serde(default)]
+    pub cursor: CursorConfig,
+
+    /// Claude options.
+    #[serde(default)]
+    pub claude: ClaudeConfig,
+
+    /// Copilot options.
+    #[serde(default)]
+    pub copilot: CopilotConfig,
+
+    /// Windsurf options.
+    #[serde(default)]
+    pub windsurf: WindsurfConfig,
+}
+
+impl ProjectConfig {
+    /// Parses a configuration file, choosing TOML or YAML from its extension.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {:?}", path))?;
+        let is_yaml = matches!(
+            path.extension().and_then(|e| e.to_str()),
+            Some("yaml" | "yml")
+        );
+        if is_yaml {
+            // An empty YAML document is an empty configuration
+            Ok(serde_yaml::from_str::<Option<ProjectConfig>>(&content)
+                .with_context(|| format!("Failed to parse config file {:?}", path))?
+                .unwrap_or_default())
+        } else {
+            toml::from_str(&content)
+                .with_context(|| format!("Failed to parse config file {:?}", path))
+        }
+    }
+
+    /// Loads the configuration file in `project_dir`, if there is one.
+    ///
+    /// It is an error for both `urules.toml` and `.urules.yaml` to exist, since only one
+    /// of them would be used.
+    pub fn load(project_dir: &Path) -> Result<Option<Self>> {
+        let candidates: Vec<PathBuf> = [CONFIG_FILE, YAML_CONFIG_FILE]
+            .iter()
+            .map(|name| project_dir.join(name))
+            .filter(|path| path.is_file())
+            .collect();
+        match candidates.as_slice() {
+            [] => Ok(None),
+            [path] => Ok(Some(Self::from_file(path)?)),
+            _ => bail!(UsageError(format!(
+                "Found both {} and {} in {:?}; keep only one",
+                CONFIG_FILE, YAML_CONFIG_FILE, project_dir
+            ))),
+        }
+    }
+}
+
+/// The default rules directory, whose presence marks a project root.
+pub const DEFAULT_RULES_DIR: &str = ".rules";
+
+/// The rules directories probed, in order, when neither `--rules-dir` nor the project
+/// configuration names one.
+pub const RULES_DIR_FALLBACKS: [&str; 4] = [DEFAULT_RULES_DIR, ".urules", "rules", "docs/ai-rules"];
+
+/// Returns the first directory of `RULES_DIR_FALLBACKS` that exists in `project_dir`, as
+/// a path relative to it, or `None` if there is none.
+pub fn probe_rules_dir(project_dir: &Path) -> Option<PathBuf> {
+    RULES_DIR_FALLBACKS
+        .iter()
+        .map(PathBuf::from)
+        .find(|dir| project_dir.join(dir).is_dir())
+}
+
+/// Finds the project directory for a run started in `start_dir`.
+///
+/// Like git, this walks up from `start_dir` and returns the first directory containing
+/// a configuration file or a `.rules/` directory. The search stops at the git root (a
+/// directory containing `.git`) or the filesystem root, returning `None` if nothing
+/// was found.
+pub fn find_project_dir(start_dir: &Path) -> Option<PathBuf> {
+    for dir in start_dir.ancestors() {
+        let is_project_dir = [CONFIG_FILE, YAML_CONFIG_FILE]
+            .iter()
+            .any(|name| dir.join(name).is_file())
+            || dir.join(DEFAULT_RULES_DIR).is_dir();
+        if is_project_dir {
+            return Some(dir.to_path_buf());
+        }
+        if dir.join(".git").exists() {
+            return None;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Test that every key of a TOML configuration is parsed.
+    #[test]
+    fn test_load_toml_config() -> Result<()> {
+        let dir = tempdir()?;
+        fs::write(
+            dir.path().join(CONFIG_FILE),
+            r#"
+rules_dir = "rules"
+agents = ["cursor", "claude"]
+no_gitignore = true
+deny_unknown_keys = true
+env_interpolation = true
+auto_description = "sentence"
+keep_comments = true
+owner_comments = true
+reproducible = true
+oversized = "trim"
+minify = true
+normalize_markdown = true
+banner = "Managed by urules: {source}"
+no_banner = true
+line_endings = "crlf"
+secrets = "warn"
+
+[cursor]
+format = "legacy"
+
+[output_dirs]
+cursor = "packages/web"
+
+[windsurf]
+global_dir = "memories"
+
+[languages]
+typescript = ["web/**/*.ts"]
+
+[token_budgets]
+claude = 8000
+
+[vars]
+project = "Acme"
+"#,
+        )?;
+
+        let config = ProjectConfig::load(dir.path())?.unwrap();
+
+        assert_eq!(config.rules_dir, Some(PathBuf::from("rules")));
+        assert_eq!(
+            config.agents,
+            Some(vec![AgentName::Cursor, AgentName::Claude])
+        );
+        assert_eq!(config.no_gitignore, Some(true));
+        assert_eq!(config.deny_unknown_keys, Some(true));
+        assert_eq!(config.env_interpolation, Some(true));
+        assert_eq!(config.auto_description, Some(AutoDescription::Sentence));
+        assert_eq!(config.keep_comments, Some(true));
+        assert_eq!(config.owner_comments, Some(true));
+        assert_eq!(config.reproducible, Some(true));
+        assert_eq!(config.oversized, Some(OversizeMode::Trim));
+        assert_eq!(config.minify, Some(true));
+        assert_eq!(config.normalize_markdown, Some(true));
+        assert_eq!(
+            config.banner.as_deref(),
+            Some("Managed by urules: {source}")
+        );
+        assert_eq!(config.no_banner, Some(true));
+        assert_eq!(config.line_endings, Some(LineEndings::Crlf));
+        assert_eq!(config.secrets, Some(SecretsMode::Warn));
+        assert_eq!(config.cursor.format, Some(CursorFormat::Legacy));
+        assert_eq!(config.cursor.nested, None);
+        assert_eq!(config.windsurf.global_dir, Some(PathBuf::from("memories")));
+        assert_eq!(config.output_dir, None);
+        assert_eq!(
+            config.languages,
+            Some(BTreeMap::from([(
+                "typescript".to_string(),
+                vec!["web/**/*.ts".to_string()]
+            )]))
+        );
+        assert_eq!(
+            config.token_budgets,
+            Some(BTreeMap::from([(AgentName::Claude, 8000)]))
+        );
+        assert_eq!(
+            config.vars,
+            Some(BTreeMap::from([(
+                "project".to_string(),
+                "Acme".to_string()
+            )]))
+        );
+        assert_eq!(
+            config.output_dirs,
+            Some(BTreeMap::from([(
+                AgentName::Cursor,
+                PathBuf::from("packages/web")
+            )]))
+        );
+        Ok(())
+    }
+
+    /// Test that `.urules.yaml` is read with the same keys.
+    #[test]
+    fn test_load_yaml_config() -> Result<()> {
+        let dir = tempdir()?;
+        fs::write(
+            dir.path().join(YAML_CONFIG_FILE),
+            "output_dir: out\nclaude:\n  format: imports\n",
+        )?;
+
+        let config = ProjectConfig::load(dir.path())?.unwrap();
+
+        assert_eq!(config.output_dir, Some(PathBuf::from("out")));
+        assert_eq!(config.claude.format, Some(ClaudeFormat::Imports));
+        Ok(())
+    }
+
+    /// Test that a missing config is not an error, while unknown keys and ambiguous
+    /// config files are.
+    #[test]
+    fn test_load_config_errors() -> Result<()> {
+        let dir = tempdir()?;
+        assert_eq!(ProjectConfig::load(dir.path())?, None);
+
+        fs::write(dir.path().join(CONFIG_FILE), "rule_dir = \"rules\"\n")?;
+        assert!(ProjectConfig::load(dir.path()).is_err());
+
+        fs::write(dir.path().join(CONFIG_FILE), "")?;
+        fs::write(dir.path().join(YAML_CONFIG_FILE), "")?;
+        let error = ProjectConfig::load(dir.path()).unwrap_err();
+        assert!(error.to_string().contains("keep only one"));
+        Ok(())
+    }
+
+    /// Test that the project directory is found from a subdirectory, and that the
+    /// search stops at the git root.
+    #[test]
+    fn test_find_project_dir() -> Result<()> {
+        let dir = tempdir()?;
+        let repo = dir.path().join("repo");
+        let nested = repo.join("src").join("module");
+        fs::create_dir_all(&nested)?;
+        fs::create_dir_all(repo.join(".git"))?;
+
+        // Nothing inside the repository: a `.rules/` above the git root is ignored
+        fs::create_dir_all(dir.path().join(DEFAULT_RULES_DIR))?;
+        assert_eq!(find_project_dir(&nested), None);
+
+        fs::create_dir_all(repo.join(DEFAULT_RULES_DIR))?;
+        assert_eq!(find_project_dir(&nested), Some(repo.clone()));
+
+        fs::write(repo.join("src").join(CONFIG_FILE), "")?;
+        assert_eq!(find_project_dir(&nested), Some(repo.join("src")));
+        Ok(())
+    }
+
+    /// Test that the fallback rules directories are probed in order.
+    #[test]
+    fn test_probe_rules_dir() -> Result<()> {
+        let dir = tempdir()?;
+        assert_eq!(probe_rules_dir(dir.path()), None);
+
+        fs::create_dir_all(dir.path().join("docs/ai-rules"))?;
+        assert_eq!(
+            probe_rules_dir(dir.path()),
+            Some(PathBuf::from("docs/ai-rules"))
+        );
+
+        fs::create_dir_all(dir.path().join("rules"))?;
+        fs::create_dir_all(dir.path().join(".urules"))?;
+        assert_eq!(probe_rules_dir(dir.path()), Some(PathBuf::from(".urules")));
+
+        // A file named like a fallback is not a rules directory
+        fs::write(dir.path().join(DEFAULT_RULES_DIR), "")?;
+        assert_eq!(probe_rules_dir(dir.path()), Some(PathBuf::from(".urules")));
+        Ok(())
+    }
+}
@@ -1,154 +1,492 @@
 // src/converters/claude.rs
 
-use super::RuleConverter;
-use crate::universal_rule::UniversalRule;
-use anyhow::{Context, Result};
-use std::fs;
-use std::path::Path;
+use super::{render_rule_block, GeneratedFile, GeneratedRules, RuleConverter, RULE_SEPARATOR};
+use crate::universal_rule::{ContentType, SubagentConfig, UniversalRule};
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The directory (relative to the output directory) holding per-rule files in import mode.
+pub const CLAUDE_RULES_DIR: &str = ".claude/rules";
+
+/// The directory (relative to the output directory) holding Claude slash commands.
+pub const CLAUDE_COMMANDS_DIR: &str = ".claude/commands";
+
+/// Represents the YAML frontmatter of a Claude slash command file.
+#[derive(Serialize, Debug, Default)]
+pub struct CommandFrontmatter {
+    /// An optional description shown in Claude's slash command list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// The model the command runs with (e.g. `sonnet`); omitted to use the current one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Returns `true` if the rule becomes a slash command: Claude has no separate prompt
+/// files, so `content_type: prompt` rules are commands too.
+fn is_command(rule: &UniversalRule) -> bool {
+    matches!(
+        rule.frontmatter.content_kind(),
+        ContentType::Command | ContentType::Prompt
+    )
+}
+
+/// Renders a rule as a Claude slash command file. The frontmatter block is only
+/// included if the rule has a description or a model.
+fn render_command_file(rule: &UniversalRule) -> Result<String> {
+    if rule.frontmatter.description.is_none() && rule.frontmatter.model.is_none() {
+        return Ok(rule.content.clone());
+    }
+    let frontmatter = CommandFrontmatter {
+        description: rule.frontmatter.description.clone(),
+        model: rule.frontmatter.model.clone(),
+    };
+    let frontmatter_yaml = serde_yaml::to_string(&frontmatter).with_context(|| {
+        format!(
+            "Failed to serialize command frontmatter for rule: {}",
+            rule.name
+        )
+    })?;
+    Ok(format!(
+        "---\n{}\n---\n{}",
+        frontmatter_yaml.trim_end(),
+        rule.content
+    ))
+}
+
+/// The directory (relative to the output directory) holding Claude subagent definitions.
+pub const CLAUDE_AGENTS_DIR: &str = ".claude/agents";
+
+/// Represents the YAML frontmatter of a Claude subagent definition file.
+#[derive(Serialize, Debug)]
+pub struct SubagentFrontmatter {
+    /// The subagent's identifier.
+    pub name: String,
+
+    /// When Claude should delegate to this subagent.
+    pub description: String,
+
+    /// Comma-separated list of tools (e.g. `Read, Grep`); omitted to inherit all tools.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<String>,
+
+    /// The model the subagent runs with (e.g. `sonnet`); omitted to inherit the model of
+    /// the main conversation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Renders a rule as a Claude subagent definition. The subagent's name and description
+/// fall back to the rule's name and description; a description is required by Claude.
+fn render_subagent_file(
+    rule: &UniversalRule,
+    subagent: &SubagentConfig,
+    alias: Option<&str>,
+) -> Result<String> {
+    let description = subagent
+        .description
+        .clone()
+        .or_else(|| rule.frontmatter.description.clone())
+        .ok_or_else(|| {
+            anyhow!(
+                "Subagent rule '{}' needs a description (set `subagent.description` or `description`)",
+                rule.name
+            )
+        })?;
+    let frontmatter = SubagentFrontmatter {
+        name: alias
+            .map(str::to_string)
+            .or_else(|| subagent.name.clone())
+            .unwrap_or_else(|| rule.name.clone()),
+        description,
+        tools: subagent.tools.as_ref().map(|tools| tools.join(", ")),
+        model: rule.frontmatter.model.clone(),
+    };
+    let frontmatter_yaml = serde_yaml::to_string(&frontmatter).with_context(|| {
+        format!(
+            "Failed to serialize subagent frontmatter for rule: {}",
+            rule.name
+        )
+    })?;
+    Ok(format!(
+        "---\n{}\n---\n{}",
+        frontmatter_yaml.trim_end(),
+        rule.content
+    ))
+}
+
+/// The prefix of the level 1 headings grouping the rules of a category in `CLAUDE.md`.
+pub const CATEGORY_HEADING_PREFIX: &str = "# Category: ";
+
+/// Renders a rule like `render_rule_block`, with its heading at the level set by its
+/// `claude.heading_level` (2 by default).
+fn render_claude_block(rule: &UniversalRule) -> String {
+    let block = render_rule_block(rule);
+    match rule
+        .frontmatter
+        .claude
+        .as_ref()
+        .and_then(|c| c.heading_level)
+    {
+        // `render_rule_block` starts with the level 2 `## ` marker
+        Some(level) => format!("{}{}", "#".repeat(usize::from(level)), &block[2..]),
+        None => block,
+    }
+}
+
+/// Returns the level 1 heading grouping the rule in `CLAUDE.md`: `# {section}` for rules
+/// with a `claude.section`, `# Category: {category}` for rules in a subdirectory of the
+/// rules directory, and `None` otherwise.
+fn group_heading(rule: &UniversalRule) -> Option<String> {
+    match rule
+        .frontmatter
+        .claude
+        .as_ref()
+        .and_then(|c| c.section.as_ref())
+    {
+        Some(section) => Some(format!("# {}", section)),
+        None => rule
+            .category()
+            .map(|category| format!("{}{}", CATEGORY_HEADING_PREFIX, category)),
+    }
+}
+
+/// Concatenates rules like `concatenate_rules`, grouped by section or category: the rules
+/// without a group heading (see `group_heading`) come first, then the rules of each group
+/// under its heading, in the order the groups first appear.
+fn concatenate_grouped(rules: Vec<&UniversalRule>) -> String {
+    let mut groups: Vec<(Option<String>, Vec<&UniversalRule>)> = vec![(None, Vec::new())];
+    for rule in rules {
+        let heading = group_heading(rule);
+        match groups.iter_mut().find(|(h, _)| *h == heading) {
+            Some((_, group)) => group.push(rule),
+            None => groups.push((heading, vec![rule])),
+        }
+    }
+    groups
+        .into_iter()
+        .filter(|(_, group)| !group.is_empty())
+        .map(|(heading, group)| {
+            let blocks = group
+                .into_iter()
+                .map(render_claude_block)
+                .collect::<Vec<String>>()
+                .join(RULE_SEPARATOR);
+            match heading {
+                Some(heading) => format!("{}\n\n{}", heading, blocks),
+                None => blocks,
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(RULE_SEPARATOR)
+}
+
+/// Groups rules by their `scope` directory, in the order the scopes first appear, with
+/// the rules without a scope first.
+fn group_by_scope(rules: Vec<&UniversalRule>) -> Vec<(PathBuf, Vec<&UniversalRule>)> {
+    let mut groups: Vec<(PathBuf, Vec<&UniversalRule>)> = vec![(PathBuf::new(), Vec::new())];
+    for rule in rules {
+        let scope = rule.scope_dir();
+        match groups.iter_mut().find(|(s, _)| *s == scope) {
+            Some((_, group)) => group.push(rule),
+            None => groups.push((scope, vec![rule])),
+        }
+    }
+    groups.retain(|(_, group)| !group.is_empty());
+    groups
+}
+
+/// The layout of the files generated by the `ClaudeConverter`.
+#[derive(ValueEnum, Clone, Debug, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClaudeFormat {
+    /// All rules concatenated into a single `CLAUDE.md`.
+    #[default]
+    Concat,
+    /// One file per rule in `.claude/rules/`, pulled into `CLAUDE.md` with `@` imports.
+    Imports,
+}
 
 /// A `RuleConverter` implementation for generating a single `CLAUDE.md` file.
 ///
 /// This converter concatenates all provided universal rules into a single Markdown
 /// file, suitable for use as a comprehensive prompt or knowledge base for Claude.
 /// Each rule's name and description (if available) are included as headings.
-pub struct ClaudeConverter;
+///
+/// With `ClaudeFormat::Imports`, each rule is written to its own file under
+/// `.claude/rules/` and `CLAUDE.md` only contains `@` import lines, which keeps the
+/// main file small while Claude still loads every rule.
+///
+/// The converter can emit several kinds of artifacts, routed by each rule's frontmatter:
+/// rules with a `subagent:` block become subagent definitions in `.claude/agents/`,
+/// rules with `content_type: command` or `prompt` become slash commands in
+/// `.claude/commands/`, and all remaining rules (including `knowledge`) go into `CLAUDE.md`.
+#[derive(Default)]
+pub struct ClaudeConverter {
+    /// Whether to concatenate rules into `CLAUDE.md` or import them from separate files.
+    pub format: ClaudeFormat,
+}
+
+impl ClaudeConverter {
+    /// Generates each command rule as `.claude/commands/<name>.md`, and a copy per alias.
+    fn generate_commands(
+        &self,
+        commands: &[&UniversalRule],
+        output_dir: &Path,
+    ) -> Result<Vec<GeneratedFile>> {
+        let commands_dir = output_dir.join(CLAUDE_COMMANDS_DIR);
+        let mut files = Vec::new();
+        for rule in commands {
+            let command_content = render_command_file(rule)?;
+            for stem in rule.output_stems() {
+                files.push(GeneratedFile::new(
+                    commands_dir.join(format!("{}.md", stem)),
+                    command_content.clone(),
+                ));
+            }
+        }
+        Ok(files)
+    }
+
+    /// Generates each subagent rule as `.claude/agents/<name>.md`, and a copy per alias
+    /// named after the alias, so that Claude sees no two subagents with the same name.
+    fn generate_subagents(
+        &self,
+        subagents: &[&UniversalRule],
+        output_dir: &Path,
+    ) -> Result<Vec<GeneratedFile>> {
+        let agents_dir = output_dir.join(CLAUDE_AGENTS_DIR);
+        let mut files = Vec::new();
+        for rule in subagents {
+            let Some(subagent) = &rule.frontmatter.subagent else {
+                continue;
+            };
+            files.push(GeneratedFile::new(
+                agents_dir.join(format!("{}.md", rule.output_stem())),
+                render_subagent_file(rule, subagent, None)?,
+            ));
+            for alias in &rule.output_stems()[1..] {
+                files.push(GeneratedFile::new(
+                    agents_dir.join(format!("{}.md", alias)),
+                    render_subagent_file(rule, subagent, Some(alias))?,
+                ));
+            }
+        }
+        Ok(files)
+    }
+
+    /// Generates each rule as `.claude/rules/<name>.md` plus a copy per alias, and a
+    /// `CLAUDE.md` importing each rule once.
+    fn generate_imported_rules(
+        &self,
+        rules: &[&UniversalRule],
+        output_dir: &Path,
+    ) -> Vec<GeneratedFile> {
+        let claude_rules_dir = output_dir.join(CLAUDE_RULES_DIR);
+        let mut files = Vec::new();
+        let mut import_lines = Vec::new();
+        for &rule in rules {
+            let block = render_claude_block(rule);
+            for stem in rule.output_stems() {
+                files.push(GeneratedFile::new(
+                    claude_rules_dir.join(format!("{}.md", stem)),
+                    block.clone(),
+                ));
+            }
+            // Imports are resolved relative to CLAUDE.md, which sits in `output_dir`.
+            import_lines.push(format!("@{}/{}.md", CLAUDE_RULES_DIR, rule.output_stem()));
+        }
+        files.push(GeneratedFile::new(
+            output_dir.join("CLAUDE.md"),
+            import_lines.join("\n"),
+        ));
+        files
+    }
+}
 
 impl RuleConverter for ClaudeConverter {
     /// Generates a `CLAUDE.md` file by concatenating all `UniversalRule`s.
     ///
-    /// If no rules are provided, no file is created. Otherwise, each rule's name
-    /// is added as a Level 2 Markdown heading (`## Rule: {name}`), followed by its
-    /// description (if any) and then its content. Rules are separated by a
-    /// Markdown horizontal rule (`\n\n---\n\n`).
+    /// If no rules are provided, no file is generated. Otherwise, each rule's name
+    /// is added as a Level 2 Markdown heading (`## Rule: {title}`, or the level of its
+    /// `claude.heading_level`), followed by its description (if any) and then its content.
+    /// Rules are separated by a Markdown horizontal rule (`\n\n---\n\n`). Rules with a
+    /// `claude.section` are grouped under a `# {section}` heading per section, and other
+    /// rules from subdirectories of the rules directory under a `# Category: {category}`
+    /// heading per subdirectory.
     /// The output file is named `CLAUDE.md` and placed in the specified `output_dir`.
-    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<()> {
-        if rules.is_empty() {
-            // Do not create an empty CLAUDE.md if there are no rules to process.
-            return Ok(());
-        }
-
-        let mut claude_content_parts = Vec::new();
-
-        for rule in rules {
-            let mut rule_block = String::new();
-            // Add rule name as a heading
-            rule_block.push_str(&format!("## Rule: {}\n", rule.name));
-            // Add description if available, followed by a blank line
-            if let Some(desc) = &rule.frontmatter.description {
-                rule_block.push_str(&format!("{}\n\n", desc));
-            } else {
-                // Ensure a blank line after the name heading even if no description
-                rule_block.push('\n');
+    ///
+    /// With `ClaudeFormat::Imports`, the same per-rule blocks go to individual
+    /// files instead, and `CLAUDE.md` lists one `@.claude/rules/<name>.md` import per rule.
+    ///
+    /// Rules with a `scope` go to the `CLAUDE.md` of their scope directory instead, which
+    /// Claude loads when working in that directory.
+    ///
+    /// Subagent rules go to `.claude/agents/` and command rules to `.claude/commands/`.
+    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<GeneratedRules> {
+        let (subagents, other_rules): (Vec<&UniversalRule>, Vec<&UniversalRule>) = rules
+            .iter()
+            .partition(|rule| rule.frontmatter.subagent.is_some());
+        let (commands, instruction_rules): (Vec<&UniversalRule>, Vec<&UniversalRule>) =
+            other_rules.into_iter().partition(|rule| is_command(rule));
+
+        let mut files = self.generate_subagents(&subagents, output_dir)?;
+        files.extend(self.generate_commands(&commands, output_dir)?);
+
+        // Each scope gets its own CLAUDE.md, and no empty CLAUDE.md is created.
+        for (scope, scope_rules) in group_by_scope(instruction_rules) {
+            let claude_dir = output_dir.join(scope);
+            if self.format == ClaudeFormat::Imports {
+                files.extend(self.generate_imported_rules(&scope_rules, &claude_dir));
+                continue;
             }
-            // Add the main rule content
-            rule_block.push_str(&rule.content);
-            claude_content_parts.push(rule_block);
+            files.push(GeneratedFile::new(
+                claude_dir.join("CLAUDE.md"),
+                concatenate_grouped(scope_rules),
+            ));
         }
 
-        // Join all individual rule blocks with a Markdown separator.
-        // This also handles the case of a single rule (no separator needed).
-        let final_claude_content = claude_content_parts.join("\n\n---\n\n");
-
-        fs::write(output_dir.join("CLAUDE.md"), final_claude_content)
-            .with_context(|| format!("Failed to write CLAUDE.md to {:?}", output_dir))?;
+        Ok(files.into())
+    }
 
-        Ok(())
+    /// Linked assets are copied to `.claude/assets/`.
+    fn assets_dir(&self) -> Option<PathBuf> {
+        Some(PathBuf::from(".claude/assets"))
     }
 
     /// Provides a description of where the Claude rules file is generated.
     fn get_output_description(&self, output_dir: &Path) -> String {
+        if self.format == ClaudeFormat::Imports {
+            return format!(
+                "Claude rules in {:?} (imported from {:?})",
+                output_dir.join("CLAUDE.md"),
+                output_dir.join(CLAUDE_RULES_DIR)
+            );
+        }
         format!("Claude rules in {:?}", output_dir.join("CLAUDE.md"))
     }
+
+    /// Explains which Claude artifact the rule becomes, like `generate_rules`.
+    fn explain_activation(&self, rule: &UniversalRule, output_dir: &Path) -> String {
+        if rule.frontmatter.subagent.is_some() {
+            return format!(
+                "Delegated: a subagent Claude hands matching tasks to, from {:?}.",
+                output_dir
+                    .join(CLAUDE_AGENTS_DIR)
+                    .join(format!("{}.md", rule.output_stem()))
+            );
+        }
+        if is_command(rule) {
+            return format!(
+                "Manual: a slash command, run with /{}, from {:?}.",
+                rule.output_stem(),
+                output_dir
+                    .join(CLAUDE_COMMANDS_DIR)
+                    .join(format!("{}.md", rule.output_stem()))
+            );
+        }
+        let claude_dir = output_dir.join(rule.scope_dir());
+        let location = match self.format {
+            ClaudeFormat::Concat => format!("part of {:?}", claude_dir.join("CLAUDE.md")),
+            ClaudeFormat::Imports => format!(
+                "imported into {:?} from {:?}",
+                claude_dir.join("CLAUDE.md"),
+                claude_dir
+                    .join(CLAUDE_RULES_DIR)
+                    .join(format!("{}.md", rule.output_stem()))
+            ),
+        };
+        if let Some(scope) = &rule.frontmatter.scope {
+            return format!("In context when working in {:?}: {}.", scope, location);
+        }
+        let globs_note = match rule.frontmatter.globs.as_ref().filter(|g| !g.is_empty()) {
+            Some(_) => " Its globs are not used, since Claude loads the whole file.",
+            None => "",
+        };
+        format!("Always in context: {}.{}", location, globs_note)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::universal_rule::{UniversalRule, UniversalRuleFrontmatter};
-    use std::fs::File;
-    use std::io::Read;
-    use tempfile::tempdir;
-
-    /// Helper function to create `UniversalRule` instances for testing the Claude converter.
-    fn create_test_claude_rule(
-        name: &str,
-        content: &str,
-        description: Option<&str>,
-    ) -> UniversalRule {
-        UniversalRule {
-            name: name.to_string(),
-            content: content.to_string(),
-            frontmatter: UniversalRuleFrontmatter {
-                description: description.map(String::from),
-                globs: None,
-                apply_globally: false,
-                cursor_rule_type: None,
-            },
-        }
+    use crate::converters::test_rule;
+    use crate::universal_rule::{ClaudeRuleConfig, SubagentConfig};
+
+    /// Test that rules are explained by the Claude artifact they become.
+    #[test]
+    fn test_explain_claude_activation() {
+        let converter = ClaudeConverter::default();
+        let output_dir = Path::new(".");
+        let rule = test_rule("style", "").build();
+        assert_eq!(
+            converter.explain_activation(&rule, output_dir),
+            "Always in context: part of \"./CLAUDE.md\"."
+        );
+        let mut command = test_rule("deploy", "").build();
+        command.frontmatter.content_type = ContentType::Command;
+        assert!(converter
+            .explain_activation(&command, output_dir)
+            .starts_with("Manual: a slash command, run with /deploy"));
+        let mut reviewer = test_rule("reviewer", "").build();
+        reviewer.frontmatter.subagent = Some(SubagentConfig::default());
+        assert!(converter
+            .explain_activation(&reviewer, output_dir)
+            .starts_with("Delegated: a subagent"));
     }
 
     /// Test the `RuleConverter` trait implementation for `ClaudeConverter`.
     #[test]
     fn test_claude_converter_trait_impl() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = ClaudeConverter;
+        let output_path = Path::new("project");
+        let converter = ClaudeConverter::default();
 
-        let rules = vec![create_test_claude_rule(
-            "trait_rule",
-            "Trait Content",
-            Some("Trait Desc"),
-        )];
+        let rules = vec![test_rule("trait_rule", "Trait Content")
+            .description("Trait Desc")
+            .build()];
 
-        converter.generate_rules(&rules, output_path).unwrap();
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
         let claude_path = output_path.join("CLAUDE.md");
-        assert!(claude_path.exists(), "CLAUDE.md file should be created.");
-        let mut content = String::new();
-        File::open(claude_path)
-            .unwrap()
-            .read_to_string(&mut content)
-            .unwrap();
-        assert!(content.contains("## Rule: trait_rule\nTrait Desc\n\nTrait Content"));
+        assert!(
+            generated.contents(&claude_path).is_some(),
+            "CLAUDE.md file should be created."
+        );
+        let content = generated.contents(&claude_path).unwrap();
+        assert!(content.contains("## Rule: Trait rule\nTrait Desc\n\nTrait Content"));
     }
 
     /// Test generation of `CLAUDE.md` with multiple rules, checking content and separators.
     #[test]
     fn test_generate_claude_rules_multiple_rules() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = ClaudeConverter;
+        let output_path = Path::new("project");
+        let converter = ClaudeConverter::default();
 
         let rules = vec![
-            create_test_claude_rule(
-                "rule1",
-                "Content for rule 1.",
-                Some("Description for rule 1."),
-            ),
-            create_test_claude_rule("rule2", "Content for rule 2.", None), // Rule without description
-            create_test_claude_rule(
-                "rule3",
-                "Content for rule 3.",
-                Some("Description for rule 3."),
-            ),
+            test_rule("rule1", "Content for rule 1.")
+                .description("Description for rule 1.")
+                .build(),
+            test_rule("rule2", "Content for rule 2.").build(), // Rule without description
+            test_rule("rule3", "Content for rule 3.")
+                .description("Description for rule 3.")
+                .build(),
         ];
 
-        converter.generate_rules(&rules, output_path).unwrap();
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
 
         let claude_file_path = output_path.join("CLAUDE.md");
-        assert!(claude_file_path.exists());
+        assert!(generated.contents(&claude_file_path).is_some());
 
-        let mut claude_content = String::new();
-        File::open(claude_file_path)
-            .unwrap()
-            .read_to_string(&mut claude_content)
-            .unwrap();
+        let claude_content = generated.contents(&claude_file_path).unwrap();
 
         // Verify content of each rule
         assert!(claude_content
-            .contains("## Rule: rule1\nDescription for rule 1.\n\nContent for rule 1."));
-        assert!(claude_content.contains("## Rule: rule2\n\nContent for rule 2.")); // Expect double newline after name for no-description rule
+            .contains("## Rule: Rule1\nDescription for rule 1.\n\nContent for rule 1."));
+        assert!(claude_content.contains("## Rule: Rule2\n\nContent for rule 2.")); // Expect double newline after name for no-description rule
         assert!(claude_content
-            .contains("## Rule: rule3\nDescription for rule 3.\n\nContent for rule 3."));
+            .contains("## Rule: Rule3\nDescription for rule 3.\n\nContent for rule 3."));
 
         // Verify separator presence
         assert!(
@@ -158,8 +496,8 @@ mod tests {
 
         // Verify order and exact structure of a segment
         let expected_block_for_rule1 =
-            "## Rule: rule1\nDescription for rule 1.\n\nContent for rule 1.";
-        let expected_block_for_rule2 = "## Rule: rule2\n\nContent for rule 2.";
+            "## Rule: Rule1\nDescription for rule 1.\n\nContent for rule 1.";
+        let expected_block_for_rule2 = "## Rule: Rule2\n\nContent for rule 2.";
         assert!(claude_content.contains(&format!(
             "{}\n\n---\n\n{}",
             expected_block_for_rule1, expected_block_for_rule2
@@ -169,84 +507,328 @@ mod tests {
     /// Test generation with a single rule, ensuring no separators are added.
     #[test]
     fn test_generate_claude_rules_single_rule() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = ClaudeConverter;
-        let rules = vec![create_test_claude_rule(
-            "single_rule",
-            "Single rule content.",
-            Some("Desc for single."),
-        )];
-        converter.generate_rules(&rules, output_path).unwrap();
+        let output_path = Path::new("project");
+        let converter = ClaudeConverter::default();
+        let rules = vec![test_rule("single_rule", "Single rule content.")
+            .description("Desc for single.")
+            .build()];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
 
         let claude_file_path = output_path.join("CLAUDE.md");
-        assert!(claude_file_path.exists());
-        let mut claude_content = String::new();
-        File::open(claude_file_path)
-            .unwrap()
-            .read_to_string(&mut claude_content)
-            .unwrap();
+        assert!(generated.contents(&claude_file_path).is_some());
+        let claude_content = generated.contents(&claude_file_path).unwrap();
 
         assert!(claude_content
-            .contains("## Rule: single_rule\nDesc for single.\n\nSingle rule content."));
+            .contains("## Rule: Single rule\nDesc for single.\n\nSingle rule content."));
         assert!(
             !claude_content.contains("\n\n---\n\n"),
             "Separator should not be present for a single rule."
         );
     }
 
-    /// Test behavior when no rules are provided; expects no file to be created.
-    #[test]
-    fn test_generate_claude_rules_no_rules() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = ClaudeConverter;
-        let rules: Vec<UniversalRule> = vec![]; // Empty rule set
-
-        converter.generate_rules(&rules, output_path).unwrap();
-
-        let claude_file_path = output_path.join("CLAUDE.md");
-        assert!(
-            !claude_file_path.exists(),
-            "CLAUDE.md should not be created if no rules are provided."
-        );
-    }
-
     /// Test the specific formatting of rule name and description (with and without description).
     #[test]
     fn test_formatting_of_rule_name_and_description() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = ClaudeConverter;
+        let output_path = Path::new("project");
+        let converter = ClaudeConverter::default();
 
         // Rule with description
-        let rule_with_desc =
-            create_test_claude_rule("desc_rule", "Content here.", Some("This is a description."));
-        converter
+        let rule_with_desc = test_rule("desc_rule", "Content here.")
+            .description("This is a description.")
+            .build();
+        let generated = converter
             .generate_rules(&[rule_with_desc], output_path)
             .unwrap();
-        let mut content_with_desc = String::new();
-        File::open(output_path.join("CLAUDE.md"))
-            .unwrap()
-            .read_to_string(&mut content_with_desc)
-            .unwrap();
-        let expected_with_desc = "## Rule: desc_rule\nThis is a description.\n\nContent here.";
+        let content_with_desc = generated.contents(output_path.join("CLAUDE.md")).unwrap();
+        let expected_with_desc = "## Rule: Desc rule\nThis is a description.\n\nContent here.";
         assert_eq!(content_with_desc.trim(), expected_with_desc);
 
-        // Clean up the file for the next test case within the same function
-        fs::remove_file(output_path.join("CLAUDE.md")).unwrap();
-
         // Rule without description
-        let rule_no_desc = create_test_claude_rule("no_desc_rule", "More content.", None);
-        converter
+        let rule_no_desc = test_rule("no_desc_rule", "More content.").build();
+        let generated = converter
             .generate_rules(&[rule_no_desc], output_path)
             .unwrap();
-        let mut content_no_desc = String::new();
-        File::open(output_path.join("CLAUDE.md"))
+        let content_no_desc = generated.contents(output_path.join("CLAUDE.md")).unwrap();
+        let expected_no_desc = "## Rule: No desc rule\n\nMore content."; // Note the expected double newline
+        assert_eq!(content_no_desc.trim(), expected_no_desc);
+    }
+
+    /// Test import mode: one file per rule and alias plus a `CLAUDE.md` made of `@`
+    /// imports, which imports aliased rules once.
+    #[test]
+    fn test_generate_claude_rules_imports() {
+        let output_path = Path::new("project");
+        let converter = ClaudeConverter {
+            format: ClaudeFormat::Imports,
+        };
+
+        let mut rule2 = test_rule("rule2", "Content for rule 2.").build();
+        rule2.frontmatter.aliases = Some(vec!["second".to_string()]);
+        let rules = vec![
+            test_rule("rule1", "Content for rule 1.")
+                .description("Description 1.")
+                .build(),
+            rule2,
+        ];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let claude_content = generated.contents(output_path.join("CLAUDE.md")).unwrap();
+        assert_eq!(
+            claude_content,
+            "@.claude/rules/rule1.md\n@.claude/rules/rule2.md"
+        );
+
+        let rules_dir = output_path.join(".claude").join("rules");
+        assert_eq!(
+            generated.contents(rules_dir.join("rule1.md")).unwrap(),
+            "## Rule: Rule1\nDescription 1.\n\nContent for rule 1."
+        );
+        assert_eq!(
+            generated.contents(rules_dir.join("rule2.md")).unwrap(),
+            "## Rule: Rule2\n\nContent for rule 2."
+        );
+        assert_eq!(
+            generated.contents(rules_dir.join("second.md")).unwrap(),
+            "## Rule: Rule2\n\nContent for rule 2."
+        );
+    }
+
+    /// Test that the `title` field names the heading while the file name still names
+    /// the output file.
+    #[test]
+    fn test_generate_claude_rules_with_title() {
+        let output_path = Path::new("project");
+        let mut titled = test_rule("rust_style", "Use rustfmt.").build();
+        titled.frontmatter.title = Some("Rust Style Guide".to_string());
+        let untitled = test_rule("error-handling", "Use anyhow.").build();
+
+        let generated = ClaudeConverter::default()
+            .generate_rules(&[titled.clone(), untitled], output_path)
+            .unwrap();
+        let claude_content = generated.contents(output_path.join("CLAUDE.md")).unwrap();
+        assert!(claude_content.starts_with("## Rule: Rust Style Guide\n"));
+        assert!(claude_content.contains("## Rule: Error handling\n"));
+
+        let converter = ClaudeConverter {
+            format: ClaudeFormat::Imports,
+        };
+        let generated = converter.generate_rules(&[titled], output_path).unwrap();
+        let rule_file = output_path
+            .join(".claude")
+            .join("rules")
+            .join("rust_style.md");
+        assert!(generated
+            .contents(rule_file)
             .unwrap()
-            .read_to_string(&mut content_no_desc)
+            .starts_with("## Rule: Rust Style Guide\n"));
+    }
+
+    /// Test that rules from subdirectories are grouped under a heading per category.
+    #[test]
+    fn test_generate_claude_rules_grouped_by_category() {
+        let output_path = Path::new("project");
+        let category_rule = |name: &str, category: &str| {
+            let mut rule = test_rule(name, "Content.").build();
+            rule.relative_dir = PathBuf::from(category);
+            rule
+        };
+        let rules = vec![
+            category_rule("react", "frontend"),
+            test_rule("style", "Content.").build(),
+            category_rule("api", "backend"),
+            category_rule("vue", "frontend"),
+        ];
+
+        let generated = ClaudeConverter::default()
+            .generate_rules(&rules, output_path)
             .unwrap();
-        let expected_no_desc = "## Rule: no_desc_rule\n\nMore content."; // Note the expected double newline
-        assert_eq!(content_no_desc.trim(), expected_no_desc);
+        let claude_content = generated.contents(output_path.join("CLAUDE.md")).unwrap();
+        assert_eq!(
+            claude_content,
+            "## Rule: Style\n\nContent.\n\n---\n\n# Category: frontend\n\n## Rule: React\n\nContent.\n\n---\n\n## Rule: Vue\n\nContent.\n\n---\n\n# Category: backend\n\n## Rule: Api\n\nContent."
+        );
+    }
+
+    /// Test that `claude.section` groups rules under its heading, ahead of their category,
+    /// and that `claude.heading_level` sets the level of their heading.
+    #[test]
+    fn test_generate_claude_rules_sections() {
+        let output_path = Path::new("project");
+        let claude = |section: Option<&str>, heading_level: Option<u8>| {
+            Some(ClaudeRuleConfig {
+                section: section.map(String::from),
+                heading_level,
+            })
+        };
+        let mut unit = test_rule("unit", "Content.").build();
+        unit.frontmatter.claude = claude(Some("Testing"), Some(3));
+        let mut e2e = test_rule("e2e", "Content.").build();
+        e2e.relative_dir = PathBuf::from("frontend");
+        e2e.frontmatter.claude = claude(Some("Testing"), None);
+        let mut style = test_rule("style", "Content.").build();
+        style.frontmatter.claude = claude(None, Some(1));
+
+        let generated = ClaudeConverter::default()
+            .generate_rules(&[unit, style, e2e], output_path)
+            .unwrap();
+        let claude_content = generated.contents(output_path.join("CLAUDE.md")).unwrap();
+        assert_eq!(
+            claude_content,
+            "# Rule: Style\n\nContent.\n\n---\n\n# Testing\n\n### Rule: Unit\n\nContent.\n\n---\n\n## Rule: E2e\n\nContent."
+        );
+    }
+
+    /// Test that command rules become slash commands and are left out of `CLAUDE.md`.
+    #[test]
+    fn test_generate_claude_commands() {
+        let output_path = Path::new("project");
+        let converter = ClaudeConverter::default();
+
+        let mut review = test_rule("review", "Review $ARGUMENTS.")
+            .description("Review code")
+            .build();
+        review.frontmatter.content_type = ContentType::Command;
+        let mut deploy = test_rule("deploy", "Deploy it.").build();
+        deploy.frontmatter.content_type = ContentType::Command;
+        let mut explain = test_rule("explain", "Explain it.").build();
+        explain.frontmatter.content_type = ContentType::Prompt;
+        explain.frontmatter.model = Some("haiku".to_string());
+        let rules = vec![
+            review,
+            deploy,
+            explain,
+            test_rule("style", "Use tabs.").build(),
+        ];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let commands_dir = output_path.join(".claude").join("commands");
+        assert_eq!(
+            generated.contents(commands_dir.join("review.md")).unwrap(),
+            "---\ndescription: Review code\n---\nReview $ARGUMENTS."
+        );
+        assert_eq!(
+            generated.contents(commands_dir.join("deploy.md")).unwrap(),
+            "Deploy it."
+        );
+        // Prompts are invoked like commands
+        assert_eq!(
+            generated.contents(commands_dir.join("explain.md")).unwrap(),
+            "---\nmodel: haiku\n---\nExplain it."
+        );
+
+        let claude_content = generated.contents(output_path.join("CLAUDE.md")).unwrap();
+        assert_eq!(claude_content, "## Rule: Style\n\nUse tabs.");
+    }
+
+    /// Test that `CLAUDE.md` is not created when every rule is a command.
+    #[test]
+    fn test_generate_claude_only_commands() {
+        let output_path = Path::new("project");
+        let converter = ClaudeConverter::default();
+
+        let mut command = test_rule("only", "Do it.").build();
+        command.frontmatter.content_type = ContentType::Command;
+        let generated = converter.generate_rules(&[command], output_path).unwrap();
+
+        assert!(generated.contents(output_path.join("CLAUDE.md")).is_none());
+        assert!(generated
+            .contents(output_path.join(".claude").join("commands").join("only.md"))
+            .is_some());
+    }
+
+    /// Test that subagent rules become `.claude/agents/` definitions with name, description
+    /// and tools, and are left out of `CLAUDE.md`.
+    #[test]
+    fn test_generate_claude_subagents() {
+        let output_path = Path::new("project");
+        let converter = ClaudeConverter::default();
+
+        let mut reviewer = test_rule("reviewer", "You review code.")
+            .description("Reviews diffs")
+            .build();
+        reviewer.frontmatter.subagent = Some(SubagentConfig {
+            name: Some("code-reviewer".to_string()),
+            description: None,
+            tools: Some(vec!["Read".to_string(), "Grep".to_string()]),
+        });
+        reviewer.frontmatter.model = Some("sonnet".to_string());
+        let rules = vec![reviewer, test_rule("style", "Use tabs.").build()];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let agent_content = generated
+            .contents(
+                output_path
+                    .join(".claude")
+                    .join("agents")
+                    .join("reviewer.md"),
+            )
+            .unwrap();
+        assert_eq!(
+            agent_content,
+            "---\nname: code-reviewer\ndescription: Reviews diffs\ntools: Read, Grep\nmodel: sonnet\n---\nYou review code."
+        );
+
+        let claude_content = generated.contents(output_path.join("CLAUDE.md")).unwrap();
+        assert!(!claude_content.contains("You review code."));
+    }
+
+    /// Test that each alias of a subagent rule gets its own definition named after it.
+    #[test]
+    fn test_generate_claude_subagent_aliases() {
+        let output_path = Path::new("project");
+        let converter = ClaudeConverter::default();
+
+        let mut reviewer = test_rule("reviewer", "You review code.")
+            .description("Reviews diffs")
+            .build();
+        reviewer.frontmatter.subagent = Some(SubagentConfig::default());
+        reviewer.frontmatter.aliases = Some(vec!["critic".to_string()]);
+        let generated = converter.generate_rules(&[reviewer], output_path).unwrap();
+
+        let agents_dir = output_path.join(".claude").join("agents");
+        assert_eq!(
+            generated.contents(agents_dir.join("reviewer.md")).unwrap(),
+            "---\nname: reviewer\ndescription: Reviews diffs\n---\nYou review code."
+        );
+        assert_eq!(
+            generated.contents(agents_dir.join("critic.md")).unwrap(),
+            "---\nname: critic\ndescription: Reviews diffs\n---\nYou review code."
+        );
+    }
+
+    /// Test that a subagent without any description is rejected.
+    #[test]
+    fn test_generate_claude_subagent_requires_description() {
+        let output_path = Path::new("project");
+        let converter = ClaudeConverter::default();
+
+        let mut helper = test_rule("helper", "Help.").build();
+        helper.frontmatter.subagent = Some(SubagentConfig::default());
+        let result = converter.generate_rules(&[helper], output_path);
+
+        assert!(result.is_err());
+    }
+
+    /// Test that scoped rules go to the `CLAUDE.md` of their scope directory.
+    #[test]
+    fn test_generate_claude_scoped_rules() {
+        let output_path = Path::new("project");
+        let mut api = test_rule("api", "Version every endpoint.").build();
+        api.frontmatter.scope = Some(PathBuf::from("packages/api"));
+        let style = test_rule("style", "Be concise.").build();
+        let converter = ClaudeConverter::default();
+        let generated = converter
+            .generate_rules(&[api.clone(), style], output_path)
+            .unwrap();
+
+        let root = generated.contents(output_path.join("CLAUDE.md")).unwrap();
+        assert_eq!(root, "## Rule: Style\n\nBe concise.");
+        let scoped = generated
+            .contents(output_path.join("packages/api/CLAUDE.md"))
+            .unwrap();
+        assert_eq!(scoped, "## Rule: Api\n\nVersion every endpoint.");
+        assert!(converter
+            .explain_activation(&api, output_path)
+            .starts_with("In context when working in \"packages/api\": part of"));
     }
 }
@@ -0,0 +1,816 @@
+// src/converters/copilot.rs
+
+use super::{
+    concatenate_rules, trim_content, GeneratedFile, GeneratedRules, OversizeMode, RuleConverter,
+};
+use crate::universal_rule::{Activation, ContentType, UniversalRule};
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// The file Copilot code review reads its repository instructions from, relative to `.github/`.
+pub const REVIEW_INSTRUCTIONS_FILE: &str = "copilot-review-instructions.md";
+
+/// The most characters Copilot code review reads of an instructions file.
+pub const REVIEW_CHAR_LIMIT: usize = 4000;
+
+/// The tag that marks a rule as a code review instruction.
+pub const REVIEW_TAG: &str = "review";
+
+/// The VS Code settings key holding Copilot's code generation instructions.
+pub const CODE_GENERATION_SETTING: &str = "github.copilot.chat.codeGeneration.instructions";
+
+/// The VS Code settings key holding Copilot's code review instructions.
+pub const REVIEW_SELECTION_SETTING: &str = "github.copilot.chat.reviewSelection.instructions";
+
+/// Where the `CopilotConverter` writes repository-wide and review instructions.
+#[derive(ValueEnum, Clone, Debug, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CopilotFormat {
+    /// `.github/copilot-instructions.md` and `.github/copilot-review-instructions.md`.
+    #[default]
+    Files,
+    /// The Copilot instruction keys of `.vscode/settings.json`, merged into existing settings.
+    Settings,
+}
+
+/// Represents the YAML frontmatter of a Copilot path-scoped `.instructions.md` file.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstructionsFrontmatter {
+    /// Comma-separated glob patterns the instructions apply to (e.g. `"**/*.ts,**/*.tsx"`).
+    pub apply_to: String,
+
+    /// An optional description of the instructions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Represents the YAML frontmatter of a Copilot Chat `.prompt.md` file.
+#[derive(Serialize, Debug, Default)]
+pub struct PromptFrontmatter {
+    /// An optional description shown when picking the prompt in Copilot Chat.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// The model the prompt runs with (e.g. `gpt-4o`); omitted to use the selected one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// Returns the rule's globs if it has at least one and is not `activation: always`, i.e.
+/// if it should be emitted as a path-scoped instructions file rather than into
+/// `copilot-instructions.md`.
+///
+/// Negated globs are left out, since `applyTo` has no exclusions.
+fn scoped_globs(rule: &UniversalRule) -> Option<Vec<String>> {
+    if rule.frontmatter.activation == Some(Activation::Always) {
+        return None;
+    }
+    Some(rule.frontmatter.include_globs()).filter(|g| !g.is_empty())
+}
+
+/// Renders a rule as a path-scoped `.instructions.md` file, with the rule's globs
+/// mapped to the `applyTo` frontmatter key.
+fn render_scoped_instructions(rule: &UniversalRule, globs: &[String]) -> Result<String> {
+    let frontmatter = InstructionsFrontmatter {
+        apply_to: globs.join(","),
+        description: rule.frontmatter.description.clone(),
+    };
+    let frontmatter_yaml = serde_yaml::to_string(&frontmatter).with_context(|| {
+        format!(
+            "Failed to serialize instructions frontmatter for rule: {}",
+            rule.name
+        )
+    })?;
+    Ok(format!(
+        "---\n{}\n---\n{}",
+        frontmatter_yaml.trim_end(),
+        rule.content
+    ))
+}
+
+/// Returns `true` if the rule becomes a prompt file: Copilot has no separate slash
+/// commands, so `content_type: command` rules are prompts too. So are `activation:
+/// manual` and `agent_decision` rules without globs, which Copilot can only apply when
+/// they are run, rather than in every request.
+fn is_prompt(rule: &UniversalRule) -> bool {
+    let on_demand = matches!(
+        rule.frontmatter.activation,
+        Some(Activation::Manual | Activation::AgentDecision)
+    );
+    matches!(
+        rule.frontmatter.content_kind(),
+        ContentType::Prompt | ContentType::Command
+    ) || (on_demand && scoped_globs(rule).is_none())
+}
+
+/// Renders a rule as a Copilot Chat `.prompt.md` file. The frontmatter block is only
+/// included if the rule has a description or a model.
+fn render_prompt_file(rule: &UniversalRule) -> Result<String> {
+    if rule.frontmatter.description.is_none() && rule.frontmatter.model.is_none() {
+        return Ok(rule.content.clone());
+    }
+    let frontmatter = PromptFrontmatter {
+        description: rule.frontmatter.description.clone(),
+        model: rule.frontmatter.model.clone(),
+    };
+    let frontmatter_yaml = serde_yaml::to_string(&frontmatter).with_context(|| {
+        format!(
+            "Failed to serialize prompt frontmatter for rule: {}",
+            rule.name
+        )
+    })?;
+    Ok(format!(
+        "---\n{}\n---\n{}",
+        frontmatter_yaml.trim_end(),
+        rule.content
+    ))
+}
+
+/// Returns `.vscode/settings.json` in `output_dir` holding the rules in Copilot's
+/// instruction keys.
+///
+/// Only the keys for non-empty rule lists are generated, each with one `{ "text": ... }`
+/// entry per rule; they are merged into the existing settings when the file is written
+/// (see `merge_vscode_settings`). The file is generated even without such rules, so that
+/// the merge clears the keys of removed rules instead of the settings being left alone
+/// or deleted as the output of a removed rule.
+fn vscode_settings(
+    output_dir: &Path,
+    code_generation_rules: &[&UniversalRule],
+    review_rules: &[&UniversalRule],
+) -> Result<GeneratedFile> {
+    let mut settings = serde_json::Map::new();
+    for (key, rules) in [
+        (CODE_GENERATION_SETTING, code_generation_rules),
+        (REVIEW_SELECTION_SETTING, review_rules),
+    ] {
+        if !rules.is_empty() {
+            let entries = rules
+                .iter()
+                .map(|rule| json!({ "text": rule.content }))
+                .collect();
+            settings.insert(key.to_string(), Value::Array(entries));
+        }
+    }
+
+    let settings_content =
+        serde_json::to_string_pretty(&settings).context("Failed to serialize VS Code settings")?;
+    Ok(GeneratedFile::new(
+        output_dir.join(".vscode").join("settings.json"),
+        settings_content + "\n",
+    ))
+}
+
+/// Returns the `existing` settings of the generated settings `file` with the instruction
+/// keys urules manages replaced by the generated ones, keeping every other setting. A
+/// managed key that is no longer generated (e.g. after the last review rule was removed)
+/// is cleared.
+///
+/// Settings files with comments (JSONC) can't be merged and are reported as an error
+/// rather than overwritten.
+fn merge_vscode_settings(file: &GeneratedFile, existing: &str) -> Result<String> {
+    let mut settings: Value = serde_json::from_str(existing).with_context(|| {
+        format!(
+            "Failed to parse {:?} (settings with comments can't be merged)",
+            file.path
+        )
+    })?;
+    let Some(settings_map) = settings.as_object_mut() else {
+        bail!("Expected a JSON object in {:?}", file.path);
+    };
+    let generated: serde_json::Map<String, Value> = serde_json::from_str(&file.contents)
+        .context("Failed to parse the generated VS Code settings")?;
+    settings_map.retain(|key, _| {
+        ![CODE_GENERATION_SETTING, REVIEW_SELECTION_SETTING].contains(&key.as_str())
+            || generated.contains_key(key)
+    });
+    settings_map.extend(generated);
+
+    let settings_content =
+        serde_json::to_string_pretty(&settings).context("Failed to serialize VS Code settings")?;
+    Ok(settings_content + "\n")
+}
+
+/// A `RuleConverter` implementation for generating GitHub Copilot custom instructions.
+///
+/// Rules with `globs` become path-scoped `.github/instructions/<name>.instructions.md`
+/// files whose `applyTo` key lists the globs, unless they are `activation: always`.
+/// All other rules are concatenated into
+/// `.github/copilot-instructions.md`, which Copilot reads as repository-wide instructions.
+/// Rules with `content_type: prompt` or `command` (or the older `prompt: true`), and
+/// `activation: manual` or `agent_decision` rules without globs, are generated as reusable
+/// Copilot Chat prompt files (`.github/prompts/<name>.prompt.md`) and left out of the
+/// instruction output.
+/// Rules tagged `review` are concatenated into `.github/copilot-review-instructions.md`
+/// for Copilot code review, separately from the coding instructions.
+///
+/// With `CopilotFormat::Settings`, the repository-wide and review instructions go to the
+/// `github.copilot.chat.*.instructions` keys of `.vscode/settings.json` instead, and the
+/// keys without rules are cleared from the existing settings.
+#[derive(Default)]
+pub struct CopilotConverter {
+    /// Where repository-wide and review instructions go.
+    pub format: CopilotFormat,
+
+    /// What to do with review instructions over `REVIEW_CHAR_LIMIT`, which cannot be split.
+    pub oversize: OversizeMode,
+
+    /// The characters the banner adds to each file, which count toward the limit.
+    pub banner_chars: usize,
+}
+
+impl RuleConverter for CopilotConverter {
+    /// Generates Copilot instruction files from a list of `UniversalRule`s.
+    ///
+    /// Rules without globs are formatted the same way as in `CLAUDE.md`: a
+    /// `## Rule: {title}` heading, the description (if any), and the rule content,
+    /// with rules separated by a Markdown horizontal rule. If there are no such rules,
+    /// `copilot-instructions.md` is not generated. Rules with globs go to
+    /// `.github/instructions/`, prompt rules to `.github/prompts/`, and review rules to
+    /// `copilot-review-instructions.md`; each output is only generated when needed.
+    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<GeneratedRules> {
+        let (prompt_rules, instruction_rules): (Vec<&UniversalRule>, Vec<&UniversalRule>) =
+            rules.iter().partition(|rule| is_prompt(rule));
+        let (review_rules, instruction_rules): (Vec<&UniversalRule>, Vec<&UniversalRule>) =
+            instruction_rules
+                .into_iter()
+                .partition(|rule| rule.has_tag(REVIEW_TAG));
+        let (scoped_rules, repository_rules): (Vec<&UniversalRule>, Vec<&UniversalRule>) =
+            instruction_rules
+                .into_iter()
+                .partition(|rule| scoped_globs(rule).is_some());
+
+        let github_dir = output_dir.join(".github");
+        let mut files = Vec::new();
+        let mut warnings = Vec::new();
+
+        if self.format == CopilotFormat::Settings {
+            files.push(vscode_settings(
+                output_dir,
+                &repository_rules,
+                &review_rules,
+            )?);
+        } else if !review_rules.is_empty() {
+            let review_path = github_dir.join(REVIEW_INSTRUCTIONS_FILE);
+            let mut review_content = concatenate_rules(review_rules);
+            let chars = review_content.chars().count() + self.banner_chars;
+            if chars > REVIEW_CHAR_LIMIT {
+                let consequence = match self.oversize {
+                    OversizeMode::Keep => "code review ignores the rest",
+                    _ => {
+                        let limit = REVIEW_CHAR_LIMIT.saturating_sub(self.banner_chars);
+                        review_content = trim_content(&review_content, limit);
+                        "its end is trimmed"
+                    }
+                };
+                warnings.push(format!(
+                    "{} takes {} characters, over the {} Copilot code review reads; {}",
+                    REVIEW_INSTRUCTIONS_FILE, chars, REVIEW_CHAR_LIMIT, consequence
+                ));
+            }
+            files.push(GeneratedFile::new(review_path, review_content));
+        }
+
+        if self.format == CopilotFormat::Files && !repository_rules.is_empty() {
+            files.push(GeneratedFile::new(
+                github_dir.join("copilot-instructions.md"),
+                concatenate_rules(repository_rules),
+            ));
+        }
+
+        let instructions_dir = github_dir.join("instructions");
+        for rule in scoped_rules {
+            let globs = scoped_globs(rule).unwrap_or_default();
+            let scoped_content = render_scoped_instructions(rule, &globs)?;
+
+            for stem in rule.output_stems() {
+                files.push(GeneratedFile::new(
+                    instructions_dir.join(format!("{}.instructions.md", stem)),
+                    scoped_content.clone(),
+                ));
+            }
+        }
+
+        let prompts_dir = github_dir.join("prompts");
+        for rule in prompt_rules {
+            let prompt_content = render_prompt_file(rule)?;
+
+            for stem in rule.output_stems() {
+                files.push(GeneratedFile::new(
+                    prompts_dir.join(format!("{}.prompt.md", stem)),
+                    prompt_content.clone(),
+                ));
+            }
+        }
+
+        Ok(GeneratedRules { files, warnings })
+    }
+
+    /// `.vscode/settings.json` is merged into the existing settings, whose other keys are
+    /// kept.
+    fn merge_with_existing(&self, file: &GeneratedFile, existing: Option<&str>) -> Result<String> {
+        match existing {
+            Some(existing)
+                if file
+                    .path
+                    .ends_with(Path::new(".vscode").join("settings.json")) =>
+            {
+                merge_vscode_settings(file, existing)
+            }
+            _ => Ok(file.contents.clone()),
+        }
+    }
+
+    /// Linked assets are copied to `.github/instructions/assets/`.
+    fn assets_dir(&self) -> Option<PathBuf> {
+        Some(PathBuf::from(".github/instructions/assets"))
+    }
+
+    /// Provides a description of where the Copilot instruction files are generated.
+    fn get_output_description(&self, output_dir: &Path) -> String {
+        let github_dir = output_dir.join(".github");
+        if self.format == CopilotFormat::Settings {
+            return format!(
+                "Copilot instructions in {:?} and potentially in {:?} and {:?}",
+                output_dir.join(".vscode").join("settings.json"),
+                github_dir.join("instructions"),
+                github_dir.join("prompts")
+            );
+        }
+        format!(
+            "Copilot instructions in {:?} and potentially in {:?}, {:?} and {:?}",
+            github_dir.join("copilot-instructions.md"),
+            github_dir.join("instructions"),
+            github_dir.join("prompts"),
+            github_dir.join(REVIEW_INSTRUCTIONS_FILE)
+        )
+    }
+
+    /// Prompts and `activation: always` rules do not use globs, and `applyTo` has no
+    /// exclusions.
+    fn supports_negated_globs(&self, rule: &UniversalRule) -> bool {
+        is_prompt(rule) || rule.frontmatter.activation == Some(Activation::Always)
+    }
+
+    /// Explains which Copilot output the rule is routed to, like `generate_rules`.
+    fn explain_activation(&self, rule: &UniversalRule, output_dir: &Path) -> String {
+        let github_dir = output_dir.join(".github");
+        let settings_file = output_dir.join(".vscode").join("settings.json");
+        if is_prompt(rule) {
+            format!(
+                "Manual: a reusable prompt, run with /{} in Copilot Chat, from {:?}.",
+                rule.output_stem(),
+                github_dir
+                    .join("prompts")
+                    .join(format!("{}.prompt.md", rule.output_stem()))
+            )
+        } else if rule.has_tag(REVIEW_TAG) {
+            match self.format {
+                CopilotFormat::Files => format!(
+                    "Code review: applied by Copilot code review, from {:?}.",
+                    github_dir.join(REVIEW_INSTRUCTIONS_FILE)
+                ),
+                CopilotFormat::Settings => format!(
+                    "Code review: applied by Copilot code review, from `{}` in {:?}.",
+                    REVIEW_SELECTION_SETTING, settings_file
+                ),
+            }
+        } else if let Some(globs) = scoped_globs(rule) {
+            format!(
+                "Applied to files matching `applyTo: {}`, from {:?}.",
+                globs.join(","),
+                github_dir
+                    .join("instructions")
+                    .join(format!("{}.instructions.md", rule.output_stem()))
+            )
+        } else {
+            match self.format {
+                CopilotFormat::Files => format!(
+                    "Always applied: repository-wide instructions in {:?}.",
+                    github_dir.join("copilot-instructions.md")
+                ),
+                CopilotFormat::Settings => format!(
+                    "Always applied: repository-wide instructions in `{}` of {:?}.",
+                    CODE_GENERATION_SETTING, settings_file
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converters::{test_rule, write_rules};
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// Test that rules are explained by the output they are routed to.
+    #[test]
+    fn test_explain_copilot_activation() {
+        let converter = CopilotConverter::default();
+        let output_dir = Path::new(".");
+        let repository = test_rule("style", "").build();
+        assert!(converter
+            .explain_activation(&repository, output_dir)
+            .starts_with("Always applied: repository-wide instructions"));
+        let scoped = test_rule("rust", "").globs(&["**/*.rs"]).build();
+        assert!(converter
+            .explain_activation(&scoped, output_dir)
+            .starts_with("Applied to files matching `applyTo: **/*.rs`"));
+        let mut always = scoped.clone();
+        always.frontmatter.activation = Some(Activation::Always);
+        assert!(converter
+            .explain_activation(&always, output_dir)
+            .starts_with("Always applied: repository-wide instructions"));
+        let mut prompt = test_rule("review", "").build();
+        prompt.frontmatter.prompt = true;
+        assert!(converter
+            .explain_activation(&prompt, output_dir)
+            .starts_with("Manual: a reusable prompt, run with /review"));
+        let mut manual = test_rule("release", "").build();
+        manual.frontmatter.activation = Some(Activation::Manual);
+        assert!(converter
+            .explain_activation(&manual, output_dir)
+            .starts_with("Manual: a reusable prompt, run with /release"));
+
+        let settings = CopilotConverter {
+            format: CopilotFormat::Settings,
+            ..Default::default()
+        };
+        assert!(settings
+            .explain_activation(&repository, output_dir)
+            .contains(CODE_GENERATION_SETTING));
+    }
+
+    /// Test generation of `copilot-instructions.md` with multiple rules.
+    #[test]
+    fn test_generate_copilot_instructions_multiple_rules() {
+        let output_path = Path::new("project");
+        let converter = CopilotConverter::default();
+
+        let rules = vec![
+            test_rule("rule1", "Content 1.")
+                .description("Description 1.")
+                .build(),
+            test_rule("rule2", "Content 2.").build(),
+        ];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let instructions_path = output_path.join(".github").join("copilot-instructions.md");
+        assert!(generated.contents(&instructions_path).is_some());
+        let content = generated.contents(instructions_path).unwrap();
+        assert_eq!(
+            content,
+            "## Rule: Rule1\nDescription 1.\n\nContent 1.\n\n---\n\n## Rule: Rule2\n\nContent 2."
+        );
+    }
+
+    /// Test that rules with globs become path-scoped instruction files with `applyTo`,
+    /// while rules without globs fall back into `copilot-instructions.md`.
+    #[test]
+    fn test_generate_copilot_scoped_instructions() {
+        let output_path = Path::new("project");
+        let converter = CopilotConverter::default();
+
+        let rules = vec![
+            test_rule("typescript", "Use strict mode.")
+                .description("TypeScript rules")
+                .globs(&["**/*.ts", "**/*.tsx"])
+                .build(),
+            test_rule("general", "Be concise.").build(),
+            test_rule("empty_globs", "No globs here.")
+                .globs(&[])
+                .build(),
+        ];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let scoped_path = output_path
+            .join(".github")
+            .join("instructions")
+            .join("typescript.instructions.md");
+        assert!(generated.contents(&scoped_path).is_some());
+        let scoped_content = generated.contents(scoped_path).unwrap();
+        assert!(scoped_content.starts_with("---\n"));
+        assert!(scoped_content.contains("applyTo: '**/*.ts,**/*.tsx'"));
+        assert!(scoped_content.contains("description: TypeScript rules"));
+        assert!(scoped_content.ends_with("\n---\nUse strict mode."));
+
+        let main_content = generated
+            .contents(output_path.join(".github").join("copilot-instructions.md"))
+            .unwrap();
+        assert!(main_content.contains("## Rule: General"));
+        assert!(main_content.contains("## Rule: Empty globs"));
+        assert!(!main_content.contains("Use strict mode."));
+    }
+
+    /// Test that `copilot-instructions.md` is not created when every rule is path-scoped.
+    #[test]
+    fn test_generate_copilot_only_scoped_rules() {
+        let output_path = Path::new("project");
+        let converter = CopilotConverter::default();
+
+        let rules = vec![test_rule("rust", "Prefer iterators.")
+            .globs(&["**/*.rs"])
+            .build()];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let github_dir = output_path.join(".github");
+        assert!(generated
+            .contents(github_dir.join("copilot-instructions.md"))
+            .is_none());
+        let scoped_content = generated
+            .contents(github_dir.join("instructions").join("rust.instructions.md"))
+            .unwrap();
+        assert_eq!(
+            scoped_content,
+            "---\napplyTo: '**/*.rs'\n---\nPrefer iterators."
+        );
+    }
+
+    /// Test that prompt rules become `.prompt.md` files and stay out of the instructions.
+    #[test]
+    fn test_generate_copilot_prompt_files() {
+        let output_path = Path::new("project");
+        let converter = CopilotConverter::default();
+
+        let mut review_prompt = test_rule("review", "Review the selected code.")
+            .description("Code review prompt")
+            .build();
+        review_prompt.frontmatter.prompt = true;
+        let mut scoped_prompt = test_rule("explain", "Explain this file.")
+            .globs(&["**/*.rs"])
+            .build();
+        scoped_prompt.frontmatter.prompt = true;
+        let mut deploy_command = test_rule("deploy", "Deploy it.").build();
+        deploy_command.frontmatter.content_type = ContentType::Command;
+        deploy_command.frontmatter.model = Some("gpt-4o".to_string());
+        let mut manual = test_rule("release", "Tag the release.").build();
+        manual.frontmatter.activation = Some(Activation::Manual);
+        let mut agent_decision = test_rule("migrations", "Keep them small.").build();
+        agent_decision.frontmatter.activation = Some(Activation::AgentDecision);
+        let rules = vec![
+            review_prompt,
+            scoped_prompt,
+            deploy_command,
+            manual,
+            agent_decision,
+            test_rule("general", "Be concise.").build(),
+        ];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let prompts_dir = output_path.join(".github").join("prompts");
+        let review_content = generated
+            .contents(prompts_dir.join("review.prompt.md"))
+            .unwrap();
+        assert_eq!(
+            review_content,
+            "---\ndescription: Code review prompt\n---\nReview the selected code."
+        );
+        let explain_content = generated
+            .contents(prompts_dir.join("explain.prompt.md"))
+            .unwrap();
+        assert_eq!(explain_content, "Explain this file.");
+        // Copilot has no commands, so they become prompt files too
+        let deploy_content = generated
+            .contents(prompts_dir.join("deploy.prompt.md"))
+            .unwrap();
+        assert_eq!(deploy_content, "---\nmodel: gpt-4o\n---\nDeploy it.");
+        // Rules that are not always applied stay out of the repository-wide instructions
+        assert_eq!(
+            generated.contents(prompts_dir.join("release.prompt.md")),
+            Some("Tag the release.")
+        );
+        assert_eq!(
+            generated.contents(prompts_dir.join("migrations.prompt.md")),
+            Some("Keep them small.")
+        );
+
+        // Prompts are not instructions, even when they have globs
+        assert!(generated
+            .contents(
+                output_path
+                    .join(".github")
+                    .join("instructions")
+                    .join("explain.instructions.md")
+            )
+            .is_none());
+        let main_content = generated
+            .contents(output_path.join(".github").join("copilot-instructions.md"))
+            .unwrap();
+        assert!(!main_content.contains("Review the selected code."));
+        assert!(!main_content.contains("Tag the release."));
+        assert!(!main_content.contains("Keep them small."));
+        assert!(main_content.contains("Be concise."));
+    }
+
+    /// Test that `review`-tagged rules go to the code review instructions only.
+    #[test]
+    fn test_generate_copilot_review_instructions() {
+        let output_path = Path::new("project");
+        let converter = CopilotConverter::default();
+
+        let mut review_rule = test_rule("security", "Flag unchecked input.")
+            .description("Security")
+            .build();
+        review_rule.frontmatter.tags = Some(vec!["review".to_string()]);
+        let rules = vec![review_rule, test_rule("general", "Be concise.").build()];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let github_dir = output_path.join(".github");
+        let review_content = generated
+            .contents(github_dir.join(REVIEW_INSTRUCTIONS_FILE))
+            .unwrap();
+        assert_eq!(
+            review_content,
+            "## Rule: Security\nSecurity\n\nFlag unchecked input."
+        );
+        let main_content = generated
+            .contents(github_dir.join("copilot-instructions.md"))
+            .unwrap();
+        assert!(!main_content.contains("Flag unchecked input."));
+        assert!(main_content.contains("Be concise."));
+    }
+
+    /// Test that review instructions over the limit of code review are trimmed.
+    #[test]
+    fn test_generate_copilot_review_instructions_oversized() {
+        let output_path = Path::new("project");
+        let content = format!("{}\n\n{}\n", "a".repeat(3000), "b".repeat(3000));
+        let mut review_rule = test_rule("security", &content).build();
+        review_rule.frontmatter.tags = Some(vec!["review".to_string()]);
+        let generated = CopilotConverter::default()
+            .generate_rules(&[review_rule], output_path)
+            .unwrap();
+        assert_eq!(generated.warnings.len(), 1);
+        assert!(generated.warnings[0].ends_with("its end is trimmed"));
+
+        let review_content = generated
+            .contents(output_path.join(".github").join(REVIEW_INSTRUCTIONS_FILE))
+            .unwrap();
+        assert!(review_content.chars().count() <= REVIEW_CHAR_LIMIT);
+        assert!(review_content.contains(&"a".repeat(3000)));
+        assert!(!review_content.contains('b'));
+    }
+
+    /// Test that settings mode merges instructions into an existing `settings.json`.
+    #[test]
+    fn test_generate_copilot_vscode_settings() {
+        let dir = tempdir().unwrap();
+        let output_path = dir.path();
+        let vscode_dir = output_path.join(".vscode");
+        fs::create_dir_all(&vscode_dir).unwrap();
+        fs::write(
+            vscode_dir.join("settings.json"),
+            "{\"editor.tabSize\": 2, \"github.copilot.chat.codeGeneration.instructions\": []}",
+        )
+        .unwrap();
+        let converter = CopilotConverter {
+            format: CopilotFormat::Settings,
+            ..Default::default()
+        };
+
+        let mut review_rule = test_rule("security", "Flag unchecked input.").build();
+        review_rule.frontmatter.tags = Some(vec!["review".to_string()]);
+        let rules = vec![
+            test_rule("general", "Be concise.").build(),
+            review_rule,
+            test_rule("rust", "Prefer iterators.")
+                .globs(&["**/*.rs"])
+                .build(),
+        ];
+        write_rules(&converter, &rules, output_path).unwrap();
+
+        let settings_content = fs::read_to_string(vscode_dir.join("settings.json")).unwrap();
+        let settings: Value = serde_json::from_str(&settings_content).unwrap();
+        assert_eq!(settings["editor.tabSize"], 2);
+        assert_eq!(
+            settings[CODE_GENERATION_SETTING],
+            json!([{ "text": "Be concise." }])
+        );
+        assert_eq!(
+            settings[REVIEW_SELECTION_SETTING],
+            json!([{ "text": "Flag unchecked input." }])
+        );
+        // Existing keys keep their position
+        assert!(settings_content
+            .trim_start()
+            .starts_with("{\n  \"editor.tabSize\""));
+
+        let github_dir = output_path.join(".github");
+        assert!(!github_dir.join("copilot-instructions.md").exists());
+        assert!(!github_dir.join(REVIEW_INSTRUCTIONS_FILE).exists());
+        assert!(github_dir
+            .join("instructions")
+            .join("rust.instructions.md")
+            .exists());
+    }
+
+    /// Test that managed instruction keys without rules left are cleared from the existing
+    /// settings.
+    #[test]
+    fn test_merge_vscode_settings_clears_managed_keys() {
+        let converter = CopilotConverter {
+            format: CopilotFormat::Settings,
+            ..Default::default()
+        };
+        let rules = vec![test_rule("general", "Be concise.").build()];
+        let files = converter
+            .generate_rules(&rules, Path::new("project"))
+            .unwrap()
+            .files;
+
+        let existing = json!({
+            CODE_GENERATION_SETTING: [{ "text": "Old rule." }],
+            "editor.tabSize": 2,
+            REVIEW_SELECTION_SETTING: [{ "text": "Removed review rule." }],
+        });
+        let merged = converter
+            .merge_with_existing(&files[0], Some(&existing.to_string()))
+            .unwrap();
+        let settings: Value = serde_json::from_str(&merged).unwrap();
+        assert_eq!(
+            settings,
+            json!({
+                CODE_GENERATION_SETTING: [{ "text": "Be concise." }],
+                "editor.tabSize": 2,
+            })
+        );
+    }
+
+    /// Test that settings with comments are reported instead of being overwritten.
+    #[test]
+    fn test_generate_copilot_vscode_settings_rejects_jsonc() {
+        let dir = tempdir().unwrap();
+        let output_path = dir.path();
+        let settings_path = output_path.join(".vscode").join("settings.json");
+        fs::create_dir_all(settings_path.parent().unwrap()).unwrap();
+        let original = "{\n  // my settings\n  \"editor.tabSize\": 2\n}\n";
+        fs::write(&settings_path, original).unwrap();
+        let converter = CopilotConverter {
+            format: CopilotFormat::Settings,
+            ..Default::default()
+        };
+
+        let rules = vec![test_rule("general", "Be concise.").build()];
+        assert!(write_rules(&converter, &rules, output_path).is_err());
+        assert_eq!(fs::read_to_string(&settings_path).unwrap(), original);
+    }
+
+    /// Test that `generate_rules` returns the instruction settings without reading or
+    /// writing the existing ones, which they are merged into when written.
+    #[test]
+    fn test_generate_copilot_vscode_settings_in_memory() {
+        let output_path = Path::new("project");
+        let settings_path = output_path.join(".vscode").join("settings.json");
+        let converter = CopilotConverter {
+            format: CopilotFormat::Settings,
+            ..Default::default()
+        };
+
+        let rules = vec![
+            test_rule("general", "Be concise.").build(),
+            test_rule("rust", "Prefer iterators.")
+                .globs(&["**/*.rs"])
+                .build(),
+        ];
+        let files = converter.generate_rules(&rules, output_path).unwrap().files;
+
+        let paths: Vec<&Path> = files.iter().map(|file| file.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            [
+                settings_path.as_path(),
+                &output_path
+                    .join(".github")
+                    .join("instructions")
+                    .join("rust.instructions.md")
+            ]
+        );
+        assert_eq!(
+            files[0].contents,
+            "{\n  \"github.copilot.chat.codeGeneration.instructions\": [\n    {\n      \"text\": \"Be concise.\"\n    }\n  ]\n}\n"
+        );
+        assert!(files[1].contents.contains("applyTo: '**/*.rs'"));
+
+        let merged = converter
+            .merge_with_existing(&files[0], Some("{\"editor.tabSize\": 2}"))
+            .unwrap();
+        let settings: Value = serde_json::from_str(&merged).unwrap();
+        assert_eq!(settings["editor.tabSize"], 2);
+        assert_eq!(
+            settings[CODE_GENERATION_SETTING],
+            json!([{ "text": "Be concise." }])
+        );
+        assert_eq!(
+            converter
+                .merge_with_existing(&files[1], Some("old"))
+                .unwrap(),
+            files[1].contents
+        );
+    }
+}
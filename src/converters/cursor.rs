@@ -1,13 +1,13 @@
 // src/converters/cursor.rs
 
-use super::RuleConverter;
-use crate::universal_rule::UniversalRule;
+use super::{concatenate_rules, GeneratedFile, GeneratedRules, RuleConverter};
+use crate::universal_rule::{Activation, UniversalRule};
 use anyhow::{Context, Result};
-use serde::Serialize;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
 use serde_yaml;
 use std::fmt::Debug;
-use std::fs;
-use std::path::Path; // Required for derive(Debug) on MdcFrontmatter
+use std::path::{Path, PathBuf}; // Required for derive(Debug) on MdcFrontmatter
 
 /// Represents the YAML frontmatter structure for Cursor.ai's `.mdc` rule files.
 ///
@@ -40,60 +40,126 @@ pub struct MdcFrontmatter {
 fn convert_to_cursor_rule(universal_rule: &UniversalRule) -> (MdcFrontmatter, String) {
     let mut mdc_frontmatter = MdcFrontmatter {
         description: universal_rule.frontmatter.description.clone(),
-        globs: universal_rule.frontmatter.globs.clone(),
+        // Cursor has no exclusions, so negated globs are dropped
+        globs: universal_rule
+            .frontmatter
+            .globs
+            .as_ref()
+            .map(|_| universal_rule.frontmatter.include_globs()),
         ..Default::default() // Initializes always_apply and agent_requested to None
     };
 
-    // Map UniversalRule's cursor_rule_type to MdcFrontmatter fields
-    match universal_rule.frontmatter.cursor_rule_type.as_deref() {
-        Some("Always") => {
+    // Map the rule's activation (or deprecated cursor_rule_type) to MdcFrontmatter fields
+    match universal_rule.frontmatter.cursor_activation() {
+        Some(Activation::Always) => {
             mdc_frontmatter.always_apply = Some(true);
         }
-        Some("AutoAttached") => {
-            // For "AutoAttached", the presence of `globs` is usually sufficient.
+        Some(Activation::Glob) => {
+            // For auto-attached rules, the presence of `globs` is usually sufficient.
             // No specific MdcFrontmatter boolean flag needs to be set unless Cursor's
             // interpretation requires it (e.g., agentRequested: false explicitly).
             // Currently, we leave agent_requested as None or its default.
         }
-        Some("AgentRequested") => {
+        Some(Activation::AgentDecision) => {
             mdc_frontmatter.agent_requested = Some(true);
+            // The agent requests the rule by this text
+            mdc_frontmatter.description = universal_rule
+                .frontmatter
+                .trigger_description()
+                .map(String::from);
         }
-        Some("Manual") | None => {
-            // "Manual" rules or those with no specified type typically don't set
+        Some(Activation::Manual) | None => {
+            // Manual rules or those with no specified activation typically don't set
             // `always_apply` or `agent_requested` to true. They might be picked up
             // by Cursor based on their presence and `globs`.
         }
-        Some(other_type) => {
-            // Log unknown types but treat them as "Manual" to avoid errors.
-            eprintln!(
-                "Warning: Unknown cursor_rule_type '{}' for rule '{}', treating as Manual.",
-                other_type, universal_rule.name
-            );
-        }
     }
 
     (mdc_frontmatter, universal_rule.content.clone())
 }
 
+/// The file name used by older Cursor versions for project rules.
+pub const LEGACY_RULES_FILE: &str = ".cursorrules";
+
+/// The layout of the rules generated by the `CursorConverter`.
+#[derive(ValueEnum, Clone, Debug, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CursorFormat {
+    /// One `.mdc` file per rule in `.cursor/rules/` (current Cursor versions).
+    #[default]
+    Mdc,
+    /// A single concatenated `.cursorrules` file (older Cursor versions).
+    Legacy,
+}
+
 /// A `RuleConverter` implementation for generating Cursor.ai specific rule files (`.mdc`).
-pub struct CursorConverter;
+#[derive(Default)]
+pub struct CursorConverter {
+    /// Whether to emit `.mdc` files or a legacy `.cursorrules` file.
+    pub format: CursorFormat,
+
+    /// If `true`, `.mdc` files are placed in subdirectories of `.cursor/rules/` that mirror
+    /// each rule's location under the rules directory, instead of being flattened.
+    pub nested: bool,
+}
+
+impl CursorConverter {
+    /// Returns the `.cursor/rules/` directory receiving the `.mdc` file of `rule`: the one
+    /// of its `scope` directory (Cursor reads nested `.cursor/rules/` directories), with
+    /// the rule's subdirectory appended if `nested` is set.
+    fn rules_dir(&self, rule: &UniversalRule, output_dir: &Path) -> PathBuf {
+        let mut rules_dir = output_dir
+            .join(rule.scope_dir())
+            .join(".cursor")
+            .join("rules");
+        if self.nested {
+            rules_dir.push(&rule.relative_dir);
+        }
+        rules_dir
+    }
+
+    /// Generates a single legacy `.cursorrules` file by concatenating all `UniversalRule`s.
+    ///
+    /// The file uses the same headings and separators as `CLAUDE.md`. Cursor-specific
+    /// frontmatter (e.g. `alwaysApply`) cannot be expressed in this format and is dropped.
+    /// If no rules are provided, no file is generated.
+    fn generate_legacy_rules(
+        &self,
+        rules: &[UniversalRule],
+        output_dir: &Path,
+    ) -> Vec<GeneratedFile> {
+        if rules.is_empty() {
+            return Vec::new();
+        }
+
+        let legacy_content = concatenate_rules(rules);
+
+        vec![GeneratedFile::new(
+            output_dir.join(LEGACY_RULES_FILE),
+            legacy_content,
+        )]
+    }
+}
 
 impl RuleConverter for CursorConverter {
     /// Generates Cursor-specific `.mdc` rule files from a list of `UniversalRule`s.
     ///
-    /// Each `UniversalRule` is converted into an individual `.mdc` file named after the rule.
+    /// Each `UniversalRule` is converted into an individual `.mdc` file named after the rule,
+    /// plus a copy per alias.
     /// These files are placed in a `.cursor/rules/` subdirectory within the specified `output_dir`.
     /// The content of each `.mdc` file includes YAML frontmatter derived from `MdcFrontmatter`
     /// and the rule's Markdown body.
-    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<()> {
-        let cursor_rules_dir = output_dir.join(".cursor").join("rules");
-        fs::create_dir_all(&cursor_rules_dir).with_context(|| {
-            format!(
-                "Failed to create .cursor/rules directory at {:?}",
-                cursor_rules_dir
-            )
-        })?;
+    ///
+    /// If `nested` is set, each file goes to the subdirectory of `.cursor/rules/`
+    /// matching the rule's `relative_dir`. Rules with a `scope` go to the
+    /// `.cursor/rules/` directory of their scope instead of the top-level one.
+    /// With `CursorFormat::Legacy`, a single `.cursorrules` file is generated instead.
+    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<GeneratedRules> {
+        if self.format == CursorFormat::Legacy {
+            return Ok(self.generate_legacy_rules(rules, output_dir).into());
+        }
 
+        let mut files = Vec::new();
         for rule in rules {
             let (mdc_frontmatter, rule_content) = convert_to_cursor_rule(rule);
 
@@ -115,73 +181,162 @@ impl RuleConverter for CursorConverter {
             let mdc_content = if frontmatter_yaml.is_empty() {
                 rule_content
             } else {
-                format!("---\n{}---\n{}", frontmatter_yaml.trim_end(), rule_content)
+                format!(
+                    "---\n{}\n---\n{}",
+                    frontmatter_yaml.trim_end(),
+                    rule_content
+                )
             };
 
-            let output_file_path = cursor_rules_dir.join(format!("{}.mdc", rule.name));
-            fs::write(&output_file_path, mdc_content)
-                .with_context(|| format!("Failed to write .mdc file for rule: {}", rule.name))?;
+            let rule_dir = self.rules_dir(rule, output_dir);
+            for stem in rule.output_stems() {
+                files.push(GeneratedFile::new(
+                    rule_dir.join(format!("{}.mdc", stem)),
+                    mdc_content.clone(),
+                ));
+            }
         }
-        Ok(())
+        Ok(files.into())
+    }
+
+    /// Linked assets are copied to `.cursor/assets/`.
+    fn assets_dir(&self) -> Option<PathBuf> {
+        Some(PathBuf::from(".cursor/assets"))
     }
 
     /// Provides a description of where the Cursor rules are generated.
     fn get_output_description(&self, output_dir: &Path) -> String {
+        if self.format == CursorFormat::Legacy {
+            return format!("Cursor rules in {:?}", output_dir.join(LEGACY_RULES_FILE));
+        }
         format!(
             "Cursor rules in {:?}",
             output_dir.join(".cursor").join("rules")
         )
     }
+
+    /// `.mdc` globs have no exclusions; `.cursorrules` ignores globs altogether.
+    fn supports_negated_globs(&self, _rule: &UniversalRule) -> bool {
+        self.format == CursorFormat::Legacy
+    }
+
+    /// Explains the activation Cursor derives from the generated `.mdc` frontmatter.
+    fn explain_activation(&self, rule: &UniversalRule, output_dir: &Path) -> String {
+        if self.format == CursorFormat::Legacy {
+            return format!(
+                "Always applied: concatenated into {:?}, which has no per-rule activation.",
+                output_dir.join(LEGACY_RULES_FILE)
+            );
+        }
+        let file = self
+            .rules_dir(rule, output_dir)
+            .join(format!("{}.mdc", rule.output_stem()));
+        let (mdc_frontmatter, _) = convert_to_cursor_rule(rule);
+        let globs = mdc_frontmatter.globs.as_ref().filter(|g| !g.is_empty());
+        if mdc_frontmatter.always_apply == Some(true) {
+            format!("Always applied (`alwaysApply: true`), from {:?}.", file)
+        } else if mdc_frontmatter.agent_requested == Some(true) {
+            match &mdc_frontmatter.description {
+                Some(description) => format!(
+                    "Agent-requested: the agent includes {:?} when it judges it relevant from its description ({:?}).",
+                    file, description
+                ),
+                None => format!(
+                    "Agent-requested from {:?}, but without a `description` the agent cannot tell when it is relevant.",
+                    file
+                ),
+            }
+        } else if let Some(globs) = globs {
+            format!(
+                "Auto-attached when files matching {} are in context, from {:?}.",
+                globs.join(", "),
+                file
+            )
+        } else {
+            format!(
+                "Manual: only applied when mentioned with @{}, from {:?}.",
+                rule.output_stem(),
+                file
+            )
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::universal_rule::{UniversalRule, UniversalRuleFrontmatter};
-    use tempfile::tempdir;
-
-    /// Helper function to create `UniversalRule` instances for testing the Cursor converter.
-    fn create_test_universal_rule(
-        name: &str,
-        description: Option<&str>,
-        globs: Option<Vec<&str>>,
-        cursor_rule_type: Option<&str>,
-        content: &str,
-    ) -> UniversalRule {
-        UniversalRule {
-            name: name.to_string(),
-            frontmatter: UniversalRuleFrontmatter {
-                description: description.map(String::from),
-                globs: globs.map(|g| g.iter().map(|s| s.to_string()).collect()),
-                apply_globally: false,
-                cursor_rule_type: cursor_rule_type.map(String::from),
-            },
-            content: content.to_string(),
-        }
+    use crate::converters::{test_rule, TestRule};
+    use crate::universal_rule::{ContentType, CursorRuleType};
+
+    /// Test that negated globs are dropped from the `.mdc` frontmatter.
+    #[test]
+    fn test_convert_to_cursor_rule_negated_globs() {
+        let rule = test_rule("ts", "")
+            .globs(&["**/*.ts", "!**/*.test.ts"])
+            .build();
+        let (frontmatter, _) = convert_to_cursor_rule(&rule);
+        assert_eq!(frontmatter.globs, Some(vec!["**/*.ts".to_string()]));
+        assert!(!CursorConverter::default().supports_negated_globs(&rule));
+    }
+
+    /// Test that the explained activation follows the generated `.mdc` frontmatter.
+    #[test]
+    fn test_explain_cursor_activation() {
+        let converter = CursorConverter::default();
+        let explain = |rule: TestRule| {
+            let rule = rule.description("Style").build();
+            converter.explain_activation(&rule, Path::new("."))
+        };
+        let style = || test_rule("style", "");
+        assert!(
+            explain(style().cursor_rule_type(CursorRuleType::Always)).starts_with("Always applied")
+        );
+        assert!(
+            explain(style().cursor_rule_type(CursorRuleType::AgentRequested))
+                .starts_with("Agent-requested")
+        );
+        assert!(explain(
+            style()
+                .globs(&["*.rs"])
+                .cursor_rule_type(CursorRuleType::AutoAttached)
+        )
+        .starts_with("Auto-attached when files matching *.rs"));
+        assert!(explain(style()).starts_with("Manual: only applied when mentioned with @style"));
+
+        let legacy = CursorConverter {
+            format: CursorFormat::Legacy,
+            ..Default::default()
+        };
+        let rule = test_rule("style", "")
+            .cursor_rule_type(CursorRuleType::Always)
+            .build();
+        assert!(legacy
+            .explain_activation(&rule, Path::new("."))
+            .contains(".cursorrules"));
     }
 
     /// Test the `RuleConverter` trait implementation for `CursorConverter`.
     #[test]
     fn test_cursor_converter_trait_impl() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = CursorConverter;
-
-        let rules = vec![create_test_universal_rule(
-            "trait_rule1",
-            Some("Trait First rule"),
-            Some(vec!["*.rs"]),
-            Some("Always"),
-            "Trait Rule 1 content",
-        )];
-
-        converter.generate_rules(&rules, output_path).unwrap();
+        let output_path = Path::new("project");
+        let converter = CursorConverter::default();
+
+        let rules = vec![test_rule("trait_rule1", "Trait Rule 1 content")
+            .description("Trait First rule")
+            .globs(&["*.rs"])
+            .cursor_rule_type(CursorRuleType::Always)
+            .build()];
+
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
         let rule1_path = output_path
             .join(".cursor")
             .join("rules")
             .join("trait_rule1.mdc");
-        assert!(rule1_path.exists(), "Cursor rule file should be created.");
-        let content1 = fs::read_to_string(rule1_path).unwrap();
+        assert!(
+            generated.contents(&rule1_path).is_some(),
+            "Cursor rule file should be created."
+        );
+        let content1 = generated.contents(rule1_path).unwrap();
         assert!(content1.contains("description: Trait First rule"));
         assert!(content1.contains("alwaysApply: true"));
         assert!(content1.ends_with("Trait Rule 1 content"));
@@ -190,13 +345,10 @@ mod tests {
     /// Test mapping from `UniversalRule` with `cursor_rule_type: "Always"`.
     #[test]
     fn test_convert_to_cursor_rule_always() {
-        let rule = create_test_universal_rule(
-            "always_rule",
-            Some("Always active"),
-            None,
-            Some("Always"),
-            "Content for always rule",
-        );
+        let rule = test_rule("always_rule", "Content for always rule")
+            .description("Always active")
+            .cursor_rule_type(CursorRuleType::Always)
+            .build();
         let (frontmatter, content) = convert_to_cursor_rule(&rule);
         assert_eq!(frontmatter.description, Some("Always active".to_string()));
         assert_eq!(frontmatter.always_apply, Some(true));
@@ -208,13 +360,11 @@ mod tests {
     /// Test mapping from `UniversalRule` with `cursor_rule_type: "AgentRequested"`.
     #[test]
     fn test_convert_to_cursor_rule_agent_requested() {
-        let rule = create_test_universal_rule(
-            "agent_rule",
-            Some("Agent needs this"),
-            Some(vec!["*.py"]),
-            Some("AgentRequested"),
-            "Content for agent rule",
-        );
+        let rule = test_rule("agent_rule", "Content for agent rule")
+            .description("Agent needs this")
+            .globs(&["*.py"])
+            .cursor_rule_type(CursorRuleType::AgentRequested)
+            .build();
         let (frontmatter, content) = convert_to_cursor_rule(&rule);
         assert_eq!(
             frontmatter.description,
@@ -229,13 +379,11 @@ mod tests {
     /// Test mapping from `UniversalRule` with `cursor_rule_type: "AutoAttached"`.
     #[test]
     fn test_convert_to_cursor_rule_auto_attached() {
-        let rule = create_test_universal_rule(
-            "auto_attach_rule",
-            Some("Auto attaches"),
-            Some(vec!["*.ts"]),
-            Some("AutoAttached"),
-            "Content for auto-attach rule",
-        );
+        let rule = test_rule("auto_attach_rule", "Content for auto-attach rule")
+            .description("Auto attaches")
+            .globs(&["*.ts"])
+            .cursor_rule_type(CursorRuleType::AutoAttached)
+            .build();
         let (frontmatter, content) = convert_to_cursor_rule(&rule);
         assert_eq!(frontmatter.description, Some("Auto attaches".to_string()));
         assert_eq!(frontmatter.globs, Some(vec!["*.ts".to_string()]));
@@ -250,19 +398,76 @@ mod tests {
     /// Test mapping for "Manual" `cursor_rule_type` or when it's `None`.
     #[test]
     fn test_convert_to_cursor_rule_manual_or_none() {
-        let rule_manual =
-            create_test_universal_rule("manual_rule", None, None, Some("Manual"), "Manual content");
+        let rule_manual = test_rule("manual_rule", "Manual content")
+            .cursor_rule_type(CursorRuleType::Manual)
+            .build();
         let (fm_manual, _) = convert_to_cursor_rule(&rule_manual);
         assert!(fm_manual.always_apply.is_none());
         assert!(fm_manual.agent_requested.is_none());
 
-        let rule_none =
-            create_test_universal_rule("none_type_rule", None, None, None, "None type content");
+        let rule_none = test_rule("none_type_rule", "None type content").build();
         let (fm_none, _) = convert_to_cursor_rule(&rule_none);
         assert!(fm_none.always_apply.is_none());
         assert!(fm_none.agent_requested.is_none());
     }
 
+    /// Test that `activation` is mapped like its `cursor_rule_type` and takes precedence.
+    #[test]
+    fn test_convert_to_cursor_rule_activation() {
+        let mut rule = test_rule("activation_rule", "Content")
+            .description("Relevant for APIs")
+            .cursor_rule_type(CursorRuleType::Always)
+            .build();
+        rule.frontmatter.activation = Some(Activation::AgentDecision);
+        let (frontmatter, _) = convert_to_cursor_rule(&rule);
+        assert_eq!(frontmatter.agent_requested, Some(true));
+        assert!(frontmatter.always_apply.is_none());
+
+        rule.frontmatter.activation = Some(Activation::Always);
+        let (frontmatter, _) = convert_to_cursor_rule(&rule);
+        assert_eq!(frontmatter.always_apply, Some(true));
+    }
+
+    /// Test that agent-requested rules are requested by their `trigger_description`, while
+    /// other rules keep their `description`.
+    #[test]
+    fn test_convert_to_cursor_rule_trigger_description() {
+        let mut rule = test_rule("api", "Content")
+            .description("API design guide")
+            .cursor_rule_type(CursorRuleType::AgentRequested)
+            .build();
+        rule.frontmatter.trigger_description = Some("When changing endpoints".to_string());
+        let (frontmatter, _) = convert_to_cursor_rule(&rule);
+        assert_eq!(
+            frontmatter.description,
+            Some("When changing endpoints".to_string())
+        );
+
+        rule.frontmatter.activation = Some(Activation::Always);
+        let (frontmatter, _) = convert_to_cursor_rule(&rule);
+        assert_eq!(
+            frontmatter.description,
+            Some("API design guide".to_string())
+        );
+    }
+
+    /// Test that knowledge rules are requested by the agent unless their activation says
+    /// otherwise.
+    #[test]
+    fn test_convert_to_cursor_rule_knowledge() {
+        let mut rule = test_rule("schema", "Tables")
+            .description("Database schema")
+            .build();
+        rule.frontmatter.content_type = ContentType::Knowledge;
+        let (frontmatter, _) = convert_to_cursor_rule(&rule);
+        assert_eq!(frontmatter.agent_requested, Some(true));
+
+        rule.frontmatter.activation = Some(Activation::Always);
+        let (frontmatter, _) = convert_to_cursor_rule(&rule);
+        assert_eq!(frontmatter.always_apply, Some(true));
+        assert!(frontmatter.agent_requested.is_none());
+    }
+
     /// Test YAML serialization of `MdcFrontmatter` with various fields set.
     #[test]
     fn test_mdc_frontmatter_serialization() {
@@ -275,8 +480,8 @@ mod tests {
         let yaml = serde_yaml::to_string(&fm).unwrap();
         assert!(yaml.contains("description: Test desc"));
         assert!(yaml.contains("globs:"));
-        assert!(yaml.contains("- \"*.rs\""));
-        assert!(yaml.contains("- \"*.toml\""));
+        assert!(yaml.contains("- '*.rs'"));
+        assert!(yaml.contains("- '*.toml'"));
         assert!(yaml.contains("alwaysApply: true"));
         assert!(
             !yaml.contains("agentRequested:"),
@@ -324,50 +529,37 @@ mod tests {
     /// Test the creation of `.mdc` files by `generate_rules`.
     #[test]
     fn test_generate_cursor_rules_creates_files() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = CursorConverter;
+        let output_path = Path::new("project");
+        let converter = CursorConverter::default();
 
         let rules = vec![
-            create_test_universal_rule(
-                "rule1",
-                Some("First rule"),
-                Some(vec!["*.txt"]),
-                Some("Always"),
-                "Rule 1 content",
-            ),
-            create_test_universal_rule(
-                "rule2",
-                Some("Second rule"),
-                None,
-                Some("AgentRequested"),
-                "Rule 2 content",
-            ),
-            create_test_universal_rule(
-                // Rule with no frontmatter fields effectively
-                "rule3",
-                None,
-                None,
-                None,
-                "Rule 3 content",
-            ),
+            test_rule("rule1", "Rule 1 content")
+                .description("First rule")
+                .globs(&["*.txt"])
+                .cursor_rule_type(CursorRuleType::Always)
+                .build(),
+            test_rule("rule2", "Rule 2 content")
+                .description("Second rule")
+                .cursor_rule_type(CursorRuleType::AgentRequested)
+                .build(),
+            // Rule with no frontmatter fields effectively
+            test_rule("rule3", "Rule 3 content").build(),
         ];
 
-        converter.generate_rules(&rules, output_path).unwrap();
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
 
         let cursor_rules_dir = output_path.join(".cursor").join("rules");
         assert!(
-            cursor_rules_dir.exists(),
+            generated.has_files_in(&cursor_rules_dir),
             "Cursor rules directory should be created."
         );
-        assert!(cursor_rules_dir.is_dir());
 
         let rule1_path = cursor_rules_dir.join("rule1.mdc");
-        assert!(rule1_path.exists());
-        let content1 = fs::read_to_string(rule1_path).unwrap();
+        assert!(generated.contents(&rule1_path).is_some());
+        let content1 = generated.contents(rule1_path).unwrap();
         assert!(content1.contains("description: First rule"));
         assert!(content1.contains("globs:"));
-        assert!(content1.contains("- \"*.txt\""));
+        assert!(content1.contains("- '*.txt'"));
         assert!(content1.contains("alwaysApply: true"));
         assert!(
             content1.contains("---"),
@@ -376,16 +568,16 @@ mod tests {
         assert!(content1.ends_with("Rule 1 content"));
 
         let rule2_path = cursor_rules_dir.join("rule2.mdc");
-        assert!(rule2_path.exists());
-        let content2 = fs::read_to_string(rule2_path).unwrap();
+        assert!(generated.contents(&rule2_path).is_some());
+        let content2 = generated.contents(rule2_path).unwrap();
         assert!(content2.contains("description: Second rule"));
         assert!(content2.contains("agentRequested: true"));
         assert!(!content2.contains("globs:"));
         assert!(content2.ends_with("Rule 2 content"));
 
         let rule3_path = cursor_rules_dir.join("rule3.mdc");
-        assert!(rule3_path.exists());
-        let content3 = fs::read_to_string(rule3_path).unwrap();
+        assert!(generated.contents(&rule3_path).is_some());
+        let content3 = generated.contents(rule3_path).unwrap();
         // Rule 3 had no frontmatter, so .mdc should only contain content
         assert_eq!(
             content3, "Rule 3 content",
@@ -396,4 +588,89 @@ mod tests {
             "No frontmatter separator should exist for rule3"
         );
     }
+
+    /// Test that the legacy format writes a single `.cursorrules` file and no `.mdc` files.
+    #[test]
+    fn test_generate_cursor_rules_legacy_format() {
+        let output_path = Path::new("project");
+        let converter = CursorConverter {
+            format: CursorFormat::Legacy,
+            ..Default::default()
+        };
+
+        let rules = vec![
+            test_rule("rule1", "Rule 1 content")
+                .description("First rule")
+                .globs(&["*.rs"])
+                .cursor_rule_type(CursorRuleType::Always)
+                .build(),
+            test_rule("rule2", "Rule 2 content").build(),
+        ];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let legacy_content = generated
+            .contents(output_path.join(".cursorrules"))
+            .unwrap();
+        assert_eq!(
+            legacy_content,
+            "## Rule: Rule1\nFirst rule\n\nRule 1 content\n\n---\n\n## Rule: Rule2\n\nRule 2 content"
+        );
+        assert!(!generated.has_files_in(output_path.join(".cursor")));
+        assert!(converter
+            .get_output_description(output_path)
+            .contains(".cursorrules"));
+    }
+
+    /// Test that nested mode mirrors the rules' source subdirectories under `.cursor/rules/`.
+    #[test]
+    fn test_generate_cursor_rules_nested() {
+        let output_path = Path::new("project");
+        let converter = CursorConverter {
+            nested: true,
+            ..Default::default()
+        };
+
+        let mut nested_rule = test_rule("hooks", "Hooks content").build();
+        nested_rule.relative_dir = std::path::PathBuf::from("frontend").join("react");
+        let top_level_rule = test_rule("general", "General").build();
+        let generated = converter
+            .generate_rules(&[nested_rule, top_level_rule], output_path)
+            .unwrap();
+
+        let cursor_rules_dir = output_path.join(".cursor").join("rules");
+        let nested_path = cursor_rules_dir
+            .join("frontend")
+            .join("react")
+            .join("hooks.mdc");
+        assert!(
+            generated.contents(&nested_path).is_some(),
+            "Nested rule should mirror its source dir."
+        );
+        assert_eq!(generated.contents(nested_path).unwrap(), "Hooks content");
+        assert!(generated
+            .contents(cursor_rules_dir.join("general.mdc"))
+            .is_some());
+        assert!(generated
+            .contents(cursor_rules_dir.join("hooks.mdc"))
+            .is_none());
+    }
+
+    /// Test that scoped rules go to the nested `.cursor/rules/` of their scope.
+    #[test]
+    fn test_generate_cursor_rules_scoped() {
+        let output_path = Path::new("project");
+        let converter = CursorConverter::default();
+        let mut scoped_rule = test_rule("api", "API content").build();
+        scoped_rule.frontmatter.scope = Some(PathBuf::from("packages/api"));
+        let generated = converter
+            .generate_rules(std::slice::from_ref(&scoped_rule), output_path)
+            .unwrap();
+
+        let scoped_path = output_path.join("packages/api/.cursor/rules/api.mdc");
+        assert_eq!(generated.contents(&scoped_path).unwrap(), "API content");
+        assert!(!generated.has_files_in(output_path.join(".cursor")));
+        assert!(converter
+            .explain_activation(&scoped_rule, output_path)
+            .contains("packages/api/.cursor/rules/api.mdc"));
+    }
 }
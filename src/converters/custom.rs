@@ -0,0 +1,294 @@
+// src/converters/custom.rs
+
+use super::{GeneratedFile, GeneratedRules, RuleConverter};
+use crate::universal_rule::{Activation, ContentType, UniversalRule};
+use anyhow::{bail, Context, Result};
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// A single output file in a custom converter definition.
+///
+/// Both `path` and `template` are minijinja templates rendered with the same context,
+/// so the output path can depend on the rule (e.g. `.myagent/{{ rule.name }}.md`).
+#[derive(Deserialize, Debug, Clone)]
+pub struct FileTemplate {
+    /// The output path, relative to the output directory.
+    pub path: String,
+
+    /// The template producing the file's content.
+    pub template: String,
+}
+
+/// A user-defined converter, loaded from a YAML file.
+///
+/// At least one of `per_rule` (one file per rule, rendered with a `rule` variable) or
+/// `concatenated` (one file for all rules, rendered with a `rules` list) must be set.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct CustomConverterDefinition {
+    /// A display name for the target agent, used in messages. Defaults to `Custom`.
+    pub name: Option<String>,
+
+    /// A file generated once per rule.
+    pub per_rule: Option<FileTemplate>,
+
+    /// A single file generated from all rules.
+    pub concatenated: Option<FileTemplate>,
+
+    /// Patterns added to `.gitignore` for the generated files.
+    #[serde(default)]
+    pub gitignore: Vec<String>,
+}
+
+impl CustomConverterDefinition {
+    /// Reads and validates a custom converter definition from a YAML file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let definition_content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read custom converter definition: {:?}", path))?;
+        let definition: CustomConverterDefinition = serde_yaml::from_str(&definition_content)
+            .with_context(|| format!("Failed to parse custom converter definition: {:?}", path))?;
+        if definition.per_rule.is_none() && definition.concatenated.is_none() {
+            bail!(
+                "Custom converter definition {:?} must define `per_rule` and/or `concatenated`",
+                path
+            );
+        }
+        Ok(definition)
+    }
+}
+
+/// The view of a `UniversalRule` exposed to custom converter templates.
+#[derive(Serialize, Debug)]
+struct RuleContext<'a> {
+    name: &'a str,
+    output_name: &'a str,
+    title: String,
+    description: Option<&'a str>,
+    trigger_description: Option<&'a str>,
+    globs: &'a [String],
+    apply_globally: bool,
+    activation: Option<Activation>,
+    content_type: ContentType,
+    model: Option<&'a str>,
+    extra: &'a BTreeMap<String, serde_yaml::Value>,
+    relative_dir: String,
+    content: &'a str,
+}
+
+impl<'a> From<&'a UniversalRule> for RuleContext<'a> {
+    fn from(rule: &'a UniversalRule) -> Self {
+        RuleContext {
+            name: &rule.name,
+            output_name: rule.output_stem(),
+            title: rule.title(),
+            description: rule.frontmatter.description.as_deref(),
+            trigger_description: rule.frontmatter.trigger_description(),
+            globs: rule.frontmatter.globs.as_deref().unwrap_or_default(),
+            apply_globally: rule.frontmatter.is_always_applied(),
+            activation: rule.frontmatter.activation,
+            content_type: rule.frontmatter.content_kind(),
+            model: rule.frontmatter.model.as_deref(),
+            extra: &rule.frontmatter.extra,
+            relative_dir: rule.relative_dir.to_string_lossy().replace('\\', "/"),
+            content: &rule.content,
+        }
+    }
+}
+
+/// Resolves a rendered output path against `output_dir`, rejecting paths that would
+/// escape it (absolute paths or `..` components).
+fn resolve_output_path(output_dir: &Path, rendered_path: &str) -> Result<PathBuf> {
+    let relative_path = Path::new(rendered_path.trim());
+    if relative_path.as_os_str().is_empty()
+        || relative_path
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        bail!(
+            "Custom converter output path {:?} must be a relative path inside the output directory",
+            rendered_path
+        );
+    }
+    Ok(output_dir.join(relative_path))
+}
+
+/// A `RuleConverter` implementation driven by a user-provided `CustomConverterDefinition`,
+/// for targeting agents this crate has no built-in converter for.
+///
+/// Templates use minijinja syntax. Per-rule templates receive a `rule` object and the
+/// concatenated template receives a `rules` list; each rule exposes `name`, `output_name`,
+/// `description`, `trigger_description`, `globs`, `apply_globally`, `activation`,
+/// `content_type`, `model`, `extra`, `relative_dir`, and `content`.
+/// Per-rule templates are rendered once more for each alias, as its `output_name`.
+pub struct CustomConverter {
+    /// The loaded converter definition.
+    pub definition: CustomConverterDefinition,
+}
+
+impl RuleConverter for CustomConverter {
+    /// Renders the definition's templates for the given rules into files under
+    /// `output_dir`. If no rules are provided, no files are generated.
+    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<GeneratedRules> {
+        let mut files = Vec::new();
+        if rules.is_empty() {
+            return Ok(files.into());
+        }
+
+        let env = Environment::new();
+        let rule_contexts: Vec<RuleContext> = rules.iter().map(RuleContext::from).collect();
+
+        if let Some(per_rule) = &self.definition.per_rule {
+            // Each alias renders the templates again, with the alias as `output_name`
+            let per_rule_contexts: Vec<RuleContext> = rules
+                .iter()
+                .flat_map(|rule| {
+                    rule.output_stems()
+                        .into_iter()
+                        .map(move |stem| RuleContext {
+                            output_name: stem,
+                            ..RuleContext::from(rule)
+                        })
+                })
+                .collect();
+            for rule_context in &per_rule_contexts {
+                let context = minijinja::context! { rule => rule_context };
+                let rendered_path =
+                    env.render_str(&per_rule.path, &context).with_context(|| {
+                        format!(
+                            "Failed to render output path for rule: {}",
+                            rule_context.name
+                        )
+                    })?;
+                let rendered_content =
+                    env.render_str(&per_rule.template, &context)
+                        .with_context(|| {
+                            format!("Failed to render template for rule: {}", rule_context.name)
+                        })?;
+                files.push(GeneratedFile::new(
+                    resolve_output_path(output_dir, &rendered_path)?,
+                    rendered_content,
+                ));
+            }
+        }
+
+        if let Some(concatenated) = &self.definition.concatenated {
+            let context = minijinja::context! { rules => &rule_contexts };
+            let rendered_path = env
+                .render_str(&concatenated.path, &context)
+                .context("Failed to render output path for concatenated file")?;
+            let rendered_content = env
+                .render_str(&concatenated.template, &context)
+                .context("Failed to render template for concatenated file")?;
+            files.push(GeneratedFile::new(
+                resolve_output_path(output_dir, &rendered_path)?,
+                rendered_content,
+            ));
+        }
+
+        Ok(files.into())
+    }
+
+    /// Provides a description of where the custom rules are generated.
+    fn get_output_description(&self, output_dir: &Path) -> String {
+        format!(
+            "{} rules in {:?}",
+            self.definition.name.as_deref().unwrap_or("Custom"),
+            output_dir
+        )
+    }
+
+    /// The activation of a custom agent depends on its templates, which urules does not
+    /// interpret.
+    fn explain_activation(&self, _rule: &UniversalRule, output_dir: &Path) -> String {
+        format!(
+            "Rendered into {}; how the agent activates it depends on the definition's templates.",
+            self.get_output_description(output_dir)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converters::{test_rule, write_rules};
+    use tempfile::tempdir;
+
+    /// Test loading a definition and rendering both per-rule and concatenated templates.
+    #[test]
+    fn test_custom_converter_renders_templates() {
+        let dir = tempdir().unwrap();
+        let definition_path = dir.path().join("agent.yaml");
+        fs::write(
+            &definition_path,
+            r#"
+name: MyAgent
+gitignore: [".myagent/"]
+per_rule:
+  path: ".myagent/rules/{{ rule.name }}.txt"
+  template: "{{ rule.description or 'None' }}: {{ rule.content }}"
+concatenated:
+  path: "MYAGENT.md"
+  template: "{% for rule in rules %}# {{ rule.name }}{% if rule.extra.jira %} ({{ rule.extra.jira }}){% endif %}\n{% endfor %}"
+"#,
+        )
+        .unwrap();
+        let definition = CustomConverterDefinition::from_file(&definition_path).unwrap();
+        assert_eq!(definition.gitignore, vec![".myagent/".to_string()]);
+        let converter = CustomConverter { definition };
+
+        let output_path = dir.path().join("out");
+        let mut rule1 = test_rule("rule1", "Content 1")
+            .description("Desc 1")
+            .build();
+        rule1.frontmatter.extra = BTreeMap::from([("jira".to_string(), "PLAT-1".into())]);
+        let rules = vec![rule1, test_rule("rule2", "Content 2").build()];
+        let generated = converter.generate_rules(&rules, &output_path).unwrap();
+
+        let rules_dir = output_path.join(".myagent").join("rules");
+        assert_eq!(
+            generated.files,
+            [
+                GeneratedFile::new(rules_dir.join("rule1.txt"), "Desc 1: Content 1"),
+                GeneratedFile::new(rules_dir.join("rule2.txt"), "None: Content 2"),
+                GeneratedFile::new(
+                    output_path.join("MYAGENT.md"),
+                    "# rule1 (PLAT-1)\n# rule2\n"
+                ),
+            ]
+        );
+        assert!(converter
+            .get_output_description(&output_path)
+            .starts_with("MyAgent rules"));
+    }
+
+    /// Test that a definition without any output is rejected.
+    #[test]
+    fn test_custom_definition_requires_output() {
+        let dir = tempdir().unwrap();
+        let definition_path = dir.path().join("empty.yaml");
+        fs::write(&definition_path, "name: Nothing\n").unwrap();
+
+        assert!(CustomConverterDefinition::from_file(&definition_path).is_err());
+    }
+
+    /// Test that rendered paths may not escape the output directory.
+    #[test]
+    fn test_custom_converter_rejects_escaping_paths() {
+        let dir = tempdir().unwrap();
+        let converter = CustomConverter {
+            definition: CustomConverterDefinition {
+                concatenated: Some(FileTemplate {
+                    path: "../outside.md".to_string(),
+                    template: "x".to_string(),
+                }),
+                ..Default::default()
+            },
+        };
+        let rules = vec![test_rule("rule1", "Content").build()];
+
+        assert!(write_rules(&converter, &rules, &dir.path().join("out")).is_err());
+        assert!(!dir.path().join("outside.md").exists());
+    }
+}
@@ -0,0 +1,170 @@
+// src/converters/devin.rs
+
+use super::{GeneratedFile, GeneratedRules, RuleConverter};
+use crate::universal_rule::UniversalRule;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// The folder, relative to the output directory, holding the Devin knowledge entries.
+pub const DEVIN_KNOWLEDGE_DIR: &str = "devin_knowledge";
+
+/// Represents the YAML frontmatter of a Devin knowledge entry.
+#[derive(Serialize, Debug)]
+pub struct KnowledgeFrontmatter {
+    /// The entry's name, shown in Devin's knowledge list.
+    pub name: String,
+
+    /// When Devin should recall this knowledge.
+    pub trigger: String,
+}
+
+/// Derives the trigger description for a rule's knowledge entry.
+///
+/// Devin recalls knowledge based on a trigger description, so one is always produced:
+/// the rule's `trigger_description` or `description` if present, otherwise a sentence
+/// built from its globs or global scope, falling back to the rule name.
+fn trigger_description(rule: &UniversalRule) -> String {
+    if let Some(desc) = rule.frontmatter.trigger_description() {
+        return desc.to_string();
+    }
+    let (include_globs, exclude_globs) = (
+        rule.frontmatter.include_globs(),
+        rule.frontmatter.exclude_globs(),
+    );
+    match (include_globs.is_empty(), exclude_globs.is_empty()) {
+        (false, true) => format!(
+            "When working with files matching {}",
+            include_globs.join(", ")
+        ),
+        (false, false) => format!(
+            "When working with files matching {}, except {}",
+            include_globs.join(", "),
+            exclude_globs.join(", ")
+        ),
+        _ if rule.frontmatter.is_always_applied() => {
+            "For every task in this repository".to_string()
+        }
+        _ => format!("When working on {}", rule.name.replace(['_', '-'], " ")),
+    }
+}
+
+/// Renders a rule as a Devin knowledge entry: a frontmatter block with the entry's
+/// name and trigger description, followed by the rule content.
+fn render_knowledge_entry(rule: &UniversalRule) -> Result<String> {
+    let frontmatter = KnowledgeFrontmatter {
+        name: rule.name.clone(),
+        trigger: trigger_description(rule),
+    };
+    let frontmatter_yaml = serde_yaml::to_string(&frontmatter).with_context(|| {
+        format!(
+            "Failed to serialize knowledge frontmatter for rule: {}",
+            rule.name
+        )
+    })?;
+    Ok(format!(
+        "---\n{}\n---\n{}",
+        frontmatter_yaml.trim_end(),
+        rule.content
+    ))
+}
+
+/// A `RuleConverter` implementation that packages rules as Devin knowledge entries.
+///
+/// Devin has no repository rule file; instead teams add knowledge entries, each made of
+/// a trigger description and content. This converter writes one Markdown file per rule
+/// to `devin_knowledge/<name>.md`, ready to be added to Devin's knowledge.
+pub struct DevinConverter;
+
+impl RuleConverter for DevinConverter {
+    /// Generates one knowledge entry file per `UniversalRule` in `devin_knowledge/`.
+    ///
+    /// If no rules are provided, no file is generated.
+    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<GeneratedRules> {
+        let knowledge_dir = output_dir.join(DEVIN_KNOWLEDGE_DIR);
+        let mut files = Vec::new();
+        for rule in rules {
+            let entry_content = render_knowledge_entry(rule)?;
+
+            for stem in rule.output_stems() {
+                files.push(GeneratedFile::new(
+                    knowledge_dir.join(format!("{}.md", stem)),
+                    entry_content.clone(),
+                ));
+            }
+        }
+
+        Ok(files.into())
+    }
+
+    /// Linked assets are copied to `devin_knowledge/assets/`.
+    fn assets_dir(&self) -> Option<PathBuf> {
+        Some(PathBuf::from("devin_knowledge/assets"))
+    }
+
+    /// Provides a description of where the Devin knowledge entries are generated.
+    fn get_output_description(&self, output_dir: &Path) -> String {
+        format!(
+            "Devin knowledge entries in {:?}",
+            output_dir.join(DEVIN_KNOWLEDGE_DIR)
+        )
+    }
+
+    /// Explains that Devin recalls the entry from its trigger description.
+    fn explain_activation(&self, rule: &UniversalRule, output_dir: &Path) -> String {
+        format!(
+            "Recalled when relevant, from the trigger {:?} of the knowledge entry {:?}.",
+            trigger_description(rule),
+            output_dir
+                .join(DEVIN_KNOWLEDGE_DIR)
+                .join(format!("{}.md", rule.output_stem()))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converters::test_rule;
+
+    /// Test that negated globs become exceptions of the trigger description.
+    #[test]
+    fn test_trigger_description_negated_globs() {
+        let rule = test_rule("ts", "")
+            .globs(&["**/*.ts", "!**/*.test.ts"])
+            .build();
+        assert_eq!(
+            trigger_description(&rule),
+            "When working with files matching **/*.ts, except **/*.test.ts"
+        );
+    }
+
+    /// Test that each rule becomes a knowledge entry with a trigger description.
+    #[test]
+    fn test_generate_devin_knowledge_entries() {
+        let output_path = Path::new("project");
+        let converter = DevinConverter;
+
+        let rules = vec![
+            test_rule("api", "Use REST.")
+                .description("When designing APIs")
+                .build(),
+            test_rule("rust", "Use clippy.").globs(&["*.rs"]).build(),
+            test_rule("code_style", "Be consistent.").build(),
+        ];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let knowledge_dir = output_path.join(DEVIN_KNOWLEDGE_DIR);
+        assert_eq!(generated.files.len(), 3);
+        assert_eq!(
+            generated.contents(knowledge_dir.join("api.md")).unwrap(),
+            "---\nname: api\ntrigger: When designing APIs\n---\nUse REST."
+        );
+        let rust_content = generated.contents(knowledge_dir.join("rust.md")).unwrap();
+        assert!(rust_content.contains("trigger: When working with files matching *.rs"));
+        let style_content = generated
+            .contents(knowledge_dir.join("code_style.md"))
+            .unwrap();
+        assert!(style_content.contains("trigger: When working on code style"));
+    }
+}
@@ -0,0 +1,102 @@
+// src/converters/goose.rs
+
+use super::{GeneratedFile, GeneratedRules, RuleConverter, RULE_SEPARATOR};
+use crate::universal_rule::UniversalRule;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Renders a single rule block for `.goosehints`.
+///
+/// The block starts with a `## Rule: {title}` heading and the description (if any), like
+/// the other single-file outputs. Goose has no native glob targeting, so the rule's globs
+/// are kept as a `# Globs:` comment line (the same annotation used by the Windsurf converter).
+fn render_goose_block(rule: &UniversalRule) -> String {
+    let mut rule_block = format!("## Rule: {}\n", rule.title());
+    if let Some(desc) = &rule.frontmatter.description {
+        rule_block.push_str(&format!("{}\n\n", desc));
+    } else {
+        rule_block.push('\n');
+    }
+    if let Some(globs) = &rule.frontmatter.globs {
+        if !globs.is_empty() {
+            rule_block.push_str(&format!("# Globs: {:?}\n\n", globs));
+        }
+    }
+    rule_block.push_str(&rule.content);
+    rule_block
+}
+
+/// A `RuleConverter` implementation for generating a Goose `.goosehints` file.
+///
+/// All rules are concatenated into a single `.goosehints` file at the output root.
+/// Rules marked with `apply_globally: true` are placed first, followed by the remaining
+/// rules in their original order.
+pub struct GooseConverter;
+
+impl RuleConverter for GooseConverter {
+    /// Generates a `.goosehints` file from a list of `UniversalRule`s.
+    ///
+    /// If no rules are provided, no file is generated. Rules are separated by a
+    /// Markdown horizontal rule (`\n\n---\n\n`).
+    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<GeneratedRules> {
+        if rules.is_empty() {
+            // Do not create an empty .goosehints if there are no rules to process.
+            return Ok(GeneratedRules::default());
+        }
+
+        // Global rules first, then everything else; `partition` keeps the relative order.
+        let (global_rules, other_rules): (Vec<&UniversalRule>, Vec<&UniversalRule>) = rules
+            .iter()
+            .partition(|rule| rule.frontmatter.is_always_applied());
+
+        let goose_content = global_rules
+            .into_iter()
+            .chain(other_rules)
+            .map(render_goose_block)
+            .collect::<Vec<String>>()
+            .join(RULE_SEPARATOR);
+
+        Ok(vec![GeneratedFile::new(
+            output_dir.join(".goosehints"),
+            goose_content,
+        )]
+        .into())
+    }
+
+    /// Linked assets are copied to `.goose/assets/`.
+    fn assets_dir(&self) -> Option<PathBuf> {
+        Some(PathBuf::from(".goose/assets"))
+    }
+
+    /// Provides a description of where the Goose hints file is generated.
+    fn get_output_description(&self, output_dir: &Path) -> String {
+        format!("Goose hints in {:?}", output_dir.join(".goosehints"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converters::test_rule;
+
+    /// Test that global rules come first and glob-scoped rules carry a `# Globs:` comment.
+    #[test]
+    fn test_generate_goose_hints_ordering_and_globs() {
+        let output_path = Path::new("project");
+        let converter = GooseConverter;
+
+        let rules = vec![
+            test_rule("rust", "Use clippy.").globs(&["*.rs"]).build(),
+            test_rule("global", "Be concise.").apply_globally().build(),
+            test_rule("docs", "Write docs.").build(),
+        ];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        assert_eq!(
+            generated.contents(output_path.join(".goosehints")).unwrap(),
+            "## Rule: Global\n\nBe concise.\n\n---\n\n\
+             ## Rule: Rust\n\n# Globs: [\"*.rs\"]\n\nUse clippy.\n\n---\n\n\
+             ## Rule: Docs\n\nWrite docs."
+        );
+    }
+}
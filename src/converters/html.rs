@@ -0,0 +1,192 @@
+// src/converters/html.rs
+
+use super::{GeneratedFile, GeneratedRules, RuleConverter};
+use crate::universal_rule::UniversalRule;
+use anyhow::Result;
+use pulldown_cmark::{html, Parser};
+use std::path::Path;
+
+/// The file name of the generated preview page.
+pub const HTML_PREVIEW_FILE: &str = "rules.html";
+
+/// Inline stylesheet for the preview page, so the file works without any assets.
+const PREVIEW_STYLE: &str = "body{margin:0;font-family:system-ui,sans-serif;display:flex}\
+nav{width:16rem;height:100vh;position:sticky;top:0;overflow-y:auto;background:#f4f4f5;padding:1rem;box-sizing:border-box}\
+nav ul{list-style:none;padding:0}nav a{color:#18181b;text-decoration:none}\
+main{flex:1;padding:1rem 2rem;max-width:60rem}\
+section{border-bottom:1px solid #e4e4e7;padding-bottom:1rem}\
+.badge{display:inline-block;font-size:.75rem;padding:.1rem .5rem;margin-right:.25rem;border-radius:1rem;background:#dbeafe}\
+.badge.all{background:#dcfce7}.meta{color:#52525b;font-size:.875rem}\
+pre{background:#f4f4f5;padding:.5rem;overflow-x:auto}";
+
+/// Escapes text for safe inclusion in HTML element content and attribute values.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Renders the agent applicability badges for a rule: one badge per listed agent,
+/// or a single "all agents" badge if the rule has no `agents` restriction.
+fn render_agent_badges(rule: &UniversalRule) -> String {
+    match &rule.frontmatter.agents {
+        Some(agents) => agents
+            .iter()
+            .map(|agent| format!("<span class=\"badge\">{}</span>", agent))
+            .collect::<Vec<String>>()
+            .join(""),
+        None => "<span class=\"badge all\">All agents</span>".to_string(),
+    }
+}
+
+/// Renders a single rule as an HTML `<section>`, with its Markdown content converted to HTML.
+fn render_rule_section(index: usize, rule: &UniversalRule) -> String {
+    let mut section = format!(
+        "<section id=\"rule-{}\">\n<h2>{}</h2>\n<p>{}</p>\n",
+        index,
+        escape_html(&rule.title()),
+        render_agent_badges(rule)
+    );
+    if let Some(desc) = &rule.frontmatter.description {
+        section.push_str(&format!("<p class=\"meta\">{}</p>\n", escape_html(desc)));
+    }
+    if let Some(globs) = rule.frontmatter.globs.as_ref().filter(|g| !g.is_empty()) {
+        section.push_str(&format!(
+            "<p class=\"meta\">Globs: <code>{}</code></p>\n",
+            escape_html(&globs.join(", "))
+        ));
+    }
+    if rule.frontmatter.is_always_applied() {
+        section.push_str("<p class=\"meta\">Applied globally</p>\n");
+    }
+    html::push_html(&mut section, Parser::new(&rule.content));
+    section.push_str("</section>");
+    section
+}
+
+/// Renders the full preview page: a sidebar index linking to one section per rule.
+fn render_preview_page(rules: &[UniversalRule]) -> String {
+    let index_items = rules
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| {
+            format!(
+                "<li><a href=\"#rule-{}\">{}</a></li>",
+                i,
+                escape_html(&rule.title())
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+    let sections = rules
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| render_rule_section(i, rule))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Rules preview</title>\n<style>{}</style>\n</head>\n<body>\n\
+         <nav>\n<h1>Rules ({})</h1>\n<ul>\n{}\n</ul>\n</nav>\n\
+         <main>\n{}\n</main>\n</body>\n</html>\n",
+        PREVIEW_STYLE,
+        rules.len(),
+        index_items,
+        sections
+    )
+}
+
+/// A `RuleConverter` implementation that renders all rules into a static HTML page.
+///
+/// The page (`rules.html`) has a sidebar index and one section per rule showing its
+/// description, globs, agent applicability badges and rendered Markdown content, so the
+/// rule set can be reviewed in a browser. Like the JSON export, it includes every rule
+/// regardless of its `agents` list.
+pub struct HtmlConverter;
+
+impl RuleConverter for HtmlConverter {
+    /// Generates the preview page for all `UniversalRule`s, `rules.html` in `output_dir`.
+    ///
+    /// If no rules are provided, no file is generated.
+    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<GeneratedRules> {
+        if rules.is_empty() {
+            // Do not create an empty preview if there are no rules to process.
+            return Ok(GeneratedRules::default());
+        }
+
+        Ok(vec![GeneratedFile::new(
+            output_dir.join(HTML_PREVIEW_FILE),
+            render_preview_page(rules),
+        )]
+        .into())
+    }
+
+    /// Provides a description of where the HTML preview is generated.
+    fn get_output_description(&self, output_dir: &Path) -> String {
+        format!("HTML preview in {:?}", output_dir.join(HTML_PREVIEW_FILE))
+    }
+
+    /// The preview is an export for people, not loaded by an agent.
+    fn explain_activation(&self, _rule: &UniversalRule, output_dir: &Path) -> String {
+        format!(
+            "Not loaded by an agent: exported to the {}.",
+            self.get_output_description(output_dir)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universal_rule::{UniversalRule, UniversalRuleFrontmatter};
+    use crate::AgentName;
+
+    /// Test that the page contains an index entry, badges and rendered Markdown per rule.
+    #[test]
+    fn test_generate_html_preview() {
+        let output_path = Path::new("project");
+        let converter = HtmlConverter;
+
+        let rules = vec![
+            UniversalRule {
+                name: "rust".to_string(),
+                content: "Use **clippy** & <rustfmt>.".to_string(),
+                frontmatter: UniversalRuleFrontmatter {
+                    description: Some("Rust <style>".to_string()),
+                    globs: Some(vec!["*.rs".to_string()]),
+                    agents: Some(vec![AgentName::Cursor, AgentName::Claude]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            UniversalRule {
+                name: "general".to_string(),
+                content: "Be concise.".to_string(),
+                ..Default::default()
+            },
+        ];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let content = generated
+            .contents(output_path.join(HTML_PREVIEW_FILE))
+            .unwrap();
+        assert!(content.contains("<li><a href=\"#rule-0\">Rust</a></li>"));
+        assert!(content.contains("<li><a href=\"#rule-1\">General</a></li>"));
+        assert!(content
+            .contains("<span class=\"badge\">Cursor</span><span class=\"badge\">Claude</span>"));
+        assert!(content.contains("<span class=\"badge all\">All agents</span>"));
+        assert!(content.contains("<p class=\"meta\">Rust &lt;style&gt;</p>"));
+        assert!(content.contains("<code>*.rs</code>"));
+        assert!(content.contains("<strong>clippy</strong>"));
+    }
+}
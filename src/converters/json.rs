@@ -0,0 +1,98 @@
+// src/converters/json.rs
+
+use super::{GeneratedFile, GeneratedRules, RuleConverter};
+use crate::universal_rule::UniversalRule;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// A `RuleConverter` implementation that exports the parsed rules as `rules.json`.
+///
+/// Unlike the agent converters, this performs no formatting: the full `UniversalRule`
+/// list (name, frontmatter, content, and relative directory) is serialized as a
+/// pretty-printed JSON array so that other tools can consume the rules programmatically.
+pub struct JsonConverter;
+
+impl RuleConverter for JsonConverter {
+    /// Generates `rules.json` in `output_dir` with all `UniversalRule`s.
+    ///
+    /// The file is generated even if no rules are provided (as an empty array), so consumers
+    /// always find a valid document.
+    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<GeneratedRules> {
+        let json_content =
+            serde_json::to_string_pretty(rules).context("Failed to serialize rules to JSON")?;
+
+        Ok(vec![GeneratedFile::new(
+            output_dir.join("rules.json"),
+            json_content + "\n",
+        )]
+        .into())
+    }
+
+    /// Provides a description of where the JSON export is generated.
+    fn get_output_description(&self, output_dir: &Path) -> String {
+        format!("JSON rules in {:?}", output_dir.join("rules.json"))
+    }
+
+    /// The JSON export is read by tools, not loaded by an agent.
+    fn explain_activation(&self, _rule: &UniversalRule, output_dir: &Path) -> String {
+        format!(
+            "Not loaded by an agent: exported to the {}.",
+            self.get_output_description(output_dir)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universal_rule::{UniversalRule, UniversalRuleFrontmatter};
+    use crate::AgentName;
+
+    /// Test that the full rule, including frontmatter, round-trips into `rules.json`.
+    #[test]
+    fn test_generate_json_rules() {
+        let output_path = Path::new("project");
+        let converter = JsonConverter;
+
+        let rules = vec![UniversalRule {
+            name: "rust".to_string(),
+            content: "Use clippy.".to_string(),
+            frontmatter: UniversalRuleFrontmatter {
+                description: Some("Rust rules".to_string()),
+                globs: Some(vec!["*.rs".to_string()]),
+                agents: Some(vec![AgentName::Cursor, AgentName::Json]),
+                ..Default::default()
+            },
+            relative_dir: "backend".into(),
+        }];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let content = generated.contents(output_path.join("rules.json")).unwrap();
+        let json: serde_json::Value = serde_json::from_str(content).unwrap();
+        let rule = &json[0];
+        assert_eq!(rule["name"], "rust");
+        assert_eq!(rule["content"], "Use clippy.");
+        assert_eq!(rule["relative_dir"], "backend");
+        assert_eq!(rule["frontmatter"]["description"], "Rust rules");
+        assert_eq!(rule["frontmatter"]["globs"][0], "*.rs");
+        assert_eq!(rule["frontmatter"]["apply_globally"], false);
+        assert_eq!(rule["frontmatter"]["content_type"], "instructions");
+        assert_eq!(
+            rule["frontmatter"]["agents"],
+            serde_json::json!(["cursor", "json"])
+        );
+    }
+
+    /// Test that an empty rule list still produces a valid, empty JSON array.
+    #[test]
+    fn test_generate_json_rules_no_rules() {
+        let output_path = Path::new("project");
+        let converter = JsonConverter;
+        let rules: Vec<UniversalRule> = vec![];
+
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let content = generated.contents(output_path.join("rules.json")).unwrap();
+        assert_eq!(content.trim(), "[]");
+    }
+}
@@ -2,27 +2,357 @@
 
 use crate::universal_rule::UniversalRule;
 use anyhow::Result;
-use std::path::Path;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// A file a converter generates: where it goes and what it contains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratedFile {
+    /// The path of the file, in the output directory given to the converter (or, for
+    /// outputs such as Windsurf's global rules, wherever the agent reads them).
+    pub path: PathBuf,
+    /// The content of the file.
+    pub contents: String,
+}
+
+impl GeneratedFile {
+    /// Returns a generated file at `path` with `contents`.
+    pub fn new(path: impl Into<PathBuf>, contents: impl Into<String>) -> Self {
+        GeneratedFile {
+            path: path.into(),
+            contents: contents.into(),
+        }
+    }
+}
+
+/// What a converter generates from the rules: its files, and the problems with them
+/// (e.g. outputs over the size the agent reads) for the caller to report.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GeneratedRules {
+    /// The files to generate.
+    pub files: Vec<GeneratedFile>,
+    /// Warnings about the files, without a label.
+    pub warnings: Vec<String>,
+}
+
+#[cfg(test)]
+impl GeneratedRules {
+    /// Returns the content of the generated file at `path`, if there is one.
+    pub fn contents(&self, path: impl AsRef<Path>) -> Option<&str> {
+        self.files
+            .iter()
+            .find(|file| file.path == path.as_ref())
+            .map(|file| file.contents.as_str())
+    }
+
+    /// Returns `true` if a file is generated in the directory `dir`, at any depth.
+    pub fn has_files_in(&self, dir: impl AsRef<Path>) -> bool {
+        self.files.iter().any(|file| file.path.starts_with(&dir))
+    }
+}
+
+/// Writes the files `converter` generates from `rules` into `output_dir`, merged with
+/// the files already there, through an `OutputPlan` as generation does, and returns the
+/// warnings about them.
+#[cfg(test)]
+pub(crate) fn write_rules(
+    converter: &impl RuleConverter,
+    rules: &[UniversalRule],
+    output_dir: &Path,
+) -> Result<Vec<String>> {
+    let generated = converter.generate_rules(rules, output_dir)?;
+    let mut plan = crate::dry_run::OutputPlan::default();
+    for file in &generated.files {
+        let existing = plan.current_text(&file.path)?;
+        plan.insert(
+            file.path.clone(),
+            converter.merge_with_existing(file, existing.as_deref())?,
+        );
+    }
+    plan.write()?;
+    Ok(generated.warnings)
+}
+
+/// Builds the rules of the converter tests, with only the frontmatter they set.
+#[cfg(test)]
+pub(crate) struct TestRule(UniversalRule);
+
+/// Starts a test rule named `name` with `content` and an empty frontmatter.
+#[cfg(test)]
+pub(crate) fn test_rule(name: &str, content: &str) -> TestRule {
+    TestRule(UniversalRule {
+        name: name.to_string(),
+        content: content.to_string(),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+impl TestRule {
+    /// Sets the rule's `description`.
+    pub(crate) fn description(mut self, description: &str) -> Self {
+        self.0.frontmatter.description = Some(description.to_string());
+        self
+    }
+
+    /// Sets the rule's `globs`.
+    pub(crate) fn globs(mut self, globs: &[&str]) -> Self {
+        self.0.frontmatter.globs = Some(globs.iter().map(|glob| glob.to_string()).collect());
+        self
+    }
+
+    /// Sets the rule's `apply_globally`.
+    pub(crate) fn apply_globally(mut self) -> Self {
+        self.0.frontmatter.apply_globally = true;
+        self
+    }
+
+    /// Sets the rule's `cursor_rule_type`.
+    pub(crate) fn cursor_rule_type(
+        mut self,
+        cursor_rule_type: crate::universal_rule::CursorRuleType,
+    ) -> Self {
+        self.0.frontmatter.cursor_rule_type = Some(cursor_rule_type);
+        self
+    }
+
+    /// Returns the rule.
+    pub(crate) fn build(self) -> UniversalRule {
+        self.0
+    }
+}
+
+impl From<Vec<GeneratedFile>> for GeneratedRules {
+    fn from(files: Vec<GeneratedFile>) -> Self {
+        GeneratedRules {
+            files,
+            warnings: Vec::new(),
+        }
+    }
+}
 
 /// A trait for types that can convert a list of UniversalRules
-/// into a specific agent's rule format and write them to disk.
+/// into a specific agent's rule format.
 pub trait RuleConverter {
-    /// Generates agent-specific rule files from a slice of UniversalRules.
+    /// Converts a slice of UniversalRules into the agent-specific files to generate,
+    /// without writing them, so that callers can write, stage, diff or print them.
     ///
     /// # Arguments
     /// * `rules` - A slice of `UniversalRule` to be converted.
-    /// * `output_dir` - The directory where the generated rule files should be saved.
+    /// * `output_dir` - The directory the generated rule files are meant for.
     ///
     /// # Returns
-    /// A `Result` indicating success or failure.
-    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<()>;
+    /// A `Result` with the generated files, whose paths are in `output_dir`, and the
+    /// warnings about them.
+    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<GeneratedRules>;
+
+    /// Returns the content to write for the generated `file`, given the `existing`
+    /// content of its path (`None` if there is no file there yet), for outputs shared
+    /// with other tools.
+    ///
+    /// Defaults to the generated content, which replaces the existing file.
+    fn merge_with_existing(&self, file: &GeneratedFile, _existing: Option<&str>) -> Result<String> {
+        Ok(file.contents.clone())
+    }
 
     /// Provides a descriptive name for the output generated by this converter.
     /// This is used for user-facing messages.
     /// For example, "Cursor rules (.cursor/rules)" or "Claude.md".
     fn get_output_description(&self, output_dir: &Path) -> String;
+
+    /// Returns `false` if the converter drops the negated (`!`) globs of `rule` because
+    /// the agent's format cannot express exclusions, so that generation warns about them.
+    ///
+    /// Defaults to `true`, for converters that keep or never use globs.
+    fn supports_negated_globs(&self, _rule: &UniversalRule) -> bool {
+        true
+    }
+
+    /// Returns the directory, relative to the output directory, that the files of the
+    /// rules directory's `assets` the rules link to are copied to, with the links
+    /// rewritten to point at the copies.
+    ///
+    /// Defaults to `None`, which leaves the links as they are.
+    fn assets_dir(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// Describes how the agent activates `rule` once it is generated into `output_dir`
+    /// (e.g. always, for matching files or on request), for `urules explain`.
+    ///
+    /// Defaults to a rule that is always in context, as for single-file outputs.
+    fn explain_activation(&self, _rule: &UniversalRule, output_dir: &Path) -> String {
+        format!(
+            "Always in context: merged into {}.",
+            self.get_output_description(output_dir)
+        )
+    }
+}
+
+/// What converters do with outputs over the size an agent reads of them.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OversizeMode {
+    /// Split per-rule files into numbered parts, and trim the outputs that cannot be split.
+    #[default]
+    Split,
+    /// Trim every oversized output to the limit.
+    Trim,
+    /// Write oversized outputs as they are, with a warning.
+    Keep,
+}
+
+/// Splits `content` into parts of at most `max_chars` characters, at blank lines outside
+/// fenced code blocks where possible, or else at line ends and, for longer lines, anywhere.
+///
+/// Each part ends with a single newline. Content within the limit is returned as it is.
+pub fn split_content(content: &str, max_chars: usize) -> Vec<String> {
+    if content.chars().count() <= max_chars {
+        return vec![content.to_string()];
+    }
+    // Leave room for the newline each part ends with
+    let budget = max_chars.saturating_sub(1).max(1);
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0;
+    // The byte offset in `current` after its last blank line outside a code block
+    let mut last_break = 0;
+    let mut in_fence = false;
+    let finish = |part: &str, parts: &mut Vec<String>| {
+        let part = part.trim_end();
+        if !part.is_empty() {
+            parts.push(format!("{}\n", part));
+        }
+    };
+    for line in content.split_inclusive('\n') {
+        let mut line = line;
+        while current_chars + line.chars().count() > budget {
+            if last_break > 0 {
+                finish(&current[..last_break], &mut parts);
+                current = current[last_break..].to_string();
+            } else if !current.is_empty() {
+                finish(&current, &mut parts);
+                current.clear();
+            } else {
+                // A single line over the limit is cut at the limit
+                let cut = line
+                    .char_indices()
+                    .nth(budget)
+                    .map_or(line.len(), |(index, _)| index);
+                finish(&line[..cut], &mut parts);
+                line = &line[cut..];
+            }
+            current_chars = current.chars().count();
+            last_break = 0;
+        }
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        current.push_str(line);
+        current_chars += line.chars().count();
+        if !in_fence && line.trim().is_empty() {
+            last_break = current.len();
+        }
+    }
+    finish(&current, &mut parts);
+    parts
+}
+
+/// Returns `content` cut to its first part of at most `max_chars` characters, as split by
+/// `split_content`.
+pub fn trim_content(content: &str, max_chars: usize) -> String {
+    split_content(content, max_chars)
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+}
+
+/// The Markdown separator placed between rules in single-file outputs.
+pub const RULE_SEPARATOR: &str = "\n\n---\n\n";
+
+/// Renders a single rule as a Markdown block: its title as a Level 2 heading
+/// (`## Rule: {title}`), followed by its description (if any) and then its content.
+pub fn render_rule_block(rule: &UniversalRule) -> String {
+    let mut rule_block = String::new();
+    // Add rule title as a heading
+    rule_block.push_str(&format!("## Rule: {}\n", rule.title()));
+    // Add description if available, followed by a blank line
+    if let Some(desc) = &rule.frontmatter.description {
+        rule_block.push_str(&format!("{}\n\n", desc));
+    } else {
+        // Ensure a blank line after the name heading even if no description
+        rule_block.push('\n');
+    }
+    // Add the main rule content
+    rule_block.push_str(&rule.content);
+    rule_block
+}
+
+/// Concatenates rules into a single Markdown document, as used by single-file
+/// converters such as `CLAUDE.md` or `.github/copilot-instructions.md`.
+///
+/// Each rule is rendered with `render_rule_block`, and rules are joined with `RULE_SEPARATOR`.
+/// A single rule produces no separator.
+pub fn concatenate_rules<'a>(rules: impl IntoIterator<Item = &'a UniversalRule>) -> String {
+    rules
+        .into_iter()
+        .map(render_rule_block)
+        .collect::<Vec<String>>()
+        .join(RULE_SEPARATOR)
 }
 
 pub mod claude;
+pub mod copilot;
 pub mod cursor;
+pub mod custom;
+pub mod devin;
+pub mod goose;
+pub mod html;
+pub mod json;
+pub mod registry;
+pub mod warp;
 pub mod windsurf;
+pub mod zed;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converters::registry::{available_agents, build_converter, ConverterOptions};
+    use crate::AgentName;
+
+    /// Test that no converter but the JSON export generates a file without rules.
+    #[test]
+    fn test_generate_no_rules() {
+        for agent in available_agents(false) {
+            let converter = build_converter(&agent, &ConverterOptions::default()).unwrap();
+            let generated = converter.generate_rules(&[], Path::new("project")).unwrap();
+            match agent {
+                AgentName::Json => assert_eq!(generated.files.len(), 1),
+                _ => assert_eq!(generated, GeneratedRules::default(), "{}", agent),
+            }
+        }
+    }
+
+    /// Test that content is split at blank lines, then at line ends, and never inside
+    /// code blocks when a blank line outside them fits.
+    #[test]
+    fn test_split_content() {
+        assert_eq!(split_content("Short.", 10), ["Short."]);
+        assert_eq!(
+            split_content("First paragraph.\n\nSecond one.\nMore.\n", 24),
+            ["First paragraph.\n", "Second one.\nMore.\n"]
+        );
+        assert_eq!(
+            split_content("One line.\nTwo lines.\n", 12),
+            ["One line.\n", "Two lines.\n"]
+        );
+        assert_eq!(split_content("abcdefgh", 5), ["abcd\n", "efgh\n"]);
+        assert_eq!(
+            split_content("Intro.\n\n```\na\n\nb\n```\n", 20),
+            ["Intro.\n", "```\na\n\nb\n```\n"]
+        );
+        assert_eq!(trim_content("Kept.\n\nDropped.\n", 10), "Kept.\n");
+    }
+}
@@ -0,0 +1,405 @@
+// src/converters/registry.rs
+
+use super::claude::{ClaudeConverter, ClaudeFormat, CLAUDE_RULES_DIR};
+use super::copilot::{CopilotConverter, CopilotFormat};
+use super::cursor::{self, CursorConverter, CursorFormat};
+use super::custom::{CustomConverter, CustomConverterDefinition};
+use super::devin::DevinConverter;
+use super::goose::GooseConverter;
+use super::html::{HtmlConverter, HTML_PREVIEW_FILE};
+use super::json::JsonConverter;
+use super::warp::WarpConverter;
+use super::windsurf::{self, WindsurfConverter, WindsurfFormat};
+use super::zed::ZedConverter;
+use super::{OversizeMode, RuleConverter};
+use crate::AgentName;
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use std::path::Path;
+
+/// The options converters are built with, gathered from the command line and the
+/// project configuration. Each converter only reads its own.
+#[derive(Debug, Clone, Default)]
+pub struct ConverterOptions<'a> {
+    pub cursor_format: CursorFormat,
+    pub cursor_nested: bool,
+    pub windsurf_format: WindsurfFormat,
+    pub windsurf_nested: bool,
+    /// Where Windsurf's `global_rules.md` goes, if not in the output directory.
+    pub windsurf_global_dir: Option<&'a Path>,
+    pub claude_format: ClaudeFormat,
+    pub copilot_format: CopilotFormat,
+    pub oversize: OversizeMode,
+    /// The characters the banner adds to each generated file, which count toward the
+    /// character limits of agents.
+    pub banner_chars: usize,
+    /// The definition of the `custom` converter, from `--custom-converter`.
+    pub custom_definition: Option<&'a CustomConverterDefinition>,
+}
+
+/// Builds the converter of an agent from the options.
+pub type ConverterFactory = fn(&ConverterOptions) -> Result<Box<dyn RuleConverter>>;
+
+/// Returns the `.gitignore` patterns covering the files of an agent in the output layout
+/// of the options.
+pub type GitignorePatterns = fn(&ConverterOptions) -> Vec<String>;
+
+/// Returns the file an agent writes its rules to in the output layout of the options, or
+/// `None` if its output spans several files.
+pub type SingleOutputFile = fn(&ConverterOptions) -> Option<&'static str>;
+
+/// Turns a list of patterns into owned strings, for `GitignorePatterns`.
+fn to_patterns(patterns: &[&str]) -> Vec<String> {
+    patterns.iter().map(|pattern| pattern.to_string()).collect()
+}
+
+/// An agent that rules can be generated for, and how to build its converter.
+pub struct ConverterRegistration {
+    pub agent: AgentName,
+    /// Whether `--agent all` generates the agent (exports are only generated on request).
+    pub in_all: bool,
+    /// Whether the agent is only available with a converter definition
+    /// (`--custom-converter`).
+    pub needs_definition: bool,
+    pub factory: ConverterFactory,
+    pub gitignore: GitignorePatterns,
+    pub single_output_file: SingleOutputFile,
+}
+
+/// Every agent rules can be generated for, in the order `--agent all` generates them.
+///
+/// Adding a converter only takes an entry here (and an `AgentName` variant).
+pub const CONVERTERS: &[ConverterRegistration] = &[
+    ConverterRegistration {
+        agent: AgentName::Cursor,
+        in_all: true,
+        needs_definition: false,
+        factory: |options| {
+            Ok(Box::new(CursorConverter {
+                format: options.cursor_format.clone(),
+                nested: options.cursor_nested,
+            }))
+        },
+        gitignore: |options| match options.cursor_format {
+            CursorFormat::Legacy => to_patterns(&[cursor::LEGACY_RULES_FILE]),
+            _ => to_patterns(&[".cursor/"]),
+        },
+        single_output_file: |options| {
+            (options.cursor_format == CursorFormat::Legacy).then_some(cursor::LEGACY_RULES_FILE)
+        },
+    },
+    ConverterRegistration {
+        agent: AgentName::Windsurf,
+        in_all: true,
+        needs_definition: false,
+        factory: |options| {
+            Ok(Box::new(WindsurfConverter {
+                format: options.windsurf_format.clone(),
+                global_dir: options.windsurf_global_dir.map(Path::to_path_buf),
+                nested: options.windsurf_nested,
+                oversize: options.oversize,
+                banner_chars: options.banner_chars,
+            }))
+        },
+        gitignore: |options| {
+            let mut patterns = Vec::new();
+            // Global rules written outside the project don't need ignoring
+            if options.windsurf_global_dir.is_none() {
+                patterns.push("global_rules.md".to_string());
+            }
+            patterns.push(match options.windsurf_format {
+                WindsurfFormat::Legacy => windsurf::LEGACY_RULES_FILE.to_string(),
+                WindsurfFormat::Rules => ".windsurf/".to_string(),
+            });
+            patterns
+        },
+        single_output_file: |_| None,
+    },
+    ConverterRegistration {
+        agent: AgentName::Claude,
+        in_all: true,
+        needs_definition: false,
+        factory: |options| {
+            Ok(Box::new(ClaudeConverter {
+                format: options.claude_format.clone(),
+            }))
+        },
+        gitignore: |options| {
+            let mut patterns = to_patterns(&["CLAUDE.md", ".claude/commands/", ".claude/agents/"]);
+            if options.claude_format == ClaudeFormat::Imports {
+                patterns.push(format!("{}/", CLAUDE_RULES_DIR));
+            }
+            patterns
+        },
+        single_output_file: |options| {
+            (options.claude_format == ClaudeFormat::Concat).then_some("CLAUDE.md")
+        },
+    },
+    ConverterRegistration {
+        agent: AgentName::Copilot,
+        in_all: true,
+        needs_definition: false,
+        factory: |options| {
+            Ok(Box::new(CopilotConverter {
+                format: options.copilot_format.clone(),
+                oversize: options.oversize,
+                banner_chars: options.banner_chars,
+            }))
+        },
+        gitignore: |options| match options.copilot_format {
+            // settings.json is shared with other VS Code settings, so it is never ignored
+            CopilotFormat::Settings => to_patterns(&[".github/instructions/", ".github/prompts/"]),
+            _ => to_patterns(&[
+                ".github/copilot-instructions.md",
+                ".github/instructions/",
+                ".github/prompts/",
+                ".github/copilot-review-instructions.md",
+            ]),
+        },
+        single_output_file: |_| None,
+    },
+    ConverterRegistration {
+        agent: AgentName::Zed,
+        in_all: true,
+        needs_definition: false,
+        factory: |_| Ok(Box::new(ZedConverter)),
+        gitignore: |_| to_patterns(&["/.rules"]),
+        single_output_file: |_| Some(".rules"),
+    },
+    ConverterRegistration {
+        agent: AgentName::Goose,
+        in_all: true,
+        needs_definition: false,
+        factory: |_| Ok(Box::new(GooseConverter)),
+        gitignore: |_| to_patterns(&[".goosehints"]),
+        single_output_file: |_| Some(".goosehints"),
+    },
+    ConverterRegistration {
+        agent: AgentName::Warp,
+        in_all: true,
+        needs_definition: false,
+        factory: |_| Ok(Box::new(WarpConverter)),
+        gitignore: |_| to_patterns(&["WARP.md"]),
+        single_output_file: |_| Some("WARP.md"),
+    },
+    ConverterRegistration {
+        agent: AgentName::Devin,
+        in_all: true,
+        needs_definition: false,
+        factory: |_| Ok(Box::new(DevinConverter)),
+        gitignore: |_| to_patterns(&["devin_knowledge/"]),
+        single_output_file: |_| None,
+    },
+    ConverterRegistration {
+        agent: AgentName::Json,
+        in_all: false,
+        needs_definition: false,
+        factory: |_| Ok(Box::new(JsonConverter)),
+        gitignore: |_| to_patterns(&["rules.json"]),
+        single_output_file: |_| Some("rules.json"),
+    },
+    ConverterRegistration {
+        agent: AgentName::Html,
+        in_all: false,
+        needs_definition: false,
+        factory: |_| Ok(Box::new(HtmlConverter)),
+        gitignore: |_| to_patterns(&[HTML_PREVIEW_FILE]),
+        single_output_file: |_| Some(HTML_PREVIEW_FILE),
+    },
+    ConverterRegistration {
+        agent: AgentName::Custom,
+        in_all: true,
+        needs_definition: true,
+        factory: |options| {
+            Ok(Box::new(CustomConverter {
+                definition: options
+                    .custom_definition
+                    .cloned()
+                    .context("--agent custom requires --custom-converter <FILE>")?,
+            }))
+        },
+        // Custom converters list their own patterns in the converter definition
+        gitignore: |options| {
+            options
+                .custom_definition
+                .map(|definition| definition.gitignore.clone())
+                .unwrap_or_default()
+        },
+        single_output_file: |_| None,
+    },
+];
+
+/// Returns the registration of `agent`, or `None` for `all`, which stands for several.
+pub fn registration(agent: &AgentName) -> Option<&'static ConverterRegistration> {
+    CONVERTERS
+        .iter()
+        .find(|registration| &registration.agent == agent)
+}
+
+/// Builds the converter of `agent` from `options`.
+pub fn build_converter(
+    agent: &AgentName,
+    options: &ConverterOptions,
+) -> Result<Box<dyn RuleConverter>> {
+    match registration(agent) {
+        Some(registration) => (registration.factory)(options),
+        None => bail!("`all` must be expanded before building converters"),
+    }
+}
+
+/// Returns the `.gitignore` patterns covering the files of `agent` in the output layout
+/// of `options`; for `all`, those of every coding agent.
+pub fn gitignore_patterns(agent: &AgentName, options: &ConverterOptions) -> Vec<String> {
+    match registration(agent) {
+        Some(registration) => (registration.gitignore)(options),
+        None => coding_agents()
+            .iter()
+            .flat_map(|agent| gitignore_patterns(agent, options))
+            .collect(),
+    }
+}
+
+/// Returns the file `agent` writes its rules to in the output layout of `options`, or
+/// `None` if its output spans several files.
+pub fn single_output_file(agent: &AgentName, options: &ConverterOptions) -> Option<&'static str> {
+    registration(agent).and_then(|registration| (registration.single_output_file)(options))
+}
+
+/// Returns the agents `--agent all` generates: the built-in coding agents, plus those
+/// needing a definition when `has_definition` is set.
+pub fn all_agents(has_definition: bool) -> Vec<AgentName> {
+    CONVERTERS
+        .iter()
+        .filter(|registration| {
+            registration.in_all && (has_definition || !registration.needs_definition)
+        })
+        .map(|registration| registration.agent.clone())
+        .collect()
+}
+
+/// Returns the built-in coding agents, those `--agent all` generates without a
+/// converter definition.
+pub fn coding_agents() -> Vec<AgentName> {
+    all_agents(false)
+}
+
+/// Returns the agents that can be picked, with or without a converter definition.
+pub fn available_agents(has_definition: bool) -> Vec<AgentName> {
+    CONVERTERS
+        .iter()
+        .filter(|registration| has_definition || !registration.needs_definition)
+        .map(|registration| registration.agent.clone())
+        .collect()
+}
+
+/// Returns the long help of `--agent`, listing the agents `all` generates.
+pub fn agent_help() -> String {
+    let names = |agents: Vec<AgentName>| {
+        agents
+            .iter()
+            .filter_map(|agent| agent.to_possible_value())
+            .map(|value| value.get_name().to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    };
+    let needing_definition: Vec<AgentName> = CONVERTERS
+        .iter()
+        .filter(|registration| registration.in_all && registration.needs_definition)
+        .map(|registration| registration.agent.clone())
+        .collect();
+    format!(
+        "Target agent(s) for rule generation (repeatable, or `all`).\n\n\
+         `all` generates {}, plus {} with --custom-converter.",
+        names(coding_agents()),
+        names(needing_definition)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that every agent but `all` is registered once, and that `all` only adds the
+    /// custom converter with a definition.
+    #[test]
+    fn test_registry() {
+        for agent in AgentName::value_variants() {
+            let count = CONVERTERS
+                .iter()
+                .filter(|registration| &registration.agent == agent)
+                .count();
+            assert_eq!(count, usize::from(agent != &AgentName::All), "{}", agent);
+        }
+        assert_eq!(
+            coding_agents(),
+            [
+                AgentName::Cursor,
+                AgentName::Windsurf,
+                AgentName::Claude,
+                AgentName::Copilot,
+                AgentName::Zed,
+                AgentName::Goose,
+                AgentName::Warp,
+                AgentName::Devin,
+            ]
+        );
+        assert_eq!(all_agents(true).last(), Some(&AgentName::Custom));
+        assert!(!available_agents(false).contains(&AgentName::Custom));
+        assert!(available_agents(false).contains(&AgentName::Json));
+
+        let options = ConverterOptions::default();
+        assert!(build_converter(&AgentName::Zed, &options).is_ok());
+        assert!(build_converter(&AgentName::Custom, &options).is_err());
+        assert!(build_converter(&AgentName::All, &options).is_err());
+        assert!(agent_help().contains("`all` generates cursor, windsurf, claude"));
+    }
+
+    /// Test that the gitignore patterns and the single output file follow the layout.
+    #[test]
+    fn test_registry_output_layout() {
+        let default = ConverterOptions::default();
+        assert_eq!(
+            gitignore_patterns(&AgentName::Cursor, &default),
+            [".cursor/"]
+        );
+        assert_eq!(single_output_file(&AgentName::Cursor, &default), None);
+        let legacy = ConverterOptions {
+            cursor_format: CursorFormat::Legacy,
+            ..Default::default()
+        };
+        assert_eq!(
+            gitignore_patterns(&AgentName::Cursor, &legacy),
+            [cursor::LEGACY_RULES_FILE]
+        );
+        assert_eq!(
+            single_output_file(&AgentName::Cursor, &legacy),
+            Some(cursor::LEGACY_RULES_FILE)
+        );
+
+        let global_dir = ConverterOptions {
+            windsurf_global_dir: Some(Path::new("memories")),
+            ..Default::default()
+        };
+        assert_eq!(
+            gitignore_patterns(&AgentName::Windsurf, &global_dir),
+            [".windsurf/"]
+        );
+        let imports = ConverterOptions {
+            claude_format: ClaudeFormat::Imports,
+            ..Default::default()
+        };
+        assert!(gitignore_patterns(&AgentName::Claude, &imports)
+            .contains(&".claude/rules/".to_string()));
+        assert_eq!(single_output_file(&AgentName::Claude, &imports), None);
+        assert_eq!(
+            single_output_file(&AgentName::Claude, &default),
+            Some("CLAUDE.md")
+        );
+        assert_eq!(
+            single_output_file(&AgentName::Zed, &default),
+            Some(".rules")
+        );
+        assert!(gitignore_patterns(&AgentName::Custom, &default).is_empty());
+        assert!(gitignore_patterns(&AgentName::All, &default).contains(&"WARP.md".to_string()));
+    }
+}
@@ -0,0 +1,76 @@
+// src/converters/warp.rs
+
+use super::{concatenate_rules, GeneratedFile, GeneratedRules, RuleConverter};
+use crate::universal_rule::UniversalRule;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// A `RuleConverter` implementation for generating a single `WARP.md` file.
+///
+/// Warp's terminal agents read project rules from a `WARP.md` file at the project root.
+/// This converter concatenates all provided universal rules into that file, using the
+/// same headings and separators as `CLAUDE.md`.
+pub struct WarpConverter;
+
+impl RuleConverter for WarpConverter {
+    /// Generates a `WARP.md` file by concatenating all `UniversalRule`s.
+    ///
+    /// If no rules are provided, no file is generated. Otherwise, each rule's name
+    /// is added as a Level 2 Markdown heading (`## Rule: {title}`), followed by its
+    /// description (if any) and then its content. Rules are separated by a
+    /// Markdown horizontal rule (`\n\n---\n\n`).
+    /// The output file is named `WARP.md` and placed in the specified `output_dir`.
+    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<GeneratedRules> {
+        if rules.is_empty() {
+            // Do not create an empty WARP.md if there are no rules to process.
+            return Ok(GeneratedRules::default());
+        }
+
+        let final_warp_content = concatenate_rules(rules);
+
+        Ok(vec![GeneratedFile::new(
+            output_dir.join("WARP.md"),
+            final_warp_content,
+        )]
+        .into())
+    }
+
+    /// Linked assets are copied to `.warp/assets/`.
+    fn assets_dir(&self) -> Option<PathBuf> {
+        Some(PathBuf::from(".warp/assets"))
+    }
+
+    /// Provides a description of where the Warp rules file is generated.
+    fn get_output_description(&self, output_dir: &Path) -> String {
+        format!("Warp rules in {:?}", output_dir.join("WARP.md"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converters::test_rule;
+
+    /// Test generation of `WARP.md` with multiple rules, checking content and separators.
+    #[test]
+    fn test_generate_warp_rules_multiple_rules() {
+        let output_path = Path::new("project");
+        let converter = WarpConverter;
+
+        let rules = vec![
+            test_rule("rule1", "Content 1.")
+                .description("Description 1.")
+                .build(),
+            test_rule("rule2", "Content 2.").build(),
+        ];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        assert_eq!(
+            generated.files,
+            [GeneratedFile::new(
+                output_path.join("WARP.md"),
+                "## Rule: Rule1\nDescription 1.\n\nContent 1.\n\n---\n\n## Rule: Rule2\n\nContent 2."
+            )]
+        );
+    }
+}
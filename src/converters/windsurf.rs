@@ -1,10 +1,116 @@
 // src/converters/windsurf.rs
 
-use super::RuleConverter;
-use crate::universal_rule::UniversalRule;
-use anyhow::{Context, Result};
-use std::fs;
-use std::path::Path;
+use super::{
+    split_content, trim_content, GeneratedFile, GeneratedRules, OversizeMode, RuleConverter,
+    RULE_SEPARATOR,
+};
+use crate::universal_rule::{Activation, UniversalRule};
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// The file name used by older Windsurf versions for workspace rules.
+pub const LEGACY_RULES_FILE: &str = ".windsurfrules";
+
+/// The most characters Windsurf reads of each rules file (`global_rules.md`,
+/// `.windsurfrules` and the files of `.windsurf/rules/`).
+pub const FILE_CHAR_LIMIT: usize = 6000;
+
+/// The most characters Windsurf reads of the global and workspace rules together.
+pub const TOTAL_CHAR_LIMIT: usize = 12000;
+
+/// Returns Windsurf's real global rules location, `~/.codeium/windsurf/memories/`.
+pub fn default_global_dir() -> Result<PathBuf> {
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .ok_or_else(|| {
+            anyhow!("Could not determine the home directory for Windsurf global rules")
+        })?;
+    Ok(PathBuf::from(home)
+        .join(".codeium")
+        .join("windsurf")
+        .join("memories"))
+}
+
+/// The layout of the workspace rules generated by the `WindsurfConverter`.
+#[derive(ValueEnum, Clone, Debug, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindsurfFormat {
+    /// One `.md` file per rule in `.windsurf/rules/` (current Windsurf versions).
+    #[default]
+    Rules,
+    /// A single concatenated `.windsurfrules` file (older Windsurf versions).
+    Legacy,
+}
+
+/// Renders a workspace rule with its description and globs prepended as comments.
+fn render_workspace_rule(rule: &UniversalRule) -> String {
+    let mut individual_rule_content = String::new();
+    // Prepend description as a comment if available
+    if let Some(desc) = &rule.frontmatter.description {
+        individual_rule_content.push_str(&format!("# Description: {}\n", desc));
+    }
+    // Prepend globs as a comment if available
+    if let Some(globs) = &rule.frontmatter.globs {
+        if !globs.is_empty() {
+            individual_rule_content.push_str(&format!("# Globs: {:?}\n", globs));
+        }
+    }
+    // Add a newline after comments if any were added, before rule content
+    if !individual_rule_content.is_empty() {
+        individual_rule_content.push('\n');
+    }
+    individual_rule_content.push_str(&rule.content);
+    individual_rule_content
+}
+
+/// Returns the Windsurf `trigger` matching the rule's `activation` (or its `content_type`),
+/// or `None` if the rule has no `activation`, in which case its workspace rule is always on.
+fn workspace_trigger(rule: &UniversalRule) -> Option<&'static str> {
+    Some(match rule.frontmatter.effective_activation()? {
+        Activation::Always => "always_on",
+        Activation::Glob => "glob",
+        Activation::AgentDecision => "model_decision",
+        Activation::Manual => "manual",
+    })
+}
+
+/// Renders the file of a workspace rule in `.windsurf/rules/`. Rules with an `activation`
+/// get Windsurf's `trigger` frontmatter, with the `description` (the `trigger_description`
+/// for `model_decision` rules) and `globs` it reads;
+/// other rules are rendered as in `render_workspace_rule`.
+fn render_workspace_rule_file(rule: &UniversalRule) -> String {
+    let Some(trigger) = workspace_trigger(rule) else {
+        return render_workspace_rule(rule);
+    };
+    let mut frontmatter = format!("trigger: {}\n", trigger);
+    // Cascade decides by the description whether to apply a `model_decision` rule
+    let description = match trigger {
+        "model_decision" => rule.frontmatter.trigger_description(),
+        _ => rule.frontmatter.description.as_deref(),
+    };
+    if let Some(desc) = description {
+        frontmatter.push_str(&format!("description: {}\n", desc));
+    }
+    // Windsurf has no exclusions, so negated globs are dropped
+    let globs = rule.frontmatter.include_globs();
+    if !globs.is_empty() {
+        frontmatter.push_str(&format!("globs: {}\n", globs.join(", ")));
+    }
+    format!("---\n{}---\n\n{}", frontmatter, rule.content)
+}
+
+/// Warns if the written rules take more than the `TOTAL_CHAR_LIMIT` characters Windsurf
+/// reads of them together, which splitting files does not help with.
+fn warn_over_total(total_chars: usize, warnings: &mut Vec<String>) {
+    if total_chars > TOTAL_CHAR_LIMIT {
+        warnings.push(format!(
+            "The Windsurf rules take {} characters, over the {} Windsurf reads of all rules together; it ignores the rest",
+            total_chars, TOTAL_CHAR_LIMIT
+        ));
+    }
+}
 
 /// A `RuleConverter` implementation for generating Windsurf-compatible rule files.
 ///
@@ -12,23 +118,146 @@ use std::path::Path;
 /// 1.  A `global_rules.md` file for rules that apply across the entire workspace.
 /// 2.  Individual rule files within a `.windsurf/rules/` directory for workspace-specific
 ///     or file-type-specific rules.
-pub struct WindsurfConverter;
+///
+/// With `WindsurfFormat::Legacy`, workspace rules are concatenated into a single
+/// `.windsurfrules` file instead of the `.windsurf/rules/` directory.
+#[derive(Default)]
+pub struct WindsurfConverter {
+    /// Whether to emit workspace rules as individual files or a legacy `.windsurfrules` file.
+    pub format: WindsurfFormat,
+
+    /// Directory where `global_rules.md` is written. If `None`, it is written to the
+    /// output directory alongside the workspace rules.
+    pub global_dir: Option<PathBuf>,
+
+    /// Whether to mirror the rules' subdirectories (their categories) under
+    /// `.windsurf/rules/` instead of flattening all workspace rules into it.
+    pub nested: bool,
+
+    /// What to do with files over `FILE_CHAR_LIMIT`.
+    pub oversize: OversizeMode,
+
+    /// The characters the banner adds to each file, which count toward the limits.
+    pub banner_chars: usize,
+}
+
+impl WindsurfConverter {
+    /// Returns the directory that receives `global_rules.md`.
+    fn global_rules_dir<'a>(&'a self, output_dir: &'a Path) -> &'a Path {
+        self.global_dir.as_deref().unwrap_or(output_dir)
+    }
+
+    /// Returns the directory of the workspace rule files of `rule`: `.windsurf/rules/`,
+    /// with the rule's subdirectory appended if `nested` is set.
+    fn workspace_rules_dir(&self, rule: &UniversalRule, output_dir: &Path) -> PathBuf {
+        let mut rules_dir = output_dir.join(".windsurf").join("rules");
+        if self.nested {
+            rules_dir.push(&rule.relative_dir);
+        }
+        rules_dir
+    }
+
+    /// Returns the characters a file can take before the banner, within `FILE_CHAR_LIMIT`.
+    fn content_char_limit(&self) -> usize {
+        FILE_CHAR_LIMIT.saturating_sub(self.banner_chars)
+    }
+
+    /// Returns `content` of the single-file output `file`, trimmed to `FILE_CHAR_LIMIT`
+    /// unless `oversize` is `OversizeMode::Keep`, adding a warning if it is over the limit.
+    fn fit_file(&self, file: &str, content: String, warnings: &mut Vec<String>) -> String {
+        let chars = content.chars().count() + self.banner_chars;
+        if chars <= FILE_CHAR_LIMIT {
+            return content;
+        }
+        let (content, consequence) = match self.oversize {
+            OversizeMode::Keep => (content, "Windsurf ignores the rest"),
+            _ => (
+                trim_content(&content, self.content_char_limit()),
+                "its end is trimmed",
+            ),
+        };
+        warnings.push(format!(
+            "{} takes {} characters, over the {} Windsurf reads of a rules file; {}",
+            file, chars, FILE_CHAR_LIMIT, consequence
+        ));
+        content
+    }
+
+    /// Returns the workspace rule files of `rule`, as the suffixes of their stems paired
+    /// with their contents: the rendered rule, or for a rule over `FILE_CHAR_LIMIT` with
+    /// `OversizeMode::Split`, numbered parts (`-1`, `-2`, ...) repeating its frontmatter.
+    fn workspace_rule_files(
+        &self,
+        rule: &UniversalRule,
+        warnings: &mut Vec<String>,
+    ) -> Vec<(String, String)> {
+        let content = render_workspace_rule_file(rule);
+        let chars = content.chars().count() + self.banner_chars;
+        if chars <= FILE_CHAR_LIMIT || self.oversize != OversizeMode::Split {
+            let file = format!("Rule {:?}", rule.relative_path());
+            return vec![(String::new(), self.fit_file(&file, content, warnings))];
+        }
+        let header_chars = chars - self.banner_chars - rule.content.chars().count();
+        let parts = split_content(
+            &rule.content,
+            self.content_char_limit().saturating_sub(header_chars),
+        );
+        warnings.push(format!(
+            "Rule {:?} takes {} characters, over the {} Windsurf reads of a rules file; it is split into {} files",
+            rule.relative_path(),
+            chars,
+            FILE_CHAR_LIMIT,
+            parts.len()
+        ));
+        parts
+            .into_iter()
+            .enumerate()
+            .map(|(index, part)| {
+                let part_rule = UniversalRule {
+                    content: part,
+                    ..rule.clone()
+                };
+                (
+                    format!("-{}", index + 1),
+                    render_workspace_rule_file(&part_rule),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the path of the workspace rule file of `rule` in `.windsurf/rules/`.
+    fn workspace_rule_path(&self, rule: &UniversalRule, output_dir: &Path) -> PathBuf {
+        self.workspace_rules_dir(rule, output_dir)
+            .join(format!("{}.md", rule.output_stem()))
+    }
+}
 
 impl RuleConverter for WindsurfConverter {
     /// Generates Windsurf rule files from a list of `UniversalRule`s.
     ///
-    /// Rules marked with `apply_globally: true` in their frontmatter are concatenated
-    /// into `global_rules.md` in the `output_dir`. Other rules are saved as individual
-    /// `.md` files (named after the rule) within an `output_dir/.windsurf/rules/` subdirectory.
-    /// Descriptions and globs from the frontmatter are prepended as comments in the
-    /// generated rule files.
-    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<()> {
+    /// Rules marked with `activation: always` (or `apply_globally: true`) in their frontmatter
+    /// are concatenated into `global_rules.md` in the `output_dir` (or in `global_dir`, if set).
+    /// Other rules are saved as individual `.md` files (named after the rule) within an
+    /// `output_dir/.windsurf/rules/` subdirectory. Descriptions and globs from the
+    /// frontmatter are prepended as comments in the generated rule files, or as Windsurf
+    /// frontmatter with the `trigger` of the rule's `activation`. If `nested` is set, each
+    /// file goes to the subdirectory of `.windsurf/rules/` matching the rule's
+    /// `relative_dir`.
+    ///
+    /// With `WindsurfFormat::Legacy`, workspace rules are joined with a Markdown
+    /// separator into `output_dir/.windsurfrules` instead.
+    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<GeneratedRules> {
+        let mut files = Vec::new();
+        let mut warnings = Vec::new();
         let mut global_rules_content = String::new();
         let mut has_workspace_rules = false; // Track if any non-global rules exist
 
+        // The characters of the written rules, which Windsurf caps as a whole
+        let mut total_chars = 0;
+
         // First pass: collect global rules and identify if workspace rules are present
         for rule in rules {
-            if rule.frontmatter.apply_globally {
+            if rule.frontmatter.is_always_applied() {
                 if let Some(desc) = &rule.frontmatter.description {
                     global_rules_content.push_str(&format!("# Description: {}\n", desc));
                 }
@@ -45,59 +274,133 @@ impl RuleConverter for WindsurfConverter {
             if global_rules_content.ends_with("\n\n---\n\n") {
                 global_rules_content.truncate(global_rules_content.len() - "\n\n---\n\n".len());
             }
-            fs::write(output_dir.join("global_rules.md"), &global_rules_content)
-                .with_context(|| format!("Failed to write global_rules.md to {:?}", output_dir))?;
+            let global_rules_content =
+                self.fit_file("global_rules.md", global_rules_content, &mut warnings);
+            total_chars += global_rules_content.chars().count() + self.banner_chars;
+            files.push(GeneratedFile::new(
+                self.global_rules_dir(output_dir).join("global_rules.md"),
+                global_rules_content,
+            ));
         }
 
-        // Process and write workspace-specific rules if any exist
-        if has_workspace_rules {
-            let windsurf_workspace_rules_dir = output_dir.join(".windsurf").join("rules");
-            fs::create_dir_all(&windsurf_workspace_rules_dir).with_context(|| {
-                format!(
-                    "Failed to create Windsurf workspace rules directory at {:?}",
-                    windsurf_workspace_rules_dir
-                )
-            })?;
-
-            for rule in rules {
-                if !rule.frontmatter.apply_globally {
-                    let mut individual_rule_content = String::new();
-                    // Prepend description as a comment if available
-                    if let Some(desc) = &rule.frontmatter.description {
-                        individual_rule_content.push_str(&format!("# Description: {}\n", desc));
-                    }
-                    // Prepend globs as a comment if available
-                    if let Some(globs) = &rule.frontmatter.globs {
-                        if !globs.is_empty() {
-                            individual_rule_content.push_str(&format!("# Globs: {:?}\n", globs));
-                        }
-                    }
-                    // Add a newline after comments if any were added, before rule content
-                    if !individual_rule_content.is_empty() {
-                        individual_rule_content.push('\n');
+        // Legacy mode: all workspace rules go into a single .windsurfrules file
+        if has_workspace_rules && self.format == WindsurfFormat::Legacy {
+            let legacy_content = rules
+                .iter()
+                .filter(|rule| !rule.frontmatter.is_always_applied())
+                .map(render_workspace_rule)
+                .collect::<Vec<String>>()
+                .join(RULE_SEPARATOR);
+            let legacy_content = self.fit_file(LEGACY_RULES_FILE, legacy_content, &mut warnings);
+            total_chars += legacy_content.chars().count() + self.banner_chars;
+            files.push(GeneratedFile::new(
+                output_dir.join(LEGACY_RULES_FILE),
+                legacy_content,
+            ));
+            warn_over_total(total_chars, &mut warnings);
+            return Ok(GeneratedRules { files, warnings });
+        }
+
+        // Process workspace-specific rules
+        for rule in rules {
+            if !rule.frontmatter.is_always_applied() {
+                let parts = self.workspace_rule_files(rule, &mut warnings);
+                total_chars += parts
+                    .iter()
+                    .map(|(_, content)| content.chars().count() + self.banner_chars)
+                    .sum::<usize>();
+
+                let rule_dir = self.workspace_rules_dir(rule, output_dir);
+                for stem in rule.output_stems() {
+                    for (suffix, individual_rule_content) in &parts {
+                        files.push(GeneratedFile::new(
+                            rule_dir.join(format!("{}{}.md", stem, suffix)),
+                            individual_rule_content.clone(),
+                        ));
                     }
-                    individual_rule_content.push_str(&rule.content);
-
-                    let output_file_path =
-                        windsurf_workspace_rules_dir.join(format!("{}.md", rule.name));
-                    fs::write(&output_file_path, individual_rule_content).with_context(|| {
-                        format!(
-                            "Failed to write Windsurf workspace rule file for '{}' to {:?}",
-                            rule.name, output_file_path
-                        )
-                    })?;
                 }
             }
         }
-        Ok(())
+        warn_over_total(total_chars, &mut warnings);
+        Ok(GeneratedRules { files, warnings })
+    }
+
+    /// Linked assets are copied to `.windsurf/assets/`.
+    fn assets_dir(&self) -> Option<PathBuf> {
+        Some(PathBuf::from(".windsurf/assets"))
     }
 
     /// Provides a description of where the Windsurf rules are generated.
     fn get_output_description(&self, output_dir: &Path) -> String {
+        let workspace_location = match self.format {
+            WindsurfFormat::Legacy => output_dir.join(LEGACY_RULES_FILE),
+            WindsurfFormat::Rules => output_dir.join(".windsurf").join("rules"),
+        };
         format!(
             "Windsurf rules in {:?} (global) and potentially in {:?}",
-            output_dir.join("global_rules.md"),
-            output_dir.join(".windsurf").join("rules")
+            self.global_rules_dir(output_dir).join("global_rules.md"),
+            workspace_location
+        )
+    }
+
+    /// The `globs` of the `trigger` frontmatter have no exclusions; the other rules keep
+    /// their globs as a comment or drop them altogether.
+    fn supports_negated_globs(&self, rule: &UniversalRule) -> bool {
+        self.format == WindsurfFormat::Legacy
+            || rule.frontmatter.is_always_applied()
+            || workspace_trigger(rule).is_none()
+    }
+
+    /// Explains whether the rule is a global rule or a workspace rule.
+    fn explain_activation(&self, rule: &UniversalRule, output_dir: &Path) -> String {
+        if rule.frontmatter.is_always_applied() {
+            let key = match rule.frontmatter.activation {
+                Some(_) => "activation: always",
+                None => "apply_globally: true",
+            };
+            return format!(
+                "Global: always applied in every workspace (`{}`), from {:?}.",
+                key,
+                self.global_rules_dir(output_dir).join("global_rules.md")
+            );
+        }
+        let file = match self.format {
+            WindsurfFormat::Legacy => output_dir.join(LEGACY_RULES_FILE),
+            WindsurfFormat::Rules => self.workspace_rule_path(rule, output_dir),
+        };
+        if self.format == WindsurfFormat::Rules {
+            match rule.frontmatter.effective_activation() {
+                Some(Activation::Glob) => {
+                    let globs = rule.frontmatter.include_globs();
+                    return format!(
+                        "Glob (`trigger: glob`): applied when files matching {} are in context, from {:?}.",
+                        globs.join(", "),
+                        file
+                    );
+                }
+                Some(Activation::AgentDecision) => {
+                    return format!(
+                        "Model decision (`trigger: model_decision`): applied when Cascade judges its description relevant, from {:?}.",
+                        file
+                    );
+                }
+                Some(Activation::Manual) => {
+                    return format!(
+                        "Manual (`trigger: manual`): only applied when mentioned with @{}, from {:?}.",
+                        rule.output_stem(),
+                        file
+                    );
+                }
+                Some(Activation::Always) | None => {}
+            }
+        }
+        let globs_note = match rule.frontmatter.globs.as_ref().filter(|g| !g.is_empty()) {
+            Some(_) => " Its globs are only kept as a comment.",
+            None => "",
+        };
+        format!(
+            "Workspace: always applied in this workspace, from {:?}.{}",
+            file, globs_note
         )
     }
 }
@@ -105,65 +408,109 @@ impl RuleConverter for WindsurfConverter {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::universal_rule::{UniversalRule, UniversalRuleFrontmatter};
-    use std::fs::File;
-    use std::io::Read;
-    use tempfile::tempdir;
-
-    /// Helper function to create `UniversalRule` instances for testing the Windsurf converter.
-    fn create_test_rule(
-        name: &str,
-        content: &str,
-        apply_globally: bool,
-        description: Option<&str>,
-        globs: Option<Vec<&str>>,
-    ) -> UniversalRule {
-        UniversalRule {
-            name: name.to_string(),
-            content: content.to_string(),
-            frontmatter: UniversalRuleFrontmatter {
-                description: description.map(String::from),
-                globs: globs.map(|g| g.iter().map(|s| s.to_string()).collect()),
-                apply_globally,
-                cursor_rule_type: None,
-            },
-        }
+    use crate::converters::test_rule;
+
+    /// Test that global and workspace rules are explained with their file.
+    #[test]
+    fn test_explain_windsurf_activation() {
+        let converter = WindsurfConverter::default();
+        let global = test_rule("style", "").apply_globally().build();
+        assert!(converter
+            .explain_activation(&global, Path::new("."))
+            .starts_with("Global: always applied in every workspace"));
+        let workspace = test_rule("rust", "").globs(&["*.rs"]).build();
+        let explanation = converter.explain_activation(&workspace, Path::new("."));
+        assert!(explanation.starts_with("Workspace: always applied in this workspace"));
+        assert!(explanation.contains(".windsurf/rules/rust.md"));
+        assert!(explanation.ends_with("Its globs are only kept as a comment."));
+    }
+
+    /// Test that rules with an `activation` get Windsurf's `trigger` frontmatter.
+    #[test]
+    fn test_windsurf_activation_triggers() {
+        let output_path = Path::new("project");
+        let converter = WindsurfConverter::default();
+        let mut rust = test_rule("rust", "Use rustfmt.").globs(&["*.rs"]).build();
+        rust.frontmatter.activation = Some(Activation::Glob);
+        let mut api = test_rule("api", "Version APIs.")
+            .description("API design")
+            .build();
+        api.frontmatter.activation = Some(Activation::AgentDecision);
+        let mut style = test_rule("style", "Be concise.").build();
+        style.frontmatter.activation = Some(Activation::Always);
+
+        let generated = converter
+            .generate_rules(&[rust.clone(), api, style], output_path)
+            .unwrap();
+
+        let rules_dir = output_path.join(".windsurf").join("rules");
+        assert_eq!(
+            generated.contents(rules_dir.join("rust.md")).unwrap(),
+            "---\ntrigger: glob\nglobs: *.rs\n---\n\nUse rustfmt."
+        );
+        assert_eq!(
+            generated.contents(rules_dir.join("api.md")).unwrap(),
+            "---\ntrigger: model_decision\ndescription: API design\n---\n\nVersion APIs."
+        );
+        // `activation: always` is an alias of `apply_globally: true`
+        assert!(generated.contents(rules_dir.join("style.md")).is_none());
+        assert_eq!(
+            generated
+                .contents(output_path.join("global_rules.md"))
+                .unwrap(),
+            "Be concise."
+        );
+        assert!(converter
+            .explain_activation(&rust, Path::new("."))
+            .starts_with("Glob (`trigger: glob`): applied when files matching *.rs"));
+    }
+
+    /// Test that `model_decision` rules are described by their `trigger_description`.
+    #[test]
+    fn test_windsurf_trigger_description() {
+        let mut api = test_rule("api", "Version APIs.")
+            .description("API design")
+            .build();
+        api.frontmatter.trigger_description = Some("When changing endpoints".to_string());
+        api.frontmatter.activation = Some(Activation::AgentDecision);
+        assert_eq!(
+            render_workspace_rule_file(&api),
+            "---\ntrigger: model_decision\ndescription: When changing endpoints\n---\n\nVersion APIs."
+        );
+
+        api.frontmatter.activation = Some(Activation::Manual);
+        assert_eq!(
+            render_workspace_rule_file(&api),
+            "---\ntrigger: manual\ndescription: API design\n---\n\nVersion APIs."
+        );
     }
 
     /// Test the `RuleConverter` trait implementation for `WindsurfConverter`.
     /// Checks creation of both global and workspace rule files.
     #[test]
     fn test_windsurf_converter_trait_impl() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = WindsurfConverter;
+        let output_path = Path::new("project");
+        let converter = WindsurfConverter::default();
 
         let rules = vec![
-            create_test_rule(
-                "trait_global",
-                "Trait Global Content",
-                true,
-                Some("Trait Global Desc"),
-                None,
-            ),
-            create_test_rule(
-                "trait_ws",
-                "Trait WS Content",
-                false,
-                Some("Trait WS Desc"),
-                Some(vec!["*.test"]),
-            ),
+            test_rule("trait_global", "Trait Global Content")
+                .description("Trait Global Desc")
+                .apply_globally()
+                .build(),
+            test_rule("trait_ws", "Trait WS Content")
+                .description("Trait WS Desc")
+                .globs(&["*.test"])
+                .build(),
         ];
 
-        converter.generate_rules(&rules, output_path).unwrap();
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
 
         let global_path = output_path.join("global_rules.md");
-        assert!(global_path.exists(), "Global rules file should be created.");
-        let mut global_content = String::new();
-        File::open(global_path)
-            .unwrap()
-            .read_to_string(&mut global_content)
-            .unwrap();
+        assert!(
+            generated.contents(&global_path).is_some(),
+            "Global rules file should be created."
+        );
+        let global_content = generated.contents(&global_path).unwrap();
         assert!(global_content.contains("# Description: Trait Global Desc"));
         assert!(global_content.contains("Trait Global Content"));
 
@@ -171,12 +518,11 @@ mod tests {
             .join(".windsurf")
             .join("rules")
             .join("trait_ws.md");
-        assert!(ws_path.exists(), "Workspace rule file should be created.");
-        let mut ws_content = String::new();
-        File::open(ws_path)
-            .unwrap()
-            .read_to_string(&mut ws_content)
-            .unwrap();
+        assert!(
+            generated.contents(&ws_path).is_some(),
+            "Workspace rule file should be created."
+        );
+        let ws_content = generated.contents(&ws_path).unwrap();
         assert!(ws_content.contains("# Description: Trait WS Desc"));
         assert!(ws_content.contains("# Globs: [\"*.test\"]"));
         assert!(ws_content.contains("Trait WS Content"));
@@ -185,46 +531,33 @@ mod tests {
     /// Test generation with a mix of global and workspace rules.
     #[test]
     fn test_generate_windsurf_rules_mixed() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = WindsurfConverter;
+        let output_path = Path::new("project");
+        let converter = WindsurfConverter::default();
 
         let rules = vec![
-            create_test_rule(
-                "global1",
-                "Global rule 1 content",
-                true,
-                Some("Global desc 1"),
-                None,
-            ),
-            create_test_rule(
-                "workspace1",
-                "Workspace rule 1 content",
-                false,
-                Some("WS desc 1"),
-                Some(vec!["*.rs"]),
-            ),
-            create_test_rule("global2", "Global rule 2 content", true, None, None), // Global without description
-            create_test_rule(
-                "workspace2",
-                "Workspace rule 2 content",
-                false,
-                None,
-                Some(vec!["*.ts", "*.js"]),
-            ), // Workspace without description
-            create_test_rule("workspace3_no_meta", "WS rule 3 no meta", false, None, None), // Workspace with no metadata
+            test_rule("global1", "Global rule 1 content")
+                .description("Global desc 1")
+                .apply_globally()
+                .build(),
+            test_rule("workspace1", "Workspace rule 1 content")
+                .description("WS desc 1")
+                .globs(&["*.rs"])
+                .build(),
+            test_rule("global2", "Global rule 2 content")
+                .apply_globally()
+                .build(), // Global without description
+            test_rule("workspace2", "Workspace rule 2 content")
+                .globs(&["*.ts", "*.js"])
+                .build(), // Workspace without description
+            test_rule("workspace3_no_meta", "WS rule 3 no meta").build(), // Workspace with no metadata
         ];
 
-        converter.generate_rules(&rules, output_path).unwrap();
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
 
         // Verify global_rules.md
         let global_rules_path = output_path.join("global_rules.md");
-        assert!(global_rules_path.exists());
-        let mut global_content = String::new();
-        File::open(global_rules_path)
-            .unwrap()
-            .read_to_string(&mut global_content)
-            .unwrap();
+        assert!(generated.contents(&global_rules_path).is_some());
+        let global_content = generated.contents(&global_rules_path).unwrap();
 
         assert!(global_content.contains("# Description: Global desc 1"));
         assert!(global_content.contains("Global rule 1 content"));
@@ -241,42 +574,30 @@ mod tests {
         // Verify workspace rules
         let ws_rules_dir = output_path.join(".windsurf").join("rules");
         assert!(
-            ws_rules_dir.exists(),
+            generated.has_files_in(&ws_rules_dir),
             "Workspace rules directory not created."
         );
 
         // Workspace Rule 1
         let ws1_path = ws_rules_dir.join("workspace1.md");
-        assert!(ws1_path.exists());
-        let mut ws1_content = String::new();
-        File::open(ws1_path)
-            .unwrap()
-            .read_to_string(&mut ws1_content)
-            .unwrap();
+        assert!(generated.contents(&ws1_path).is_some());
+        let ws1_content = generated.contents(&ws1_path).unwrap();
         assert!(ws1_content.contains("# Description: WS desc 1\n"));
         assert!(ws1_content.contains("# Globs: [\"*.rs\"]\n\n")); // Expect newline after comments
         assert!(ws1_content.ends_with("Workspace rule 1 content"));
 
         // Workspace Rule 2 (no description)
         let ws2_path = ws_rules_dir.join("workspace2.md");
-        assert!(ws2_path.exists());
-        let mut ws2_content = String::new();
-        File::open(ws2_path)
-            .unwrap()
-            .read_to_string(&mut ws2_content)
-            .unwrap();
+        assert!(generated.contents(&ws2_path).is_some());
+        let ws2_content = generated.contents(&ws2_path).unwrap();
         assert!(!ws2_content.contains("# Description:"));
         assert!(ws2_content.contains("# Globs: [\"*.ts\", \"*.js\"]\n\n"));
         assert!(ws2_content.ends_with("Workspace rule 2 content"));
 
         // Workspace Rule 3 (no metadata)
         let ws3_path = ws_rules_dir.join("workspace3_no_meta.md");
-        assert!(ws3_path.exists());
-        let mut ws3_content = String::new();
-        File::open(ws3_path)
-            .unwrap()
-            .read_to_string(&mut ws3_content)
-            .unwrap();
+        assert!(generated.contents(&ws3_path).is_some());
+        let ws3_content = generated.contents(&ws3_path).unwrap();
         assert!(!ws3_content.contains("# Description:"));
         assert!(!ws3_content.contains("# Globs:"));
         assert_eq!(ws3_content, "WS rule 3 no meta"); // Content should be exactly this
@@ -285,22 +606,22 @@ mod tests {
     /// Test generation when only global rules are provided.
     #[test]
     fn test_generate_windsurf_rules_only_global() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = WindsurfConverter;
+        let output_path = Path::new("project");
+        let converter = WindsurfConverter::default();
         let rules = vec![
-            create_test_rule("global_only1", "Content G1", true, Some("Desc G1"), None),
-            create_test_rule("global_only2", "Content G2", true, None, None),
+            test_rule("global_only1", "Content G1")
+                .description("Desc G1")
+                .apply_globally()
+                .build(),
+            test_rule("global_only2", "Content G2")
+                .apply_globally()
+                .build(),
         ];
-        converter.generate_rules(&rules, output_path).unwrap();
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
 
         let global_rules_path = output_path.join("global_rules.md");
-        assert!(global_rules_path.exists());
-        let mut global_content = String::new();
-        File::open(global_rules_path)
-            .unwrap()
-            .read_to_string(&mut global_content)
-            .unwrap();
+        assert!(generated.contents(&global_rules_path).is_some());
+        let global_content = generated.contents(&global_rules_path).unwrap();
         assert!(global_content.contains("# Description: Desc G1"));
         assert!(global_content.contains("Content G1"));
         assert!(global_content.contains("Content G2"));
@@ -308,7 +629,7 @@ mod tests {
 
         let ws_rules_dir = output_path.join(".windsurf").join("rules");
         assert!(
-            !ws_rules_dir.exists(),
+            !generated.has_files_in(&ws_rules_dir),
             "Workspace rules directory should not be created if only global rules exist."
         );
     }
@@ -316,75 +637,43 @@ mod tests {
     /// Test generation when only workspace rules are provided.
     #[test]
     fn test_generate_windsurf_rules_only_workspace() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = WindsurfConverter;
-        let rules = vec![create_test_rule(
-            "ws_only1",
-            "Content WS1",
-            false,
-            Some("Desc WS1"),
-            Some(vec!["*.py"]),
-        )];
-        converter.generate_rules(&rules, output_path).unwrap();
+        let output_path = Path::new("project");
+        let converter = WindsurfConverter::default();
+        let rules = vec![test_rule("ws_only1", "Content WS1")
+            .description("Desc WS1")
+            .globs(&["*.py"])
+            .build()];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
 
         let global_rules_path = output_path.join("global_rules.md");
         assert!(
-            !global_rules_path.exists(),
+            generated.contents(&global_rules_path).is_none(),
             "Global rules file should not be created if only workspace rules exist."
         );
 
         let ws_rules_dir = output_path.join(".windsurf").join("rules");
-        assert!(ws_rules_dir.exists());
+        assert!(generated.has_files_in(&ws_rules_dir));
         let ws1_path = ws_rules_dir.join("ws_only1.md");
-        assert!(ws1_path.exists());
-        let mut ws1_content = String::new();
-        File::open(ws1_path)
-            .unwrap()
-            .read_to_string(&mut ws1_content)
-            .unwrap();
+        assert!(generated.contents(&ws1_path).is_some());
+        let ws1_content = generated.contents(&ws1_path).unwrap();
         assert!(ws1_content.contains("# Description: Desc WS1"));
         assert!(ws1_content.contains("# Globs: [\"*.py\"]"));
         assert!(ws1_content.ends_with("Content WS1"));
     }
 
-    /// Test behavior when no rules are provided.
-    #[test]
-    fn test_generate_windsurf_rules_no_rules() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = WindsurfConverter;
-        let rules: Vec<UniversalRule> = vec![];
-        converter.generate_rules(&rules, output_path).unwrap();
-
-        assert!(
-            !output_path.join("global_rules.md").exists(),
-            "Global rules file should not exist for no rules."
-        );
-        assert!(
-            !output_path.join(".windsurf").join("rules").exists(),
-            "Workspace rules directory should not exist for no rules."
-        );
-    }
-
     /// Test that the Markdown separator in `global_rules.md` is correctly trimmed.
     #[test]
     fn test_global_rule_separator_trimmed_correctly() {
-        let dir = tempdir().unwrap();
-        let output_path = dir.path();
-        let converter = WindsurfConverter;
+        let output_path = Path::new("project");
+        let converter = WindsurfConverter::default();
 
         // Single global rule
-        let rules_single = vec![create_test_rule("g1", "content1", true, None, None)];
-        converter
+        let rules_single = vec![test_rule("g1", "content1").apply_globally().build()];
+        let generated = converter
             .generate_rules(&rules_single, output_path)
             .unwrap();
         let global_rules_path = output_path.join("global_rules.md");
-        let mut global_content_single = String::new();
-        File::open(&global_rules_path)
-            .unwrap()
-            .read_to_string(&mut global_content_single)
-            .unwrap();
+        let global_content_single = generated.contents(&global_rules_path).unwrap();
         assert_eq!(
             global_content_single.trim_end(),
             "content1",
@@ -393,17 +682,13 @@ mod tests {
 
         // Multiple global rules
         let rules_multiple = vec![
-            create_test_rule("g1", "content1", true, None, None),
-            create_test_rule("g2", "content2", true, None, None),
+            test_rule("g1", "content1").apply_globally().build(),
+            test_rule("g2", "content2").apply_globally().build(),
         ];
-        converter
+        let generated = converter
             .generate_rules(&rules_multiple, output_path)
             .unwrap(); // Overwrites previous file
-        let mut global_content_multiple = String::new();
-        File::open(&global_rules_path)
-            .unwrap()
-            .read_to_string(&mut global_content_multiple)
-            .unwrap();
+        let global_content_multiple = generated.contents(&global_rules_path).unwrap();
         assert!(
             global_content_multiple.contains("content1\n\n---\n\ncontent2"),
             "Separator missing between multiple global rules."
@@ -413,4 +698,172 @@ mod tests {
             "Multiple global rules should not have a trailing separator."
         );
     }
+
+    /// Test that the legacy format concatenates workspace rules into `.windsurfrules`
+    /// while global rules still go to `global_rules.md`.
+    #[test]
+    fn test_generate_windsurf_rules_legacy_format() {
+        let output_path = Path::new("project");
+        let converter = WindsurfConverter {
+            format: WindsurfFormat::Legacy,
+            ..Default::default()
+        };
+
+        let rules = vec![
+            test_rule("global1", "Global content")
+                .apply_globally()
+                .build(),
+            test_rule("ws1", "WS 1")
+                .description("WS desc")
+                .globs(&["*.rs"])
+                .build(),
+            test_rule("ws2", "WS 2").build(),
+        ];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        let legacy_content = generated
+            .contents(output_path.join(".windsurfrules"))
+            .unwrap();
+        assert_eq!(
+            legacy_content,
+            "# Description: WS desc\n# Globs: [\"*.rs\"]\n\nWS 1\n\n---\n\nWS 2"
+        );
+        assert_eq!(
+            generated
+                .contents(output_path.join("global_rules.md"))
+                .unwrap(),
+            "Global content"
+        );
+        assert!(!generated.has_files_in(output_path.join(".windsurf")));
+    }
+
+    /// Test that oversized workspace rules are split into numbered files repeating their
+    /// frontmatter, and oversized global rules trimmed or kept.
+    #[test]
+    fn test_generate_windsurf_rules_oversized() {
+        let output_path = Path::new("project");
+        let paragraph = format!("{}\n\n", "a".repeat(3999));
+        let mut api = test_rule("api", &paragraph.repeat(3))
+            .description("API")
+            .build();
+        api.frontmatter.activation = Some(Activation::AgentDecision);
+        let global = test_rule("global", &paragraph.repeat(2))
+            .apply_globally()
+            .build();
+        let rules = [api, global];
+
+        let generated = WindsurfConverter::default()
+            .generate_rules(&rules, output_path)
+            .unwrap();
+        let warnings = &generated.warnings;
+        assert_eq!(warnings.len(), 3);
+        assert!(warnings[0].starts_with("global_rules.md takes"));
+        assert!(warnings[1].ends_with("it is split into 3 files"));
+        assert!(warnings[2].starts_with("The Windsurf rules take"));
+        let rules_dir = output_path.join(".windsurf").join("rules");
+        assert!(generated.contents(rules_dir.join("api.md")).is_none());
+        for part in ["api-1.md", "api-2.md", "api-3.md"] {
+            let content = generated.contents(rules_dir.join(part)).unwrap();
+            assert!(content.starts_with("---\ntrigger: model_decision\ndescription: API\n---\n"));
+            assert!(content.chars().count() <= FILE_CHAR_LIMIT);
+        }
+        let global_rules = generated
+            .contents(output_path.join("global_rules.md"))
+            .unwrap();
+        assert_eq!(global_rules, format!("{}\n", "a".repeat(3999)));
+
+        let converter = WindsurfConverter {
+            oversize: OversizeMode::Keep,
+            ..Default::default()
+        };
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+        let global_rules = generated
+            .contents(output_path.join("global_rules.md"))
+            .unwrap();
+        assert_eq!(global_rules, paragraph.repeat(2));
+        assert!(generated.contents(rules_dir.join("api.md")).is_some());
+    }
+
+    /// Test that the characters of the banner added to each file are left out of the
+    /// parts of split rules and of trimmed files.
+    #[test]
+    fn test_generate_windsurf_rules_oversized_with_banner() {
+        let output_path = Path::new("project");
+        let line = format!("{}\n\n", "a".repeat(98));
+        let rules = [
+            test_rule("api", &line.repeat(120)).build(),
+            test_rule("global", &line.repeat(80))
+                .apply_globally()
+                .build(),
+        ];
+        let converter = WindsurfConverter {
+            banner_chars: 100,
+            ..Default::default()
+        };
+
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+        assert!(generated.files.len() > 2);
+        for file in &generated.files {
+            assert!(file.contents.chars().count() <= FILE_CHAR_LIMIT - 100);
+        }
+    }
+
+    /// Test that global rules are written to `global_dir` when it is set.
+    #[test]
+    fn test_generate_windsurf_rules_custom_global_dir() {
+        let output_path = PathBuf::from("project");
+        let global_path = PathBuf::from("memories");
+        let converter = WindsurfConverter {
+            global_dir: Some(global_path.clone()),
+            ..Default::default()
+        };
+
+        let rules = vec![
+            test_rule("global1", "Global content")
+                .apply_globally()
+                .build(),
+            test_rule("ws1", "WS 1").build(),
+        ];
+        let generated = converter.generate_rules(&rules, &output_path).unwrap();
+
+        assert_eq!(
+            generated
+                .contents(global_path.join("global_rules.md"))
+                .unwrap(),
+            "Global content"
+        );
+        assert!(generated
+            .contents(output_path.join("global_rules.md"))
+            .is_none());
+        assert!(generated
+            .contents(output_path.join(".windsurf").join("rules").join("ws1.md"))
+            .is_some());
+    }
+
+    /// Test that nested mode mirrors the rules' source subdirectories under
+    /// `.windsurf/rules/`.
+    #[test]
+    fn test_generate_windsurf_rules_nested() {
+        let output_path = Path::new("project");
+        let converter = WindsurfConverter {
+            nested: true,
+            ..Default::default()
+        };
+
+        let mut nested_rule = test_rule("hooks", "Hooks content").build();
+        nested_rule.relative_dir = PathBuf::from("frontend").join("react");
+        let top_level_rule = test_rule("general", "General").build();
+        let generated = converter
+            .generate_rules(&[nested_rule.clone(), top_level_rule], output_path)
+            .unwrap();
+
+        let rules_dir = output_path.join(".windsurf").join("rules");
+        let nested_path = rules_dir.join("frontend").join("react").join("hooks.md");
+        assert_eq!(generated.contents(&nested_path).unwrap(), "Hooks content");
+        assert!(generated.contents(rules_dir.join("general.md")).is_some());
+        assert!(generated.contents(rules_dir.join("hooks.md")).is_none());
+        assert!(converter
+            .explain_activation(&nested_rule, output_path)
+            .contains("frontend/react/hooks.md"));
+    }
 }
@@ -0,0 +1,87 @@
+// src/converters/zed.rs
+
+use super::{concatenate_rules, GeneratedFile, GeneratedRules, RuleConverter};
+use crate::universal_rule::UniversalRule;
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// A `RuleConverter` implementation for generating a single Zed `.rules` file.
+///
+/// Zed reads project rules from a `.rules` file at the project root. This converter
+/// concatenates all provided universal rules into that file, using the same headings
+/// and separators as the other single-file converters.
+pub struct ZedConverter;
+
+impl RuleConverter for ZedConverter {
+    /// Generates a `.rules` file in `output_dir` by concatenating all `UniversalRule`s.
+    ///
+    /// If no rules are provided, no file is generated. Because `.rules/` is also the default
+    /// universal rules directory, writing the file fails instead of touching an existing
+    /// directory with that name (see `OutputPlan::write`).
+    fn generate_rules(&self, rules: &[UniversalRule], output_dir: &Path) -> Result<GeneratedRules> {
+        if rules.is_empty() {
+            // Do not create an empty .rules file if there are no rules to process.
+            return Ok(GeneratedRules::default());
+        }
+
+        let zed_content = concatenate_rules(rules);
+
+        Ok(vec![GeneratedFile::new(output_dir.join(".rules"), zed_content)].into())
+    }
+
+    /// Linked assets are copied to `.zed/assets/`.
+    fn assets_dir(&self) -> Option<PathBuf> {
+        Some(PathBuf::from(".zed/assets"))
+    }
+
+    /// Provides a description of where the Zed rules file is generated.
+    fn get_output_description(&self, output_dir: &Path) -> String {
+        format!("Zed rules in {:?}", output_dir.join(".rules"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converters::{test_rule, write_rules};
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// Test generation of `.rules` with multiple rules, checking order and separators.
+    #[test]
+    fn test_generate_zed_rules_multiple_rules() {
+        let output_path = Path::new("project");
+        let converter = ZedConverter;
+
+        let rules = vec![
+            test_rule("rule1", "Content 1.")
+                .description("Description 1.")
+                .build(),
+            test_rule("rule2", "Content 2.").build(),
+        ];
+        let generated = converter.generate_rules(&rules, output_path).unwrap();
+
+        assert_eq!(
+            generated.files,
+            [GeneratedFile::new(
+                output_path.join(".rules"),
+                "## Rule: Rule1\nDescription 1.\n\nContent 1.\n\n---\n\n## Rule: Rule2\n\nContent 2."
+            )]
+        );
+    }
+
+    /// Test that writing never overwrites an existing `.rules/` directory.
+    #[test]
+    fn test_generate_zed_rules_refuses_rules_directory() {
+        let dir = tempdir().unwrap();
+        let output_path = dir.path();
+        fs::create_dir(output_path.join(".rules")).unwrap();
+        let converter = ZedConverter;
+
+        let rules = vec![test_rule("rule1", "Content 1.").build()];
+        let result = write_rules(&converter, &rules, output_path);
+
+        assert!(result.is_err());
+        assert!(output_path.join(".rules").is_dir());
+    }
+}
@@ -0,0 +1,278 @@
+// src/dry_run.rs
+
+use crate::color;
+use crate::ignore_files::AIEXCLUDE_FILE;
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use similar::TextDiff;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Outputs that are merged with what already exists in the output directory, so
+/// regenerating them keeps hand edits.
+pub const MERGED_OUTPUTS: &[&str] = &[".gitignore", ".vscode/settings.json", AIEXCLUDE_FILE];
+
+/// How a planned file differs from what is currently on disk.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// The file does not exist yet.
+    Create,
+    /// The file exists with different content.
+    Update,
+    /// The file was generated by an earlier run and is no longer generated.
+    Delete,
+}
+
+/// A file that a real run would write or remove.
+#[derive(Debug, Clone)]
+pub struct PlannedChange {
+    /// The path the file would be written to.
+    pub path: PathBuf,
+
+    /// Whether the file would be created, updated or removed.
+    pub kind: ChangeKind,
+
+    /// A unified diff against the current content, for updated text files.
+    pub diff: Option<String>,
+}
+
+/// The files a run would write, with their final content, planned without touching the
+/// project.
+///
+/// Files are keyed by their real path. Comparing the plan with what is on disk yields
+/// the changes a real run would make (`--dry-run`, `urules check`), and writing it makes
+/// them.
+#[derive(Debug, Default)]
+pub struct OutputPlan {
+    /// The content of every planned file, keyed by its path.
+    pub files: BTreeMap<PathBuf, Vec<u8>>,
+
+    /// The files generated by an earlier run that are no longer generated.
+    pub deletions: BTreeSet<PathBuf>,
+}
+
+impl OutputPlan {
+    /// Plans `path` with `content`, replacing what was planned for it before.
+    pub fn insert(&mut self, path: PathBuf, content: impl Into<Vec<u8>>) {
+        self.files.insert(path, content.into());
+    }
+
+    /// Plans the removal of `path`, unless a file is planned for it.
+    pub fn delete(&mut self, path: PathBuf) {
+        self.deletions.insert(path);
+    }
+
+    /// Adds the files and deletions of `other`, whose files replace those planned for
+    /// the same paths.
+    pub fn extend(&mut self, other: OutputPlan) {
+        self.files.extend(other.files);
+        self.deletions.extend(other.deletions);
+    }
+
+    /// Returns the planned deletions that remove a file: those without a planned file
+    /// at the same path.
+    fn removed_files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.deletions
+            .iter()
+            .filter(|path| !self.files.contains_key(*path) && path.is_file())
+    }
+
+    /// Returns the content `path` would have so far: what is planned for it, or else
+    /// what is on disk, or `None` if it does not exist. Text that is not UTF-8 is
+    /// returned lossily.
+    pub fn current_text(&self, path: &Path) -> Result<Option<String>> {
+        if let Some(content) = self.files.get(path) {
+            return Ok(Some(String::from_utf8_lossy(content).into_owned()));
+        }
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+        Ok(Some(String::from_utf8_lossy(&content).into_owned()))
+    }
+
+    /// Compares every planned file with what is on disk and returns the files that
+    /// would be created, updated or removed, sorted by path.
+    pub fn planned_changes(&self) -> Result<Vec<PlannedChange>> {
+        let mut changes = Vec::new();
+        for (path, content) in &self.files {
+            if let Some(change) = compare_file(path, content)? {
+                changes.push(change);
+            }
+        }
+        changes.extend(self.removed_files().map(|path| PlannedChange {
+            path: path.clone(),
+            kind: ChangeKind::Delete,
+            diff: None,
+        }));
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(changes)
+    }
+
+    /// Writes the planned files that differ from what is on disk, creating their parent
+    /// directories, and removes the planned deletions.
+    pub fn write(&self) -> Result<()> {
+        for (path, content) in &self.files {
+            if fs::read(path).ok().as_ref() == Some(content) {
+                continue;
+            }
+            if path.is_dir() {
+                bail!(
+                    "Cannot write {:?}: a directory with that name already exists",
+                    path
+                );
+            }
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create directory {:?}", parent))?;
+            }
+            fs::write(path, content).with_context(|| format!("Failed to write {:?}", path))?;
+        }
+        for path in self.removed_files() {
+            fs::remove_file(path).with_context(|| format!("Failed to remove {:?}", path))?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the planned `content` of `path` with the file on disk, returning `None` if
+/// they are identical.
+fn compare_file(path: &Path, content: &[u8]) -> Result<Option<PlannedChange>> {
+    if !path.is_file() {
+        return Ok(Some(PlannedChange {
+            path: path.to_path_buf(),
+            kind: ChangeKind::Create,
+            diff: None,
+        }));
+    }
+    let current_content = fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+    if current_content == content {
+        return Ok(None);
+    }
+
+    let diff = match (
+        String::from_utf8(current_content),
+        std::str::from_utf8(content),
+    ) {
+        (Ok(old), Ok(new)) => {
+            let display_path = path.display().to_string();
+            Some(
+                TextDiff::from_lines(old.as_str(), new)
+                    .unified_diff()
+                    .header(&display_path, &display_path)
+                    .to_string(),
+            )
+        }
+        _ => None,
+    };
+    Ok(Some(PlannedChange {
+        path: path.to_path_buf(),
+        kind: ChangeKind::Update,
+        diff,
+    }))
+}
+
+/// Prints the planned changes: one line per file, followed by the diff of updated files.
+pub fn print_planned_changes(changes: &[PlannedChange]) {
+    for change in changes {
+        let label = match change.kind {
+            ChangeKind::Create => color::success("create"),
+            ChangeKind::Update => color::problem("update"),
+            ChangeKind::Delete => color::problem("delete"),
+        };
+        println!("  {} {}", label, change.path.display());
+    }
+    for diff in changes.iter().filter_map(|c| c.diff.as_ref()) {
+        println!();
+        print!("{}", color::diff(diff));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Test that planned files are classified against the disk, and that nothing is
+    /// written until the plan is.
+    #[test]
+    fn test_planned_changes() -> Result<()> {
+        let dir = tempdir()?;
+        fs::write(dir.path().join("CLAUDE.md"), "old\n")?;
+        fs::write(dir.path().join("WARP.md"), "same")?;
+
+        let mut plan = OutputPlan::default();
+        plan.insert(dir.path().join("CLAUDE.md"), "new\n");
+        plan.insert(dir.path().join("WARP.md"), "same");
+        plan.insert(dir.path().join(".claude/commands/review.md"), "Review");
+        assert_eq!(
+            plan.current_text(&dir.path().join("CLAUDE.md"))?.as_deref(),
+            Some("new\n")
+        );
+        assert_eq!(plan.current_text(&dir.path().join(".gitignore"))?, None);
+
+        let changes = plan.planned_changes()?;
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            changes[0].path,
+            dir.path().join(".claude/commands/review.md")
+        );
+        assert_eq!(changes[0].kind, ChangeKind::Create);
+        assert_eq!(changes[1].path, dir.path().join("CLAUDE.md"));
+        assert_eq!(changes[1].kind, ChangeKind::Update);
+        let diff = changes[1].diff.as_deref().unwrap();
+        assert!(diff.contains("-old\n+new\n"));
+
+        // Nothing was written until the plan is
+        assert_eq!(fs::read_to_string(dir.path().join("CLAUDE.md"))?, "old\n");
+        assert!(!dir.path().join(".claude").exists());
+        plan.write()?;
+        assert_eq!(fs::read_to_string(dir.path().join("CLAUDE.md"))?, "new\n");
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".claude/commands/review.md"))?,
+            "Review"
+        );
+        assert!(plan.planned_changes()?.is_empty());
+        Ok(())
+    }
+
+    /// Test that planned deletions remove the files they name, except those planned
+    /// again.
+    #[test]
+    fn test_planned_deletions() -> Result<()> {
+        let dir = tempdir()?;
+        fs::write(dir.path().join("old.mdc"), "Old")?;
+        fs::write(dir.path().join("kept.mdc"), "Kept")?;
+
+        let mut plan = OutputPlan::default();
+        plan.insert(dir.path().join("kept.mdc"), "Kept");
+        plan.delete(dir.path().join("old.mdc"));
+        plan.delete(dir.path().join("kept.mdc"));
+        plan.delete(dir.path().join("missing.mdc"));
+
+        let changes = plan.planned_changes()?;
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, dir.path().join("old.mdc"));
+        assert_eq!(changes[0].kind, ChangeKind::Delete);
+
+        plan.write()?;
+        assert!(!dir.path().join("old.mdc").exists());
+        assert_eq!(fs::read_to_string(dir.path().join("kept.mdc"))?, "Kept");
+        assert!(plan.planned_changes()?.is_empty());
+        Ok(())
+    }
+
+    /// Test that a planned file is not written over a directory.
+    #[test]
+    fn test_write_refuses_directory() -> Result<()> {
+        let dir = tempdir()?;
+        fs::create_dir(dir.path().join(".rules"))?;
+        let mut plan = OutputPlan::default();
+        plan.insert(dir.path().join(".rules"), "Rules");
+        assert!(plan.write().is_err());
+        assert!(dir.path().join(".rules").is_dir());
+        Ok(())
+    }
+}
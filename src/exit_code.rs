@@ -0,0 +1,98 @@
+// src/exit_code.rs
+
+use std::fmt;
+use std::io;
+
+/// The rules were generated (or checked) successfully.
+pub const SUCCESS: u8 = 0;
+
+/// Generation failed for another reason, e.g. `check` found out-of-date outputs.
+pub const FAILURE: u8 = 1;
+
+/// The command line or the project configuration is invalid. This matches the exit
+/// code clap uses for invalid arguments.
+pub const USAGE: u8 = 2;
+
+/// A rule file, configuration file or converter definition could not be parsed.
+pub const PARSE: u8 = 3;
+
+/// Reading or writing a file failed.
+pub const IO: u8 = 4;
+
+/// An error caused by how urules was invoked, reported with the `USAGE` exit code.
+#[derive(Debug)]
+pub struct UsageError(pub String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for UsageError {}
+
+/// The error of a `--strict` run in which some rule files failed to parse.
+#[derive(Debug)]
+pub struct RuleParseError {
+    /// The number of rule files that failed to parse.
+    pub failures: usize,
+}
+
+impl fmt::Display for RuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} rule file(s) failed to parse (--strict)",
+            self.failures
+        )
+    }
+}
+
+impl std::error::Error for RuleParseError {}
+
+/// Returns the exit code for `error`, based on the errors in its chain: usage errors
+/// first, then parse errors, then IO errors, and `FAILURE` for anything else.
+pub fn exit_code(error: &anyhow::Error) -> u8 {
+    let chain = || error.chain();
+    if chain().any(|cause| cause.is::<UsageError>()) {
+        USAGE
+    } else if chain().any(|cause| {
+        cause.is::<RuleParseError>()
+            || cause.is::<serde_yaml::Error>()
+            || cause.is::<serde_json::Error>()
+            || cause.is::<toml::de::Error>()
+            || cause.is::<minijinja::Error>()
+    }) {
+        PARSE
+    } else if chain().any(|cause| cause.is::<io::Error>()) {
+        IO
+    } else {
+        FAILURE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Context};
+
+    /// Test that errors are classified by the causes in their chain.
+    #[test]
+    fn test_exit_code() {
+        let usage = anyhow!(UsageError("No agent selected".to_string()));
+        assert_eq!(exit_code(&usage), USAGE);
+
+        let parse = serde_yaml::from_str::<Vec<String>>("[unclosed")
+            .context("Failed to parse rule")
+            .unwrap_err();
+        assert_eq!(exit_code(&parse), PARSE);
+        assert_eq!(exit_code(&anyhow!(RuleParseError { failures: 2 })), PARSE);
+
+        let io = std::fs::read_to_string("/nonexistent/urules/file")
+            .context("Failed to read")
+            .unwrap_err();
+        assert_eq!(exit_code(&io), IO);
+
+        assert_eq!(exit_code(&anyhow!("Outputs are out of date")), FAILURE);
+    }
+}
@@ -0,0 +1,128 @@
+// src/git.rs
+
+use crate::exit_code::UsageError;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Returns the root of the git repository containing `start_dir` and its git directory,
+/// following the `gitdir:` of worktrees and submodules.
+pub(crate) fn find_git_dir(start_dir: &Path) -> Result<(PathBuf, PathBuf)> {
+    for dir in start_dir.ancestors() {
+        let dot_git = dir.join(".git");
+        if dot_git.is_dir() {
+            return Ok((dir.to_path_buf(), dot_git));
+        }
+        if dot_git.is_file() {
+            let content = fs::read_to_string(&dot_git)
+                .with_context(|| format!("Failed to read {:?}", dot_git))?;
+            if let Some(git_dir) = content.trim().strip_prefix("gitdir:") {
+                return Ok((dir.to_path_buf(), dir.join(git_dir.trim())));
+            }
+        }
+    }
+    bail!(UsageError(format!(
+        "{:?} is not inside a git repository",
+        start_dir
+    )))
+}
+
+/// Returns the git directory that the worktrees of a repository share, given the git
+/// directory `git_dir` of one of them: the directory named by its `commondir` file, or
+/// `git_dir` itself for the main worktree.
+pub(crate) fn common_git_dir(git_dir: &Path) -> Result<PathBuf> {
+    let commondir_path = git_dir.join("commondir");
+    if !commondir_path.is_file() {
+        return Ok(git_dir.to_path_buf());
+    }
+    let commondir = fs::read_to_string(&commondir_path)
+        .with_context(|| format!("Failed to read {:?}", commondir_path))?;
+    Ok(git_dir.join(commondir.trim()))
+}
+
+/// Returns the directory git runs the hooks of the worktree at `repo_root` from:
+/// `core.hooksPath` if it is set, or else the `hooks` directory of the common git
+/// directory, since git never runs hooks from the git directory of a linked worktree.
+pub(crate) fn hooks_dir(repo_root: &Path, git_dir: &Path) -> Result<PathBuf> {
+    // Git resolves both, with `core.hooksPath` from any configuration file
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .current_dir(repo_root)
+        .output()
+        .ok()
+        .filter(|output| output.status.success());
+    if let Some(output) = output {
+        let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !path.is_empty() {
+            return Ok(repo_root.join(path));
+        }
+    }
+    Ok(common_git_dir(git_dir)?.join("hooks"))
+}
+
+/// Returns the branch checked out in `git_dir`, or `None` for a detached `HEAD`.
+pub(crate) fn current_branch(git_dir: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    Some(head.trim().strip_prefix("ref: refs/heads/")?.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Runs `git` with `args` in `dir`, failing the test if it fails.
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args([
+                "-c",
+                "user.name=urules",
+                "-c",
+                "user.email=urules@example.com",
+            ])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// Test that the hook of a linked worktree goes to the common git directory, where
+    /// git runs it from, and that `core.hooksPath` is honored.
+    #[test]
+    fn test_hooks_dir_of_worktree() -> Result<()> {
+        let dir = tempdir()?;
+        let repo = dir.path().join("repo");
+        let worktree = dir.path().join("worktree");
+        fs::create_dir(&repo)?;
+        git(&repo, &["init", "-q"]);
+        git(
+            &repo,
+            &["commit", "-q", "--allow-empty", "-m", "Initial commit"],
+        );
+        git(
+            &repo,
+            &["worktree", "add", "-q", worktree.to_str().unwrap()],
+        );
+
+        let (root, git_dir) = find_git_dir(&worktree)?;
+        assert_eq!(root, worktree);
+        assert_eq!(
+            fs::canonicalize(hooks_dir(&root, &git_dir)?)?,
+            fs::canonicalize(repo.join(".git/hooks"))?
+        );
+        // Without git, the common directory is found from the worktree's `commondir`
+        assert_eq!(
+            fs::canonicalize(common_git_dir(&git_dir)?)?,
+            fs::canonicalize(repo.join(".git"))?
+        );
+
+        git(&repo, &["config", "core.hooksPath", ".githooks"]);
+        assert_eq!(
+            hooks_dir(&repo, &repo.join(".git"))?,
+            repo.join(".githooks")
+        );
+        Ok(())
+    }
+}
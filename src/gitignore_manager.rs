@@ -2,14 +2,14 @@
 
 use anyhow::{Context, Result};
 use std::collections::HashSet;
-use std::fs::{self, File};
-use std::io::{BufRead, BufReader};
+use std::fs;
 use std::path::Path;
 
 // Assuming AgentName will be accessible from main.rs or a shared module.
 // This path will work if main.rs is part of the crate root (e.g. lib.rs then main.rs)
 // or if main.rs is effectively the crate root for a binary crate.
 // If AgentName is moved to its own module, this path will need to change.
+use crate::converters::registry::{self, ConverterOptions};
 use crate::AgentName; // Corrected path assuming AgentName is pub in main.rs or lib.rs
 
 const GITIGNORE_HEADER: &str = "# Added by urules";
@@ -24,6 +24,16 @@ struct GitignoreSections {
 }
 
 fn parse_gitignore_sections(gitignore_path: &Path) -> Result<GitignoreSections> {
+    let content = if gitignore_path.exists() {
+        fs::read_to_string(gitignore_path)
+            .with_context(|| format!("Failed to read .gitignore file at {:?}", gitignore_path))?
+    } else {
+        String::new()
+    };
+    Ok(parse_sections(&content))
+}
+
+fn parse_sections(content: &str) -> GitignoreSections {
     let mut result = GitignoreSections {
         pre: String::new(),
         section: String::new(),
@@ -33,41 +43,34 @@ fn parse_gitignore_sections(gitignore_path: &Path) -> Result<GitignoreSections>
     };
     let mut in_section = false;
 
-    if gitignore_path.exists() {
-        let file = File::open(gitignore_path)
-            .with_context(|| format!("Failed to open .gitignore file at {:?}", gitignore_path))?;
-        let reader = BufReader::new(file);
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed == GITIGNORE_HEADER {
+            in_section = true;
+            result.header_found = true;
+            continue;
+        } else if trimmed == GITIGNORE_FOOTER && in_section {
+            in_section = false;
+            continue;
+        }
 
-        for line_result in reader.lines() {
-            let line = line_result.with_context(|| "Failed to read line from .gitignore")?;
-            let trimmed = line.trim();
-
-            if trimmed == GITIGNORE_HEADER {
-                in_section = true;
-                result.header_found = true;
-                continue;
-            } else if trimmed == GITIGNORE_FOOTER && in_section {
-                in_section = false;
-                continue;
-            }
-
-            if result.header_found && in_section {
-                result.section.push_str(trimmed);
-                result.section.push('\n');
-                result.lines.insert(trimmed.to_string());
-            } else if !result.header_found {
-                result.pre.push_str(trimmed);
-                result.pre.push('\n');
-                result.lines.insert(trimmed.to_string());
-            } else {
-                result.post.push_str(trimmed);
-                result.post.push('\n');
-                result.lines.insert(trimmed.to_string());
-            }
+        if result.header_found && in_section {
+            result.section.push_str(trimmed);
+            result.section.push('\n');
+            result.lines.insert(trimmed.to_string());
+        } else if !result.header_found {
+            result.pre.push_str(trimmed);
+            result.pre.push('\n');
+            result.lines.insert(trimmed.to_string());
+        } else {
+            result.post.push_str(trimmed);
+            result.post.push('\n');
+            result.lines.insert(trimmed.to_string());
         }
     }
 
-    Ok(result)
+    result
 }
 
 /// Updates the .gitignore file in the specified output directory to include
@@ -81,22 +84,28 @@ fn parse_gitignore_sections(gitignore_path: &Path) -> Result<GitignoreSections>
 /// # Returns
 /// A `Result` indicating success or failure of the .gitignore update operation.
 pub fn update_gitignore(output_dir: &Path, agent_name: &AgentName) -> Result<()> {
-    let patterns_to_add: Vec<String> = match agent_name {
-        AgentName::Cursor => vec![".cursor/".to_string()],
-        AgentName::Windsurf => vec!["global_rules.md".to_string(), ".windsurf/".to_string()],
-        AgentName::Claude => vec!["CLAUDE.md".to_string()],
-    };
+    update_gitignore_patterns(output_dir, &gitignore_patterns(agent_name))
+}
 
-    let gitignore_path = output_dir.join(".gitignore");
-    let sections = parse_gitignore_sections(&gitignore_path)?;
+/// Returns the `.gitignore` patterns covering the files generated for an agent
+/// in its default output layout, as listed in the converter registry.
+pub fn gitignore_patterns(agent_name: &AgentName) -> Vec<String> {
+    registry::gitignore_patterns(agent_name, &ConverterOptions::default())
+}
 
-    // If the header was found but the footer was missing, all remaining lines
-    // are treated as part of the urules section. This is implicitly handled
-    // by `parse_gitignore_sections` which collects everything after the header
-    // when no footer is present.
+/// Updates the .gitignore file in `output_dir` to include the given patterns, using the
+/// same urules-managed section and duplicate detection as `update_gitignore`.
+///
+/// This is used when a converter's output layout differs from the agent's default
+/// (e.g. Cursor's legacy `.cursorrules` file).
+pub fn update_gitignore_patterns(output_dir: &Path, patterns_to_add: &[String]) -> Result<()> {
+    update_managed_section(&output_dir.join(".gitignore"), patterns_to_add)
+}
 
-    let mut final_new_patterns = Vec::new();
-    for pattern_to_check in &patterns_to_add {
+/// Returns the patterns that are not present in `lines`, in any of their spellings.
+fn absent_patterns(lines: &HashSet<String>, patterns: &[String]) -> Vec<String> {
+    let mut absent = Vec::new();
+    for pattern_to_check in patterns {
         let trimmed_pattern = pattern_to_check.trim_matches('/');
         // Check variations: exact, /dir, dir/, /dir/
         let variations = [
@@ -106,57 +115,95 @@ pub fn update_gitignore(output_dir: &Path, agent_name: &AgentName) -> Result<()>
             format!("/{}/", trimmed_pattern),
             trimmed_pattern.to_string(),
         ];
-        let is_present = variations.iter().any(|v| sections.lines.contains(v.trim()));
+        let is_present = variations.iter().any(|v| lines.contains(v.trim()));
 
         if !is_present {
-            final_new_patterns.push(pattern_to_check.clone());
+            absent.push(pattern_to_check.clone());
         }
     }
+    absent
+}
 
-    if !final_new_patterns.is_empty() || !sections.header_found {
-        // Rebuild .gitignore content
-        let mut new_gitignore_content = String::new();
-        new_gitignore_content.push_str(&sections.pre);
+/// Returns the patterns missing from the ignore file at `gitignore_path`, inside or
+/// outside the urules-managed section. A missing file is missing every pattern.
+pub fn missing_patterns(gitignore_path: &Path, patterns: &[String]) -> Result<Vec<String>> {
+    let sections = parse_gitignore_sections(gitignore_path)?;
+    Ok(absent_patterns(&sections.lines, patterns))
+}
 
-        // Ensure there's a newline before our section if pre_content is not empty and doesn't end with one
-        if !sections.pre.is_empty() && !sections.pre.ends_with('\n') {
-            new_gitignore_content.push('\n');
-        }
+/// Adds `patterns_to_add` to the urules-managed section of the ignore file at `gitignore_path`,
+/// creating the file or section if needed and skipping patterns that are already present.
+///
+/// Any file using `.gitignore` syntax can be managed this way (e.g. `.cursorignore`).
+pub fn update_managed_section(gitignore_path: &Path, patterns_to_add: &[String]) -> Result<()> {
+    let content =
+        match gitignore_path.exists() {
+            true => Some(fs::read_to_string(gitignore_path).with_context(|| {
+                format!("Failed to read .gitignore file at {:?}", gitignore_path)
+            })?),
+            false => None,
+        };
+    if let Some(new_content) = with_managed_section(content.as_deref(), patterns_to_add) {
+        fs::write(gitignore_path, new_content).with_context(|| {
+            format!("Failed to write updated .gitignore to {:?}", gitignore_path)
+        })?;
+    }
+    Ok(())
+}
 
-        new_gitignore_content.push_str(GITIGNORE_HEADER);
-        new_gitignore_content.push('\n');
+/// Returns `content`, the content of an ignore file (`None` if it does not exist yet),
+/// with `patterns_to_add` in its urules-managed section, or `None` if the file already
+/// has the section and every pattern.
+pub fn with_managed_section(content: Option<&str>, patterns_to_add: &[String]) -> Option<String> {
+    let sections = parse_sections(content.unwrap_or_default());
 
-        // Add existing lines from our old section (if any)
-        new_gitignore_content.push_str(&sections.section);
-
-        // Add new patterns
-        for pattern in final_new_patterns {
-            if !sections.section.lines().any(|l| l.trim() == pattern.trim())
-                && !sections.pre.lines().any(|l| l.trim() == pattern.trim())
-            {
-                new_gitignore_content.push_str(&pattern);
-                new_gitignore_content.push('\n');
-            }
-        }
+    // If the header was found but the footer was missing, all remaining lines
+    // are treated as part of the urules section. This is implicitly handled
+    // by `parse_sections` which collects everything after the header
+    // when no footer is present.
+
+    let final_new_patterns = absent_patterns(&sections.lines, patterns_to_add);
+    if final_new_patterns.is_empty() && sections.header_found {
+        return None;
+    }
+
+    // Rebuild .gitignore content
+    let mut new_gitignore_content = String::new();
+    new_gitignore_content.push_str(&sections.pre);
 
-        new_gitignore_content.push_str(GITIGNORE_FOOTER);
+    // Ensure there's a newline before our section if pre_content is not empty and doesn't end with one
+    if !sections.pre.is_empty() && !sections.pre.ends_with('\n') {
         new_gitignore_content.push('\n');
-        new_gitignore_content.push_str(&sections.post);
+    }
+
+    new_gitignore_content.push_str(GITIGNORE_HEADER);
+    new_gitignore_content.push('\n');
+
+    // Add existing lines from our old section (if any)
+    new_gitignore_content.push_str(&sections.section);
 
-        fs::write(
-            &gitignore_path,
-            new_gitignore_content.trim_end_matches('\n').to_string() + "\n",
-        )
-        .with_context(|| format!("Failed to write updated .gitignore to {:?}", gitignore_path))?;
+    // Add new patterns
+    for pattern in final_new_patterns {
+        if !sections.section.lines().any(|l| l.trim() == pattern.trim())
+            && !sections.pre.lines().any(|l| l.trim() == pattern.trim())
+        {
+            new_gitignore_content.push_str(&pattern);
+            new_gitignore_content.push('\n');
+        }
     }
 
-    Ok(())
+    new_gitignore_content.push_str(GITIGNORE_FOOTER);
+    new_gitignore_content.push('\n');
+    new_gitignore_content.push_str(&sections.post);
+
+    Some(new_gitignore_content.trim_end_matches('\n').to_string() + "\n")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs;
+    use std::fs::{self, File};
+    use std::io::{BufRead, BufReader};
     use tempfile::tempdir;
     // Assuming AgentName is pub and accessible for tests
     // If not, tests might need to be in main.rs or AgentName moved.
@@ -206,6 +253,24 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_add_copilot_patterns() -> Result<()> {
+        let dir = tempdir()?;
+        let output_path = dir.path();
+
+        update_gitignore(output_path, &AgentName::Copilot)?;
+        let lines = read_gitignore_lines(&output_path.join(".gitignore"));
+
+        assert!(lines.contains(".github/copilot-instructions.md"));
+        assert!(lines.contains(".github/instructions/"));
+        assert!(lines.contains(".github/prompts/"));
+        assert!(
+            !lines.contains(".github/"),
+            "The whole .github directory must not be ignored."
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_add_to_existing_gitignore_with_section() -> Result<()> {
         let dir = tempdir()?;
@@ -271,7 +336,10 @@ mod tests {
         update_gitignore(output_path, &AgentName::Cursor)?;
         let content_after_first_update = fs::read_to_string(&gitignore_file)?;
         assert_eq!(
-            content_after_first_update.matches(".cursor/").count(),
+            content_after_first_update
+                .lines()
+                .filter(|l| l.trim() == ".cursor/")
+                .count(),
             0,
             "'.cursor/' should not be added if '/.cursor/' exists."
         );
@@ -281,7 +349,10 @@ mod tests {
         update_gitignore(output_path, &AgentName::Cursor)?;
         let content_after_second_update = fs::read_to_string(&gitignore_file)?;
         assert_eq!(
-            content_after_second_update.matches(".cursor/").count(),
+            content_after_second_update
+                .lines()
+                .filter(|l| l.trim() == ".cursor/")
+                .count(),
             0,
             "'.cursor/' should not be added if '.cursor' exists."
         );
@@ -0,0 +1,140 @@
+// src/ignore_files.rs
+
+use crate::converters::registry::coding_agents;
+use crate::dry_run::OutputPlan;
+use crate::gitignore_manager::with_managed_section;
+use crate::universal_rule::UniversalRule;
+use crate::AgentName;
+use anyhow::Result;
+use std::path::Path;
+
+/// The agent-agnostic AI ignore file read by Gemini Code Assist, written for every
+/// coding agent alongside its own ignore file.
+pub const AIEXCLUDE_FILE: &str = ".aiexclude";
+
+/// Returns the ignore files that keep matching paths out of an agent's context.
+///
+/// Exports and custom converters have no ignore files.
+pub fn ignore_files(agent_name: &AgentName) -> Vec<&'static str> {
+    if !coding_agents().contains(agent_name) {
+        return Vec::new();
+    }
+    let agent_file = match agent_name {
+        AgentName::Cursor => Some(".cursorignore"),
+        AgentName::Windsurf => Some(".codeiumignore"),
+        AgentName::Goose => Some(".gooseignore"),
+        _ => None,
+    };
+    agent_file.into_iter().chain([AIEXCLUDE_FILE]).collect()
+}
+
+/// Collects the `ignore_patterns` of all rules, in order and without duplicates.
+pub fn collect_ignore_patterns(rules: &[UniversalRule]) -> Vec<String> {
+    let mut patterns: Vec<String> = Vec::new();
+    for pattern in rules
+        .iter()
+        .filter_map(|rule| rule.frontmatter.ignore_patterns.as_ref())
+        .flatten()
+    {
+        if !patterns.contains(pattern) {
+            patterns.push(pattern.clone());
+        }
+    }
+    patterns
+}
+
+/// Plans the agent's ignore files in `output_dir` with the rules' `ignore_patterns` added,
+/// merged with their content so far in `plan` (or on disk).
+///
+/// The patterns are kept in the same urules-managed section as the `.gitignore` entries,
+/// so hand-written lines in those files are preserved. Nothing is planned if no rule
+/// declares ignore patterns.
+pub fn plan_ignore_files(
+    plan: &mut OutputPlan,
+    output_dir: &Path,
+    agent_name: &AgentName,
+    rules: &[UniversalRule],
+) -> Result<()> {
+    let patterns = collect_ignore_patterns(rules);
+    if patterns.is_empty() {
+        return Ok(());
+    }
+    for file_name in ignore_files(agent_name) {
+        let path = output_dir.join(file_name);
+        let content = plan.current_text(&path)?;
+        if let Some(new_content) = with_managed_section(content.as_deref(), &patterns) {
+            plan.insert(path, new_content);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universal_rule::UniversalRuleFrontmatter;
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// Helper function to create a rule declaring the given ignore patterns.
+    fn create_test_ignore_rule(name: &str, patterns: &[&str]) -> UniversalRule {
+        UniversalRule {
+            name: name.to_string(),
+            frontmatter: UniversalRuleFrontmatter {
+                ignore_patterns: Some(patterns.iter().map(|p| p.to_string()).collect()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Test that Cursor gets `.cursorignore` and `.aiexclude`, with deduplicated patterns
+    /// in a managed section that keeps existing lines.
+    #[test]
+    fn test_plan_ignore_files_for_cursor() -> Result<()> {
+        let dir = tempdir()?;
+        fs::write(dir.path().join(".cursorignore"), "secrets/\n")?;
+        let rules = vec![
+            create_test_ignore_rule("build", &["target/", "dist/"]),
+            create_test_ignore_rule("secrets", &["secrets/", "dist/"]),
+        ];
+
+        let mut plan = OutputPlan::default();
+        plan_ignore_files(&mut plan, dir.path(), &AgentName::Cursor, &rules)?;
+
+        assert_eq!(
+            plan.files.keys().collect::<Vec<_>>(),
+            [
+                &dir.path().join(AIEXCLUDE_FILE),
+                &dir.path().join(".cursorignore")
+            ]
+        );
+        assert_eq!(
+            plan.files[&dir.path().join(".cursorignore")],
+            b"secrets/\n# Added by urules\ntarget/\ndist/\n# End urules section\n"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join(".cursorignore"))?,
+            "secrets/\n"
+        );
+        Ok(())
+    }
+
+    /// Test that nothing is planned without ignore patterns or for export targets.
+    #[test]
+    fn test_plan_ignore_files_skips_when_not_needed() -> Result<()> {
+        let dir = tempdir()?;
+        let mut plan = OutputPlan::default();
+        plan_ignore_files(
+            &mut plan,
+            dir.path(),
+            &AgentName::Windsurf,
+            &[UniversalRule::default()],
+        )?;
+        let rules = vec![create_test_ignore_rule("build", &["target/"])];
+        plan_ignore_files(&mut plan, dir.path(), &AgentName::Json, &rules)?;
+
+        assert!(plan.files.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+// src/importers/claude.rs
+
+use super::RuleImporter;
+use crate::converters::claude::CATEGORY_HEADING_PREFIX;
+use crate::universal_rule::{UniversalRule, UniversalRuleFrontmatter};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// The file read by `ClaudeImporter`.
+pub const CLAUDE_FILE: &str = "CLAUDE.md";
+
+/// The base name of rules without a heading of their own.
+const UNNAMED_RULE_PREFIX: &str = "claude";
+
+/// A part of `CLAUDE.md` delimited by `## ` headings and horizontal rules.
+#[derive(Debug, Default)]
+struct Section {
+    heading: Option<String>,
+    lines: Vec<String>,
+    /// The category of the `# Category: ` heading the section follows, if any.
+    category: Option<String>,
+}
+
+/// Returns `true` for Markdown horizontal rules such as `---`, `***` or `___`.
+fn is_horizontal_rule(line: &str) -> bool {
+    let line = line.trim();
+    line.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&c| line.chars().all(|ch| ch == c))
+}
+
+/// Splits a Markdown document on level 2 headings and horizontal rules, ignoring
+/// anything inside fenced code blocks. The `# Category: ` headings written by the
+/// `ClaudeConverter` set the category of the sections that follow them.
+fn split_sections(content: &str) -> Vec<Section> {
+    let mut sections = vec![Section::default()];
+    let mut category = None;
+    let mut in_fence = false;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if !in_fence {
+            if let Some(name) = line.strip_prefix(CATEGORY_HEADING_PREFIX) {
+                // Categories become directories of the rules directory, so stay inside it
+                let name = name.trim();
+                category = Path::new(name)
+                    .components()
+                    .all(|c| matches!(c, Component::Normal(_)))
+                    .then(|| name.to_string());
+                sections.push(Section {
+                    category: category.clone(),
+                    ..Default::default()
+                });
+                continue;
+            }
+            if let Some(heading) = line.strip_prefix("## ") {
+                sections.push(Section {
+                    heading: Some(heading.trim().to_string()),
+                    lines: Vec::new(),
+                    category: category.clone(),
+                });
+                continue;
+            }
+            if is_horizontal_rule(line) {
+                sections.push(Section {
+                    category: category.clone(),
+                    ..Default::default()
+                });
+                continue;
+            }
+        }
+        if let Some(section) = sections.last_mut() {
+            section.lines.push(line.to_string());
+        }
+    }
+    sections
+        .into_iter()
+        .filter(|s| s.heading.is_some() || s.lines.iter().any(|l| !l.trim().is_empty()))
+        .collect()
+}
+
+/// Turns a heading into a rule name: the `Rule: ` prefix written by `render_rule_block` is
+/// dropped, and the text is lowercased with runs of other characters replaced by `_`.
+fn rule_name_from_heading(heading: &str) -> String {
+    let heading = heading.strip_prefix("Rule:").unwrap_or(heading).trim();
+    let mut name = String::new();
+    for c in heading.chars() {
+        if c.is_alphanumeric() || c == '-' || c == '_' {
+            name.extend(c.to_lowercase());
+        } else if !name.is_empty() && !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    name.trim_end_matches('_').to_string()
+}
+
+/// Separates a section's description from its content.
+///
+/// `render_rule_block` writes a rule's description on the line right below its heading,
+/// followed by a blank line and the content, so a single line in that position is read as
+/// the description. Sections whose body starts after a blank line have no description.
+fn split_description(section: &Section) -> (Option<String>, String) {
+    let body = section.lines.join("\n");
+    if section.heading.is_none() {
+        return (None, body.trim().to_string());
+    }
+    match section.lines.as_slice() {
+        [first, second, rest @ ..]
+            if !first.trim().is_empty()
+                && !first.starts_with('#')
+                && second.trim().is_empty()
+                && rest.iter().any(|l| !l.trim().is_empty()) =>
+        {
+            (
+                Some(first.trim().to_string()),
+                rest.join("\n").trim().to_string(),
+            )
+        }
+        _ => (None, body.trim().to_string()),
+    }
+}
+
+/// Converts sections into rules with unique names. Sections without a heading are named
+/// `<unnamed_prefix>` if they are the only section and `<unnamed_prefix>_<n>` otherwise.
+fn sections_to_rules(sections: Vec<Section>, unnamed_prefix: &str) -> Vec<UniversalRule> {
+    let single = sections.len() == 1;
+    let mut rules: Vec<UniversalRule> = Vec::new();
+    for (i, section) in sections.iter().enumerate() {
+        let base_name = section
+            .heading
+            .as_deref()
+            .map(rule_name_from_heading)
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| {
+                if single {
+                    unnamed_prefix.to_string()
+                } else {
+                    format!("{}_{}", unnamed_prefix, i + 1)
+                }
+            });
+        let mut name = base_name.clone();
+        let mut suffix = 2;
+        while rules.iter().any(|r| r.name == name) {
+            name = format!("{}_{}", base_name, suffix);
+            suffix += 1;
+        }
+
+        let (description, content) = split_description(section);
+        rules.push(UniversalRule {
+            name,
+            frontmatter: UniversalRuleFrontmatter {
+                description,
+                ..Default::default()
+            },
+            content,
+            relative_dir: section
+                .category
+                .as_deref()
+                .map(PathBuf::from)
+                .unwrap_or_default(),
+        });
+    }
+    rules
+}
+
+/// Splits a concatenated Markdown rules file, such as `CLAUDE.md`, into one rule per
+/// `## ` heading and per horizontal rule.
+pub fn split_markdown_rules(content: &str, unnamed_prefix: &str) -> Vec<UniversalRule> {
+    sections_to_rules(split_sections(content), unnamed_prefix)
+}
+
+/// A `RuleImporter` implementation reading a `CLAUDE.md` file.
+///
+/// The file is split into one rule per `## ` heading and per horizontal rule. Headings
+/// become rule names, and descriptions are recovered when they follow the layout
+/// written by the `ClaudeConverter`.
+pub struct ClaudeImporter;
+
+impl RuleImporter for ClaudeImporter {
+    fn import_rules(&self, input_dir: &Path) -> Result<Vec<UniversalRule>> {
+        let claude_path = input_dir.join(CLAUDE_FILE);
+        if !claude_path.is_file() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&claude_path)
+            .with_context(|| format!("Failed to read {:?}", claude_path))?;
+        Ok(split_markdown_rules(&content, UNNAMED_RULE_PREFIX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converters::claude::ClaudeConverter;
+    use crate::converters::write_rules;
+    use tempfile::tempdir;
+
+    /// Test that a `CLAUDE.md` generated by urules is split back into its rules.
+    #[test]
+    fn test_import_generated_claude_md() -> Result<()> {
+        let dir = tempdir()?;
+        let rules = vec![
+            UniversalRule {
+                name: "rust".to_string(),
+                content: "Use clippy.\n\n- Run tests.".to_string(),
+                frontmatter: UniversalRuleFrontmatter {
+                    description: Some("Rust rules".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            UniversalRule {
+                name: "style".to_string(),
+                content: "Be concise.".to_string(),
+                ..Default::default()
+            },
+        ];
+        write_rules(&ClaudeConverter::default(), &rules, dir.path())?;
+
+        let imported = ClaudeImporter.import_rules(dir.path())?;
+
+        assert_eq!(imported.len(), 2);
+        assert_eq!(imported[0].name, "rust");
+        assert_eq!(
+            imported[0].frontmatter.description.as_deref(),
+            Some("Rust rules")
+        );
+        assert_eq!(imported[0].content, "Use clippy.\n\n- Run tests.");
+        assert_eq!(imported[1].name, "style");
+        assert!(imported[1].frontmatter.description.is_none());
+        assert_eq!(imported[1].content, "Be concise.");
+        Ok(())
+    }
+
+    /// Test that the category headings of a generated `CLAUDE.md` restore the rules'
+    /// subdirectories.
+    #[test]
+    fn test_import_claude_md_categories() -> Result<()> {
+        let dir = tempdir()?;
+        let rules = vec![
+            UniversalRule {
+                name: "style".to_string(),
+                content: "Be concise.".to_string(),
+                ..Default::default()
+            },
+            UniversalRule {
+                name: "hooks".to_string(),
+                content: "Use hooks.".to_string(),
+                relative_dir: PathBuf::from("frontend/react"),
+                ..Default::default()
+            },
+        ];
+        write_rules(&ClaudeConverter::default(), &rules, dir.path())?;
+
+        let imported = ClaudeImporter.import_rules(dir.path())?;
+
+        assert_eq!(imported.len(), 2);
+        assert_eq!(imported[0].relative_dir, PathBuf::new());
+        assert_eq!(imported[1].name, "hooks");
+        assert_eq!(imported[1].relative_dir, PathBuf::from("frontend/react"));
+        assert_eq!(imported[1].content, "Use hooks.");
+
+        let rules = split_markdown_rules("# Category: ../outside\n\n## Rule: x\n\nX.", "claude");
+        assert_eq!(rules[0].relative_dir, PathBuf::new());
+        Ok(())
+    }
+
+    /// Test splitting a hand-written file with a preamble, horizontal rules and fenced code.
+    #[test]
+    fn test_split_hand_written_claude_md() {
+        let content = "# Project\n\nOverview.\n\n## Build & Test\n\n```sh\n## not a heading\n---\n```\n\n---\n\nUnnamed part.\n";
+        let rules = split_markdown_rules(content, UNNAMED_RULE_PREFIX);
+
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules[0].name, "claude_1");
+        assert_eq!(rules[0].content, "# Project\n\nOverview.");
+        assert_eq!(rules[1].name, "build_test");
+        assert!(rules[1].frontmatter.description.is_none());
+        assert_eq!(rules[1].content, "```sh\n## not a heading\n---\n```");
+        assert_eq!(rules[2].name, "claude_3");
+        assert_eq!(rules[2].content, "Unnamed part.");
+    }
+
+    /// Test that a project without `CLAUDE.md` imports nothing.
+    #[test]
+    fn test_import_claude_md_missing() -> Result<()> {
+        let dir = tempdir()?;
+        assert!(ClaudeImporter.import_rules(dir.path())?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+// src/importers/copilot.rs
+
+use super::claude::split_markdown_rules;
+use super::{split_globs, RuleImporter};
+use crate::rule_parser::split_frontmatter;
+use crate::universal_rule::{UniversalRule, UniversalRuleFrontmatter};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The repository-wide instructions file, relative to `.github/`.
+pub const INSTRUCTIONS_FILE: &str = "copilot-instructions.md";
+
+/// The extension of path-scoped instruction files in `.github/instructions/`.
+pub const SCOPED_INSTRUCTIONS_EXTENSION: &str = ".instructions.md";
+
+/// The base name of repository-wide rules without a heading of their own.
+const UNNAMED_RULE_PREFIX: &str = "copilot";
+
+/// The frontmatter of a path-scoped `.instructions.md` file, as read back by the importer.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct ParsedInstructionsFrontmatter {
+    apply_to: Option<String>,
+    description: Option<String>,
+}
+
+/// Parses the frontmatter of an `.instructions.md` file.
+///
+/// Unquoted globs such as `applyTo: **/*.ts` are not valid YAML, so if YAML parsing fails
+/// each `key: value` line is read as plain text instead.
+fn parse_instructions_frontmatter(frontmatter: &str) -> ParsedInstructionsFrontmatter {
+    if let Ok(parsed) = serde_yaml::from_str::<Option<ParsedInstructionsFrontmatter>>(frontmatter) {
+        return parsed.unwrap_or_default();
+    }
+    let mut parsed = ParsedInstructionsFrontmatter::default();
+    for (key, value) in frontmatter
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim(), value.trim().trim_matches(['"', '\''])))
+    {
+        match key {
+            "applyTo" if !value.is_empty() => parsed.apply_to = Some(value.to_string()),
+            "description" if !value.is_empty() => parsed.description = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    parsed
+}
+
+/// Reads a path-scoped `.instructions.md` file, mapping `applyTo` to the rule's globs.
+fn parse_scoped_instructions(path: &Path, name: String) -> Result<UniversalRule> {
+    let file_content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let (frontmatter, content) = split_frontmatter(&file_content);
+    let parsed = parse_instructions_frontmatter(frontmatter);
+    Ok(UniversalRule {
+        name,
+        frontmatter: UniversalRuleFrontmatter {
+            description: parsed.description.filter(|d| !d.trim().is_empty()),
+            globs: parsed
+                .apply_to
+                .map(|globs| split_globs(&globs))
+                .filter(|g| !g.is_empty()),
+            ..Default::default()
+        },
+        content: content.to_string(),
+        relative_dir: PathBuf::new(),
+    })
+}
+
+/// A `RuleImporter` implementation reading GitHub Copilot instructions.
+///
+/// `.github/copilot-instructions.md` is split into rules like `CLAUDE.md`, and each
+/// `.github/instructions/<name>.instructions.md` file becomes a rule named `<name>` whose
+/// `applyTo` patterns become its globs.
+pub struct CopilotImporter;
+
+impl RuleImporter for CopilotImporter {
+    fn import_rules(&self, input_dir: &Path) -> Result<Vec<UniversalRule>> {
+        let mut rules = Vec::new();
+        let github_dir = input_dir.join(".github");
+
+        let instructions_path = github_dir.join(INSTRUCTIONS_FILE);
+        if instructions_path.is_file() {
+            let content = fs::read_to_string(&instructions_path)
+                .with_context(|| format!("Failed to read {:?}", instructions_path))?;
+            rules.extend(split_markdown_rules(&content, UNNAMED_RULE_PREFIX));
+        }
+
+        let instructions_dir = github_dir.join("instructions");
+        if instructions_dir.is_dir() {
+            let mut scoped_files: Vec<(PathBuf, String)> = fs::read_dir(&instructions_dir)
+                .with_context(|| format!("Failed to read {:?}", instructions_dir))?
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .filter_map(|p| {
+                    let name = p
+                        .file_name()?
+                        .to_str()?
+                        .strip_suffix(SCOPED_INSTRUCTIONS_EXTENSION)?
+                        .to_string();
+                    Some((p, name))
+                })
+                .collect();
+            scoped_files.sort();
+            for (path, name) in scoped_files {
+                rules.push(parse_scoped_instructions(&path, name)?);
+            }
+        }
+
+        Ok(rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converters::copilot::CopilotConverter;
+    use crate::converters::write_rules;
+    use tempfile::tempdir;
+
+    /// Test that rules generated by the `CopilotConverter` are imported back, with
+    /// `applyTo` mapped to globs.
+    #[test]
+    fn test_import_copilot_rules_round_trip() -> Result<()> {
+        let dir = tempdir()?;
+        let rules = vec![
+            UniversalRule {
+                name: "general".to_string(),
+                content: "Be concise.".to_string(),
+                ..Default::default()
+            },
+            UniversalRule {
+                name: "typescript".to_string(),
+                content: "Use strict mode.".to_string(),
+                frontmatter: UniversalRuleFrontmatter {
+                    description: Some("TypeScript rules".to_string()),
+                    globs: Some(vec!["**/*.ts".to_string(), "**/*.tsx".to_string()]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ];
+        write_rules(&CopilotConverter::default(), &rules, dir.path())?;
+
+        let imported = CopilotImporter.import_rules(dir.path())?;
+
+        assert_eq!(imported.len(), 2);
+        assert_eq!(imported[0].name, "general");
+        assert_eq!(imported[0].content, "Be concise.");
+        assert_eq!(imported[1].name, "typescript");
+        assert_eq!(
+            imported[1].frontmatter.description.as_deref(),
+            Some("TypeScript rules")
+        );
+        assert_eq!(
+            imported[1].frontmatter.globs,
+            Some(vec!["**/*.ts".to_string(), "**/*.tsx".to_string()])
+        );
+        assert_eq!(imported[1].content, "Use strict mode.");
+        Ok(())
+    }
+
+    /// Test that hand-written frontmatter with unquoted globs is read.
+    #[test]
+    fn test_parse_instructions_frontmatter_not_yaml() {
+        let parsed = parse_instructions_frontmatter("applyTo: **/*.py,tests/**");
+        assert_eq!(
+            split_globs(parsed.apply_to.as_deref().unwrap()),
+            vec!["**/*.py".to_string(), "tests/**".to_string()]
+        );
+        assert!(parsed.description.is_none());
+    }
+}
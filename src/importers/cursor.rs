@@ -0,0 +1,210 @@
+// src/importers/cursor.rs
+
+use super::{split_globs, RuleImporter};
+use crate::converters::cursor::LEGACY_RULES_FILE;
+use crate::rule_parser::split_frontmatter;
+use crate::universal_rule::{Activation, UniversalRule, UniversalRuleFrontmatter};
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// The name given to the rule imported from a legacy `.cursorrules` file.
+pub const LEGACY_RULE_NAME: &str = "cursorrules";
+
+/// The `globs` value of an `.mdc` file, which Cursor writes either as a list or as a
+/// single comma-separated string.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum MdcGlobs {
+    List(Vec<String>),
+    Text(String),
+}
+
+/// The frontmatter of an existing `.mdc` rule file, as read back by the importer.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+struct ParsedMdcFrontmatter {
+    description: Option<String>,
+    globs: Option<MdcGlobs>,
+    #[serde(default)]
+    always_apply: bool,
+    #[serde(default)]
+    agent_requested: bool,
+}
+
+/// Parses the frontmatter of an `.mdc` file.
+///
+/// Cursor's editor writes values such as `globs: *.ts,*.tsx` unquoted, which is not valid
+/// YAML, so if YAML parsing fails each `key: value` line is read as plain text instead.
+fn parse_mdc_frontmatter(frontmatter: &str) -> ParsedMdcFrontmatter {
+    if let Ok(parsed) = serde_yaml::from_str::<Option<ParsedMdcFrontmatter>>(frontmatter) {
+        return parsed.unwrap_or_default();
+    }
+    let mut parsed = ParsedMdcFrontmatter::default();
+    for (key, value) in frontmatter
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim(), value.trim()))
+    {
+        match key {
+            "description" if !value.is_empty() => parsed.description = Some(value.to_string()),
+            "globs" if !value.is_empty() => parsed.globs = Some(MdcGlobs::Text(value.to_string())),
+            "alwaysApply" => parsed.always_apply = value == "true",
+            "agentRequested" => parsed.agent_requested = value == "true",
+            _ => {}
+        }
+    }
+    parsed
+}
+
+/// Maps an `.mdc` file's frontmatter back to `UniversalRuleFrontmatter`.
+///
+/// `alwaysApply: true` becomes `activation: always`, and `agentRequested: true`
+/// `activation: agent_decision`, mirroring the `CursorConverter`.
+fn to_universal_frontmatter(mdc: ParsedMdcFrontmatter) -> UniversalRuleFrontmatter {
+    let globs = match mdc.globs {
+        Some(MdcGlobs::List(globs)) => globs,
+        Some(MdcGlobs::Text(globs)) => split_globs(&globs),
+        None => Vec::new(),
+    };
+    let activation = if mdc.always_apply {
+        Some(Activation::Always)
+    } else if mdc.agent_requested {
+        Some(Activation::AgentDecision)
+    } else {
+        None
+    };
+    UniversalRuleFrontmatter {
+        description: mdc.description.filter(|d| !d.trim().is_empty()),
+        globs: Some(globs).filter(|g| !g.is_empty()),
+        activation,
+        ..Default::default()
+    }
+}
+
+/// Reads a single `.mdc` file into a `UniversalRule` named after its file stem.
+fn parse_mdc_file(path: &Path) -> Result<UniversalRule> {
+    let file_content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let (frontmatter, content) = split_frontmatter(&file_content);
+    let name = path
+        .file_stem()
+        .ok_or_else(|| anyhow!("Failed to get file stem for {:?}", path))?
+        .to_string_lossy()
+        .into_owned();
+    Ok(UniversalRule {
+        name,
+        frontmatter: to_universal_frontmatter(parse_mdc_frontmatter(frontmatter)),
+        content: content.to_string(),
+        relative_dir: PathBuf::new(),
+    })
+}
+
+/// A `RuleImporter` implementation reading Cursor rules.
+///
+/// Every `.mdc` file under `.cursor/rules/` becomes a rule; subdirectories are kept as
+/// the rule's relative directory. A legacy `.cursorrules` file is imported as a single
+/// global rule named `cursorrules`.
+pub struct CursorImporter;
+
+impl RuleImporter for CursorImporter {
+    fn import_rules(&self, input_dir: &Path) -> Result<Vec<UniversalRule>> {
+        let mut rules = Vec::new();
+
+        let cursor_rules_dir = input_dir.join(".cursor").join("rules");
+        let mut mdc_paths: Vec<PathBuf> = WalkDir::new(&cursor_rules_dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .map(|e| e.into_path())
+            .filter(|p| p.is_file() && p.extension().and_then(|s| s.to_str()) == Some("mdc"))
+            .collect();
+        mdc_paths.sort();
+        for path in mdc_paths {
+            let mut rule = parse_mdc_file(&path)?;
+            rule.relative_dir = path
+                .parent()
+                .and_then(|parent| parent.strip_prefix(&cursor_rules_dir).ok())
+                .map(Path::to_path_buf)
+                .unwrap_or_default();
+            rules.push(rule);
+        }
+
+        let legacy_path = input_dir.join(LEGACY_RULES_FILE);
+        if legacy_path.is_file() {
+            let content = fs::read_to_string(&legacy_path)
+                .with_context(|| format!("Failed to read {:?}", legacy_path))?;
+            rules.push(UniversalRule {
+                name: LEGACY_RULE_NAME.to_string(),
+                frontmatter: UniversalRuleFrontmatter {
+                    activation: Some(Activation::Always),
+                    ..Default::default()
+                },
+                content: content.trim().to_string(),
+                relative_dir: PathBuf::new(),
+            });
+        }
+
+        Ok(rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Test that `.mdc` frontmatter, nested directories and `.cursorrules` are imported.
+    #[test]
+    fn test_import_cursor_rules() -> Result<()> {
+        let dir = tempdir()?;
+        let cursor_rules_dir = dir.path().join(".cursor").join("rules");
+        fs::create_dir_all(cursor_rules_dir.join("backend"))?;
+        fs::write(
+            cursor_rules_dir.join("always.mdc"),
+            "---\ndescription: Always on\nalwaysApply: true\n---\nBe concise.\n",
+        )?;
+        fs::write(
+            cursor_rules_dir.join("backend").join("rust.mdc"),
+            "---\ndescription: Rust rules\nglobs:\n- '*.rs'\n---\nUse clippy.",
+        )?;
+        fs::write(dir.path().join(LEGACY_RULES_FILE), "Legacy rule.\n")?;
+
+        let rules = CursorImporter.import_rules(dir.path())?;
+
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules[0].name, "always");
+        assert_eq!(rules[0].frontmatter.activation, Some(Activation::Always));
+        assert_eq!(rules[0].content, "Be concise.");
+        assert_eq!(rules[1].name, "rust");
+        assert_eq!(rules[1].relative_dir, PathBuf::from("backend"));
+        assert_eq!(rules[1].frontmatter.globs, Some(vec!["*.rs".to_string()]));
+        assert_eq!(rules[2].name, LEGACY_RULE_NAME);
+        assert!(rules[2].frontmatter.is_always_applied());
+        assert_eq!(rules[2].content, "Legacy rule.");
+        Ok(())
+    }
+
+    /// Test that the unquoted comma-separated globs written by Cursor's editor are read.
+    #[test]
+    fn test_parse_mdc_frontmatter_not_yaml() {
+        let frontmatter = to_universal_frontmatter(parse_mdc_frontmatter(
+            "description:\nglobs: *.ts,*.tsx\nalwaysApply: false",
+        ));
+        assert_eq!(frontmatter.description, None);
+        assert_eq!(
+            frontmatter.globs,
+            Some(vec!["*.ts".to_string(), "*.tsx".to_string()])
+        );
+        assert_eq!(frontmatter.activation, None);
+    }
+
+    /// Test that a project without Cursor rules imports nothing.
+    #[test]
+    fn test_import_cursor_rules_none() -> Result<()> {
+        let dir = tempdir()?;
+        assert!(CursorImporter.import_rules(dir.path())?.is_empty());
+        Ok(())
+    }
+}
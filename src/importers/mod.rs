@@ -0,0 +1,114 @@
+// src/importers/mod.rs
+
+use crate::universal_rule::{Activation, UniversalRule};
+use crate::AgentName;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+/// A trait for types that read an agent's existing rule files back into UniversalRules,
+/// the reverse of a `RuleConverter`.
+pub trait RuleImporter {
+    /// Reads the agent's rule files found in `input_dir`.
+    ///
+    /// # Arguments
+    /// * `input_dir` - The project directory containing the agent's rule files.
+    ///
+    /// # Returns
+    /// A `Result` containing the imported rules (empty if the agent has no rule files).
+    fn import_rules(&self, input_dir: &Path) -> Result<Vec<UniversalRule>>;
+}
+
+/// Splits a comma-separated glob list, dropping empty entries.
+pub fn split_globs(globs: &str) -> Vec<String> {
+    globs
+        .split(',')
+        .map(str::trim)
+        .filter(|g| !g.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// The frontmatter written to imported rule files.
+///
+/// Only the fields importers can recover are included, and unset values are omitted
+/// so that the generated files stay as short as hand-written ones.
+#[derive(Serialize, Debug)]
+struct ImportedFrontmatter<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    globs: Option<&'a [String]>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    activation: Option<Activation>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    agents: Option<&'a [AgentName]>,
+}
+
+/// Renders an imported rule as a universal rule file: a YAML frontmatter block with the
+/// recovered fields (omitted entirely if there are none), followed by the rule content.
+pub fn render_rule_file(rule: &UniversalRule) -> Result<String> {
+    let frontmatter = ImportedFrontmatter {
+        description: rule.frontmatter.description.as_deref(),
+        globs: rule.frontmatter.globs.as_deref().filter(|g| !g.is_empty()),
+        activation: rule.frontmatter.activation,
+        agents: rule.frontmatter.agents.as_deref(),
+    };
+    let frontmatter_yaml = serde_yaml::to_string(&frontmatter)
+        .with_context(|| format!("Failed to serialize frontmatter for rule: {}", rule.name))?;
+    if frontmatter_yaml.trim() == "{}" {
+        return Ok(format!("{}\n", rule.content));
+    }
+    Ok(format!(
+        "---\n{}\n---\n{}\n",
+        frontmatter_yaml.trim_end(),
+        rule.content
+    ))
+}
+
+pub mod claude;
+pub mod copilot;
+pub mod cursor;
+pub mod windsurf;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universal_rule::UniversalRuleFrontmatter;
+
+    /// Test that only the recovered fields are written to the frontmatter.
+    #[test]
+    fn test_render_rule_file() -> Result<()> {
+        let rule = UniversalRule {
+            name: "rust".to_string(),
+            content: "Use clippy.".to_string(),
+            frontmatter: UniversalRuleFrontmatter {
+                description: Some("Rust rules".to_string()),
+                globs: Some(vec!["*.rs".to_string()]),
+                activation: Some(Activation::Glob),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            render_rule_file(&rule)?,
+            "---\ndescription: Rust rules\nglobs:\n- '*.rs'\nactivation: glob\n---\nUse clippy.\n"
+        );
+        Ok(())
+    }
+
+    /// Test that rules without recovered metadata are written without a frontmatter block.
+    #[test]
+    fn test_render_rule_file_without_frontmatter() -> Result<()> {
+        let rule = UniversalRule {
+            name: "plain".to_string(),
+            content: "Be concise.".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(render_rule_file(&rule)?, "Be concise.\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,264 @@
+// src/importers/windsurf.rs
+
+use super::{split_globs, RuleImporter};
+use crate::converters::windsurf::LEGACY_RULES_FILE;
+use crate::converters::RULE_SEPARATOR;
+use crate::rule_parser::split_frontmatter;
+use crate::universal_rule::{Activation, UniversalRule, UniversalRuleFrontmatter};
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The file holding the rules Windsurf applies to every workspace.
+pub const GLOBAL_RULES_FILE: &str = "global_rules.md";
+
+/// Splits the `# Description:` and `# Globs:` comment headers written by the
+/// `WindsurfConverter` off the start of a rule, returning the frontmatter they describe
+/// and the remaining content.
+fn parse_comment_headers(text: &str) -> (UniversalRuleFrontmatter, &str) {
+    let mut frontmatter = UniversalRuleFrontmatter::default();
+    let mut rest = text.trim();
+    loop {
+        let (line, remainder) = rest.split_once('\n').unwrap_or((rest, ""));
+        if let Some(description) = line.strip_prefix("# Description:") {
+            frontmatter.description = Some(description.trim().to_string());
+        } else if let Some(globs) = line.strip_prefix("# Globs:") {
+            let globs = globs.trim();
+            // The converter writes globs as a quoted list, e.g. `["*.rs", "*.toml"]`.
+            let globs = serde_json::from_str::<Vec<String>>(globs)
+                .unwrap_or_else(|_| split_globs(globs.trim_matches(['[', ']'])));
+            frontmatter.globs = Some(globs).filter(|g| !g.is_empty());
+        } else {
+            break;
+        }
+        rest = remainder;
+    }
+    (frontmatter, rest.trim())
+}
+
+/// Splits the Windsurf frontmatter (`trigger`, `description` and `globs`) off the start
+/// of a workspace rule, falling back to `parse_comment_headers` for files without it.
+///
+/// The frontmatter is read line by line rather than as YAML, since Windsurf writes
+/// globs unquoted (e.g. `globs: *.ts, *.tsx`).
+fn parse_workspace_rule(text: &str) -> (UniversalRuleFrontmatter, &str) {
+    let (frontmatter_block, body) = split_frontmatter(text);
+    if frontmatter_block.is_empty() {
+        return parse_comment_headers(text);
+    }
+    let mut frontmatter = UniversalRuleFrontmatter::default();
+    for line in frontmatter_block.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "trigger" => {
+                frontmatter.activation = match value {
+                    "glob" => Some(Activation::Glob),
+                    "model_decision" => Some(Activation::AgentDecision),
+                    "manual" => Some(Activation::Manual),
+                    // `always_on` is the default of workspace rules
+                    _ => None,
+                }
+            }
+            "description" if !value.is_empty() => {
+                frontmatter.description = Some(value.to_string());
+            }
+            "globs" => frontmatter.globs = Some(split_globs(value)).filter(|g| !g.is_empty()),
+            _ => {}
+        }
+    }
+    (frontmatter, body)
+}
+
+/// Splits a concatenated rules file into its rules, named `<prefix>` if the file holds
+/// a single rule and `<prefix>_1`, `<prefix>_2`, ... otherwise.
+fn split_concatenated_rules(content: &str, prefix: &str) -> Vec<UniversalRule> {
+    let sections: Vec<&str> = content
+        .split(RULE_SEPARATOR)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect();
+    let single = sections.len() == 1;
+    sections
+        .into_iter()
+        .enumerate()
+        .map(|(i, section)| {
+            let (frontmatter, body) = parse_comment_headers(section);
+            UniversalRule {
+                name: if single {
+                    prefix.to_string()
+                } else {
+                    format!("{}_{}", prefix, i + 1)
+                },
+                frontmatter,
+                content: body.to_string(),
+                relative_dir: PathBuf::new(),
+            }
+        })
+        .collect()
+}
+
+/// Reads a file if it exists, returning `None` otherwise.
+fn read_if_exists(path: &Path) -> Result<Option<String>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    fs::read_to_string(path)
+        .map(Some)
+        .with_context(|| format!("Failed to read {:?}", path))
+}
+
+/// A `RuleImporter` implementation reading Windsurf rules.
+///
+/// Each `.windsurf/rules/*.md` file becomes a workspace rule named after the file, and
+/// `global_rules.md` is split on its rule separators into global rules. Rules in a legacy
+/// `.windsurfrules` file are imported as workspace rules. The names of concatenated rules
+/// are not recorded by Windsurf, so they are numbered after their file.
+pub struct WindsurfImporter;
+
+impl RuleImporter for WindsurfImporter {
+    fn import_rules(&self, input_dir: &Path) -> Result<Vec<UniversalRule>> {
+        let mut rules = Vec::new();
+
+        if let Some(content) = read_if_exists(&input_dir.join(GLOBAL_RULES_FILE))? {
+            for mut rule in split_concatenated_rules(&content, "global_rules") {
+                rule.frontmatter.activation = Some(Activation::Always);
+                rules.push(rule);
+            }
+        }
+
+        let workspace_rules_dir = input_dir.join(".windsurf").join("rules");
+        if workspace_rules_dir.is_dir() {
+            let mut paths: Vec<PathBuf> = fs::read_dir(&workspace_rules_dir)
+                .with_context(|| format!("Failed to read {:?}", workspace_rules_dir))?
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && p.extension().and_then(|s| s.to_str()) == Some("md"))
+                .collect();
+            paths.sort();
+            for path in paths {
+                let content = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {:?}", path))?;
+                let name = path
+                    .file_stem()
+                    .ok_or_else(|| anyhow!("Failed to get file stem for {:?}", path))?
+                    .to_string_lossy()
+                    .into_owned();
+                let (frontmatter, body) = parse_workspace_rule(&content);
+                rules.push(UniversalRule {
+                    name,
+                    frontmatter,
+                    content: body.to_string(),
+                    relative_dir: PathBuf::new(),
+                });
+            }
+        }
+
+        if let Some(content) = read_if_exists(&input_dir.join(LEGACY_RULES_FILE))? {
+            rules.extend(split_concatenated_rules(&content, "windsurfrules"));
+        }
+
+        Ok(rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::converters::windsurf::WindsurfConverter;
+    use crate::converters::write_rules;
+    use tempfile::tempdir;
+
+    /// Test that rules generated by the `WindsurfConverter` are imported back with their
+    /// description, globs and global scope.
+    #[test]
+    fn test_import_windsurf_rules_round_trip() -> Result<()> {
+        let dir = tempdir()?;
+        let rules = vec![
+            UniversalRule {
+                name: "style".to_string(),
+                content: "Be concise.".to_string(),
+                frontmatter: UniversalRuleFrontmatter {
+                    description: Some("Style".to_string()),
+                    apply_globally: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            UniversalRule {
+                name: "tone".to_string(),
+                content: "Be friendly.".to_string(),
+                frontmatter: UniversalRuleFrontmatter {
+                    apply_globally: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            UniversalRule {
+                name: "rust".to_string(),
+                content: "# Rust\n\nUse clippy.".to_string(),
+                frontmatter: UniversalRuleFrontmatter {
+                    description: Some("Rust rules".to_string()),
+                    globs: Some(vec!["*.rs".to_string(), "Cargo.toml".to_string()]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ];
+        write_rules(&WindsurfConverter::default(), &rules, dir.path())?;
+
+        let imported = WindsurfImporter.import_rules(dir.path())?;
+
+        assert_eq!(imported.len(), 3);
+        assert_eq!(imported[0].name, "global_rules_1");
+        assert_eq!(imported[0].frontmatter.activation, Some(Activation::Always));
+        assert_eq!(
+            imported[0].frontmatter.description.as_deref(),
+            Some("Style")
+        );
+        assert_eq!(imported[0].content, "Be concise.");
+        assert_eq!(imported[1].name, "global_rules_2");
+        assert_eq!(imported[1].content, "Be friendly.");
+        assert_eq!(imported[2].name, "rust");
+        assert_eq!(imported[2].frontmatter.activation, None);
+        assert_eq!(
+            imported[2].frontmatter.description.as_deref(),
+            Some("Rust rules")
+        );
+        assert_eq!(
+            imported[2].frontmatter.globs,
+            Some(vec!["*.rs".to_string(), "Cargo.toml".to_string()])
+        );
+        assert_eq!(imported[2].content, "# Rust\n\nUse clippy.");
+        Ok(())
+    }
+
+    /// Test that Windsurf's `trigger` frontmatter is imported as the rule's `activation`.
+    #[test]
+    fn test_parse_workspace_rule_trigger() {
+        let (frontmatter, body) = parse_workspace_rule(
+            "---\ntrigger: glob\ndescription:\nglobs: *.ts, *.tsx\n---\n\nUse strict mode.\n",
+        );
+        assert_eq!(frontmatter.activation, Some(Activation::Glob));
+        assert_eq!(frontmatter.description, None);
+        assert_eq!(
+            frontmatter.globs,
+            Some(vec!["*.ts".to_string(), "*.tsx".to_string()])
+        );
+        assert_eq!(body, "Use strict mode.");
+
+        let (frontmatter, _) = parse_workspace_rule("---\ntrigger: always_on\n---\nBe concise.");
+        assert_eq!(frontmatter.activation, None);
+    }
+
+    /// Test that files without comment headers keep their whole content.
+    #[test]
+    fn test_parse_comment_headers_without_headers() {
+        let (frontmatter, body) = parse_comment_headers("# Title\n\nText.\n");
+        assert!(frontmatter.description.is_none());
+        assert!(frontmatter.globs.is_none());
+        assert_eq!(body, "# Title\n\nText.");
+    }
+}
@@ -0,0 +1,87 @@
+// src/interactive.rs
+
+use crate::converters::registry::available_agents;
+use crate::exit_code::UsageError;
+use crate::universal_rule::UniversalRule;
+use crate::AgentName;
+use anyhow::{bail, Context, Result};
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::MultiSelect;
+use std::io::IsTerminal;
+
+/// Fails unless the pickers can be shown, i.e. stdin and stderr are terminals.
+pub fn ensure_terminal() -> Result<()> {
+    if !std::io::stdin().is_terminal() || !std::io::stderr().is_terminal() {
+        bail!(UsageError("--interactive requires a terminal".to_string()));
+    }
+    Ok(())
+}
+
+/// The agents offered by the agent picker: every agent except `all`, and `custom` only
+/// when a custom converter definition was given.
+fn agent_choices(has_custom_converter: bool) -> Vec<AgentName> {
+    available_agents(has_custom_converter)
+}
+
+/// Shows a checkbox list of `items` with the `checked` ones selected, returning the
+/// indices picked by the user. Cancelling the picker is an error.
+fn pick(prompt: &str, items: &[String], checked: &[bool]) -> Result<Vec<usize>> {
+    MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .items(items)
+        .defaults(checked)
+        .interact_opt()
+        .context("Failed to show the interactive picker")?
+        .context("Selection cancelled")
+}
+
+/// Lets the user pick the agents to generate, starting from the agents selected by
+/// `--agent` or the project configuration.
+pub fn select_agents(
+    preselected: &[AgentName],
+    has_custom_converter: bool,
+) -> Result<Vec<AgentName>> {
+    let choices = agent_choices(has_custom_converter);
+    let items: Vec<String> = choices.iter().map(|agent| agent.to_string()).collect();
+    let checked: Vec<bool> = choices.iter().map(|a| preselected.contains(a)).collect();
+    let picked = pick("Agents to generate", &items, &checked)?;
+    Ok(picked.into_iter().map(|i| choices[i].clone()).collect())
+}
+
+/// Lets the user pick the rules to emit; every rule starts selected. Returns the picked
+/// rules and the deselected ones.
+pub fn select_rules(rules: Vec<UniversalRule>) -> Result<(Vec<UniversalRule>, Vec<UniversalRule>)> {
+    let items: Vec<String> = rules
+        .iter()
+        .map(|rule| match &rule.frontmatter.description {
+            Some(description) => format!("{} - {}", rule.name, description),
+            None => rule.name.clone(),
+        })
+        .collect();
+    let picked = pick("Rules to emit", &items, &vec![true; rules.len()])?;
+    let (picked, deselected): (Vec<_>, Vec<_>) = rules
+        .into_iter()
+        .enumerate()
+        .partition(|(i, _)| picked.contains(i));
+    Ok((
+        picked.into_iter().map(|(_, rule)| rule).collect(),
+        deselected.into_iter().map(|(_, rule)| rule).collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that `all` is never offered and `custom` only with a converter definition.
+    #[test]
+    fn test_agent_choices() {
+        let choices = agent_choices(false);
+        assert!(choices.contains(&AgentName::Cursor));
+        assert!(choices.contains(&AgentName::Html));
+        assert!(!choices.contains(&AgentName::All));
+        assert!(!choices.contains(&AgentName::Custom));
+
+        assert!(agent_choices(true).contains(&AgentName::Custom));
+    }
+}
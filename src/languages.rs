@@ -0,0 +1,115 @@
+// src/languages.rs
+
+use crate::universal_rule::UniversalRule;
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+
+/// The globs of the languages rules can list in `languages`, unless the `[languages]`
+/// table of the project configuration overrides them.
+const BUILTIN_LANGUAGES: &[(&str, &[&str])] = &[
+    ("c", &["**/*.c", "**/*.h"]),
+    (
+        "cpp",
+        &["**/*.cpp", "**/*.cc", "**/*.cxx", "**/*.hpp", "**/*.hh"],
+    ),
+    ("csharp", &["**/*.cs"]),
+    ("css", &["**/*.css", "**/*.scss"]),
+    ("go", &["**/*.go"]),
+    ("html", &["**/*.html", "**/*.htm"]),
+    ("java", &["**/*.java"]),
+    (
+        "javascript",
+        &["**/*.js", "**/*.jsx", "**/*.mjs", "**/*.cjs"],
+    ),
+    ("json", &["**/*.json"]),
+    ("kotlin", &["**/*.kt", "**/*.kts"]),
+    ("markdown", &["**/*.md"]),
+    ("php", &["**/*.php"]),
+    ("python", &["**/*.py", "**/*.pyi"]),
+    ("ruby", &["**/*.rb"]),
+    ("rust", &["**/*.rs"]),
+    ("scala", &["**/*.scala"]),
+    ("shell", &["**/*.sh", "**/*.bash"]),
+    ("sql", &["**/*.sql"]),
+    ("swift", &["**/*.swift"]),
+    ("toml", &["**/*.toml"]),
+    ("typescript", &["**/*.ts", "**/*.tsx"]),
+    ("yaml", &["**/*.yaml", "**/*.yml"]),
+];
+
+/// Returns the globs of `language` (case-insensitive), from `custom` or else the
+/// built-in table, or `None` if the language is unknown.
+pub fn language_globs(
+    language: &str,
+    custom: &BTreeMap<String, Vec<String>>,
+) -> Option<Vec<String>> {
+    let language = language.to_lowercase();
+    if let Some(globs) = custom.get(&language) {
+        return Some(globs.clone());
+    }
+    BUILTIN_LANGUAGES
+        .iter()
+        .find(|(name, _)| *name == language)
+        .map(|(_, globs)| globs.iter().map(|g| g.to_string()).collect())
+}
+
+/// Appends the globs of the rule's `languages` to its `globs`, skipping the ones it
+/// already has. Fails if a language is neither built in nor in `custom`.
+pub fn expand_languages(
+    rule: &mut UniversalRule,
+    custom: &BTreeMap<String, Vec<String>>,
+) -> Result<()> {
+    let Some(languages) = &rule.frontmatter.languages else {
+        return Ok(());
+    };
+    let mut globs = rule.frontmatter.globs.clone().unwrap_or_default();
+    for language in languages {
+        let Some(language_globs) = language_globs(language, custom) else {
+            bail!(
+                "Unknown language `{}` in `languages`; add its globs to the [languages] table of the project configuration",
+                language
+            );
+        };
+        for glob in language_globs {
+            if !globs.contains(&glob) {
+                globs.push(glob);
+            }
+        }
+    }
+    rule.frontmatter.globs = Some(globs).filter(|g| !g.is_empty());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universal_rule::UniversalRuleFrontmatter;
+
+    /// Test that languages expand to their globs, with configured ones taking precedence.
+    #[test]
+    fn test_expand_languages() {
+        let mut rule = UniversalRule {
+            name: "style".to_string(),
+            frontmatter: UniversalRuleFrontmatter {
+                globs: Some(vec!["**/*.rs".to_string(), "build.rs".to_string()]),
+                languages: Some(vec!["Rust".to_string(), "typescript".to_string()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let custom = BTreeMap::from([("typescript".to_string(), vec!["web/**/*.ts".to_string()])]);
+        expand_languages(&mut rule, &custom).unwrap();
+        assert_eq!(
+            rule.frontmatter.globs,
+            Some(vec![
+                "**/*.rs".to_string(),
+                "build.rs".to_string(),
+                "web/**/*.ts".to_string()
+            ])
+        );
+
+        rule.frontmatter.languages = Some(vec!["cobol".to_string()]);
+        let error = expand_languages(&mut rule, &custom).unwrap_err();
+        assert!(error.to_string().contains("Unknown language `cobol`"));
+    }
+}
@@ -0,0 +1,116 @@
+// src/lib.rs
+
+//! Converts universal rule files (Markdown with optional YAML frontmatter) into the rule
+//! formats of AI coding agents.
+//!
+//! The `urules` binary is a thin wrapper around `cli::main`; other tools can embed the
+//! conversion instead of running it: `rule_parser` discovers and parses the rules,
+//! `universal_rule` holds their schema, `converters` generates the files of an agent,
+//! `dry_run` writes them, and `gitignore_manager` keeps the outputs out of version
+//! control.
+
+pub mod assets;
+pub mod backup;
+pub mod banner;
+pub mod cli;
+pub mod color;
+pub mod commands;
+pub mod config;
+pub mod converters; // New module for all converters
+pub mod dry_run;
+pub mod exit_code;
+pub mod git;
+pub mod gitignore_manager;
+pub mod ignore_files;
+pub mod importers;
+pub mod interactive;
+pub mod languages;
+pub mod line_endings;
+pub mod manifest;
+pub mod minify;
+pub mod normalize;
+pub mod report;
+pub mod rule_parser;
+pub mod secrets;
+pub mod template;
+pub mod tokens;
+pub mod universal_rule;
+
+use crate::universal_rule::UniversalRule;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(ValueEnum, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")] // Matches the CLI spelling, e.g. `agents: [cursor]`
+/// Specifies the target AI agent for rule generation.
+pub enum AgentName {
+    // Made AgentName public
+    /// Rules for Cursor.ai.
+    Cursor,
+    /// Rules for Windsurf.
+    Windsurf,
+    /// Rules for Claude (concatenated into a single file).
+    Claude,
+    /// Rules for GitHub Copilot (`.github/copilot-instructions.md`).
+    Copilot,
+    /// Rules for Zed (concatenated into a `.rules` file).
+    Zed,
+    /// Rules for Goose (concatenated into a `.goosehints` file).
+    Goose,
+    /// Rules for Warp (concatenated into a `WARP.md` file).
+    Warp,
+    /// Rules as Devin knowledge entries (`devin_knowledge/<rule>.md`).
+    Devin,
+    /// The parsed rules as JSON (`rules.json`), for downstream tooling.
+    Json,
+    /// A static HTML page (`rules.html`) for reviewing the rules in a browser.
+    Html,
+    /// Rules for any other agent, using templates from `--custom-converter`.
+    Custom,
+    /// Every built-in coding agent in one run (plus `custom` with `--custom-converter`).
+    /// Only valid on the command line, not in a rule's `agents` list.
+    #[serde(skip)]
+    All,
+}
+
+impl AgentName {
+    /// Returns `true` for targets that export the whole rule set (JSON, HTML preview)
+    /// rather than configure a single agent, and therefore ignore rules' `agents` lists.
+    pub fn is_export(&self) -> bool {
+        matches!(self, AgentName::Json | AgentName::Html)
+    }
+}
+
+// No changes needed for Display impl
+impl std::fmt::Display for AgentName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AgentName::Cursor => write!(f, "Cursor"),
+            AgentName::Windsurf => write!(f, "Windsurf"),
+            AgentName::Claude => write!(f, "Claude"),
+            AgentName::Copilot => write!(f, "Copilot"),
+            AgentName::Zed => write!(f, "Zed"),
+            AgentName::Goose => write!(f, "Goose"),
+            AgentName::Warp => write!(f, "Warp"),
+            AgentName::Devin => write!(f, "Devin"),
+            AgentName::Json => write!(f, "JSON"),
+            AgentName::Html => write!(f, "HTML"),
+            AgentName::Custom => write!(f, "Custom"),
+            AgentName::All => write!(f, "All"),
+        }
+    }
+}
+
+/// Returns why `rule` is not emitted for `agent`, or `None` if it is, mirroring the
+/// filtering of `cli::generate_agent`.
+fn skipped_rule_reason(agent: &AgentName, rule: &UniversalRule) -> Option<&'static str> {
+    if agent.is_export() {
+        None
+    } else if !rule.applies_to(agent) {
+        Some("not targeted at this agent by its `agents` list")
+    } else if rule.is_ignore_only() {
+        Some("only declares `ignore_patterns`")
+    } else {
+        None
+    }
+}
@@ -0,0 +1,69 @@
+// src/line_endings.rs
+
+use clap::ValueEnum;
+use serde::Deserialize;
+
+/// The line endings of the generated files.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEndings {
+    /// `\n`, as on Linux and macOS.
+    #[default]
+    Lf,
+    /// `\r\n`, as on Windows.
+    Crlf,
+    /// The line endings of the platform urules runs on.
+    Native,
+}
+
+impl LineEndings {
+    /// Returns `true` if these are `\r\n` line endings on the current platform, which
+    /// the generated files must be converted to.
+    pub fn is_crlf(self) -> bool {
+        match self {
+            LineEndings::Lf => false,
+            LineEndings::Crlf => true,
+            LineEndings::Native => cfg!(windows),
+        }
+    }
+
+    /// Returns `text` with its line endings, whatever they are, converted to these.
+    pub fn apply(self, text: &str) -> String {
+        let text = normalize_line_endings(text);
+        match self.is_crlf() {
+            true => text.replace('\n', "\r\n"),
+            false => text,
+        }
+    }
+}
+
+/// Returns `text` with its `\r\n` and lone `\r` line endings replaced with `\n`, so that
+/// rule files saved on Windows read the same as the others.
+pub fn normalize_line_endings(text: &str) -> String {
+    if !text.contains('\r') {
+        return text.to_string();
+    }
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that mixed line endings are made consistent.
+    #[test]
+    fn test_line_endings() {
+        let text = "---\r\nname: api\r\n---\nUse REST.\rNo SOAP.\r\n";
+        assert_eq!(
+            normalize_line_endings(text),
+            "---\nname: api\n---\nUse REST.\nNo SOAP.\n"
+        );
+        assert_eq!(
+            LineEndings::Crlf.apply(text),
+            "---\r\nname: api\r\n---\r\nUse REST.\r\nNo SOAP.\r\n"
+        );
+        assert_eq!(LineEndings::Lf.apply("a\r\nb"), "a\nb");
+        assert!(!LineEndings::Lf.is_crlf());
+        assert_eq!(LineEndings::Native.is_crlf(), cfg!(windows));
+    }
+}
@@ -0,0 +1,394 @@
+// src/manifest.rs
+
+use crate::converters::registry::coding_agents;
+use crate::dry_run::{ChangeKind, PlannedChange, MERGED_OUTPUTS};
+use crate::ignore_files::ignore_files;
+use crate::universal_rule::UniversalRule;
+use crate::AgentName;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The file in the output directory recording the hashes of the generated files.
+pub const MANIFEST_FILE: &str = ".urules-manifest.json";
+
+/// The hashes of the files urules generated in an output directory, used to detect
+/// files that were edited by hand since the last generation.
+///
+/// Outputs that are merged with their existing content (`.gitignore`, ignore files,
+/// settings) are not recorded, since regenerating them keeps hand edits.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct Manifest {
+    /// The SHA-256 hash of each generated file, keyed by its path relative to the output
+    /// directory.
+    pub files: BTreeMap<String, String>,
+
+    /// The SHA-256 hash of each rule generated for each agent, keyed by the rule's path
+    /// relative to the rules directory, to find the rules changed since (`--changed-only`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rules: BTreeMap<AgentName, BTreeMap<String, String>>,
+
+    /// The files last generated for each agent, keyed by their path relative to the
+    /// output directory, with the rules producing them, to remove the outputs of rules
+    /// that were deleted since.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub outputs: BTreeMap<AgentName, BTreeMap<String, BTreeSet<String>>>,
+}
+
+/// Returns the hex-encoded SHA-256 hash of `content`.
+fn hash(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Returns the hash of everything converters read from `rule`: its name, location,
+/// frontmatter and content.
+fn rule_hash(rule: &UniversalRule) -> String {
+    // Serializing a rule cannot fail, since all its map keys are strings
+    hash(&serde_json::to_vec(rule).unwrap_or_default())
+}
+
+/// Returns the manifest key of `rule`, its path relative to the rules directory.
+fn rule_key(rule: &UniversalRule) -> String {
+    rule.relative_path().to_string_lossy().replace('\\', "/")
+}
+
+/// Returns `true` for outputs that are merged with their existing content.
+fn is_merged_output(relative_path: &str) -> bool {
+    MERGED_OUTPUTS.contains(&relative_path)
+        || coding_agents()
+            .iter()
+            .flat_map(ignore_files)
+            .any(|file| file == relative_path)
+}
+
+/// Returns the path of `path` relative to `output_dir` with `/` separators, or `None` if
+/// it is outside `output_dir`.
+fn relative_key(output_dir: &Path, path: &Path) -> Option<String> {
+    let relative_path = path.strip_prefix(output_dir).ok()?;
+    // Use `/` separators so the manifest is the same on every platform
+    Some(
+        relative_path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
+
+/// Returns the manifest key of `path`, or `None` if it is outside `output_dir` or is a
+/// merged output.
+fn manifest_key(output_dir: &Path, path: &Path) -> Option<String> {
+    relative_key(output_dir, path).filter(|key| !is_merged_output(key))
+}
+
+/// Returns `true` if `path` is an output of `output_dir` that is merged with its existing
+/// content (`.gitignore`, ignore files, settings), and so is never stale.
+pub fn is_merged_path(output_dir: &Path, path: &Path) -> bool {
+    relative_key(output_dir, path).is_some_and(|key| is_merged_output(&key))
+}
+
+impl Manifest {
+    /// Loads the manifest of `output_dir`, or an empty one if there is none yet.
+    pub fn load(output_dir: &Path) -> Result<Self> {
+        let path = output_dir.join(MANIFEST_FILE);
+        if !path.is_file() {
+            return Ok(Manifest::default());
+        }
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {:?}", path))
+    }
+
+    /// Writes the manifest to `output_dir`.
+    pub fn save(&self, output_dir: &Path) -> Result<()> {
+        let path = output_dir.join(MANIFEST_FILE);
+        let content =
+            serde_json::to_string_pretty(self).context("Failed to serialize manifest")? + "\n";
+        fs::write(&path, content).with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    /// Returns the files that the planned `changes` would overwrite or remove although
+    /// they were modified since the last generation, i.e. whose content no longer matches
+    /// their recorded hash. Files that are not in the manifest are never reported.
+    pub fn modified_files(
+        &self,
+        output_dir: &Path,
+        changes: &[PlannedChange],
+    ) -> Result<Vec<PathBuf>> {
+        let mut modified = Vec::new();
+        for change in changes.iter().filter(|c| c.kind != ChangeKind::Create) {
+            let Some(recorded_hash) =
+                manifest_key(output_dir, &change.path).and_then(|key| self.files.get(&key))
+            else {
+                continue;
+            };
+            let content = fs::read(&change.path)
+                .with_context(|| format!("Failed to read {:?}", change.path))?;
+            if hash(&content) != *recorded_hash {
+                modified.push(change.path.clone());
+            }
+        }
+        Ok(modified)
+    }
+
+    /// Returns `true` if `content` is what urules generated for `path` in its last run,
+    /// according to the recorded hash.
+    pub fn is_recorded(&self, output_dir: &Path, path: &Path, content: &[u8]) -> bool {
+        manifest_key(output_dir, path)
+            .and_then(|key| self.files.get(&key))
+            .is_some_and(|recorded_hash| *recorded_hash == hash(content))
+    }
+
+    /// Records `rules` as the rules last generated for `agent`.
+    pub fn record_rules(&mut self, agent: &AgentName, rules: &[UniversalRule]) {
+        let hashes = rules
+            .iter()
+            .map(|rule| (rule_key(rule), rule_hash(rule)))
+            .collect();
+        self.rules.insert(agent.clone(), hashes);
+    }
+
+    /// Returns the `rules` that changed since they were last generated for `agent`:
+    /// new rules and rules whose frontmatter or content differs.
+    ///
+    /// Every rule is returned if none were recorded for `agent`, or if a recorded rule
+    /// is gone, since its removal changes the outputs shared with other rules.
+    pub fn changed_rules(&self, agent: &AgentName, rules: &[UniversalRule]) -> Vec<UniversalRule> {
+        let Some(recorded) = self.rules.get(agent) else {
+            return rules.to_vec();
+        };
+        let keys: Vec<String> = rules.iter().map(rule_key).collect();
+        if recorded.keys().any(|key| !keys.contains(key)) {
+            return rules.to_vec();
+        }
+        rules
+            .iter()
+            .filter(|rule| recorded.get(&rule_key(rule)) != Some(&rule_hash(rule)))
+            .cloned()
+            .collect()
+    }
+
+    /// Records `outputs` as the files last generated for `agent` in `output_dir`, each
+    /// with the rules producing it. Merged outputs are left out, since they are never
+    /// removed.
+    ///
+    /// Files recorded before that still exist stay recorded: those no longer generated
+    /// were kept because they were edited by hand, and `--force` removes them later.
+    pub fn record_outputs(
+        &mut self,
+        output_dir: &Path,
+        agent: &AgentName,
+        outputs: &[(PathBuf, Vec<&UniversalRule>)],
+    ) {
+        let recorded = self.outputs.entry(agent.clone()).or_default();
+        recorded.retain(|key, _| output_dir.join(key).is_file());
+        for (path, rules) in outputs {
+            if let Some(key) = manifest_key(output_dir, path) {
+                recorded.insert(key, rules.iter().map(|rule| rule_key(rule)).collect());
+            }
+        }
+    }
+
+    /// Returns the paths of the files last generated for `agent` in `output_dir` whose
+    /// rules no longer exist in `rules_dir`. Files produced by no single rule are never
+    /// returned.
+    pub fn removed_rule_outputs(
+        &self,
+        output_dir: &Path,
+        rules_dir: &Path,
+        agent: &AgentName,
+    ) -> Vec<PathBuf> {
+        self.outputs
+            .get(agent)
+            .into_iter()
+            .flatten()
+            .filter(|(_, rules)| {
+                !rules.is_empty() && rules.iter().all(|rule| !rules_dir.join(rule).is_file())
+            })
+            .map(|(key, _)| output_dir.join(key))
+            .collect()
+    }
+
+    /// Returns `true` if `path` is recorded as a file generated for some agent in
+    /// `output_dir`, which regenerating removes once its rules are deleted.
+    pub fn records_output(&self, output_dir: &Path, path: &Path) -> bool {
+        manifest_key(output_dir, path)
+            .is_some_and(|key| self.outputs.values().any(|keys| keys.contains_key(&key)))
+    }
+
+    /// Records the current content of the files written by the planned `changes`, and
+    /// forgets files that no longer exist.
+    pub fn record(&mut self, output_dir: &Path, changes: &[PlannedChange]) -> Result<()> {
+        for change in changes {
+            let Some(key) = manifest_key(output_dir, &change.path) else {
+                continue;
+            };
+            if change.path.is_file() {
+                let content = fs::read(&change.path)
+                    .with_context(|| format!("Failed to read {:?}", change.path))?;
+                self.files.insert(key, hash(&content));
+            }
+        }
+        self.files.retain(|key, _| output_dir.join(key).is_file());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn change(path: PathBuf, kind: ChangeKind) -> PlannedChange {
+        PlannedChange {
+            path,
+            kind,
+            diff: None,
+        }
+    }
+
+    /// Test that only recorded files edited since they were generated are reported.
+    #[test]
+    fn test_modified_files() -> Result<()> {
+        let dir = tempdir()?;
+        let claude_path = dir.path().join("CLAUDE.md");
+        let warp_path = dir.path().join("WARP.md");
+        let gitignore_path = dir.path().join(".gitignore");
+        fs::write(&claude_path, "Generated.\n")?;
+        fs::write(&warp_path, "Generated.\n")?;
+        fs::write(&gitignore_path, "target/\n")?;
+
+        let mut manifest = Manifest::default();
+        let created = vec![
+            change(claude_path.clone(), ChangeKind::Create),
+            change(warp_path.clone(), ChangeKind::Create),
+            change(gitignore_path.clone(), ChangeKind::Create),
+        ];
+        manifest.record(dir.path(), &created)?;
+        manifest.save(dir.path())?;
+        let manifest = Manifest::load(dir.path())?;
+        assert_eq!(
+            manifest.files.keys().collect::<Vec<_>>(),
+            vec!["CLAUDE.md", "WARP.md"]
+        );
+
+        fs::write(&claude_path, "Generated.\n\nHand edit.\n")?;
+        fs::write(&gitignore_path, "target/\nnode_modules/\n")?;
+        let updates = vec![
+            change(claude_path.clone(), ChangeKind::Update),
+            change(warp_path.clone(), ChangeKind::Update),
+            change(gitignore_path, ChangeKind::Update),
+            change(dir.path().join("unknown.md"), ChangeKind::Update),
+        ];
+
+        assert_eq!(
+            manifest.modified_files(dir.path(), &updates)?,
+            vec![claude_path.clone()]
+        );
+        let deletions = vec![
+            change(claude_path.clone(), ChangeKind::Delete),
+            change(warp_path.clone(), ChangeKind::Delete),
+        ];
+        assert_eq!(
+            manifest.modified_files(dir.path(), &deletions)?,
+            vec![claude_path.clone()]
+        );
+        assert!(manifest.is_recorded(dir.path(), &warp_path, b"Generated.\n"));
+        assert!(!manifest.is_recorded(dir.path(), &claude_path, b"Edited.\n"));
+        Ok(())
+    }
+
+    /// Test that only the outputs whose rules are all gone from the rules directory are
+    /// returned for removal.
+    #[test]
+    fn test_removed_rule_outputs() -> Result<()> {
+        let dir = tempdir()?;
+        let rules_dir = dir.path().join(".rules");
+        fs::create_dir_all(rules_dir.join("backend"))?;
+        fs::write(rules_dir.join("style.md"), "Be terse.\n")?;
+        let rule = |name: &str, relative_dir: &str| UniversalRule {
+            name: name.to_string(),
+            relative_dir: PathBuf::from(relative_dir),
+            ..Default::default()
+        };
+        let style = rule("style", "");
+        let api = rule("api", "backend");
+        let out = |file: &str| dir.path().join(file);
+
+        let mut manifest = Manifest::default();
+        manifest.record_outputs(
+            dir.path(),
+            &AgentName::Cursor,
+            &[
+                (out(".cursor/rules/style.mdc"), vec![&style]),
+                (out(".cursor/rules/api.mdc"), vec![&api]),
+                (out(".cursor/rules/shared.mdc"), vec![&style, &api]),
+                (out(".cursor/rules/index.mdc"), vec![]),
+                (out(".gitignore"), vec![&api]),
+            ],
+        );
+        manifest.save(dir.path())?;
+        let manifest = Manifest::load(dir.path())?;
+
+        assert_eq!(
+            manifest.removed_rule_outputs(dir.path(), &rules_dir, &AgentName::Cursor),
+            vec![out(".cursor/rules/api.mdc")]
+        );
+        assert!(manifest
+            .removed_rule_outputs(dir.path(), &rules_dir, &AgentName::Claude)
+            .is_empty());
+        assert!(manifest.records_output(dir.path(), &out(".cursor/rules/index.mdc")));
+        assert!(!manifest.records_output(dir.path(), &out(".gitignore")));
+        Ok(())
+    }
+
+    /// Test that only new and edited rules are changed, unless a rule was removed.
+    #[test]
+    fn test_changed_rules() -> Result<()> {
+        let rule = |name: &str, content: &str| UniversalRule {
+            name: name.to_string(),
+            content: content.to_string(),
+            ..Default::default()
+        };
+        let names = |rules: Vec<UniversalRule>| -> Vec<String> {
+            rules.into_iter().map(|r| r.name).collect()
+        };
+        let rules = vec![rule("rust", "Use rustfmt."), rule("style", "Be terse.")];
+        let mut manifest = Manifest::default();
+        assert_eq!(
+            names(manifest.changed_rules(&AgentName::Cursor, &rules)),
+            vec!["rust", "style"]
+        );
+
+        manifest.record_rules(&AgentName::Cursor, &rules);
+        let dir = tempdir()?;
+        manifest.save(dir.path())?;
+        let manifest = Manifest::load(dir.path())?;
+        assert!(manifest
+            .changed_rules(&AgentName::Cursor, &rules)
+            .is_empty());
+        assert_eq!(manifest.changed_rules(&AgentName::Claude, &rules).len(), 2);
+
+        let edited = vec![
+            rule("rust", "Use rustfmt and clippy."),
+            rule("style", "Be terse."),
+            rule("testing", "Write tests."),
+        ];
+        assert_eq!(
+            names(manifest.changed_rules(&AgentName::Cursor, &edited)),
+            vec!["rust", "testing"]
+        );
+        let removed = vec![rule("rust", "Use rustfmt.")];
+        assert_eq!(
+            manifest.changed_rules(&AgentName::Cursor, &removed).len(),
+            1
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+// src/minify.rs
+
+use std::path::Path;
+
+/// The names of the generated Markdown files that have no `.md` or `.mdc` extension.
+const MARKDOWN_FILE_NAMES: &[&str] = &[".cursorrules", ".windsurfrules", ".rules", ".goosehints"];
+
+/// Returns `true` if `line` is a Markdown thematic break (`---`, `***`, `___`, possibly
+/// with spaces between the characters).
+fn is_thematic_break(line: &str) -> bool {
+    let chars: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    chars.len() >= 3 && ['-', '*', '_'].iter().any(|m| chars.iter().all(|c| c == m))
+}
+
+/// Removes the HTML comments of `line`. `in_comment` tells whether the line starts
+/// inside a comment opened on an earlier line, and is updated for the next line.
+fn strip_comments(line: &str, in_comment: &mut bool) -> String {
+    let mut result = String::new();
+    let mut rest = line;
+    loop {
+        if *in_comment {
+            match rest.find("-->") {
+                Some(end) => {
+                    rest = &rest[end + 3..];
+                    *in_comment = false;
+                }
+                None => return result,
+            }
+        }
+        match rest.find("<!--") {
+            Some(start) => {
+                result.push_str(&rest[..start]);
+                rest = &rest[start + 4..];
+                *in_comment = true;
+            }
+            None => {
+                result.push_str(rest);
+                return result;
+            }
+        }
+    }
+}
+
+/// Removes the HTML comments (`<!-- ... -->`) of `text`, dropping the lines that only
+/// held comments. Comments inside fenced code blocks and `<!-- urules:... -->` marker
+/// lines are kept.
+pub fn strip_html_comments(text: &str) -> String {
+    if !text.contains("<!--") {
+        return text.to_string();
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut in_fence = false;
+    let mut in_comment = false;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if !in_comment && (trimmed.starts_with("```") || trimmed.starts_with("~~~")) {
+            in_fence = !in_fence;
+        }
+        if in_fence || (!in_comment && trimmed.starts_with("<!-- urules:")) {
+            result.push_str(line);
+            continue;
+        }
+        let was_in_comment = in_comment;
+        let stripped = strip_comments(line.trim_end_matches(['\r', '\n']), &mut in_comment);
+        if stripped.len() == line.trim_end_matches(['\r', '\n']).len() && !was_in_comment {
+            result.push_str(line);
+        } else if !stripped.trim().is_empty() {
+            result.push_str(stripped.trim_end());
+            if line.ends_with('\n') {
+                result.push('\n');
+            }
+        }
+    }
+    result
+}
+
+/// Minifies Markdown to save context: removes HTML comments, the thematic breaks that
+/// only separate blocks and the trailing whitespace of lines, and collapses runs of blank
+/// lines into one.
+///
+/// A leading frontmatter block and fenced code blocks are kept as they are, as are `---`
+/// lines right below text, which underline a heading.
+pub fn minify_markdown(text: &str) -> String {
+    let (frontmatter, body) = match text.strip_prefix("---\n") {
+        Some(rest) => match rest.find("\n---\n") {
+            Some(end) => text.split_at(4 + end + 5),
+            None => ("", text),
+        },
+        None => ("", text),
+    };
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_fence = false;
+    let mut in_comment = false;
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if !in_comment && (trimmed.starts_with("```") || trimmed.starts_with("~~~")) {
+            in_fence = !in_fence;
+            lines.push(line.trim_end().to_string());
+            continue;
+        }
+        if in_fence {
+            lines.push(line.to_string());
+            continue;
+        }
+        let stripped = strip_comments(line, &mut in_comment);
+        let stripped = stripped.trim_end();
+        let previous_blank = lines.last().is_none_or(|last| last.is_empty());
+        if stripped.is_empty() {
+            // Lines holding only a comment disappear instead of leaving a blank line
+            if line.trim().is_empty() && !previous_blank {
+                lines.push(String::new());
+            }
+            continue;
+        }
+        if is_thematic_break(stripped) && previous_blank {
+            continue;
+        }
+        lines.push(stripped.to_string());
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+    if lines.is_empty() {
+        return frontmatter.to_string();
+    }
+    format!("{}{}\n", frontmatter, lines.join("\n"))
+}
+
+/// Returns `true` if `path` names a generated Markdown file: a `.md` or `.mdc` file, or
+/// one of the agent files without extension such as `.cursorrules`.
+pub fn is_markdown_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("md" | "mdc")
+    ) || path
+        .file_name()
+        .is_some_and(|name| MARKDOWN_FILE_NAMES.contains(&name.to_string_lossy().as_ref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that comments, separators and extra blank lines are dropped, but not the
+    /// frontmatter, code blocks or heading underlines.
+    #[test]
+    fn test_minify_markdown() {
+        let text = "---\ndescription: API\n---\n\n\n## Rule: Api  \n<!-- Owner: @web -->\n\nUse REST. <!-- for now -->\n\n\n\n---\n\n<!--\nTODO\n-->\nSetext\n---\n```\n<!-- kept -->\n\n\n```\n\n* * *\n";
+        assert_eq!(
+            minify_markdown(text),
+            "---\ndescription: API\n---\n## Rule: Api\n\nUse REST.\n\nSetext\n---\n```\n<!-- kept -->\n\n\n```\n"
+        );
+        assert_eq!(minify_markdown("<!-- only -->\n"), "");
+    }
+
+    /// Test that comments are removed with the lines holding only them, keeping code
+    /// blocks and agent markers.
+    #[test]
+    fn test_strip_html_comments() {
+        let text = "<!-- Author note -->\nUse tabs. <!-- why? -->\n<!--\nlonger\nnote -->\n<!-- urules:if agent == \"zed\" -->\nZed.\n<!-- urules:endif -->\n```html\n<!-- markup -->\n```\n";
+        assert_eq!(
+            strip_html_comments(text),
+            "Use tabs.\n<!-- urules:if agent == \"zed\" -->\nZed.\n<!-- urules:endif -->\n```html\n<!-- markup -->\n```\n"
+        );
+        assert_eq!(strip_html_comments("No comments."), "No comments.");
+    }
+}
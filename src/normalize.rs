@@ -0,0 +1,481 @@
+// src/normalize.rs
+
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use std::ops::Range;
+
+/// A block containing other blocks, which prefixes their lines.
+enum Container {
+    /// A block quote, whose lines start with `> `.
+    Quote,
+    /// A list; `next` is the number of its next item if it is ordered, and `delimiter`
+    /// its marker (`-` or `*`) or the character after the item numbers (`.` or `)`).
+    List {
+        tight: bool,
+        next: Option<u64>,
+        delimiter: char,
+    },
+    /// A list item, whose first line starts with `marker` and whose other lines are
+    /// indented by its width.
+    Item { marker: String, started: bool },
+}
+
+/// Renders the blocks of a Markdown document with canonical markers and spacing.
+struct Writer<'a> {
+    source: &'a str,
+    out: String,
+    containers: Vec<Container>,
+    /// Whether a block ended in the current container, so that the next one is
+    /// separated from it.
+    after_block: bool,
+    /// The marker or delimiter of the list that just ended, which the next list of the
+    /// same kind must not use, or it would continue that list.
+    ended_list: Option<char>,
+}
+
+/// Returns `true` if `event` is part of the inline content of a block.
+fn is_inline(event: &Event) -> bool {
+    match event {
+        Event::Start(tag) => matches!(
+            tag,
+            Tag::Emphasis
+                | Tag::Strong
+                | Tag::Strikethrough
+                | Tag::Superscript
+                | Tag::Subscript
+                | Tag::Link { .. }
+                | Tag::Image { .. }
+        ),
+        Event::End(tag) => matches!(
+            tag,
+            TagEnd::Emphasis
+                | TagEnd::Strong
+                | TagEnd::Strikethrough
+                | TagEnd::Superscript
+                | TagEnd::Subscript
+                | TagEnd::Link
+                | TagEnd::Image
+        ),
+        Event::Text(_)
+        | Event::Code(_)
+        | Event::InlineMath(_)
+        | Event::DisplayMath(_)
+        | Event::InlineHtml(_)
+        | Event::FootnoteReference(_)
+        | Event::SoftBreak
+        | Event::HardBreak
+        | Event::TaskListMarker(_) => true,
+        _ => false,
+    }
+}
+
+/// Returns `true` if `line` would start a block (a list item, heading, quote, code fence
+/// or setext underline) instead of continuing a paragraph when not indented.
+fn starts_block(line: &str) -> bool {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let after_digits = &line[digits..];
+    matches!(line.as_bytes().first(), Some(b'#' | b'>' | b'='))
+        || ["- ", "+ ", "* ", "```", "~~~", "---", "***", "___"]
+            .iter()
+            .any(|marker| line.starts_with(marker))
+        || ["-", "+", "*"].contains(&line)
+        || digits > 0 && (after_digits.starts_with(". ") || after_digits.starts_with(") "))
+}
+
+/// Returns the length of the longest run of `fence_char` starting a line of `code`.
+fn longest_fence(code: &str, fence_char: char) -> usize {
+    code.lines()
+        .map(|line| {
+            let line = line.trim_start();
+            line.len() - line.trim_start_matches(fence_char).len()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+impl<'a> Writer<'a> {
+    /// Returns the prefix of the next line, with the markers of the list items that have
+    /// no line yet.
+    fn line_prefix(&mut self) -> String {
+        let mut prefix = String::new();
+        for container in &mut self.containers {
+            match container {
+                Container::Quote => prefix.push_str("> "),
+                Container::List { .. } => {}
+                Container::Item { marker, started } if *started => {
+                    prefix.push_str(&" ".repeat(marker.len() + 1))
+                }
+                Container::Item { marker, started } => {
+                    prefix.push_str(marker);
+                    prefix.push(' ');
+                    *started = true;
+                }
+            }
+        }
+        prefix
+    }
+
+    /// Writes `line` with the prefix of the current containers.
+    fn write_line(&mut self, line: &str) {
+        let prefix = self.line_prefix();
+        if line.is_empty() {
+            self.out.push_str(prefix.trim_end());
+        } else {
+            self.out.push_str(&prefix);
+            self.out.push_str(line);
+        }
+        self.out.push('\n');
+    }
+
+    /// Returns `true` if the blocks of the current container are not separated by blank
+    /// lines: the current container is a tight list or one of its items.
+    fn in_tight_list(&self) -> bool {
+        let list = match self.containers.as_slice() {
+            [.., list, Container::Item { .. }] => list,
+            [.., list] => list,
+            [] => return false,
+        };
+        matches!(list, Container::List { tight: true, .. })
+    }
+
+    /// Separates the next block from the previous one in its container.
+    fn start_block(&mut self) {
+        if self.after_block && !self.in_tight_list() {
+            self.write_line("");
+        }
+        self.after_block = false;
+        self.ended_list = None;
+    }
+
+    /// Returns the lines of the inline content at `range` of the source, without the
+    /// prefixes of their containers. Two trailing spaces (a hard line break) become a
+    /// backslash, and lines that would start a block are indented to stay in the
+    /// paragraph.
+    fn inline_lines(&self, range: Range<usize>) -> Vec<String> {
+        let mut start = range.start;
+        // The backslash of an escaped first character is not part of the text's range
+        if self.source[..start].ends_with('\\') {
+            start -= 1;
+        }
+        let quotes = self
+            .containers
+            .iter()
+            .filter(|c| matches!(c, Container::Quote))
+            .count();
+        let text = &self.source[start..range.end];
+        let count = text.lines().count();
+        text.lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let mut line = line;
+                if index > 0 {
+                    for _ in 0..quotes {
+                        let trimmed = line.trim_start();
+                        line = match trimmed.strip_prefix('>') {
+                            Some(rest) => rest.strip_prefix(' ').unwrap_or(rest),
+                            None => trimmed,
+                        };
+                    }
+                }
+                let indented = index > 0 && line.starts_with([' ', '\t']);
+                let line = if index > 0 { line.trim_start() } else { line };
+                let mut line = match line.trim_end() {
+                    trimmed if index + 1 < count && line.ends_with("  ") => {
+                        format!("{}\\", trimmed)
+                    }
+                    trimmed => trimmed.to_string(),
+                };
+                if indented && starts_block(&line) {
+                    line.insert_str(0, "    ");
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+/// Re-renders Markdown with consistent syntax, so that the outputs look the same however
+/// their authors write Markdown: `-` list markers (`*` for a list following another one),
+/// items numbered from the list's start with `.`, ATX (`#`) headings, fenced code blocks
+/// with backticks, `---` thematic breaks, single blank lines between blocks (none between
+/// the items of tight lists), trailing whitespace trimmed, and link reference definitions
+/// at the end.
+///
+/// The inline content (emphasis, links, code spans, escapes) is kept as it is written.
+pub fn normalize_markdown(text: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(text, options).into_offset_iter();
+    let mut definitions: Vec<Range<usize>> = parser
+        .reference_definitions()
+        .iter()
+        .map(|(_, definition)| definition.span.clone())
+        .collect();
+    definitions.sort_by_key(|span| span.start);
+    let events: Vec<(Event, Range<usize>)> = parser.collect();
+
+    let mut writer = Writer {
+        source: text,
+        out: String::with_capacity(text.len()),
+        containers: Vec::new(),
+        after_block: false,
+        ended_list: None,
+    };
+    let mut table: Vec<Vec<String>> = Vec::new();
+    let mut alignments: Vec<Alignment> = Vec::new();
+    let mut index = 0;
+    while index < events.len() {
+        let (event, _) = &events[index];
+        if is_inline(event) {
+            // Inline content up to the end of its block, such as a paragraph or cell
+            let mut range = events[index].1.clone();
+            let mut end = index;
+            while end < events.len() && is_inline(&events[end].0) {
+                range.start = range.start.min(events[end].1.start);
+                range.end = range.end.max(events[end].1.end);
+                end += 1;
+            }
+            let lines = writer.inline_lines(range);
+            match events.get(end).map(|(event, _)| event) {
+                Some(Event::End(TagEnd::Heading(level))) => {
+                    let hashes = "#".repeat(*level as usize);
+                    writer.write_line(&format!("{} {}", hashes, lines.join(" ")));
+                    end += 1;
+                }
+                Some(Event::End(TagEnd::TableCell)) => {
+                    if let Some(row) = table.last_mut() {
+                        row.push(lines.join(" ").trim().to_string());
+                    }
+                    end += 1;
+                }
+                Some(Event::End(TagEnd::Paragraph)) => {
+                    for line in lines {
+                        writer.write_line(&line);
+                    }
+                    end += 1;
+                }
+                _ => {
+                    // The content of a tight list item, without a paragraph
+                    writer.start_block();
+                    for line in lines {
+                        writer.write_line(&line);
+                    }
+                }
+            }
+            writer.after_block = true;
+            index = end;
+            continue;
+        }
+
+        match event {
+            Event::Start(Tag::Paragraph) => writer.start_block(),
+            Event::Start(Tag::Heading { level, .. }) => {
+                writer.start_block();
+                // A heading without text has no inline events
+                if matches!(events.get(index + 1), Some((Event::End(_), _))) {
+                    writer.write_line(&"#".repeat(*level as usize));
+                    writer.after_block = true;
+                    index += 1;
+                }
+            }
+            Event::End(TagEnd::Paragraph) | Event::End(TagEnd::Heading(_)) => {
+                writer.after_block = true;
+            }
+            Event::Start(Tag::BlockQuote(_)) => {
+                writer.start_block();
+                writer.containers.push(Container::Quote);
+            }
+            Event::Start(Tag::List(start)) => {
+                let ended_list = writer.ended_list;
+                writer.start_block();
+                let tight = !matches!(
+                    events.get(index + 2),
+                    Some((Event::Start(Tag::Paragraph), _))
+                );
+                let delimiter = match (start, ended_list) {
+                    (Some(_), Some('.')) => ')',
+                    (Some(_), _) => '.',
+                    (None, Some('-')) => '*',
+                    (None, _) => '-',
+                };
+                writer.containers.push(Container::List {
+                    tight,
+                    next: *start,
+                    delimiter,
+                });
+            }
+            Event::Start(Tag::Item) => {
+                writer.start_block();
+                let marker = match writer.containers.last_mut() {
+                    Some(Container::List {
+                        next: Some(next),
+                        delimiter,
+                        ..
+                    }) => {
+                        *next += 1;
+                        format!("{}{}", *next - 1, delimiter)
+                    }
+                    Some(Container::List { delimiter, .. }) => delimiter.to_string(),
+                    _ => "-".to_string(),
+                };
+                writer.containers.push(Container::Item {
+                    marker,
+                    started: false,
+                });
+            }
+            Event::End(TagEnd::Item) => {
+                if matches!(
+                    writer.containers.last(),
+                    Some(Container::Item { started: false, .. })
+                ) {
+                    writer.write_line("");
+                }
+                writer.containers.pop();
+                writer.after_block = true;
+            }
+            Event::End(TagEnd::List(_)) => {
+                let delimiter = match writer.containers.pop() {
+                    Some(Container::List { delimiter, .. }) => Some(delimiter),
+                    _ => None,
+                };
+                writer.after_block = true;
+                writer.ended_list = delimiter;
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                writer.containers.pop();
+                writer.after_block = true;
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                writer.start_block();
+                let mut code = String::new();
+                while let Some((Event::Text(text), _)) = events.get(index + 1) {
+                    code.push_str(text);
+                    index += 1;
+                }
+                let info = match kind {
+                    CodeBlockKind::Fenced(info) => info.trim(),
+                    CodeBlockKind::Indented => "",
+                };
+                let fence_char = if info.contains('`') { '~' } else { '`' };
+                let fence = fence_char
+                    .to_string()
+                    .repeat(3.max(longest_fence(&code, fence_char) + 1));
+                writer.write_line(&format!("{}{}", fence, info));
+                for line in code.lines() {
+                    writer.write_line(line);
+                }
+                writer.write_line(&fence);
+            }
+            Event::End(TagEnd::CodeBlock) => writer.after_block = true,
+            Event::Start(Tag::HtmlBlock) => {
+                writer.start_block();
+                let mut html = String::new();
+                while let Some((Event::Html(text), _)) = events.get(index + 1) {
+                    html.push_str(text);
+                    index += 1;
+                }
+                for line in html.lines() {
+                    writer.write_line(line.trim_end());
+                }
+            }
+            Event::End(TagEnd::HtmlBlock) => writer.after_block = true,
+            Event::Rule => {
+                // Always after a blank line, or `---` would underline a heading
+                if writer.after_block {
+                    writer.write_line("");
+                }
+                writer.after_block = false;
+                writer.ended_list = None;
+                writer.write_line("---");
+                writer.after_block = true;
+            }
+            Event::Start(Tag::Table(table_alignments)) => {
+                writer.start_block();
+                alignments = table_alignments.clone();
+                table.clear();
+            }
+            Event::Start(Tag::TableHead) | Event::Start(Tag::TableRow) => table.push(Vec::new()),
+            Event::Start(Tag::TableCell) => {
+                // An empty cell has no inline events
+                if matches!(
+                    events.get(index + 1),
+                    Some((Event::End(TagEnd::TableCell), _))
+                ) {
+                    if let Some(row) = table.last_mut() {
+                        row.push(String::new());
+                    }
+                    index += 1;
+                }
+            }
+            Event::End(TagEnd::Table) => {
+                let separators: Vec<&str> = alignments
+                    .iter()
+                    .map(|alignment| match alignment {
+                        Alignment::None => "---",
+                        Alignment::Left => ":---",
+                        Alignment::Center => ":---:",
+                        Alignment::Right => "---:",
+                    })
+                    .collect();
+                let rows = std::mem::take(&mut table);
+                for (row_index, row) in rows.iter().enumerate() {
+                    writer.write_line(&format!("| {} |", row.join(" | ")));
+                    if row_index == 0 {
+                        writer.write_line(&format!("| {} |", separators.join(" | ")));
+                    }
+                }
+                writer.after_block = true;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    for definition in definitions {
+        writer.containers.clear();
+        writer.start_block();
+        writer.write_line(text[definition].trim());
+        writer.after_block = true;
+    }
+    let normalized = writer.out.trim_end();
+    match text.ends_with('\n') {
+        true => format!("{}\n", normalized),
+        false => normalized.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that block syntax is made consistent while inline content is kept.
+    #[test]
+    fn test_normalize_markdown() {
+        let text = "Rust style\n==========\n\n\n\nUse *tabs*  \nand `snake_case`, see [the guide][guide].\n\
+                    * First\n* Second\n    + Nested\n\n1) One\n1) Two\n\n***\n\n    let x = 1;\n\n\
+                    ~~~rust\nfn main() {}\n~~~\n> Quoted\ncontinued\n\n[guide]: https://example.com \"Guide\"\n\
+                    | A | B |\n|:--|--:|\n| 1 | 2 |\n";
+        assert_eq!(
+            normalize_markdown(text),
+            "# Rust style\n\nUse *tabs*\\\nand `snake_case`, see [the guide][guide].\n\n\
+             - First\n- Second\n  - Nested\n\n1. One\n2. Two\n\n---\n\n```\nlet x = 1;\n```\n\n\
+             ```rust\nfn main() {}\n```\n\n> Quoted\n> continued\n\n| A | B |\n| :--- | ---: |\n| 1 | 2 |\n\n\
+             [guide]: https://example.com \"Guide\"\n"
+        );
+    }
+
+    /// Test that loose lists, adjacent lists, escapes and nested fences keep their
+    /// meaning, and that normalizing twice changes nothing.
+    #[test]
+    fn test_normalize_markdown_keeps_meaning() {
+        let text = "+ a\n\n+ b\n\n  More b.\n- c\n\nfoo\n    - not a list\n\n\\# Not a heading\n\n\
+                    ````md\n```rust\n```\n````\n\n- [x] Done\n-\n";
+        let normalized = normalize_markdown(text);
+        assert_eq!(
+            normalized,
+            "- a\n\n- b\n\n  More b.\n\n* c\n\nfoo\n    - not a list\n\n\\# Not a heading\n\n\
+             ````md\n```rust\n```\n````\n\n- [x] Done\n-\n"
+        );
+        assert_eq!(normalize_markdown(&normalized), normalized);
+        assert_eq!(normalize_markdown("Plain text."), "Plain text.");
+    }
+}
@@ -0,0 +1,204 @@
+// src/report.rs
+
+use crate::dry_run::{ChangeKind, PlannedChange};
+use crate::AgentName;
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The format of the run summary written with `--report`.
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum ReportFormat {
+    /// A pretty-printed JSON object.
+    Json,
+}
+
+/// A rule that was not emitted, either for every agent (it failed to parse) or for one.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SkippedRule {
+    /// The rule name, or the file path for rules that failed to parse.
+    pub rule: String,
+
+    /// The agent the rule was skipped for, or `None` if it was skipped for all agents.
+    pub agent: Option<AgentName>,
+
+    /// Why the rule was skipped.
+    pub reason: String,
+}
+
+/// The outcome of one agent's generation.
+#[derive(Serialize, Debug, Clone)]
+pub struct AgentReport {
+    /// The agent.
+    pub agent: AgentName,
+
+    /// Whether the agent's outputs were generated without errors.
+    pub success: bool,
+
+    /// The number of rules emitted for the agent.
+    pub rules: usize,
+}
+
+/// A file created or updated by the run.
+#[derive(Serialize, Debug, Clone)]
+pub struct FileReport {
+    /// The path of the file.
+    pub path: PathBuf,
+
+    /// Whether the file was created or updated.
+    pub change: ChangeKind,
+}
+
+/// The urules-managed `.gitignore` section.
+#[derive(Serialize, Debug, Clone)]
+pub struct GitignoreReport {
+    /// The path of the `.gitignore` file.
+    pub path: PathBuf,
+
+    /// The patterns of the generated agents.
+    pub patterns: Vec<String>,
+
+    /// Whether the run changed the file.
+    pub changed: bool,
+}
+
+/// A machine-readable summary of a generation run.
+///
+/// In `--dry-run` and `check` runs, `files_written` lists the files a real run would
+/// write, and `gitignore.changed` whether it would change the `.gitignore`.
+#[derive(Serialize, Debug, Clone)]
+pub struct RunReport {
+    /// How the run applied its outputs: `write`, `dry-run` or `check`.
+    pub mode: &'static str,
+
+    /// The directory the rules were read from.
+    pub rules_dir: PathBuf,
+
+    /// The directory the outputs were generated in.
+    pub output_dir: PathBuf,
+
+    /// The names of the rules that were parsed.
+    pub rules_parsed: Vec<String>,
+
+    /// The rules that failed to parse or were not emitted for some agents.
+    pub rules_skipped: Vec<SkippedRule>,
+
+    /// The outcome of each selected agent.
+    pub agents: Vec<AgentReport>,
+
+    /// The files that were created or updated, sorted by path.
+    pub files_written: Vec<FileReport>,
+
+    /// The managed `.gitignore` section, or `None` if `.gitignore` was not updated.
+    pub gitignore: Option<GitignoreReport>,
+}
+
+impl RunReport {
+    /// Creates an empty report for a run reading `rules_dir` into `output_dir`.
+    pub fn new(mode: &'static str, rules_dir: &Path, output_dir: &Path) -> Self {
+        RunReport {
+            mode,
+            rules_dir: rules_dir.to_path_buf(),
+            output_dir: output_dir.to_path_buf(),
+            rules_parsed: Vec::new(),
+            rules_skipped: Vec::new(),
+            agents: Vec::new(),
+            files_written: Vec::new(),
+            gitignore: None,
+        }
+    }
+
+    /// Records the planned changes of the run as its written files, and whether they
+    /// include the `.gitignore` in the output directory.
+    pub fn record_changes(&mut self, changes: &[PlannedChange], gitignore_patterns: Vec<String>) {
+        self.files_written = changes
+            .iter()
+            .map(|change| FileReport {
+                path: change.path.clone(),
+                change: change.kind.clone(),
+            })
+            .collect();
+        if !gitignore_patterns.is_empty() {
+            let path = self.output_dir.join(".gitignore");
+            self.gitignore = Some(GitignoreReport {
+                changed: changes.iter().any(|change| change.path == path),
+                path,
+                patterns: gitignore_patterns,
+            });
+        }
+    }
+}
+
+/// Writes the report in `format` to `path`, or to standard output if no path is given.
+pub fn write_report(report: &RunReport, format: &ReportFormat, path: Option<&Path>) -> Result<()> {
+    let content = match format {
+        ReportFormat::Json => {
+            serde_json::to_string_pretty(report).context("Failed to serialize run report")? + "\n"
+        }
+    };
+    match path {
+        Some(path) => {
+            fs::write(path, content).with_context(|| format!("Failed to write report {:?}", path))
+        }
+        None => {
+            print!("{}", content);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    /// Test that changes are recorded and that the `.gitignore` change is detected.
+    #[test]
+    fn test_record_changes() {
+        let mut report = RunReport::new("write", Path::new(".rules"), Path::new("out"));
+        let changes = vec![
+            PlannedChange {
+                path: PathBuf::from("out/.gitignore"),
+                kind: ChangeKind::Update,
+                diff: None,
+            },
+            PlannedChange {
+                path: PathBuf::from("out/CLAUDE.md"),
+                kind: ChangeKind::Create,
+                diff: None,
+            },
+        ];
+
+        report.record_changes(&changes, vec!["CLAUDE.md".to_string()]);
+
+        assert_eq!(report.files_written.len(), 2);
+        let gitignore = report.gitignore.as_ref().unwrap();
+        assert!(gitignore.changed);
+        assert_eq!(gitignore.patterns, vec!["CLAUDE.md".to_string()]);
+    }
+
+    /// Test that the JSON report is written to a file with its top-level fields.
+    #[test]
+    fn test_write_json_report() -> Result<()> {
+        let dir = tempdir()?;
+        let mut report = RunReport::new("dry-run", Path::new(".rules"), Path::new("."));
+        report.rules_parsed.push("style".to_string());
+        report.rules_skipped.push(SkippedRule {
+            rule: "rust".to_string(),
+            agent: Some(AgentName::Claude),
+            reason: "not targeted at this agent".to_string(),
+        });
+        let report_path = dir.path().join("report.json");
+
+        write_report(&report, &ReportFormat::Json, Some(&report_path))?;
+
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(report_path)?)?;
+        assert_eq!(json["mode"], "dry-run");
+        assert_eq!(json["rules_parsed"], serde_json::json!(["style"]));
+        assert_eq!(json["rules_skipped"][0]["agent"], "claude");
+        assert_eq!(json["gitignore"], serde_json::Value::Null);
+        Ok(())
+    }
+}
@@ -1,12 +1,39 @@
 // src/rule_parser.rs
 
-use crate::universal_rule::{UniversalRule, UniversalRuleFrontmatter};
-use anyhow::{anyhow, Context, Result};
-use serde_yaml;
+use crate::assets::ASSETS_DIR;
+use crate::color::warning_label;
+use crate::languages::expand_languages;
+use crate::line_endings::normalize_line_endings;
+use crate::template::interpolate_env;
+use crate::universal_rule::{UniversalRule, UniversalRuleFrontmatter, SCHEMA_VERSION};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::NaiveDate;
+use clap::Args;
+use glob::Pattern;
+use serde_yaml::{self, Mapping, Value};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path; // PathBuf is not used directly here, but often useful with Path
+use std::path::{Component, Path, PathBuf};
 use walkdir::WalkDir;
 
+/// Splits a Markdown file into its frontmatter block and its body.
+///
+/// Frontmatter is expected to be enclosed by `---` lines at the start of the file.
+/// Without frontmatter, the frontmatter part is empty and the whole file is the body.
+/// Surrounding whitespace (e.g. the file's final newline) is trimmed from both parts.
+pub fn split_frontmatter(file_content: &str) -> (&str, &str) {
+    if file_content.starts_with("---") {
+        let mut parts = file_content.splitn(3, "---");
+        parts.next(); // Skip the part before the first '---' (should be empty)
+        let fm_block = parts.next().unwrap_or("").trim(); // The YAML block
+        let main_content = parts.next().unwrap_or("").trim(); // The rest of the file
+        (fm_block, main_content)
+    } else {
+        // No frontmatter detected, treat the entire file as content.
+        ("", file_content.trim_end())
+    }
+}
+
 /// Parses a single universal rule file from the given `file_path`.
 ///
 /// The function reads the file content, attempts to extract a YAML frontmatter block
@@ -18,39 +45,159 @@ use walkdir::WalkDir;
 ///
 /// # Arguments
 /// * `file_path` - A reference to a `Path` pointing to the rule file.
+/// * `deny_unknown_keys` - Whether unknown frontmatter keys (e.g. typos) are errors
+///   instead of being ignored.
 ///
 /// # Returns
 /// A `Result` containing the parsed `UniversalRule` on success, or an `anyhow::Error`
 /// if reading the file, parsing frontmatter, or deriving the name fails.
-pub fn parse_rule_file(file_path: &Path) -> Result<UniversalRule> {
+pub fn parse_rule_file(file_path: &Path, deny_unknown_keys: bool) -> Result<UniversalRule> {
     let file_content = fs::read_to_string(file_path)
         .with_context(|| format!("Failed to read rule file: {:?}", file_path))?;
+    parse_rule_content(file_path, &file_content, deny_unknown_keys)
+}
 
-    // Attempt to split the file content into frontmatter and main content.
-    // Frontmatter is expected to be enclosed by '---' at the start and end.
-    let (frontmatter_str, content_str) = if file_content.starts_with("---") {
-        let mut parts = file_content.splitn(3, "---");
-        parts.next(); // Skip the part before the first '---' (should be empty)
-        let fm_block = parts.next().unwrap_or("").trim(); // The YAML block
-        let main_content = parts.next().unwrap_or("").trim_start(); // The rest of the file
-        (fm_block, main_content)
-    } else {
-        // No frontmatter detected, treat the entire file as content.
-        ("", file_content.as_str())
+/// Parses the content of a universal rule file, like `parse_rule_file`, without reading
+/// it: `file_path` only names the rule (e.g. `<name>.md` for a rule read from stdin).
+pub fn parse_rule_content(
+    file_path: &Path,
+    file_content: &str,
+    deny_unknown_keys: bool,
+) -> Result<UniversalRule> {
+    parse_rule_content_with_defaults(file_path, file_content, &Mapping::new(), deny_unknown_keys)
+}
+
+/// Parses the content of a universal rule file like `parse_rule_content`, filling the
+/// frontmatter keys the rule does not set from `defaults` (see `DIRECTORY_DEFAULTS_FILE`).
+pub fn parse_rule_content_with_defaults(
+    file_path: &Path,
+    file_content: &str,
+    defaults: &Mapping,
+    deny_unknown_keys: bool,
+) -> Result<UniversalRule> {
+    let file_content = &normalize_line_endings(file_content);
+    let (frontmatter_str, content_str) = split_frontmatter(file_content);
+    let frontmatter_context = || {
+        format!(
+            "Failed to parse YAML frontmatter for rule file: {:?}",
+            file_path
+        )
     };
 
-    // Parse the extracted frontmatter string into UniversalRuleFrontmatter.
-    // If the frontmatter string is empty, use default values.
-    let frontmatter: UniversalRuleFrontmatter = if frontmatter_str.is_empty() {
+    // Parse the extracted frontmatter string into UniversalRuleFrontmatter, recording the
+    // keys it does not know. If the frontmatter string is empty, use default values.
+    let mut unknown_keys = Vec::new();
+    let mut frontmatter: UniversalRuleFrontmatter = if !defaults.is_empty() {
+        let mut merged: Mapping = if frontmatter_str.is_empty() {
+            Mapping::new()
+        } else {
+            serde_yaml::from_str::<Option<Mapping>>(frontmatter_str)
+                .with_context(frontmatter_context)?
+                .unwrap_or_default()
+        };
+        for (key, value) in defaults {
+            if !merged.contains_key(key) {
+                merged.insert(key.clone(), value.clone());
+            }
+        }
+        serde_ignored::deserialize(Value::Mapping(merged), |key| {
+            unknown_keys.push(key_path(&key))
+        })
+        .with_context(frontmatter_context)?
+    } else if frontmatter_str.is_empty() {
         UniversalRuleFrontmatter::default()
     } else {
-        serde_yaml::from_str(frontmatter_str).with_context(|| {
-            format!(
-                "Failed to parse YAML frontmatter for rule file: {:?}",
-                file_path
-            )
-        })?
+        serde_ignored::deserialize(serde_yaml::Deserializer::from_str(frontmatter_str), |key| {
+            unknown_keys.push(key_path(&key))
+        })
+        .with_context(frontmatter_context)?
     };
+    if deny_unknown_keys && !unknown_keys.is_empty() {
+        bail!(
+            "Unknown frontmatter key(s) {} in rule file {:?}; check them for typos",
+            unknown_keys
+                .iter()
+                .map(|key| format!("`{}`", key))
+                .collect::<Vec<_>>()
+                .join(", "),
+            file_path
+        );
+    }
+    if !unknown_keys.is_empty() {
+        // Unknown top-level keys are kept as custom metadata, e.g. for templates
+        let declared: Mapping = serde_yaml::from_str::<Option<Mapping>>(frontmatter_str)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        for key in unknown_keys.iter().filter(|key| !key.contains(['.', '['])) {
+            if let Some(value) = declared.get(key.as_str()).or(defaults.get(key.as_str())) {
+                frontmatter
+                    .extra
+                    .entry(key.clone())
+                    .or_insert_with(|| value.clone());
+            }
+        }
+    }
+    frontmatter.unknown_keys = unknown_keys;
+    if let Some(schema_version) = frontmatter.schema_version {
+        if schema_version > SCHEMA_VERSION {
+            bail!(
+                "Rule file {:?} uses schema version {}, but this version of urules only supports up to {}; upgrade urules to read it",
+                file_path,
+                schema_version,
+                SCHEMA_VERSION
+            );
+        }
+    }
+
+    if let Some(scope) = &frontmatter.scope {
+        if !scope
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            bail!(
+                "Rule file {:?} has an invalid `scope` {:?}; it must be a directory relative to the output directory, without `..`",
+                file_path,
+                scope
+            );
+        }
+    }
+
+    let is_file_name =
+        |name: &str| !name.is_empty() && !name.contains(['/', '\\']) && name != "." && name != "..";
+    if let Some(output_name) = frontmatter
+        .output_name
+        .as_deref()
+        .filter(|n| !is_file_name(n))
+    {
+        bail!(
+            "Rule file {:?} has an invalid `output_name` {:?}; it must be a file name without path separators",
+            file_path,
+            output_name
+        );
+    }
+    if let Some(alias) = frontmatter
+        .aliases
+        .iter()
+        .flatten()
+        .find(|a| !is_file_name(a))
+    {
+        bail!(
+            "Rule file {:?} has an invalid alias {:?} in `aliases`; it must be a file name without path separators",
+            file_path,
+            alias
+        );
+    }
+
+    if let Some(level) = frontmatter.claude.as_ref().and_then(|c| c.heading_level) {
+        if !(1..=6).contains(&level) {
+            bail!(
+                "Rule file {:?} has an invalid `claude.heading_level` {}; Markdown headings go from 1 to 6",
+                file_path,
+                level
+            );
+        }
+    }
 
     // Derive the rule name from the file's stem (filename without extension).
     let name = file_path
@@ -59,51 +206,535 @@ pub fn parse_rule_file(file_path: &Path) -> Result<UniversalRule> {
         .to_string_lossy() // Convert OsStr to String, lossily if necessary.
         .into_owned();
 
+    let mut include_stack = vec![fs::canonicalize(file_path).unwrap_or(file_path.to_path_buf())];
+    let content = resolve_includes(content_str, file_path, &mut include_stack)?;
+
     Ok(UniversalRule {
         name,
         frontmatter,
-        content: content_str.to_string(),
+        content,
+        relative_dir: PathBuf::new(), // Assigned by `discover_and_parse_rules`
+    })
+}
+
+/// Formats the path of a frontmatter key as dotted keys and list indexes
+/// (e.g. `subagent.tols`), leaving out serde's `Option` and newtype levels.
+fn key_path(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, index } => format!("{}[{}]", key_path(parent), index),
+        Path::Map { parent, key } => match key_path(parent) {
+            parent if parent.is_empty() => key.clone(),
+            parent => format!("{}.{}", parent, key),
+        },
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => key_path(parent),
+    }
+}
+
+/// Returns the path named by `line` if it is an include directive, either
+/// `{{include "path"}}` or `<!-- include: path -->` on a line of its own.
+fn include_directive(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if let Some(inner) = line.strip_prefix("{{").and_then(|l| l.strip_suffix("}}")) {
+        let path = inner.trim().strip_prefix("include")?.trim_start();
+        return path.strip_prefix('"')?.strip_suffix('"');
+    }
+    let inner = line.strip_prefix("<!--")?.strip_suffix("-->")?.trim();
+    Some(inner.strip_prefix("include:")?.trim())
+}
+
+/// Replaces the include directives of `content`, the body of `file_path`, with the body
+/// of the files they name, resolved relative to the directory of `file_path`.
+///
+/// Included files may include others in turn; their frontmatter is dropped. Directives
+/// inside fenced code blocks are kept as they are. `stack` holds the canonical paths of
+/// the files being included, so that include cycles fail instead of recursing forever.
+fn resolve_includes(content: &str, file_path: &Path, stack: &mut Vec<PathBuf>) -> Result<String> {
+    let base_dir = file_path.parent().unwrap_or(Path::new(""));
+    let mut result = String::with_capacity(content.len());
+    let mut in_fence = false;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        let Some(include) = include_directive(line).filter(|_| !in_fence) else {
+            result.push_str(line);
+            continue;
+        };
+        let included_path = base_dir.join(include);
+        let canonical_path = fs::canonicalize(&included_path).with_context(|| {
+            format!(
+                "Failed to include {:?} in rule file {:?}",
+                included_path, file_path
+            )
+        })?;
+        if stack.contains(&canonical_path) {
+            bail!(
+                "Include cycle: {:?} includes {:?}, which is already being included",
+                file_path,
+                included_path
+            );
+        }
+        let included = fs::read_to_string(&canonical_path)
+            .with_context(|| format!("Failed to read included file {:?}", included_path))?;
+        let included = normalize_line_endings(&included);
+        let (_, body) = split_frontmatter(&included);
+        stack.push(canonical_path);
+        result.push_str(&resolve_includes(body, &included_path, stack)?);
+        stack.pop();
+        // Keep the line ending of the directive
+        result.push_str(&line[line.trim_end_matches(['\r', '\n']).len()..]);
+    }
+    Ok(result)
+}
+
+/// Returns the names of the templates `line` includes or imports with minijinja tags
+/// (`{% include "footer.md" %}`, `{% from "macros.md" import note %}`).
+fn template_includes(line: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find("{%") {
+        rest = &rest[start + 2..];
+        let tag = rest.trim_start_matches(['-', '+']).trim_start();
+        let is_include = ["include", "import", "from", "extends"]
+            .iter()
+            .any(|keyword| tag.starts_with(keyword));
+        let Some(end) = tag.find("%}") else {
+            break;
+        };
+        if is_include {
+            if let Some((_, quoted)) = tag[..end].split_once(['"', '\'']) {
+                if let Some(name_end) = quoted.find(['"', '\'']) {
+                    names.push(&quoted[..name_end]);
+                }
+            }
+        }
+    }
+    names
+}
+
+/// Returns the files of the `PARTIALS_DIR` of `rules_dir`, sorted by path.
+pub fn partial_files(rules_dir: &Path) -> Vec<PathBuf> {
+    WalkDir::new(rules_dir.join(PARTIALS_DIR))
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// Returns the paths of the files that `source`, a rule file or partial of `rules_dir`,
+/// includes: with include directives, resolved relative to `source`, or with minijinja
+/// tags, resolved in the `PARTIALS_DIR`. Directives inside fenced code blocks are left
+/// out; the paths may not exist. A file that cannot be read includes nothing.
+pub fn included_files(source: &Path, rules_dir: &Path) -> Vec<PathBuf> {
+    let Ok(content) = fs::read_to_string(source) else {
+        return Vec::new();
+    };
+    let partials_dir = rules_dir.join(PARTIALS_DIR);
+    let base_dir = source.parent().unwrap_or(rules_dir);
+    let mut included = Vec::new();
+    let mut in_fence = false;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        if in_fence {
+            continue;
+        }
+        included.extend(include_directive(line).map(|path| base_dir.join(path)));
+        included.extend(
+            template_includes(line)
+                .into_iter()
+                .map(|name| partials_dir.join(name)),
+        );
+    }
+    included
+}
+
+/// Returns the files of the `PARTIALS_DIR` of `rules_dir` that neither a rule file (see
+/// `rule_files`) nor another partial includes, relative to `rules_dir` and sorted by
+/// path.
+pub fn unreferenced_partials(rules_dir: &Path, options: &DiscoveryOptions) -> Vec<PathBuf> {
+    let partials = partial_files(rules_dir);
+    if partials.is_empty() {
+        return Vec::new();
+    }
+
+    let referenced: Vec<PathBuf> = rule_files(rules_dir, options)
+        .iter()
+        .chain(&partials)
+        .flat_map(|source| included_files(source, rules_dir))
+        .filter_map(|path| fs::canonicalize(path).ok())
+        .collect();
+    partials
+        .into_iter()
+        .filter(|partial| {
+            fs::canonicalize(partial).is_ok_and(|partial| !referenced.contains(&partial))
+        })
+        .map(|partial| {
+            partial
+                .strip_prefix(rules_dir)
+                .map(Path::to_path_buf)
+                .unwrap_or(partial)
+        })
+        .collect()
+}
+
+/// Returns the warnings about a parsed rule: one per unknown frontmatter key, and one if
+/// it declares a `schema_version` older than the current one. Rules with an outdated
+/// schema are read with the current one, which may have changed the meaning of some of
+/// their fields.
+pub fn rule_warnings(rule: &UniversalRule) -> Vec<String> {
+    let mut warnings: Vec<String> = rule
+        .frontmatter
+        .unknown_keys
+        .iter()
+        .map(|key| {
+            format!(
+                "Rule {:?} has an unknown frontmatter key `{}`, which is only kept as `extra` metadata; check it for typos or move it under `extra:` (--deny-unknown-keys makes this an error)",
+                rule.relative_path(),
+                key
+            )
+        })
+        .collect();
+    if let Some(schema_version) = rule.frontmatter.schema_version {
+        if schema_version < SCHEMA_VERSION {
+            warnings.push(format!(
+                "Rule {:?} declares schema version {}, older than the current version {}; check that it still behaves as intended and update its `schema_version`",
+                rule.relative_path(),
+                schema_version,
+                SCHEMA_VERSION
+            ));
+        }
+    }
+    warnings
+}
+
+/// Prints the `rule_warnings` of `rules` to stderr.
+pub fn print_rule_warnings(rules: &[UniversalRule]) {
+    for warning in rules.iter().flat_map(rule_warnings) {
+        eprintln!("{} {}", warning_label(), warning);
+    }
+}
+
+/// Directories skipped during discovery, since they hold dependencies, build outputs or
+/// version control data rather than rules.
+pub const EXCLUDED_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "target",
+    "vendor",
+    ".venv",
+    "venv",
+    "__pycache__",
+];
+
+/// Options limiting which files rule discovery reads, so that pointing `--rules-dir`
+/// at a large repository does not walk all of it, and how strictly they are parsed.
+#[derive(Args, Debug, Clone, Default)]
+pub struct DiscoveryOptions {
+    /// How deep discovery descends into the rules directory.
+    #[clap(
+        long,
+        value_name = "DEPTH",
+        help = "Only discover rule files at most DEPTH levels deep (1: only files directly in the rules directory)."
+    )]
+    pub max_depth: Option<usize>,
+
+    /// Glob patterns that rule files must match, relative to the rules directory.
+    #[clap(
+        long,
+        value_name = "GLOB",
+        help = "Only discover the .md files whose path in the rules directory matches GLOB (repeatable)."
+    )]
+    pub include_pattern: Vec<Pattern>,
+
+    /// Fail to parse rules with unknown frontmatter keys instead of ignoring the keys.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Treat unknown frontmatter keys (e.g. typos like `aplly_globally`) as parse errors."
+    )]
+    pub deny_unknown_keys: bool,
+
+    /// Replace the `${NAME}` references of rule files with environment variables.
+    #[clap(
+        long,
+        default_value_t = false,
+        help = "Replace ${NAME} references in rule files (frontmatter and content) with environment variables."
+    )]
+    pub env_interpolation: bool,
+
+    /// Globs of the languages rules list in `languages`, from the `[languages]` table of
+    /// the project configuration, replacing the built-in globs of the same language.
+    #[clap(skip)]
+    pub languages: BTreeMap<String, Vec<String>>,
+}
+
+/// Returns `content`, the text of the rule file at `file_path`, with its `${NAME}`
+/// references replaced by environment variables if `options.env_interpolation` is set.
+///
+/// Fails with the list of the referenced variables that are not set.
+pub fn interpolate_rule_env(
+    file_path: &Path,
+    content: String,
+    options: &DiscoveryOptions,
+) -> Result<String> {
+    if !options.env_interpolation {
+        return Ok(content);
+    }
+    interpolate_env(&content, |name| std::env::var(name).ok()).map_err(|undefined| {
+        anyhow!(
+            "Undefined environment variable(s) {} in rule file {:?}; set them, or write `$${{NAME}}` for a literal `${{NAME}}`",
+            undefined
+                .iter()
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(", "),
+            file_path
+        )
     })
 }
 
+/// The directory of the rules directory holding partials: fragments that are never
+/// generated on their own, but included into rules with `{{include "..."}}` directives or
+/// `{% include "..." %}` in templates.
+pub const PARTIALS_DIR: &str = "_partials";
+
+/// Returns the rule files (Markdown `.md` files) in `rules_dir` selected by `options`,
+/// sorted by path. Directories in `EXCLUDED_DIRS` are not entered, nor are the top-level
+/// `assets` directory, which holds the files rules link to, and the `PARTIALS_DIR`.
+pub fn rule_files(rules_dir: &Path, options: &DiscoveryOptions) -> Vec<PathBuf> {
+    let mut walker = WalkDir::new(rules_dir);
+    if let Some(max_depth) = options.max_depth {
+        walker = walker.max_depth(max_depth);
+    }
+    let mut files: Vec<PathBuf> = walker
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || !(EXCLUDED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref())
+                    || entry.depth() == 1
+                        && (entry.file_name() == ASSETS_DIR || entry.file_name() == PARTIALS_DIR))
+        })
+        .filter_map(|e| e.ok()) // Filter out directory reading errors, processing valid entries.
+        .map(|entry| entry.into_path())
+        .filter(|path| path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md"))
+        .filter(|path| {
+            let relative_path = path.strip_prefix(rules_dir).unwrap_or(path);
+            options.include_pattern.is_empty()
+                || options
+                    .include_pattern
+                    .iter()
+                    .any(|pattern| pattern.matches_path(relative_path))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// The file of a rules directory (or of any of its subdirectories) holding frontmatter
+/// defaults for the rules in that directory and below.
+pub const DIRECTORY_DEFAULTS_FILE: &str = "_defaults.yaml";
+
+/// Returns the frontmatter defaults of the rules in `dir`, a directory inside `rules_dir`:
+/// the `DIRECTORY_DEFAULTS_FILE`s of `rules_dir` and of the directories down to `dir`,
+/// where the keys of nearer directories win. Loaded files are kept in `cache`.
+fn directory_defaults(
+    rules_dir: &Path,
+    dir: &Path,
+    cache: &mut BTreeMap<PathBuf, Mapping>,
+) -> Result<Mapping> {
+    if let Some(defaults) = cache.get(dir) {
+        return Ok(defaults.clone());
+    }
+    let mut defaults = match dir.parent().filter(|_| dir != rules_dir) {
+        Some(parent) if parent.starts_with(rules_dir) => {
+            directory_defaults(rules_dir, parent, cache)?
+        }
+        _ => Mapping::new(),
+    };
+    let defaults_path = dir.join(DIRECTORY_DEFAULTS_FILE);
+    if defaults_path.is_file() {
+        let content = fs::read_to_string(&defaults_path)
+            .with_context(|| format!("Failed to read directory defaults {:?}", defaults_path))?;
+        let own: Option<Mapping> = serde_yaml::from_str(&content)
+            .with_context(|| format!("Failed to parse directory defaults {:?}", defaults_path))?;
+        defaults.extend(own.unwrap_or_default());
+    }
+    cache.insert(dir.to_path_buf(), defaults.clone());
+    Ok(defaults)
+}
+
+/// A rule file that could not be parsed during discovery.
+#[derive(Debug, Clone)]
+pub struct RuleParseFailure {
+    /// The path of the rule file.
+    pub path: PathBuf,
+
+    /// The parsing error, including its causes.
+    pub error: String,
+}
+
 /// Discovers and parses all universal rule files (Markdown `.md` files)
 /// within a given directory and its subdirectories.
 ///
 /// This function recursively walks through the `rules_dir`, identifies files
-/// with the `.md` extension, and attempts to parse each one using `parse_rule_file`.
-/// Errors encountered during the parsing of individual files are printed to `stderr`,
-/// but the function continues to process other files.
+/// with the `.md` extension (see `rule_files`), and attempts to parse each one using
+/// `parse_rule_file`, with the defaults of the `DIRECTORY_DEFAULTS_FILE`s of their
+/// directories, then adds the globs of their `languages` (see `expand_languages`).
+/// Files that fail to parse are returned alongside the parsed rules instead of aborting
+/// discovery.
+///
+/// The rules are ordered by `priority`, highest first, then by their path relative to
+/// `rules_dir` (see `sort_rules`), so that outputs do not depend on the file system.
 ///
 /// # Arguments
 /// * `rules_dir` - A reference to a `Path` for the directory to scan for rule files.
+/// * `options` - The depth and patterns limiting which files are read, and how they
+///   are parsed.
 ///
 /// # Returns
-/// A `Result` containing a `Vec<UniversalRule>` of all successfully parsed rules,
-/// or an `anyhow::Error` if there's an issue walking the directory itself (though
-/// individual file parsing errors are handled internally by logging).
-pub fn discover_and_parse_rules(rules_dir: &Path) -> Result<Vec<UniversalRule>> {
+/// A `Result` containing the successfully parsed rules and the files that failed to
+/// parse, or an `anyhow::Error` if there's an issue walking the directory itself.
+pub fn discover_rules(
+    rules_dir: &Path,
+    options: &DiscoveryOptions,
+) -> Result<(Vec<UniversalRule>, Vec<RuleParseFailure>)> {
     let mut rules = Vec::new();
-    for entry in WalkDir::new(rules_dir).into_iter().filter_map(|e| e.ok())
-    // Filter out directory reading errors, processing valid entries.
-    {
-        let path = entry.path();
-        // Check if the entry is a file and has a ".md" extension.
-        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md") {
-            match parse_rule_file(path) {
-                Ok(rule) => rules.push(rule),
-                Err(e) => {
-                    // Log errors for individual file parsing but continue with others.
-                    eprintln!("Failed to parse rule file {:?}: {}", path, e);
-                }
+    let mut failures = Vec::new();
+    let mut defaults_cache = BTreeMap::new();
+    for path in rule_files(rules_dir, options) {
+        let dir = path.parent().unwrap_or(rules_dir);
+        let parsed = directory_defaults(rules_dir, dir, &mut defaults_cache).and_then(|defaults| {
+            let file_content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read rule file: {:?}", path))?;
+            let file_content = interpolate_rule_env(&path, file_content, options)?;
+            let mut rule = parse_rule_content_with_defaults(
+                &path,
+                &file_content,
+                &defaults,
+                options.deny_unknown_keys,
+            )?;
+            expand_languages(&mut rule, &options.languages)?;
+            Ok(rule)
+        });
+        match parsed {
+            Ok(mut rule) => {
+                // Record where the rule lives relative to the rules directory.
+                rule.relative_dir = path
+                    .parent()
+                    .and_then(|parent| parent.strip_prefix(rules_dir).ok())
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default();
+                rules.push(rule);
             }
+            Err(e) => failures.push(RuleParseFailure {
+                path,
+                error: format!("{:#}", e),
+            }),
         }
     }
+    sort_rules(&mut rules);
+    Ok((rules, failures))
+}
+
+/// Sorts `rules` by `priority`, highest first, then by their relative path compared as a
+/// `/`-separated string, which orders them the same on every platform.
+pub fn sort_rules(rules: &mut [UniversalRule]) {
+    rules.sort_by_cached_key(|rule| {
+        (
+            std::cmp::Reverse(rule.frontmatter.priority),
+            rule.relative_path().to_string_lossy().replace('\\', "/"),
+        )
+    });
+}
+
+/// Discovers and parses all universal rule files within `rules_dir`, like
+/// `discover_rules`.
+///
+/// Errors encountered during the parsing of individual files are printed to `stderr`,
+/// but the function continues to process other files. The `rule_warnings` of the parsed
+/// rules are printed too.
+///
+/// # Returns
+/// A `Result` containing a `Vec<UniversalRule>` of all successfully parsed rules,
+/// or an `anyhow::Error` if there's an issue walking the directory itself (though
+/// individual file parsing errors are handled internally by logging).
+pub fn discover_and_parse_rules(
+    rules_dir: &Path,
+    options: &DiscoveryOptions,
+) -> Result<Vec<UniversalRule>> {
+    let (rules, failures) = discover_rules(rules_dir, options)?;
+    for failure in failures {
+        // Log errors for individual file parsing but continue with others.
+        eprintln!(
+            "Failed to parse rule file {:?}: {}",
+            failure.path, failure.error
+        );
+    }
+    print_rule_warnings(&rules);
     Ok(rules)
 }
 
+/// Returns `true` if `pattern` matches the rule's name or its path relative to the rules
+/// directory, with or without the `.md` extension.
+fn rule_matches(rule: &UniversalRule, pattern: &Pattern) -> bool {
+    let path = rule.relative_path();
+    pattern.matches(&rule.name)
+        || pattern.matches_path(&path)
+        || pattern.matches_path(&path.with_extension(""))
+}
+
+/// Splits `rules` into the rules selected by the `--only` and `--exclude` patterns and
+/// the rules filtered out.
+///
+/// With `only` patterns, a rule must match at least one of them; a rule matching any
+/// `exclude` pattern is filtered out even if it matches an `only` pattern.
+pub fn filter_rules(
+    rules: Vec<UniversalRule>,
+    only: &[Pattern],
+    exclude: &[Pattern],
+) -> (Vec<UniversalRule>, Vec<UniversalRule>) {
+    rules.into_iter().partition(|rule| {
+        (only.is_empty() || only.iter().any(|p| rule_matches(rule, p)))
+            && !exclude.iter().any(|p| rule_matches(rule, p))
+    })
+}
+
+/// Splits `rules` into the rules that are still current on `today` and the rules whose
+/// `expires` date has passed.
+pub fn filter_expired(
+    rules: Vec<UniversalRule>,
+    today: NaiveDate,
+) -> (Vec<UniversalRule>, Vec<UniversalRule>) {
+    rules.into_iter().partition(|rule| !rule.is_expired(today))
+}
+
+/// Splits `rules` into the rules whose `requires_env` variable is set according to
+/// `is_set` (or that require none) and the rules gated on an unset variable.
+pub fn filter_env_gated(
+    rules: Vec<UniversalRule>,
+    is_set: impl Fn(&str) -> bool,
+) -> (Vec<UniversalRule>, Vec<UniversalRule>) {
+    rules
+        .into_iter()
+        .partition(|rule| rule.frontmatter.requires_env.as_deref().is_none_or(&is_set))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::universal_rule::CursorRuleType;
+    use crate::AgentName;
     use std::fs::File;
     // PathBuf is used in tests for creating paths in temp directories
     use std::io::Write;
@@ -121,21 +752,24 @@ mod tests {
 description: A test rule
 globs: [\"*.rs\"]
 apply_globally: true
-cursor_rule_type: \"lint\"
+cursor_rule_type: \"AgentRequested\"
 ---
 Rule content here."
         )
         .unwrap();
 
-        let rule = parse_rule_file(&file_path).unwrap();
+        let rule = parse_rule_file(&file_path, false).unwrap();
         assert_eq!(rule.name, "test_rule");
         assert_eq!(
             rule.frontmatter.description,
             Some("A test rule".to_string())
         );
         assert_eq!(rule.frontmatter.globs, Some(vec!["*.rs".to_string()]));
-        assert_eq!(rule.frontmatter.apply_globally, true);
-        assert_eq!(rule.frontmatter.cursor_rule_type, Some("lint".to_string()));
+        assert!(rule.frontmatter.apply_globally);
+        assert_eq!(
+            rule.frontmatter.cursor_rule_type,
+            Some(CursorRuleType::AgentRequested)
+        );
         assert_eq!(rule.content, "Rule content here.");
     }
 
@@ -148,11 +782,11 @@ Rule content here."
         let mut file = File::create(&file_path).unwrap();
         writeln!(file, "Just content here.").unwrap();
 
-        let rule = parse_rule_file(&file_path).unwrap();
+        let rule = parse_rule_file(&file_path, false).unwrap();
         assert_eq!(rule.name, "test_rule_no_fm");
         assert!(rule.frontmatter.description.is_none());
         assert!(rule.frontmatter.globs.is_none());
-        assert_eq!(rule.frontmatter.apply_globally, false); // Default behavior
+        assert!(!rule.frontmatter.apply_globally); // Default behavior
         assert!(rule.frontmatter.cursor_rule_type.is_none());
         assert_eq!(rule.content, "Just content here.");
     }
@@ -172,15 +806,238 @@ Content after empty frontmatter."
         )
         .unwrap();
 
-        let rule = parse_rule_file(&file_path).unwrap();
+        let rule = parse_rule_file(&file_path, false).unwrap();
         assert_eq!(rule.name, "test_rule_empty_fm");
         assert!(rule.frontmatter.description.is_none());
         assert!(rule.frontmatter.globs.is_none());
-        assert_eq!(rule.frontmatter.apply_globally, false); // Default behavior
+        assert!(!rule.frontmatter.apply_globally); // Default behavior
         assert!(rule.frontmatter.cursor_rule_type.is_none());
         assert_eq!(rule.content, "Content after empty frontmatter.");
     }
 
+    /// Test parsing rule content that was not read from a file, such as stdin.
+    #[test]
+    fn test_parse_rule_content() {
+        let rule = parse_rule_content(
+            Path::new("style.md"),
+            "---\ndescription: Style\n---\nContent.\n",
+            false,
+        )
+        .unwrap();
+        assert_eq!(rule.name, "style");
+        assert_eq!(rule.frontmatter.description, Some("Style".to_string()));
+        assert_eq!(rule.content, "Content.");
+    }
+
+    /// Test that `extra` holds arbitrary metadata without warnings and wins over unknown
+    /// keys of the same name.
+    #[test]
+    fn test_parse_rule_content_extra() {
+        let content = "---\nextra:\n  jira: PLAT-123\n  reviewers: [ana]\njira: PLAT-1\nteam: web\n---\nContent.\n";
+        let rule = parse_rule_content(Path::new("style.md"), content, false).unwrap();
+        assert_eq!(rule.frontmatter.unknown_keys, ["jira", "team"]);
+        assert_eq!(
+            rule.frontmatter.extra.get("jira"),
+            Some(&Value::String("PLAT-123".to_string()))
+        );
+        assert_eq!(
+            rule.frontmatter.extra.get("team"),
+            Some(&Value::String("web".to_string()))
+        );
+        assert!(rule.frontmatter.extra["reviewers"].is_sequence());
+
+        parse_rule_content(
+            Path::new("style.md"),
+            "---\nextra:\n  jira: PLAT-123\n---\nContent.\n",
+            true,
+        )
+        .unwrap();
+    }
+
+    /// Test that unknown frontmatter keys, including nested ones, only fail when denied.
+    #[test]
+    fn test_parse_rule_content_unknown_keys() {
+        let content = "---\naplly_globally: true\nsubagent:\n  tols: [Read]\n---\nContent.\n";
+        let rule = parse_rule_content(Path::new("style.md"), content, false).unwrap();
+        assert!(!rule.frontmatter.apply_globally);
+        assert_eq!(
+            rule.frontmatter.unknown_keys,
+            ["aplly_globally", "subagent.tols"]
+        );
+        let warnings = rule_warnings(&rule);
+        assert_eq!(warnings.len(), 2);
+        assert!(
+            warnings[0].contains("\"style.md\" has an unknown frontmatter key `aplly_globally`")
+        );
+        // Unknown top-level keys are kept, nested ones are not
+        assert_eq!(
+            rule.frontmatter.extra,
+            BTreeMap::from([("aplly_globally".to_string(), Value::Bool(true))])
+        );
+
+        let error = parse_rule_content(Path::new("style.md"), content, true).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Unknown frontmatter key(s) `aplly_globally`, `subagent.tols`"));
+        // Aliases are known keys
+        parse_rule_content(
+            Path::new("style.md"),
+            "---\napplyGlobally: true\n---\nContent.\n",
+            true,
+        )
+        .unwrap();
+    }
+
+    /// Test that an unknown `cursor_rule_type` fails to parse, listing the accepted values.
+    #[test]
+    fn test_parse_rule_content_invalid_cursor_rule_type() {
+        let error = parse_rule_content(
+            Path::new("style.md"),
+            "---\ncursor_rule_type: lint\n---\nContent.\n",
+            false,
+        )
+        .unwrap_err();
+        assert!(format!("{:#}", error).contains(
+            "unknown variant `lint`, expected one of `Always`, `AutoAttached`, `AgentRequested`, `Manual`"
+        ));
+    }
+
+    /// Test that `scope` must be a plain relative directory.
+    #[test]
+    fn test_parse_rule_content_scope() {
+        let parse = |scope: &str| {
+            parse_rule_content(
+                Path::new("api.md"),
+                &format!("---\nscope: {}\n---\nContent.\n", scope),
+                false,
+            )
+        };
+        let rule = parse("packages/api").unwrap();
+        assert_eq!(rule.scope_dir(), PathBuf::from("packages/api"));
+        for invalid in ["../api", "/srv/api"] {
+            let error = parse(invalid).unwrap_err();
+            assert!(error.to_string().contains("has an invalid `scope`"));
+        }
+    }
+
+    /// Test that `output_name` must be a plain file name.
+    #[test]
+    fn test_parse_rule_content_output_name() {
+        let parse = |output_name: &str| {
+            parse_rule_content(
+                Path::new("api.md"),
+                &format!("---\noutput_name: '{}'\n---\nContent.\n", output_name),
+                false,
+            )
+        };
+        assert_eq!(
+            parse("api-guidelines").unwrap().output_stem(),
+            "api-guidelines"
+        );
+        for invalid in ["", "docs/api", "..\\api", ".."] {
+            let error = parse(invalid).unwrap_err();
+            assert!(error.to_string().contains("has an invalid `output_name`"));
+        }
+
+        let rule = parse_rule_content(
+            Path::new("api.md"),
+            "---\naliases: [endpoints, api, endpoints]\n---\nContent.\n",
+            false,
+        )
+        .unwrap();
+        assert_eq!(rule.output_stems(), vec!["api", "endpoints"]);
+        let error = parse_rule_content(
+            Path::new("api.md"),
+            "---\naliases: [docs/api]\n---\nContent.\n",
+            false,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("has an invalid alias"));
+    }
+
+    /// Test that `claude.heading_level` must be a Markdown heading level.
+    #[test]
+    fn test_parse_rule_content_claude_heading_level() {
+        let parse = |level: u8| {
+            parse_rule_content(
+                Path::new("api.md"),
+                &format!("---\nclaude:\n  heading_level: {}\n---\nContent.\n", level),
+                false,
+            )
+        };
+        assert!(parse(3).is_ok());
+        for invalid in [0, 7] {
+            let error = parse(invalid).unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("has an invalid `claude.heading_level`"));
+        }
+    }
+
+    /// Test that newer schema versions are rejected and older ones are warned about.
+    #[test]
+    fn test_parse_rule_schema_version() {
+        let parse = |schema_version: u32| {
+            parse_rule_content(
+                Path::new("style.md"),
+                &format!(
+                    "---\nschema_version: {}\nversion: \"2.0\"\n---\nContent.\n",
+                    schema_version
+                ),
+                false,
+            )
+        };
+        let current = parse(SCHEMA_VERSION).unwrap();
+        assert_eq!(current.frontmatter.version, Some("2.0".to_string()));
+        assert!(rule_warnings(&current).is_empty());
+
+        let error = parse(SCHEMA_VERSION + 1).unwrap_err();
+        assert!(error.to_string().contains("upgrade urules"));
+
+        let outdated = parse(SCHEMA_VERSION - 1).unwrap();
+        assert!(rule_warnings(&outdated)[0].contains("older than the current version"));
+    }
+
+    /// Test that include directives are inlined relative to the including file, except in
+    /// code blocks, and that include cycles fail.
+    #[test]
+    fn test_parse_rule_file_with_includes() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("shared")).unwrap();
+        fs::write(
+            dir.path().join("shared").join("style.md"),
+            "---\ndescription: Shared\n---\nUse tabs.\n<!-- include: naming.md -->\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("shared").join("naming.md"),
+            "Use snake_case.",
+        )
+        .unwrap();
+        let rule_path = dir.path().join("rust.md");
+        fs::write(
+            &rule_path,
+            "# Rust\n\n{{include \"shared/style.md\"}}\n\n```md\n{{include \"missing.md\"}}\n```",
+        )
+        .unwrap();
+
+        let rule = parse_rule_file(&rule_path, false).unwrap();
+        assert_eq!(
+            rule.content,
+            "# Rust\n\nUse tabs.\nUse snake_case.\n\n```md\n{{include \"missing.md\"}}\n```"
+        );
+        assert_eq!(include_directive("{{ include \"a.md\" }}"), Some("a.md"));
+        assert_eq!(include_directive("Text {{include \"a.md\"}}"), None);
+
+        fs::write(
+            dir.path().join("shared").join("naming.md"),
+            "{{include \"style.md\"}}",
+        )
+        .unwrap();
+        let error = parse_rule_file(&rule_path, false).unwrap_err();
+        assert!(error.to_string().contains("Include cycle"));
+    }
+
     /// Test parsing a rule file with malformed YAML in its frontmatter.
     /// Expects a parsing error.
     #[test]
@@ -194,13 +1051,13 @@ Content after empty frontmatter."
 description: A test rule
 globs: [\"*.rs\" 
 apply_globally: true 
-cursor_rule_type: \"lint\"
+cursor_rule_type: \"AgentRequested\"
 ---
 Rule content here."
         )
         .unwrap(); // Malformed YAML: globs array is not closed
 
-        let result = parse_rule_file(&file_path);
+        let result = parse_rule_file(&file_path, false);
         assert!(
             result.is_err(),
             "Parsing should fail for malformed frontmatter"
@@ -246,7 +1103,7 @@ Rule content here."
         )
         .unwrap();
 
-        let rules = discover_and_parse_rules(&rules_subdir).unwrap();
+        let rules = discover_and_parse_rules(&rules_subdir, &DiscoveryOptions::default()).unwrap();
         // Expect rule1, rule2, and rule5 to be parsed. rule3 has malformed YAML.
         assert_eq!(rules.len(), 3, "Expected 3 valid rules to be parsed.");
 
@@ -258,5 +1115,281 @@ Rule content here."
             && r.frontmatter.description.is_none()));
         assert!(rules.iter().any(|r| r.name == "rule5"
             && r.frontmatter.description == Some("Rule 5 in nested dir".to_string())));
+
+        // Nested rules remember their directory relative to the rules directory
+        let rule5 = rules.iter().find(|r| r.name == "rule5").unwrap();
+        assert_eq!(rule5.relative_dir, PathBuf::from("nested"));
+        let rule1 = rules.iter().find(|r| r.name == "rule1").unwrap();
+        assert_eq!(rule1.relative_dir, PathBuf::new());
+    }
+
+    /// Test that files failing to parse are returned with their error.
+    #[test]
+    fn test_discover_rules_reports_failures() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("good.md"), "Content").unwrap();
+        fs::write(dir.path().join("bad.md"), "---\nglobs: [\n---\nContent").unwrap();
+
+        let (rules, failures) = discover_rules(dir.path(), &DiscoveryOptions::default()).unwrap();
+
+        assert_eq!(rules.len(), 1);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].path, dir.path().join("bad.md"));
+        assert!(failures[0]
+            .error
+            .contains("Failed to parse YAML frontmatter"));
+    }
+
+    /// Test that rules are ordered by priority, then by path.
+    #[test]
+    fn test_discover_rules_order() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("a")).unwrap();
+        fs::write(dir.path().join("b.md"), "B").unwrap();
+        fs::write(dir.path().join("a/z.md"), "Z").unwrap();
+        fs::write(dir.path().join("a-b.md"), "AB").unwrap();
+        fs::write(dir.path().join("c.md"), "---\npriority: 10\n---\nC").unwrap();
+        fs::write(dir.path().join("d.md"), "---\npriority: -1\n---\nD").unwrap();
+
+        let (rules, _) = discover_rules(dir.path(), &DiscoveryOptions::default()).unwrap();
+
+        let paths: Vec<PathBuf> = rules.iter().map(|r| r.relative_path()).collect();
+        assert_eq!(
+            paths,
+            ["c.md", "a-b.md", "a/z.md", "b.md", "d.md"].map(PathBuf::from)
+        );
+    }
+
+    /// Test that partials included by a rule, a template or another partial are
+    /// referenced, unlike the others.
+    #[test]
+    fn test_unreferenced_partials() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("_partials/shared")).unwrap();
+        fs::create_dir_all(dir.path().join("backend")).unwrap();
+        for (file, content) in [
+            ("style.md", "Style.\n<!-- include: _partials/tone.md -->\n"),
+            (
+                "backend/api.md",
+                "---\ntemplate: true\n---\n{%- include 'shared/footer.md' %}\n```\n{{include \"../_partials/example.md\"}}\n```\n",
+            ),
+            ("_partials/tone.md", "{{include \"shared/voice.md\"}}\n"),
+            ("_partials/shared/voice.md", "Be brief.\n"),
+            ("_partials/shared/footer.md", "Thanks.\n"),
+            ("_partials/example.md", "Unused.\n"),
+            ("_partials/old.md", "Unused.\n"),
+        ] {
+            fs::write(dir.path().join(file), content).unwrap();
+        }
+
+        assert_eq!(
+            unreferenced_partials(dir.path(), &DiscoveryOptions::default()),
+            [
+                PathBuf::from("_partials/example.md"),
+                PathBuf::from("_partials/old.md")
+            ]
+        );
+        assert!(
+            unreferenced_partials(&dir.path().join("backend"), &DiscoveryOptions::default())
+                .is_empty()
+        );
+    }
+
+    /// Test that discovery skips heavy directories and honors the depth and patterns.
+    #[test]
+    fn test_rule_files_options() {
+        let dir = tempdir().unwrap();
+        for file in [
+            "top.md",
+            "notes.txt",
+            "frontend/react.md",
+            "frontend/deep/hooks.md",
+            "node_modules/pkg/README.md",
+            "target/doc.md",
+            "assets/setup.md",
+            "_partials/footer.md",
+            "frontend/assets/forms.md",
+        ] {
+            let path = dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "Content").unwrap();
+        }
+        let files = |options: &DiscoveryOptions| -> Vec<String> {
+            rule_files(dir.path(), options)
+                .iter()
+                .map(|path| {
+                    let relative_path = path.strip_prefix(dir.path()).unwrap();
+                    relative_path.to_string_lossy().replace('\\', "/")
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            files(&DiscoveryOptions::default()),
+            [
+                "frontend/assets/forms.md",
+                "frontend/deep/hooks.md",
+                "frontend/react.md",
+                "top.md"
+            ]
+        );
+        let shallow = DiscoveryOptions {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(files(&shallow), ["frontend/react.md", "top.md"]);
+        let frontend = DiscoveryOptions {
+            include_pattern: vec![Pattern::new("frontend/**/*.md").unwrap()],
+            ..Default::default()
+        };
+        assert_eq!(
+            files(&frontend),
+            [
+                "frontend/assets/forms.md",
+                "frontend/deep/hooks.md",
+                "frontend/react.md"
+            ]
+        );
+
+        // The rules directory itself is walked even if it has an excluded name
+        let vendor = dir.path().join("frontend").join("deep");
+        assert_eq!(rule_files(&vendor, &DiscoveryOptions::default()).len(), 1);
+    }
+
+    /// Test that `--only` and `--exclude` patterns match rule names and relative paths.
+    #[test]
+    fn test_filter_rules() {
+        let rule = |name: &str, relative_dir: &str| UniversalRule {
+            name: name.to_string(),
+            relative_dir: PathBuf::from(relative_dir),
+            ..Default::default()
+        };
+        let rules = vec![
+            rule("style", ""),
+            rule("hooks", "frontend/react"),
+            rule("testing", "frontend"),
+        ];
+        let patterns = |globs: &[&str]| -> Vec<Pattern> {
+            globs.iter().map(|g| Pattern::new(g).unwrap()).collect()
+        };
+        let names = |rules: &[UniversalRule]| -> Vec<String> {
+            rules.iter().map(|r| r.name.clone()).collect()
+        };
+
+        let (kept, filtered_out) = filter_rules(rules.clone(), &patterns(&["frontend/**"]), &[]);
+        assert_eq!(names(&kept), vec!["hooks", "testing"]);
+        assert_eq!(names(&filtered_out), vec!["style"]);
+
+        let (kept, _) = filter_rules(
+            rules.clone(),
+            &patterns(&["frontend/**", "style"]),
+            &patterns(&["frontend/react/hooks.md"]),
+        );
+        assert_eq!(names(&kept), vec!["style", "testing"]);
+
+        let (kept, _) = filter_rules(rules, &[], &patterns(&["test*"]));
+        assert_eq!(names(&kept), vec!["style", "hooks"]);
+    }
+
+    /// Test that rules are expired after their `expires` date, not on it.
+    #[test]
+    fn test_filter_expired() {
+        let date = |d: &str| d.parse::<NaiveDate>().unwrap();
+        let rule = |name: &str, expires: Option<&str>| {
+            parse_rule_content(
+                Path::new(&format!("{}.md", name)),
+                &match expires {
+                    Some(expires) => format!("---\nexpires: {}\n---\nContent.\n", expires),
+                    None => "Content.\n".to_string(),
+                },
+                true,
+            )
+            .unwrap()
+        };
+        let rules = vec![
+            rule("migration", Some("2025-12-31")),
+            rule("style", None),
+            rule("old", Some("2025-06-30")),
+        ];
+
+        let (current, expired) = filter_expired(rules, date("2025-12-31"));
+        let names = |rules: &[UniversalRule]| -> Vec<String> {
+            rules.iter().map(|r| r.name.clone()).collect()
+        };
+        assert_eq!(names(&current), vec!["migration", "style"]);
+        assert_eq!(names(&expired), vec!["old"]);
+    }
+
+    /// Test that rules are only kept if the variable they require is set.
+    #[test]
+    fn test_filter_env_gated() {
+        let rule = |name: &str, content: &str| {
+            parse_rule_content(Path::new(&format!("{}.md", name)), content, true).unwrap()
+        };
+        let rules = vec![
+            rule(
+                "internal",
+                "---\nrequires_env: ACME_INTERNAL\n---\nContent.\n",
+            ),
+            rule("style", "Content.\n"),
+            rule("ci", "---\nrequires_env: CI\n---\nContent.\n"),
+        ];
+
+        let (kept, gated) = filter_env_gated(rules, |name| name == "CI");
+        let names = |rules: &[UniversalRule]| -> Vec<String> {
+            rules.iter().map(|r| r.name.clone()).collect()
+        };
+        assert_eq!(names(&kept), vec!["style", "ci"]);
+        assert_eq!(names(&gated), vec!["internal"]);
+    }
+
+    /// Test that directory defaults fill unset keys, with nearer directories and the
+    /// rules themselves winning.
+    #[test]
+    fn test_discover_rules_directory_defaults() {
+        let dir = tempdir().unwrap();
+        let frontend = dir.path().join("frontend");
+        fs::create_dir_all(&frontend).unwrap();
+        fs::write(
+            dir.path().join(DIRECTORY_DEFAULTS_FILE),
+            "tags: [team]\nagents: [claude]\n",
+        )
+        .unwrap();
+        fs::write(
+            frontend.join(DIRECTORY_DEFAULTS_FILE),
+            "globs: ['**/*.tsx']\nagents: [cursor]\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("style.md"), "Be consistent.\n").unwrap();
+        fs::write(frontend.join("react.md"), "Use hooks.\n").unwrap();
+        fs::write(
+            frontend.join("css.md"),
+            "---\nglobs: ['**/*.css']\n---\nUse variables.\n",
+        )
+        .unwrap();
+
+        let (rules, failures) = discover_rules(dir.path(), &DiscoveryOptions::default()).unwrap();
+        assert!(failures.is_empty());
+        let rule = |name: &str| rules.iter().find(|r| r.name == name).unwrap();
+        assert_eq!(
+            rule("style").frontmatter.agents,
+            Some(vec![AgentName::Claude])
+        );
+        assert_eq!(
+            rule("react").frontmatter.agents,
+            Some(vec![AgentName::Cursor])
+        );
+        assert_eq!(
+            rule("react").frontmatter.tags,
+            Some(vec!["team".to_string()])
+        );
+        assert_eq!(
+            rule("react").frontmatter.globs,
+            Some(vec!["**/*.tsx".to_string()])
+        );
+        assert_eq!(
+            rule("css").frontmatter.globs,
+            Some(vec!["**/*.css".to_string()])
+        );
     }
 }
@@ -0,0 +1,457 @@
+// src/template.rs
+
+use crate::git::{current_branch, find_git_dir};
+use crate::line_endings::normalize_line_endings;
+use crate::AgentName;
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use minijinja::{Environment, UndefinedBehavior, Value};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The project information exposed to rule templates as `project`.
+#[derive(Serialize, Debug, Default)]
+pub struct ProjectContext {
+    /// The rules directory, as given or configured.
+    pub rules_dir: String,
+
+    /// The output directory, as given or configured.
+    pub output_dir: String,
+
+    /// The agents the outputs are generated for.
+    pub agents: Vec<AgentName>,
+}
+
+/// Returns `true` if `name` can be referenced as a variable: letters, digits, `_` and `-`.
+fn is_var_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Replaces the `{{name}}` references of `text` with the value of `name` in `vars`.
+///
+/// Spaces inside the braces are allowed (`{{ name }}`). References to names missing from
+/// `vars`, and braces around anything else, are kept as they are, so that rules can still
+/// show template syntax of other tools.
+pub fn substitute_vars(text: &str, vars: &BTreeMap<String, String>) -> String {
+    if vars.is_empty() {
+        return text.to_string();
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find("}}") else {
+            break;
+        };
+        let name = rest[2..end].trim();
+        match vars.get(name).filter(|_| is_var_name(name)) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[..end + 2]),
+        }
+        rest = &rest[end + 2..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Returns the `name` of the package manifest (`Cargo.toml` or `package.json`) in `dir`,
+/// if there is one.
+fn manifest_name(dir: &Path) -> Option<String> {
+    if let Ok(content) = fs::read_to_string(dir.join("Cargo.toml")) {
+        let manifest: toml::Table = toml::from_str(&content).ok()?;
+        return Some(manifest.get("package")?.get("name")?.as_str()?.to_string());
+    }
+    let content = fs::read_to_string(dir.join("package.json")).ok()?;
+    let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+    Some(manifest.get("name")?.as_str()?.to_string())
+}
+
+/// Returns the built-in variables of the project generated into `output_dir`:
+///
+/// * `project_name`: the name of its `Cargo.toml` or `package.json` package (in
+///   `output_dir` or the repository root), or else the name of the repository root or of
+///   `output_dir`.
+/// * `repo_root` and `git_branch`: the root and the current branch of the git repository
+///   containing `output_dir`, if any.
+/// * `generation_date`: `today`, left out if `None` so that outputs are reproducible.
+pub fn builtin_vars(output_dir: &Path, today: Option<NaiveDate>) -> BTreeMap<String, String> {
+    let dir: PathBuf = output_dir
+        .canonicalize()
+        .or_else(|_| std::path::absolute(output_dir))
+        .unwrap_or_else(|_| output_dir.to_path_buf());
+    let git = find_git_dir(&dir).ok();
+    let repo_root = git.as_ref().map(|(root, _)| root.as_path());
+
+    let mut vars = BTreeMap::new();
+    let project_name = manifest_name(&dir)
+        .or_else(|| repo_root.and_then(manifest_name))
+        .or_else(|| {
+            let name = repo_root.unwrap_or(&dir).file_name()?;
+            Some(name.to_string_lossy().into_owned())
+        });
+    if let Some(project_name) = project_name {
+        vars.insert("project_name".to_string(), project_name);
+    }
+    if let Some((root, git_dir)) = &git {
+        vars.insert("repo_root".to_string(), root.display().to_string());
+        if let Some(branch) = current_branch(git_dir) {
+            vars.insert("git_branch".to_string(), branch);
+        }
+    }
+    if let Some(today) = today {
+        vars.insert("generation_date".to_string(), today.to_string());
+    }
+    vars
+}
+
+/// Renders `source` as a minijinja template with `context`.
+///
+/// Printing an undefined value is an error, so that misspelled names are caught, while
+/// conditions such as `{% if rule.tags %}` may test optional values. Templates named by
+/// `{% include %}` and `{% import %}` are the files of `partials_dir` (e.g.
+/// `{% include "style.md" %}`), without their frontmatter.
+pub fn render_template(
+    source: &str,
+    context: Value,
+    partials_dir: &Path,
+) -> Result<String, minijinja::Error> {
+    let mut env = Environment::new();
+    env.set_keep_trailing_newline(true);
+    env.set_undefined_behavior(UndefinedBehavior::SemiStrict);
+    let partials_dir = partials_dir.to_path_buf();
+    env.set_loader(move |name| {
+        let path = Path::new(name);
+        if path.is_absolute() || path.components().any(|c| c.as_os_str() == "..") {
+            return Ok(None);
+        }
+        match fs::read_to_string(partials_dir.join(path)) {
+            Ok(partial) => Ok(Some(
+                crate::rule_parser::split_frontmatter(&normalize_line_endings(&partial))
+                    .1
+                    .to_string(),
+            )),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(minijinja::Error::new(
+                minijinja::ErrorKind::InvalidOperation,
+                format!("Failed to read partial {:?}", partials_dir.join(path)),
+            )
+            .with_source(error)),
+        }
+    });
+    env.render_str(source, context)
+}
+
+/// Replaces the `${NAME}` references of `text` with the values `lookup` returns for
+/// `NAME` (letters, digits and `_`), writing `$${NAME}` as a literal `${NAME}`.
+///
+/// Fails with the names `lookup` has no value for, each listed once.
+pub fn interpolate_env(
+    text: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, Vec<String>> {
+    let mut result = String::with_capacity(text.len());
+    let mut undefined: Vec<String> = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        let escaped = rest[..start].ends_with('$');
+        result.push_str(&rest[..start - usize::from(escaped)]);
+        rest = &rest[start..];
+        let name = rest[2..]
+            .find('}')
+            .map(|end| &rest[2..2 + end])
+            .filter(|name| {
+                !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            });
+        let Some(name) = name else {
+            result.push_str("${");
+            rest = &rest[2..];
+            continue;
+        };
+        match lookup(name) {
+            _ if escaped => result.push_str(&rest[..name.len() + 3]),
+            Some(value) => result.push_str(&value),
+            None => {
+                if !undefined.iter().any(|n| n == name) {
+                    undefined.push(name.to_string());
+                }
+            }
+        }
+        rest = &rest[name.len() + 3..];
+    }
+    result.push_str(rest);
+    if undefined.is_empty() {
+        Ok(result)
+    } else {
+        Err(undefined)
+    }
+}
+
+/// Evaluates the condition of an `urules:if` marker, `agent == "name"` or
+/// `agent != "name"`, for `agent`.
+fn agent_condition(condition: &str, agent: &AgentName) -> Result<bool, String> {
+    let operands = condition.strip_prefix("agent").map(str::trim_start);
+    let (negated, value) = match operands {
+        Some(rest) if rest.starts_with("==") => (false, &rest[2..]),
+        Some(rest) if rest.starts_with("!=") => (true, &rest[2..]),
+        _ => {
+            return Err(format!(
+                "unsupported condition `{}`; expected `agent == \"name\"` or `agent != \"name\"`",
+                condition
+            ))
+        }
+    };
+    let value = value.trim();
+    let name = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a quoted agent name, got `{}`", value))?;
+    let named = AgentName::from_str(name, true)
+        .ok()
+        .filter(|named| *named != AgentName::All)
+        .ok_or_else(|| format!("unknown agent `{}`", name))?;
+    Ok((named == *agent) != negated)
+}
+
+/// Keeps the passages of `text` between `<!-- urules:if agent == "name" -->` and
+/// `<!-- urules:endif -->` markers only for the agent they name (or, with `!=`, for the
+/// others), with an optional `<!-- urules:else -->` passage for the rest.
+///
+/// Markers stand on lines of their own and are removed from the output; blocks can be
+/// nested, and markers inside fenced code blocks are kept as they are. Fails on a
+/// malformed or unbalanced marker, naming its line.
+pub fn select_agent_blocks(text: &str, agent: &AgentName) -> Result<String, String> {
+    if !text.contains("<!-- urules:") {
+        return Ok(text.to_string());
+    }
+    let mut output = String::with_capacity(text.len());
+    // For each open block: whether its current passage is kept, and whether it had an `else`
+    let mut blocks: Vec<(bool, bool)> = Vec::new();
+    let mut in_fence = false;
+    for (index, line) in text.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        let marker = trimmed.strip_prefix("<!-- urules:").filter(|_| !in_fence);
+        let Some(marker) = marker else {
+            if blocks.iter().all(|(kept, _)| *kept) {
+                output.push_str(line);
+            }
+            continue;
+        };
+        let at_line = |message: String| format!("line {}: {}", index + 1, message);
+        let directive = marker
+            .strip_suffix("-->")
+            .map(str::trim)
+            .ok_or_else(|| at_line("the marker is not closed with `-->`".to_string()))?;
+        match directive {
+            "else" => match blocks.last_mut() {
+                Some((_, true)) => return Err(at_line("a second `urules:else`".to_string())),
+                Some(block) => *block = (!block.0, true),
+                None => return Err(at_line("`urules:else` outside of a block".to_string())),
+            },
+            "endif" => {
+                blocks
+                    .pop()
+                    .ok_or_else(|| at_line("`urules:endif` without `urules:if`".to_string()))?;
+            }
+            _ => match directive.strip_prefix("if ") {
+                Some(condition) => blocks.push((
+                    agent_condition(condition.trim(), agent).map_err(at_line)?,
+                    false,
+                )),
+                None => return Err(at_line(format!("unknown marker `urules:{}`", directive))),
+            },
+        }
+    }
+    if !blocks.is_empty() {
+        return Err(format!(
+            "{} `urules:if` block(s) are not closed with `<!-- urules:endif -->`",
+            blocks.len()
+        ));
+    }
+    Ok(output)
+}
+
+/// Parses a `--set KEY=VALUE` argument.
+pub fn parse_var_assignment(assignment: &str) -> Result<(String, String), String> {
+    match assignment.split_once('=') {
+        Some((key, value)) if is_var_name(key.trim()) => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!(
+            "expected KEY=VALUE with a KEY of letters, digits, `_` and `-`, got {:?}",
+            assignment
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that known variables are replaced and everything else is kept.
+    #[test]
+    fn test_substitute_vars() {
+        let vars = BTreeMap::from([
+            ("project".to_string(), "Acme".to_string()),
+            ("src-dir".to_string(), "crates/".to_string()),
+        ]);
+        assert_eq!(
+            substitute_vars(
+                "{{project}} code lives in {{ src-dir }}, see {{unknown}} and {{ a b }}.",
+                &vars
+            ),
+            "Acme code lives in crates/, see {{unknown}} and {{ a b }}."
+        );
+        assert_eq!(substitute_vars("Open {{project", &vars), "Open {{project");
+    }
+
+    /// Test that templates see their context and reject undefined values.
+    #[test]
+    fn test_render_template() {
+        let context = minijinja::context! { project => "Acme", tags => vec!["web"] };
+        assert_eq!(
+            render_template(
+                "{{ project }}{% if tags %} ({{ tags | join(', ') }}){% endif %}\n",
+                context.clone(),
+                Path::new("_partials")
+            )
+            .unwrap(),
+            "Acme (web)\n"
+        );
+        let no_partials = Path::new("_partials");
+        assert!(
+            render_template("{% if missing %}x{% endif %}", context.clone(), no_partials).is_ok()
+        );
+        let error = render_template("{{ projct }}", context.clone(), no_partials).unwrap_err();
+        assert_eq!(error.kind(), minijinja::ErrorKind::UndefinedError);
+
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("footer.md"),
+            "---\nowner: web\n---\nBy {{ project }}.\n",
+        )
+        .unwrap();
+        assert_eq!(
+            render_template(
+                "Rules.\n{% include \"footer.md\" %}",
+                context.clone(),
+                dir.path()
+            )
+            .unwrap(),
+            "Rules.\nBy Acme."
+        );
+        let error =
+            render_template("{% include \"../secret.md\" %}", context, dir.path()).unwrap_err();
+        assert_eq!(error.kind(), minijinja::ErrorKind::TemplateNotFound);
+    }
+
+    /// Test that the built-in variables come from the package manifest and git.
+    #[test]
+    fn test_builtin_vars() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("acme");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        fs::write(
+            repo.join(".git").join("HEAD"),
+            "ref: refs/heads/feature/x\n",
+        )
+        .unwrap();
+        let web = repo.join("web");
+        fs::create_dir_all(&web).unwrap();
+        fs::write(web.join("package.json"), r#"{"name": "acme-web"}"#).unwrap();
+
+        let vars = builtin_vars(&web, NaiveDate::from_ymd_opt(2025, 7, 1));
+        assert_eq!(vars["project_name"], "acme-web");
+        assert_eq!(vars["git_branch"], "feature/x");
+        assert_eq!(vars["generation_date"], "2025-07-01");
+        assert!(vars["repo_root"].ends_with("acme"));
+
+        // Without a manifest, the repository is named after its directory
+        fs::write(repo.join(".git").join("HEAD"), "0123abcd\n").unwrap();
+        let vars = builtin_vars(&repo.join("docs"), None);
+        assert_eq!(vars["project_name"], "acme");
+        assert!(!vars.contains_key("git_branch"));
+        assert!(!vars.contains_key("generation_date"));
+    }
+
+    /// Test that environment variables are interpolated, escapes kept and undefined
+    /// variables listed.
+    #[test]
+    fn test_interpolate_env() {
+        let lookup = |name: &str| (name == "ORG").then(|| "Acme".to_string());
+        assert_eq!(
+            interpolate_env("${ORG} at $${ORG}, ${not a name} and ${", lookup),
+            Ok("Acme at ${ORG}, ${not a name} and ${".to_string())
+        );
+        assert_eq!(
+            interpolate_env("${HOME_DIR}/${ORG}/${TEAM}/${HOME_DIR}", lookup),
+            Err(vec!["HOME_DIR".to_string(), "TEAM".to_string()])
+        );
+    }
+
+    /// Test that agent blocks are kept only for the agents they name, with their markers
+    /// removed, and that malformed markers are reported with their line.
+    #[test]
+    fn test_select_agent_blocks() {
+        let text = "Use tabs.\n<!-- urules:if agent == \"cursor\" -->\nSee @docs.\n<!-- urules:else -->\nSee docs/.\n<!-- urules:endif -->\n<!-- urules:if agent != \"claude\" -->\n  <!-- urules:if agent == \"windsurf\" -->\nSurf.\n  <!-- urules:endif -->\n<!-- urules:endif -->\nDone.\n";
+        assert_eq!(
+            select_agent_blocks(text, &AgentName::Cursor).unwrap(),
+            "Use tabs.\nSee @docs.\nDone.\n"
+        );
+        assert_eq!(
+            select_agent_blocks(text, &AgentName::Windsurf).unwrap(),
+            "Use tabs.\nSee docs/.\nSurf.\nDone.\n"
+        );
+        assert_eq!(
+            select_agent_blocks(text, &AgentName::Claude).unwrap(),
+            "Use tabs.\nSee docs/.\nDone.\n"
+        );
+        let fenced = "```\n<!-- urules:endif -->\n```\n";
+        assert_eq!(
+            select_agent_blocks(fenced, &AgentName::Zed).unwrap(),
+            fenced
+        );
+
+        for (text, error) in [
+            (
+                "<!-- urules:if agent == \"vim\" -->\n",
+                "line 1: unknown agent `vim`",
+            ),
+            (
+                "a\n<!-- urules:if tool == \"cursor\" -->\n",
+                "line 2: unsupported condition `tool == \"cursor\"`",
+            ),
+            ("<!-- urules:endif -->\n", "line 1: `urules:endif` without"),
+            (
+                "<!-- urules:if agent == \"zed\" -->\nx\n",
+                "1 `urules:if` block(s) are not closed",
+            ),
+        ] {
+            let message = select_agent_blocks(text, &AgentName::Zed).unwrap_err();
+            assert!(message.starts_with(error), "{}", message);
+        }
+    }
+
+    /// Test parsing `--set` arguments.
+    #[test]
+    fn test_parse_var_assignment() {
+        assert_eq!(
+            parse_var_assignment("project=Acme Corp"),
+            Ok(("project".to_string(), "Acme Corp".to_string()))
+        );
+        assert_eq!(
+            parse_var_assignment("url=https://x.org/?a=b"),
+            Ok(("url".to_string(), "https://x.org/?a=b".to_string()))
+        );
+        assert!(parse_var_assignment("project").is_err());
+        assert!(parse_var_assignment("=Acme").is_err());
+    }
+}
@@ -0,0 +1,131 @@
+// src/tokens.rs
+
+use crate::template::select_agent_blocks;
+use crate::universal_rule::UniversalRule;
+use crate::{skipped_rule_reason, AgentName};
+use std::collections::BTreeMap;
+
+/// Estimates how many tokens `text` takes for a language model, at about four characters
+/// per token as for English prose and code. Good enough for budgets, not for billing.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Returns a message for each rule whose content takes more tokens than its `max_tokens`,
+/// and for each agent whose emitted rules together take more than its budget in
+/// `budgets`, listing those rules from the largest.
+pub fn budget_problems(
+    rules: &[UniversalRule],
+    agents: &[AgentName],
+    budgets: &BTreeMap<AgentName, usize>,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    for rule in rules {
+        let tokens = estimate_tokens(&rule.content);
+        if let Some(max_tokens) = rule.frontmatter.max_tokens.filter(|max| tokens > *max) {
+            problems.push(format!(
+                "Rule {:?} takes about {} tokens, over its `max_tokens` of {}",
+                rule.relative_path(),
+                tokens,
+                max_tokens
+            ));
+        }
+    }
+
+    for agent in agents {
+        let Some(budget) = budgets.get(agent) else {
+            continue;
+        };
+        let mut sizes: Vec<(&str, usize)> = rules
+            .iter()
+            .filter(|rule| skipped_rule_reason(agent, rule).is_none())
+            .map(|rule| {
+                // Count only the agent blocks this agent receives
+                let content = select_agent_blocks(&rule.content, agent)
+                    .unwrap_or_else(|_| rule.content.clone());
+                (rule.name.as_str(), estimate_tokens(&content))
+            })
+            .collect();
+        let total: usize = sizes.iter().map(|(_, tokens)| tokens).sum();
+        if total <= *budget {
+            continue;
+        }
+        sizes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        let listed: Vec<String> = sizes
+            .iter()
+            .map(|(name, tokens)| format!("{} ({})", name, tokens))
+            .collect();
+        problems.push(format!(
+            "The {} rules take about {} tokens, over the budget of {} in `token_budgets`: {}",
+            agent,
+            total,
+            budget,
+            listed.join(", ")
+        ));
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::universal_rule::UniversalRuleFrontmatter;
+
+    fn rule(name: &str, content: &str, frontmatter: UniversalRuleFrontmatter) -> UniversalRule {
+        UniversalRule {
+            name: name.to_string(),
+            content: content.to_string(),
+            frontmatter,
+            ..Default::default()
+        }
+    }
+
+    /// Test that the estimate rounds up to whole tokens.
+    #[test]
+    fn test_estimate_tokens() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcd"), 1);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
+
+    /// Test that rules over their `max_tokens` and agents over their budget are reported,
+    /// counting only the rules each agent receives.
+    #[test]
+    fn test_budget_problems() {
+        let rules = vec![
+            rule(
+                "style",
+                &"a".repeat(40),
+                UniversalRuleFrontmatter {
+                    max_tokens: Some(5),
+                    ..Default::default()
+                },
+            ),
+            rule("api", &"a".repeat(20), UniversalRuleFrontmatter::default()),
+            rule(
+                "cursor_only",
+                &"a".repeat(400),
+                UniversalRuleFrontmatter {
+                    agents: Some(vec![AgentName::Cursor]),
+                    ..Default::default()
+                },
+            ),
+        ];
+        let budgets = BTreeMap::from([(AgentName::Claude, 12)]);
+
+        let problems = budget_problems(&rules, &[AgentName::Claude], &budgets);
+        assert_eq!(
+            problems,
+            [
+                "Rule \"style.md\" takes about 10 tokens, over its `max_tokens` of 5",
+                "The Claude rules take about 15 tokens, over the budget of 12 in `token_budgets`: style (10), api (5)",
+            ]
+        );
+
+        let budgets = BTreeMap::from([(AgentName::Claude, 15)]);
+        assert_eq!(
+            budget_problems(&rules[1..], &[AgentName::Claude], &budgets),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -1,38 +1,333 @@
 // src/universal_rule.rs
 
-use serde::Deserialize;
+use crate::minify::strip_html_comments;
+use crate::normalize::normalize_markdown;
+use crate::rule_parser::PARTIALS_DIR;
+use crate::template::{render_template, select_agent_blocks, substitute_vars, ProjectContext};
+use crate::AgentName;
+use anyhow::{anyhow, Context, Result};
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+
+/// The version of the rule file format this build of urules understands.
+/// Bump it whenever a frontmatter field changes meaning, so rules written for a newer
+/// format are rejected instead of being misread.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The kind of artifact a rule represents, used by converters to route it
+/// to the matching output (e.g. Claude slash commands instead of `CLAUDE.md`).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentType {
+    /// Regular instructions, merged into each agent's rule output. This is the default.
+    #[default]
+    Instructions,
+    /// A reusable prompt, emitted as a prompt file where the agent supports them, or else
+    /// as a slash command.
+    Prompt,
+    /// A reusable command, emitted as a slash command where the agent supports it, or
+    /// else as a prompt file.
+    Command,
+    /// Reference material the agent pulls in when relevant, by its `description`, instead
+    /// of keeping it always in context.
+    Knowledge,
+}
+
+/// Which part of its content is used as the `description` of a rule that has none, so
+/// that outputs such as Cursor's `description:` are not left empty.
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoDescription {
+    /// Rules without a `description` keep none. This is the default.
+    #[default]
+    Off,
+    /// The first sentence of the content's first paragraph.
+    Sentence,
+    /// The content's whole first paragraph.
+    Paragraph,
+}
+
+/// Returns the first paragraph of a Markdown `content`, with its lines joined by spaces,
+/// skipping headings, HTML comments and fenced code blocks before it.
+fn first_paragraph(content: &str) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut in_fence = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with("```") || line.starts_with("~~~") {
+            if !lines.is_empty() {
+                break;
+            }
+            in_fence = !in_fence;
+            continue;
+        }
+        let skipped = line.is_empty() || line.starts_with('#') || line.starts_with("<!--");
+        if in_fence || (skipped && lines.is_empty()) {
+            continue;
+        }
+        if skipped {
+            break;
+        }
+        lines.push(line);
+    }
+    Some(lines.join(" ")).filter(|paragraph| !paragraph.is_empty())
+}
+
+/// Returns the first sentence of `paragraph`: the text up to the first `.`, `!` or `?`
+/// followed by a space, or the whole paragraph.
+fn first_sentence(paragraph: &str) -> &str {
+    paragraph
+        .match_indices(['.', '!', '?'])
+        .map(|(index, _)| index + 1)
+        .find(|&end| paragraph[end..].starts_with(' '))
+        .map_or(paragraph, |end| &paragraph[..end])
+}
+
+/// How agents activate a rule, set through `activation`. Each converter maps it to the
+/// agent's own concept (Cursor's `alwaysApply`, Windsurf's `trigger`, Copilot's `applyTo`).
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Activation {
+    /// Always applied.
+    Always,
+    /// Applied when files matching the rule's `globs` are in context.
+    Glob,
+    /// Applied when the agent judges the rule's `description` relevant.
+    AgentDecision,
+    /// Only applied when the user mentions the rule.
+    Manual,
+}
+
+impl From<CursorRuleType> for Activation {
+    fn from(cursor_rule_type: CursorRuleType) -> Self {
+        match cursor_rule_type {
+            CursorRuleType::Always => Activation::Always,
+            CursorRuleType::AutoAttached => Activation::Glob,
+            CursorRuleType::AgentRequested => Activation::AgentDecision,
+            CursorRuleType::Manual => Activation::Manual,
+        }
+    }
+}
+
+/// How Cursor activates a rule, set through the deprecated `cursor_rule_type`.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum CursorRuleType {
+    /// Included in every request (`alwaysApply: true`).
+    Always,
+    /// Attached when files matching the rule's `globs` are in context.
+    AutoAttached,
+    /// Included by the agent when it judges the rule's `description` relevant.
+    AgentRequested,
+    /// Only included when mentioned with `@<name>`.
+    Manual,
+}
+
+/// Settings for the rule's block in `CLAUDE.md`.
+///
+/// Declared as a `claude:` block in the frontmatter.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ClaudeRuleConfig {
+    /// The level (1 to 6) of the rule's `Rule: {title}` heading. Defaults to 2.
+    pub heading_level: Option<u8>,
+
+    /// A top-level section of `CLAUDE.md` (e.g. `Testing`) the rule is grouped under,
+    /// instead of the category of its subdirectory.
+    pub section: Option<String>,
+}
+
+/// Settings for emitting a rule as a Claude Code subagent definition.
+///
+/// Declared as a `subagent:` block in the frontmatter. Unset fields fall back to the
+/// rule's own name and description.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct SubagentConfig {
+    /// The subagent's identifier. Defaults to the rule name.
+    pub name: Option<String>,
+
+    /// When the subagent should be used. Defaults to the rule's `description`.
+    pub description: Option<String>,
+
+    /// The tools the subagent may use (e.g., `["Read", "Grep"]`).
+    /// If `None`, the subagent inherits all tools.
+    pub tools: Option<Vec<String>>,
+}
 
 /// Represents the YAML frontmatter of a universal rule file.
 ///
 /// This structure holds metadata that defines how a rule should be processed
 /// and applied by different AI coding agents.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct UniversalRuleFrontmatter {
+    /// The version of the rule file format the rule is written for (see `SCHEMA_VERSION`).
+    /// Rules with a newer version fail to parse; if `None`, the current version is assumed.
+    pub schema_version: Option<u32>,
+
+    /// An optional free-form version of the rule itself (e.g. `"1.2.0"`), for tracking
+    /// changes to shared rules. It is not interpreted by urules.
+    pub version: Option<String>,
+
+    /// The last day (e.g. `2025-12-31`) the rule is generated, for temporary guidance
+    /// such as migration-period rules. Expired rules are skipped, or fail `--strict` runs.
+    pub expires: Option<NaiveDate>,
+
+    /// An environment variable (e.g. `ACME_INTERNAL`) that must be set for the rule to be
+    /// generated, so that internal-only rules are skipped in other checkouts.
+    pub requires_env: Option<String>,
+
+    /// If `true`, the rule must reach every selected agent: generation and `check` fail
+    /// if it is expired, gated, filtered out or not targeted at one of the agents.
+    /// Defaults to `false` if not specified in the YAML frontmatter.
+    #[serde(default)]
+    pub required: bool,
+
+    /// Where the rule goes among the others in the outputs: rules with a higher priority
+    /// come first, and rules of equal priority are ordered by path.
+    /// Defaults to `0` if not specified in the YAML frontmatter.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Who maintains the rule (e.g. `@platform-team`), so that readers know whom to ask
+    /// about it. Shown by `urules list` and, with `--owner-comments`, in the outputs.
+    #[serde(alias = "author")]
+    pub owner: Option<String>,
+
+    /// An optional display title, used for the rule's heading in concatenated outputs
+    /// (e.g. `## Rule: {title}` in `CLAUDE.md`) and by `urules list`.
+    /// Output paths still derive from the file name. If `None`, the title is derived from
+    /// the file name (see `UniversalRule::title`).
+    pub title: Option<String>,
+
+    /// The stem of the rule's generated files (e.g. `api-guidelines` for
+    /// `.cursor/rules/api-guidelines.mdc`), instead of the rule name. It must be a plain
+    /// file name without path separators.
+    pub output_name: Option<String>,
+
+    /// Further stems (e.g. `["api", "endpoints"]`) under which converters writing one file
+    /// per rule emit a copy of the rule, for tools looking rules up by file name.
+    pub aliases: Option<Vec<String>>,
+
     /// An optional human-readable description of the rule's purpose or behavior.
     /// This can be used for documentation or comments in the generated agent-specific rules.
     pub description: Option<String>,
 
+    /// When an agent should pull in the rule (e.g. `When designing REST endpoints`), used
+    /// instead of `description` for agent-requested rules: Cursor's `agentRequested`
+    /// description, Windsurf's `model_decision` description and Devin's trigger.
+    pub trigger_description: Option<String>,
+
     /// A list of glob patterns (e.g., `["*.rs", "src/utils/*.ts"]`) that specify
-    /// which files this rule should apply to.
+    /// which files this rule should apply to. Patterns starting with `!` exclude files
+    /// (see `include_globs` and `exclude_globs`).
     /// This is primarily used by agents like Cursor for auto-attaching rules or
     /// by Windsurf for workspace rule targeting.
     /// If `None` or empty, the rule's applicability might be determined by other factors
     /// or it might be considered for manual invocation.
     pub globs: Option<Vec<String>>,
 
+    /// Languages (e.g. `["rust", "typescript"]`) whose globs are added to `globs` when the
+    /// rule is discovered, from a built-in table or the `[languages]` of the project
+    /// configuration.
+    pub languages: Option<Vec<String>>,
+
+    /// A directory, relative to the output directory (e.g. `packages/api`), the rule only
+    /// applies to. Converters supporting nested configurations (Cursor's nested
+    /// `.cursor/rules/`, Claude's per-directory `CLAUDE.md`) emit the rule there.
+    /// Only plain relative paths are accepted (no `..` or absolute paths).
+    pub scope: Option<PathBuf>,
+
+    /// How agents activate the rule. If `None`, each agent falls back to its deprecated
+    /// field (`apply_globally`, `cursor_rule_type`) and then to its default behavior.
+    pub activation: Option<Activation>,
+
     /// If `true`, this rule is intended to be applied globally across a project or workspace.
     /// This is particularly relevant for the Windsurf converter, which separates rules
     /// into a global file and workspace-specific files.
+    /// Deprecated in favor of `activation: always`, and ignored if `activation` is set.
     /// Defaults to `false` if not specified in the YAML frontmatter.
-    #[serde(default)] // Ensures bool::default() (false) is used if not present in YAML
+    #[serde(default, alias = "applyGlobally")]
+    // Ensures bool::default() (false) is used if not present in YAML
     pub apply_globally: bool,
 
     /// Specifies the type of rule for the Cursor.ai agent, influencing how it's
     /// categorized and activated within Cursor.
-    /// Examples: "Always", "AutoAttached", "AgentRequested", "Manual".
-    /// This field is specific to the Cursor conversion process.
-    pub cursor_rule_type: Option<String>,
+    /// Any value other than `Always`, `AutoAttached`, `AgentRequested` and `Manual` is a
+    /// parse error. This field is specific to the Cursor conversion process.
+    /// Deprecated in favor of `activation`, and ignored if `activation` is set.
+    #[serde(alias = "cursorRuleType")]
+    pub cursor_rule_type: Option<CursorRuleType>,
+
+    /// An optional list of agents (e.g., `["cursor", "claude"]`) this rule is limited to.
+    /// If `None`, the rule is emitted for every agent.
+    pub agents: Option<Vec<AgentName>>,
+
+    /// If `true`, this rule is a reusable prompt rather than an always-on instruction.
+    /// Converters that support prompt files (e.g. Copilot's `.github/prompts/`) emit it there
+    /// instead of mixing it into their instruction output.
+    /// Deprecated in favor of `content_type: prompt`, and ignored if `content_type` is set
+    /// to another kind than `instructions`.
+    /// Defaults to `false` if not specified in the YAML frontmatter.
+    #[serde(default)]
+    pub prompt: bool,
+
+    /// The kind of artifact this rule represents (`instructions`, `prompt`, `command` or
+    /// `knowledge`), which converters route to different outputs (see `content_kind`).
+    /// Defaults to `instructions` if not specified in the YAML frontmatter.
+    #[serde(default)]
+    pub content_type: ContentType,
+
+    /// The model the rule is meant for (e.g. `sonnet`, `gpt-4o`), passed on to the outputs
+    /// with model metadata: Claude subagents and commands, and Copilot prompt files.
+    /// Ignored by the other converters.
+    pub model: Option<String>,
+
+    /// Links or paths (e.g. `https://example.com/guide`, `docs/api.md`) with more details,
+    /// rendered as a "See also" list at the end of the rule's content in the outputs.
+    pub references: Option<Vec<String>>,
+
+    /// The most tokens the rule's content may take in the outputs (estimated at about four
+    /// characters per token); generation warns about larger rules, or fails with `--strict`.
+    pub max_tokens: Option<usize>,
+
+    /// Free-form tags used to route rules to special outputs (e.g. `review` rules become
+    /// Copilot code review instructions instead of coding instructions).
+    pub tags: Option<Vec<String>>,
+
+    /// Paths (in `.gitignore` syntax) that agents should not read, written to their AI
+    /// ignore files (`.cursorignore`, `.codeiumignore`, `.aiexclude`, ...).
+    pub ignore_patterns: Option<Vec<String>>,
+
+    /// Values for the `{{name}}` references of the rule's content (e.g. `project: Acme`),
+    /// overridden by the `vars` of the project configuration and by `--set name=value`.
+    pub vars: Option<BTreeMap<String, String>>,
+
+    /// If `true`, the rule's content is a minijinja template, rendered at generation time
+    /// with its `vars` (and the configured ones) as variables, the rule's metadata as
+    /// `rule` and the project information as `project`.
+    /// Defaults to `false` if not specified in the YAML frontmatter.
+    #[serde(default)]
+    pub template: bool,
+
+    /// If set, the rule is emitted as a Claude Code subagent (`.claude/agents/<name>.md`)
+    /// instead of being merged into `CLAUDE.md`.
+    pub subagent: Option<SubagentConfig>,
+
+    /// Controls the rule's heading and section in `CLAUDE.md`.
+    pub claude: Option<ClaudeRuleConfig>,
+
+    /// Custom metadata urules does not interpret (e.g. `jira: PLAT-123`), passed on to
+    /// converters, custom converter templates and the JSON export. Unknown top-level keys
+    /// are kept here too, unless `extra` declares a key of the same name.
+    #[serde(default)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+
+    /// The frontmatter keys that are not fields of the rule format (e.g. typos such as
+    /// `aplly_globally`), as dotted paths like `subagent.tols`.
+    /// This field is assigned during rule parsing and never read from or written to YAML.
+    #[serde(skip)]
+    pub unknown_keys: Vec<String>,
 }
 
 impl Default for UniversalRuleFrontmatter {
@@ -41,12 +336,105 @@ impl Default for UniversalRuleFrontmatter {
     /// or for individual fields, and when a frontmatter block is missing or empty.
     fn default() -> Self {
         UniversalRuleFrontmatter {
+            schema_version: None, // The current schema version is assumed
+            version: None,
+            expires: None,
+            requires_env: None,
+            required: false,
+            priority: 0,
+            owner: None,
+            title: None,
+            output_name: None,
+            aliases: None,
             description: None,
+            trigger_description: None,
             globs: None,
+            languages: None,
+            scope: None,
+            activation: None,
             apply_globally: false, // Default behavior is not global application
             cursor_rule_type: None,
+            agents: None, // Applies to all agents by default
+            prompt: false,
+            content_type: ContentType::Instructions,
+            model: None,
+            references: None,
+            max_tokens: None,
+            tags: None,
+            ignore_patterns: None,
+            vars: None,
+            template: false,
+            subagent: None,
+            claude: None,
+            extra: BTreeMap::new(),
+            unknown_keys: Vec::new(),
+        }
+    }
+}
+
+impl UniversalRuleFrontmatter {
+    /// Returns `true` if the rule is always applied, from `activation: always` or, if
+    /// `activation` is not set, the deprecated `apply_globally: true`.
+    pub fn is_always_applied(&self) -> bool {
+        match self.activation {
+            Some(activation) => activation == Activation::Always,
+            None => self.apply_globally,
+        }
+    }
+
+    /// Returns the `globs` that are not negated: the patterns the rule applies to.
+    pub fn include_globs(&self) -> Vec<String> {
+        self.globs
+            .iter()
+            .flatten()
+            .filter(|glob| !glob.starts_with('!'))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the patterns of the negated `globs`, without their `!` (e.g. `**/*.test.ts`
+    /// for `!**/*.test.ts`): the files the rule does not apply to.
+    pub fn exclude_globs(&self) -> Vec<String> {
+        self.globs
+            .iter()
+            .flatten()
+            .filter_map(|glob| glob.strip_prefix('!'))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Returns the kind of artifact the rule represents: its `content_type`, or
+    /// `ContentType::Prompt` for instructions marked with the deprecated `prompt: true`.
+    pub fn content_kind(&self) -> ContentType {
+        match self.content_type {
+            ContentType::Instructions if self.prompt => ContentType::Prompt,
+            ref content_type => content_type.clone(),
         }
     }
+
+    /// Returns the text agents decide by whether to pull in the rule: its
+    /// `trigger_description`, or else its `description`.
+    pub fn trigger_description(&self) -> Option<&str> {
+        self.trigger_description
+            .as_deref()
+            .or(self.description.as_deref())
+    }
+
+    /// Returns how agents activate the rule: its `activation`, or else
+    /// `Activation::AgentDecision` for `content_type: knowledge`.
+    pub fn effective_activation(&self) -> Option<Activation> {
+        self.activation.or_else(|| {
+            (self.content_type == ContentType::Knowledge).then_some(Activation::AgentDecision)
+        })
+    }
+
+    /// Returns how Cursor activates the rule: its `activation`, or else the one of its
+    /// deprecated `cursor_rule_type`, or else the one of its `content_type`.
+    pub fn cursor_activation(&self) -> Option<Activation> {
+        self.activation
+            .or_else(|| self.cursor_rule_type.map(Activation::from))
+            .or_else(|| self.effective_activation())
+    }
 }
 
 /// Represents a complete universal rule, combining its parsed frontmatter
@@ -54,7 +442,7 @@ impl Default for UniversalRuleFrontmatter {
 ///
 /// This struct is the central representation of a rule after it has been
 /// read and parsed from a `.md` file.
-#[derive(Debug)]
+#[derive(Serialize, Debug, Clone, Default)]
 pub struct UniversalRule {
     /// The name of the rule, typically derived from the stem of its filename
     /// (e.g., "my_custom_rule" from "my_custom_rule.md").
@@ -69,4 +457,313 @@ pub struct UniversalRule {
     /// This is the body of the rule file that follows the optional frontmatter section.
     /// It contains the actual instructions or prompts for the AI agent.
     pub content: String,
+
+    /// The directory containing the rule file, relative to the rules directory
+    /// (e.g. `frontend/react` for `.rules/frontend/react/hooks.md`).
+    /// Empty for rules at the top level of the rules directory.
+    /// This field is assigned during rule discovery, not from the frontmatter.
+    pub relative_dir: PathBuf,
+}
+
+impl UniversalRule {
+    /// Returns `true` if this rule should be emitted for the given agent,
+    /// based on the optional `agents` list in its frontmatter.
+    pub fn applies_to(&self, agent: &AgentName) -> bool {
+        match &self.frontmatter.agents {
+            Some(agents) => agents.contains(agent),
+            None => true,
+        }
+    }
+
+    /// Returns `true` if the rule only declares `ignore_patterns` and has no content,
+    /// so it feeds the agents' ignore files without being emitted as a rule.
+    pub fn is_ignore_only(&self) -> bool {
+        self.frontmatter.ignore_patterns.is_some() && self.content.trim().is_empty()
+    }
+
+    /// Returns the rule's display title: its `title` frontmatter field, or else its name
+    /// with `_` and `-` replaced by spaces and the first letter capitalized
+    /// (e.g. "Rust style" for `rust_style.md`).
+    pub fn title(&self) -> String {
+        if let Some(title) = &self.frontmatter.title {
+            return title.clone();
+        }
+        let words = self.name.replace(['_', '-'], " ");
+        let mut chars = words.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => words,
+        }
+    }
+
+    /// Returns the values of the rule's variables: `builtins`, overridden by its `vars`,
+    /// overridden by `overrides` (later entries win).
+    fn resolved_vars(
+        &self,
+        builtins: &BTreeMap<String, String>,
+        overrides: &[(String, String)],
+    ) -> BTreeMap<String, String> {
+        let mut vars = builtins.clone();
+        vars.extend(self.frontmatter.vars.clone().unwrap_or_default());
+        vars.extend(overrides.iter().cloned());
+        vars
+    }
+
+    /// Returns the rule with the `{{name}}` references of its content replaced by the
+    /// values of `builtins`, its `vars` and `overrides` (see `resolved_vars`).
+    pub fn with_vars(
+        mut self,
+        builtins: &BTreeMap<String, String>,
+        overrides: &[(String, String)],
+    ) -> Self {
+        let vars = self.resolved_vars(builtins, overrides);
+        self.content = substitute_vars(&self.content, &vars);
+        self
+    }
+
+    /// Returns the rule with its content rendered as a minijinja template if it sets
+    /// `template: true`, or else with its `{{name}}` references replaced as by `with_vars`.
+    ///
+    /// Templates see the variables of `builtins`, the rule's `vars` and `overrides` by
+    /// name, its frontmatter, `name`, `title` and `relative_dir` as `rule`, and `project`.
+    pub fn with_template(
+        mut self,
+        builtins: &BTreeMap<String, String>,
+        overrides: &[(String, String)],
+        project: &ProjectContext,
+    ) -> Result<Self> {
+        if !self.frontmatter.template {
+            return Ok(self.with_vars(builtins, overrides));
+        }
+        let vars = self.resolved_vars(builtins, overrides);
+        let rule = minijinja::context! {
+            name => &self.name,
+            title => self.title(),
+            relative_dir => self.relative_dir.to_string_lossy().replace('\\', "/"),
+            ..minijinja::Value::from_serialize(&self.frontmatter)
+        };
+        let context = minijinja::context! {
+            rule => rule,
+            project => minijinja::Value::from_serialize(project),
+            ..minijinja::Value::from_serialize(&vars)
+        };
+        let partials_dir = Path::new(&project.rules_dir).join(PARTIALS_DIR);
+        self.content =
+            render_template(&self.content, context, &partials_dir).with_context(|| {
+                format!(
+                    "Failed to render the template of rule {:?}",
+                    self.relative_path()
+                )
+            })?;
+        Ok(self)
+    }
+
+    /// Returns the rule with its content reduced to the passages of its
+    /// `<!-- urules:if agent == "name" -->` blocks that apply to `agent`.
+    pub fn for_agent(mut self, agent: &AgentName) -> Result<Self> {
+        self.content = select_agent_blocks(&self.content, agent).map_err(|message| {
+            anyhow!(
+                "Invalid `urules:if` block in rule {:?}: {}",
+                self.relative_path(),
+                message
+            )
+        })?;
+        Ok(self)
+    }
+
+    /// Returns the rule with the HTML comments of its content, such as author notes,
+    /// removed (see `strip_html_comments`).
+    pub fn without_comments(mut self) -> Self {
+        self.content = strip_html_comments(&self.content);
+        self
+    }
+
+    /// Returns the rule with its content re-rendered with consistent Markdown syntax (see
+    /// `normalize_markdown`).
+    pub fn with_normalized_markdown(mut self) -> Self {
+        self.content = normalize_markdown(&self.content);
+        self
+    }
+
+    /// Returns the rule with an `<!-- Owner: {owner} -->` comment line prepended to its
+    /// content, or unchanged if it has no `owner`.
+    pub fn with_owner_comment(mut self) -> Self {
+        if let Some(owner) = &self.frontmatter.owner {
+            self.content = format!("<!-- Owner: {} -->\n\n{}", owner, self.content);
+        }
+        self
+    }
+
+    /// Returns the rule with a `description` derived from its content as chosen by `mode`,
+    /// or unchanged if it already has a `description` or `mode` is `AutoDescription::Off`.
+    pub fn with_derived_description(mut self, mode: AutoDescription) -> Self {
+        if mode == AutoDescription::Off || self.frontmatter.description.is_some() {
+            return self;
+        }
+        self.frontmatter.description = first_paragraph(&self.content).map(|paragraph| match mode {
+            AutoDescription::Sentence => first_sentence(&paragraph).to_string(),
+            _ => paragraph,
+        });
+        self
+    }
+
+    /// Returns the rule with a `See also:` list of its `references` appended to its
+    /// content, or unchanged if it has none or no content (e.g. ignore-only rules).
+    pub fn with_references(mut self) -> Self {
+        let references = self.frontmatter.references.as_deref().unwrap_or_default();
+        if references.is_empty() || self.content.trim().is_empty() {
+            return self;
+        }
+        let items: Vec<String> = references.iter().map(|r| format!("- {}", r)).collect();
+        self.content = format!(
+            "{}\n\nSee also:\n\n{}\n",
+            self.content.trim_end(),
+            items.join("\n")
+        );
+        self
+    }
+
+    /// Returns the stem of the rule's generated files: its `output_name`, or else its name.
+    pub fn output_stem(&self) -> &str {
+        self.frontmatter
+            .output_name
+            .as_deref()
+            .unwrap_or(&self.name)
+    }
+
+    /// Returns the stems of all the rule's per-rule files: its `output_stem`, followed by
+    /// its `aliases` without duplicates.
+    pub fn output_stems(&self) -> Vec<&str> {
+        let mut stems = vec![self.output_stem()];
+        for alias in self.frontmatter.aliases.iter().flatten() {
+            if !stems.contains(&alias.as_str()) {
+                stems.push(alias);
+            }
+        }
+        stems
+    }
+
+    /// Returns the rule's category: the directory containing it relative to the rules
+    /// directory, with `/` separators (e.g. `frontend/react`), or `None` for rules at the
+    /// top level of the rules directory.
+    pub fn category(&self) -> Option<String> {
+        if self.relative_dir.as_os_str().is_empty() {
+            return None;
+        }
+        Some(self.relative_dir.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// Returns the directory the rule is scoped to, relative to the output directory,
+    /// or an empty path for rules without a `scope`.
+    pub fn scope_dir(&self) -> PathBuf {
+        self.frontmatter.scope.clone().unwrap_or_default()
+    }
+
+    /// Returns `true` if the rule's `expires` date is before `today`.
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        self.frontmatter
+            .expires
+            .is_some_and(|expires| expires < today)
+    }
+
+    /// Returns the path of the rule file relative to the rules directory
+    /// (e.g. `frontend/react/hooks.md`).
+    pub fn relative_path(&self) -> PathBuf {
+        self.relative_dir.join(format!("{}.md", self.name))
+    }
+
+    /// Returns `true` if the rule's `tags` list contains `tag`.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.frontmatter
+            .tags
+            .as_ref()
+            .is_some_and(|tags| tags.iter().any(|t| t == tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that `template: true` rules are rendered with their vars, metadata and the
+    /// project, while other rules only get their `{{name}}` references replaced.
+    #[test]
+    fn test_with_template() {
+        let content = "{% for tag in rule.tags %}[{{ tag }}] {% endfor %}{{ rule.title }} for {{ project.agents | join(', ') }} in {{ org }}";
+        let rule = |template: bool| UniversalRule {
+            name: "api_design".to_string(),
+            content: content.to_string(),
+            frontmatter: UniversalRuleFrontmatter {
+                tags: Some(vec!["web".to_string()]),
+                vars: Some(BTreeMap::from([("org".to_string(), "Acme".to_string())])),
+                template,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let project = ProjectContext {
+            agents: vec![AgentName::Cursor, AgentName::Claude],
+            ..Default::default()
+        };
+        let overrides = [("org".to_string(), "Initech".to_string())];
+
+        assert_eq!(
+            rule(true)
+                .with_template(&BTreeMap::new(), &overrides, &project)
+                .unwrap()
+                .content,
+            "[web] Api design for cursor, claude in Initech"
+        );
+        assert_eq!(
+            rule(false)
+                .with_template(&BTreeMap::new(), &overrides, &project)
+                .unwrap()
+                .content,
+            content.replace("{{ org }}", "Initech")
+        );
+
+        let mut broken = rule(true);
+        broken.content = "{{ rule.nme }}".to_string();
+        let error = broken
+            .with_template(&BTreeMap::new(), &[], &project)
+            .unwrap_err();
+        assert!(format!("{:#}", error)
+            .contains("Failed to render the template of rule \"api_design.md\""));
+    }
+
+    /// Test that descriptions are derived from the first paragraph after headings and code.
+    #[test]
+    fn test_with_derived_description() {
+        let rule = |description: Option<&str>| UniversalRule {
+            name: "api".to_string(),
+            content:
+                "# API\n\n```sh\ncurl\n```\n\nVersion every endpoint. Use\nv1 first.\n\nMore.\n"
+                    .to_string(),
+            frontmatter: UniversalRuleFrontmatter {
+                description: description.map(String::from),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let derive = |mode, description| {
+            rule(description)
+                .with_derived_description(mode)
+                .frontmatter
+                .description
+        };
+        assert_eq!(
+            derive(AutoDescription::Sentence, None).as_deref(),
+            Some("Version every endpoint.")
+        );
+        assert_eq!(
+            derive(AutoDescription::Paragraph, None).as_deref(),
+            Some("Version every endpoint. Use v1 first.")
+        );
+        assert_eq!(derive(AutoDescription::Off, None), None);
+        assert_eq!(
+            derive(AutoDescription::Sentence, Some("REST APIs")).as_deref(),
+            Some("REST APIs")
+        );
+        assert_eq!(first_sentence("See v1.2 docs"), "See v1.2 docs");
+    }
 }